@@ -0,0 +1,133 @@
+use crate::config::RpcConfig;
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Most accounts a single getMultipleAccounts request may ask for
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+static GLOBAL: OnceLock<AccountBatcher> = OnceLock::new();
+
+/// Accounts returned by a batched fetch, in request order
+#[derive(Debug, Default)]
+pub struct FetchedAccounts {
+    /// Highest context slot of the batches
+    pub slot: u64,
+    pub accounts: Vec<Option<Account>>,
+}
+
+/// Splits getMultipleAccounts requests into chunks of `rpc.gma_chunk_size` with
+/// `rpc.gma_chunk_delay_ms` between them. A failed chunk is retried at half the size;
+/// the size grows back towards the configured one as requests succeed.
+#[derive(Debug)]
+pub struct AccountBatcher {
+    configured_chunk_size: usize,
+    chunk_size: AtomicUsize,
+    chunk_delay: Duration,
+}
+
+impl Default for AccountBatcher {
+    fn default() -> Self {
+        Self::new(MAX_MULTIPLE_ACCOUNTS, Duration::ZERO)
+    }
+}
+
+impl AccountBatcher {
+    fn new(chunk_size: usize, chunk_delay: Duration) -> Self {
+        let chunk_size = chunk_size.clamp(1, MAX_MULTIPLE_ACCOUNTS);
+        Self {
+            configured_chunk_size: chunk_size,
+            chunk_size: AtomicUsize::new(chunk_size),
+            chunk_delay,
+        }
+    }
+
+    /// Applies the `[rpc]` batching settings. Only the first call takes effect.
+    pub fn init(config: &RpcConfig) {
+        let batcher = GLOBAL.get_or_init(|| {
+            Self::new(
+                config.gma_chunk_size.unwrap_or(MAX_MULTIPLE_ACCOUNTS),
+                Duration::from_millis(config.gma_chunk_delay_ms.unwrap_or(0)),
+            )
+        });
+        info!(
+            "getMultipleAccounts batching: {} accounts per request, {}ms between requests",
+            batcher.configured_chunk_size,
+            batcher.chunk_delay.as_millis()
+        );
+    }
+
+    pub fn global() -> &'static AccountBatcher {
+        GLOBAL.get_or_init(AccountBatcher::default)
+    }
+
+    /// Fetches `keys` at the client's commitment
+    pub fn get_multiple_accounts(
+        &self,
+        rpc_client: &RpcClient,
+        keys: &[Pubkey],
+    ) -> Result<Vec<Option<Account>>> {
+        Ok(self
+            .get_multiple_accounts_with_commitment(rpc_client, keys, rpc_client.commitment())?
+            .accounts)
+    }
+
+    pub fn get_multiple_accounts_with_commitment(
+        &self,
+        rpc_client: &RpcClient,
+        keys: &[Pubkey],
+        commitment: CommitmentConfig,
+    ) -> Result<FetchedAccounts> {
+        let mut fetched = FetchedAccounts {
+            slot: 0,
+            accounts: Vec::with_capacity(keys.len()),
+        };
+        let mut offset = 0;
+        while offset < keys.len() {
+            if offset > 0 && !self.chunk_delay.is_zero() {
+                std::thread::sleep(self.chunk_delay);
+            }
+            let chunk_size = self.chunk_size.load(Ordering::Relaxed);
+            let chunk = &keys[offset..(offset + chunk_size).min(keys.len())];
+            match rpc_client.get_multiple_accounts_with_commitment(chunk, commitment) {
+                Ok(response) => {
+                    fetched.slot = fetched.slot.max(response.context.slot);
+                    fetched.accounts.extend(response.value);
+                    offset += chunk.len();
+                    self.grow(chunk_size);
+                }
+                Err(e) if chunk_size > 1 => {
+                    let smaller = chunk_size / 2;
+                    warn!(
+                        "getMultipleAccounts of {} accounts failed ({}), retrying with {}",
+                        chunk.len(),
+                        e,
+                        smaller
+                    );
+                    self.chunk_size.store(smaller, Ordering::Relaxed);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(fetched)
+    }
+
+    /// Doubles the chunk size after a success, up to the configured size
+    fn grow(&self, chunk_size: usize) {
+        if chunk_size < self.configured_chunk_size {
+            let grown = (chunk_size * 2).min(self.configured_chunk_size);
+            let _ = self.chunk_size.compare_exchange(
+                chunk_size,
+                grown,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+        }
+    }
+}
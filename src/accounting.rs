@@ -0,0 +1,357 @@
+use crate::base_mints::BaseMints;
+use crate::constants::{executor_program_id, sol_mint};
+use crate::jito::JitoTips;
+use crate::loader::stablecoin_sol_price;
+use crate::pricing::SolPrice;
+use crate::risk::unix_now;
+use anyhow::{anyhow, Result};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding, UiTransactionTokenBalance,
+};
+use std::str::FromStr;
+use tracing::warn;
+
+/// Realized result of a landed transaction for the fee payer
+#[derive(Debug, Clone)]
+pub struct TransactionPnl {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub fee_lamports: u64,
+    /// Lamports transferred to Jito tip accounts
+    pub tip_lamports: u64,
+    /// Net change of the wallet's SOL, WSOL and base mint balances in lamports, fees and tips
+    /// included. None when a base mint without a SOL price changed; the loss breaker skips
+    /// such transactions.
+    pub pnl_lamports: Option<i64>,
+    pub succeeded: bool,
+}
+
+/// Executor transaction found in the wallet's history at startup
+#[derive(Debug, Clone)]
+pub struct ReconciledTransaction {
+    /// The only one of the wallet's mints among the transaction's accounts, None when there
+    /// is no such mint or several
+    pub mint: Option<Pubkey>,
+    pub pnl: TransactionPnl,
+}
+
+/// SOL prices of the base mints other than SOL, for valuing the wallet's base token changes
+#[derive(Debug, Clone, Default)]
+pub struct BaseMintPrices {
+    prices: Vec<(Pubkey, Option<SolPrice>)>,
+}
+
+impl BaseMintPrices {
+    /// USDC and USD1 are priced with `sol_price_usd`; other base mints have no SOL price
+    pub fn new(base_mints: &BaseMints, sol_price_usd: Option<f64>) -> Self {
+        Self {
+            prices: base_mints
+                .iter()
+                .filter(|mint| **mint != sol_mint())
+                .map(|mint| (*mint, stablecoin_sol_price(mint, sol_price_usd)))
+                .collect(),
+        }
+    }
+}
+
+pub fn fetch_transaction(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    Ok(rpc_client.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        },
+    )?)
+}
+
+pub fn fetch_transaction_pnl(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+    wallet: &Pubkey,
+    base_prices: &BaseMintPrices,
+) -> Result<TransactionPnl> {
+    let transaction = fetch_transaction(rpc_client, signature)?;
+    transaction_pnl(signature, &transaction, wallet, base_prices)
+}
+
+pub fn transaction_pnl(
+    signature: &Signature,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    wallet: &Pubkey,
+    base_prices: &BaseMintPrices,
+) -> Result<TransactionPnl> {
+    let meta = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .ok_or_else(|| anyhow!("Transaction {} has no status meta", signature))?;
+
+    // The wallet pays the fee, so it is always the first account
+    let pre_sol = meta.pre_balances.first().copied().unwrap_or_default() as i64;
+    let post_sol = meta.post_balances.first().copied().unwrap_or_default() as i64;
+    let pre_wsol = token_balance(&meta.pre_token_balances, wallet, &sol_mint());
+    let post_wsol = token_balance(&meta.post_token_balances, wallet, &sol_mint());
+    let base_pnl = base_mint_pnl(
+        &meta.pre_token_balances,
+        &meta.post_token_balances,
+        wallet,
+        base_prices,
+    );
+
+    Ok(TransactionPnl {
+        signature: *signature,
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        fee_lamports: meta.fee,
+        tip_lamports: tip_lamports(transaction),
+        pnl_lamports: base_pnl.map(|base| (post_sol - pre_sol) + (post_wsol - pre_wsol) + base),
+        succeeded: meta.err.is_none(),
+    })
+}
+
+/// Fetches the wallet's recent executor transactions (oldest first) within the lookback window,
+/// each attributed to the one of `mints` it traded
+pub fn recent_executor_transactions(
+    rpc_client: &RpcClient,
+    wallet: &Pubkey,
+    mints: &[Pubkey],
+    limit: usize,
+    lookback_secs: u64,
+    base_prices: &BaseMintPrices,
+) -> Result<Vec<ReconciledTransaction>> {
+    let signatures = rpc_client.get_signatures_for_address_with_config(
+        wallet,
+        GetConfirmedSignaturesForAddress2Config {
+            before: None,
+            until: None,
+            limit: Some(limit),
+            commitment: Some(CommitmentConfig::confirmed()),
+        },
+    )?;
+
+    let executor = executor_program_id();
+    let oldest = unix_now() - lookback_secs as i64;
+    let mut results = Vec::new();
+
+    for status in signatures {
+        if status.block_time.map_or(false, |block_time| block_time < oldest) {
+            continue;
+        }
+        let signature = Signature::from_str(&status.signature)?;
+        let transaction = match fetch_transaction(rpc_client, &signature) {
+            Ok(transaction) => transaction,
+            Err(e) => {
+                warn!("Failed to fetch transaction {}: {}", signature, e);
+                continue;
+            }
+        };
+        let invokes_executor = transaction
+            .transaction
+            .transaction
+            .decode()
+            .map_or(false, |tx| tx.message.static_account_keys().contains(&executor));
+        if !invokes_executor {
+            continue;
+        }
+        let mint = account_keys(&transaction).and_then(|keys| traded_mint(&keys, mints));
+        match transaction_pnl(&signature, &transaction, wallet, base_prices) {
+            Ok(pnl) => results.push(ReconciledTransaction { mint, pnl }),
+            Err(e) => warn!("Failed to read result of transaction {}: {}", signature, e),
+        }
+    }
+
+    // Signatures come back newest first
+    results.reverse();
+    Ok(results)
+}
+
+/// Balance increase of the configured Jito tip accounts, 0 when tipping is off
+fn tip_lamports(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> u64 {
+    let (Some(tips), Some(meta)) = (JitoTips::global(), transaction.transaction.meta.as_ref())
+    else {
+        return 0;
+    };
+    let Some(keys) = account_keys(transaction) else {
+        return 0;
+    };
+    keys.iter()
+        .zip(meta.pre_balances.iter().zip(&meta.post_balances))
+        .filter(|(key, _)| tips.is_tip_account(key))
+        .map(|(_, (pre, post))| post.saturating_sub(*pre))
+        .sum()
+}
+
+/// Every account of the transaction in balance order: the static keys first, then the
+/// writable and readonly lookup table keys
+fn account_keys(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> Option<Vec<Pubkey>> {
+    let decoded = transaction.transaction.transaction.decode()?;
+    let mut keys: Vec<Pubkey> = decoded.message.static_account_keys().to_vec();
+    if let Some(OptionSerializer::Some(loaded)) = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .map(|meta| &meta.loaded_addresses)
+    {
+        keys.extend(
+            loaded
+                .writable
+                .iter()
+                .chain(&loaded.readonly)
+                .filter_map(|key| Pubkey::from_str(key).ok()),
+        );
+    }
+    Some(keys)
+}
+
+/// The only one of `mints` among `keys`
+fn traded_mint(keys: &[Pubkey], mints: &[Pubkey]) -> Option<Pubkey> {
+    let mut traded = mints.iter().filter(|mint| keys.contains(mint));
+    match (traded.next(), traded.next()) {
+        (Some(mint), None) => Some(*mint),
+        _ => None,
+    }
+}
+
+/// Lamport value of the wallet's base mint balance changes, None when a mint without a SOL
+/// price changed
+fn base_mint_pnl(
+    pre_balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+    post_balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+    wallet: &Pubkey,
+    base_prices: &BaseMintPrices,
+) -> Option<i64> {
+    let mut pnl = 0;
+    for (mint, sol_price) in &base_prices.prices {
+        let change =
+            token_balance(post_balances, wallet, mint) - token_balance(pre_balances, wallet, mint);
+        if change == 0 {
+            continue;
+        }
+        let sol_price = sol_price.as_ref()?;
+        let sol = change as f64 / 10f64.powi(sol_price.base_decimals as i32) / sol_price.price;
+        pnl += (sol * LAMPORTS_PER_SOL as f64).round() as i64;
+    }
+    Some(pnl)
+}
+
+fn token_balance(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> i64 {
+    let wallet = wallet.to_string();
+    let mint = mint.to_string();
+    match balances {
+        OptionSerializer::Some(balances) => balances
+            .iter()
+            .filter(|balance| balance.mint == mint)
+            .filter(|balance| matches!(&balance.owner, OptionSerializer::Some(owner) if *owner == wallet))
+            .filter_map(|balance| balance.ui_token_amount.amount.parse::<i64>().ok())
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::usdc_mint;
+    use solana_account_decoder::parse_token::UiTokenAmount;
+
+    fn balance(owner: &Pubkey, mint: &Pubkey, amount: u64) -> UiTransactionTokenBalance {
+        UiTransactionTokenBalance {
+            account_index: 1,
+            mint: mint.to_string(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: None,
+                decimals: 6,
+                amount: amount.to_string(),
+                ui_amount_string: String::new(),
+            },
+            owner: OptionSerializer::Some(owner.to_string()),
+            program_id: OptionSerializer::None,
+        }
+    }
+
+    fn prices(mints: &[(Pubkey, Option<f64>)]) -> BaseMintPrices {
+        BaseMintPrices {
+            prices: mints
+                .iter()
+                .map(|(mint, sol_price_usd)| (*mint, stablecoin_sol_price(mint, *sol_price_usd)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn stablecoin_changes_are_valued_with_the_sol_price() {
+        let wallet = Pubkey::new_unique();
+        let usdc = usdc_mint();
+        // Lost 1.5 USDC at $150 per SOL: 0.01 SOL
+        let pre = OptionSerializer::Some(vec![balance(&wallet, &usdc, 10_000_000)]);
+        let post = OptionSerializer::Some(vec![balance(&wallet, &usdc, 8_500_000)]);
+        let base_prices = prices(&[(usdc, Some(150.0))]);
+        assert_eq!(
+            base_mint_pnl(&pre, &post, &wallet, &base_prices),
+            Some(-10_000_000)
+        );
+
+        // Balances of other owners are ignored
+        let other = Pubkey::new_unique();
+        let post = OptionSerializer::Some(vec![
+            balance(&wallet, &usdc, 10_000_000),
+            balance(&other, &usdc, 1),
+        ]);
+        assert_eq!(base_mint_pnl(&pre, &post, &wallet, &base_prices), Some(0));
+    }
+
+    #[test]
+    fn transactions_are_attributed_to_their_only_traded_mint() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mints = [first, second];
+        let keys = |extra: &[Pubkey]| {
+            let mut keys = vec![Pubkey::new_unique(), sol_mint()];
+            keys.extend_from_slice(extra);
+            keys
+        };
+        assert_eq!(traded_mint(&keys(&[second]), &mints), Some(second));
+        assert_eq!(traded_mint(&keys(&[]), &mints), None);
+        assert_eq!(traded_mint(&keys(&[first, second]), &mints), None);
+    }
+
+    #[test]
+    fn unpriced_base_mint_changes_are_not_valued() {
+        let wallet = Pubkey::new_unique();
+        let (usdc, other) = (usdc_mint(), Pubkey::new_unique());
+        let pre = OptionSerializer::Some(vec![
+            balance(&wallet, &usdc, 5_000_000),
+            balance(&wallet, &other, 5_000_000),
+        ]);
+        let post = OptionSerializer::Some(vec![
+            balance(&wallet, &usdc, 4_000_000),
+            balance(&wallet, &other, 5_000_000),
+        ]);
+
+        // USDC without `sol_price_usd`
+        let base_prices = prices(&[(usdc, None), (other, None)]);
+        assert_eq!(base_mint_pnl(&pre, &post, &wallet, &base_prices), None);
+
+        // An unchanged base mint needs no price
+        let base_prices = prices(&[(usdc, Some(100.0)), (other, None)]);
+        assert_eq!(
+            base_mint_pnl(&pre, &post, &wallet, &base_prices),
+            Some(-10_000_000)
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use crate::config::AdaptiveDelayConfig;
+use std::fmt;
+use std::time::Duration;
+
+const DEFAULT_FAILURE_STREAK: u32 = 10;
+
+/// Why the adaptive controller last changed the delay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelayReason {
+    Initial,
+    Landed,
+    SpreadDetected,
+    NoProfitStreak,
+    SendFailureStreak,
+}
+
+impl fmt::Display for DelayReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            DelayReason::Initial => "initial",
+            DelayReason::Landed => "landed transaction",
+            DelayReason::SpreadDetected => "spread above threshold",
+            DelayReason::NoProfitStreak => "no-profit streak",
+            DelayReason::SendFailureStreak => "send failure streak",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// Per-mint process delay that shrinks on success signals and grows on long failure streaks
+#[derive(Debug, Clone)]
+pub struct AdaptiveDelay {
+    current: Duration,
+    min: Duration,
+    max: Duration,
+    failure_streak_limit: u32,
+    no_profit_streak: u32,
+    send_failure_streak: u32,
+    reason: DelayReason,
+}
+
+impl AdaptiveDelay {
+    pub fn new(config: &AdaptiveDelayConfig, initial_delay: Duration) -> Self {
+        let min = Duration::from_millis(config.min_delay);
+        let max = Duration::from_millis(config.max_delay.max(config.min_delay));
+        Self {
+            current: initial_delay.clamp(min, max),
+            min,
+            max,
+            failure_streak_limit: config
+                .failure_streak
+                .unwrap_or(DEFAULT_FAILURE_STREAK)
+                .max(1),
+            no_profit_streak: 0,
+            send_failure_streak: 0,
+            reason: DelayReason::Initial,
+        }
+    }
+
+    pub fn current(&self) -> Duration {
+        self.current
+    }
+
+    pub fn reason(&self) -> DelayReason {
+        self.reason
+    }
+
+    /// Returns true when the delay changed
+    pub fn on_landed(&mut self) -> bool {
+        self.no_profit_streak = 0;
+        self.send_failure_streak = 0;
+        self.shrink(DelayReason::Landed)
+    }
+
+    /// Returns true when the delay changed
+    pub fn on_spread_detected(&mut self) -> bool {
+        self.no_profit_streak = 0;
+        self.shrink(DelayReason::SpreadDetected)
+    }
+
+    /// Returns true when the delay changed
+    pub fn on_no_profit(&mut self) -> bool {
+        self.no_profit_streak += 1;
+        if self.no_profit_streak < self.failure_streak_limit {
+            return false;
+        }
+        self.no_profit_streak = 0;
+        self.grow(DelayReason::NoProfitStreak)
+    }
+
+    /// Returns true when the delay changed
+    pub fn on_send_failure(&mut self) -> bool {
+        self.send_failure_streak += 1;
+        if self.send_failure_streak < self.failure_streak_limit {
+            return false;
+        }
+        self.send_failure_streak = 0;
+        self.grow(DelayReason::SendFailureStreak)
+    }
+
+    /// A successful broadcast breaks a send failure streak without changing the delay
+    pub fn on_sent(&mut self) {
+        self.send_failure_streak = 0;
+    }
+
+    fn shrink(&mut self, reason: DelayReason) -> bool {
+        let next = (self.current / 2).max(self.min);
+        self.apply(next, reason)
+    }
+
+    fn grow(&mut self, reason: DelayReason) -> bool {
+        let next = (self.current * 3 / 2).max(self.current + Duration::from_millis(1));
+        self.apply(next.min(self.max), reason)
+    }
+
+    fn apply(&mut self, next: Duration, reason: DelayReason) -> bool {
+        if next == self.current {
+            return false;
+        }
+        self.current = next;
+        self.reason = reason;
+        true
+    }
+}
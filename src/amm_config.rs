@@ -0,0 +1,128 @@
+use crate::dex::raydium::PoolState;
+use anyhow::{anyhow, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock, RwLock};
+use tracing::warn;
+
+/// Layout family of an `amm_config` account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmmConfigKind {
+    /// Raydium CLMM and its forks (PancakeSwap, Byreal)
+    Clmm,
+    /// Raydium CP-Swap
+    Cp,
+}
+
+/// Fee settings shared by every pool that references the config
+#[derive(Debug, Clone, Copy)]
+pub struct AmmConfig {
+    /// Trade fee in hundredths of a bip (1_000_000 = 100%)
+    pub trade_fee_rate: u64,
+    /// Only set for CLMM configs
+    pub tick_spacing: Option<u16>,
+}
+
+impl AmmConfig {
+    pub fn decode(kind: AmmConfigKind, data: &[u8]) -> Result<Self> {
+        match kind {
+            // discriminator, bump, index, owner, protocol_fee_rate, trade_fee_rate, tick_spacing
+            AmmConfigKind::Clmm => Ok(Self {
+                trade_fee_rate: read_u32(data, 47)? as u64,
+                tick_spacing: Some(read_u16(data, 51)?),
+            }),
+            // discriminator, bump, disable_create_pool, index, trade_fee_rate
+            AmmConfigKind::Cp => Ok(Self {
+                trade_fee_rate: read_u64(data, 12)?,
+                tick_spacing: None,
+            }),
+        }
+    }
+}
+
+/// Process-wide cache of decoded `amm_config` accounts, fetched once per config
+#[derive(Default)]
+pub struct AmmConfigCache {
+    configs: RwLock<HashMap<Pubkey, AmmConfig>>,
+    /// Pools whose tick spacing disagrees with their config, warned about once
+    tick_spacing_mismatches: Mutex<HashSet<Pubkey>>,
+}
+
+impl AmmConfigCache {
+    pub fn global() -> &'static AmmConfigCache {
+        static CACHE: OnceLock<AmmConfigCache> = OnceLock::new();
+        CACHE.get_or_init(AmmConfigCache::default)
+    }
+
+    pub fn get(&self, address: &Pubkey) -> Option<AmmConfig> {
+        self.configs.read().unwrap().get(address).copied()
+    }
+
+    /// Returns the cached config, fetching and decoding it on first use
+    pub fn get_or_fetch(
+        &self,
+        rpc_client: &RpcClient,
+        address: &Pubkey,
+        kind: AmmConfigKind,
+    ) -> Result<AmmConfig> {
+        if let Some(config) = self.get(address) {
+            return Ok(config);
+        }
+        let account = rpc_client.get_account(address)?;
+        let config = AmmConfig::decode(kind, &account.data)?;
+        self.configs.write().unwrap().insert(*address, config);
+        Ok(config)
+    }
+
+    /// Sets the tick spacing of a CLMM `pool_state` to the one of its cached `amm_config`,
+    /// which the program treats as authoritative. Keeps the pool state's value when the config
+    /// is not cached or has no tick spacing, and warns once per pool when the two disagree.
+    pub fn reconcile_tick_spacing(&self, pool: &Pubkey, pool_state: &mut PoolState) {
+        let Some(config_spacing) = self
+            .get(&pool_state.amm_config)
+            .and_then(|config| config.tick_spacing)
+            .filter(|spacing| *spacing > 0)
+        else {
+            return;
+        };
+        let mut mismatches = self.tick_spacing_mismatches.lock().unwrap();
+        if pool_state.tick_spacing == config_spacing {
+            mismatches.remove(pool);
+            return;
+        }
+        if mismatches.insert(*pool) {
+            warn!(
+                "CLMM pool {} has tick spacing {} but its AMM config {} has {}, using {}",
+                pool, pool_state.tick_spacing, pool_state.amm_config, config_spacing, config_spacing
+            );
+        }
+        pool_state.tick_spacing = config_spacing;
+    }
+
+    pub fn len(&self) -> usize {
+        self.configs.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("AMM config account too short"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("AMM config account too short"))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("AMM config account too short"))
+}
@@ -0,0 +1,230 @@
+/// 1 / golden ratio, the interval shrink factor of golden-section search
+const INV_GOLDEN_RATIO: f64 = 0.618_033_988_749_895;
+/// Steps narrowing the whole u64 range below one unit (2^64 * 0.618^93 < 1); smaller
+/// ranges stop as soon as they are that narrow
+pub const DEFAULT_SEARCH_ITERATIONS: u32 = 93;
+
+/// Reserves of a constant-product leg, oriented in the direction of the swap
+#[derive(Debug, Clone, Copy)]
+pub struct CpLeg {
+    pub reserve_in: u64,
+    pub reserve_out: u64,
+    /// Trade fee in basis points, taken from the input
+    pub fee_bps: u64,
+}
+
+impl CpLeg {
+    fn fee_factor(&self) -> f64 {
+        1.0 - self.fee_bps as f64 / 10_000.0
+    }
+
+    /// Output of swapping `amount_in` through the leg
+    pub fn amount_out(&self, amount_in: u64) -> u64 {
+        let amount_in = amount_in as f64 * self.fee_factor();
+        let out = self.reserve_out as f64 * amount_in / (self.reserve_in as f64 + amount_in);
+        out.max(0.0) as u64
+    }
+}
+
+/// Input size of a round trip and what it returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizedTrade {
+    pub input: u64,
+    pub output: u64,
+    pub profit: u64,
+}
+
+/// Closed-form profit-maximizing input for buying on `buy` and selling on `sell`.
+///
+/// The two legs compose to `out = K x / (M + N x)`, whose profit `out - x` peaks at
+/// `x = (sqrt(K M) - M) / N`. Returns None when no input is profitable.
+pub fn cp_optimal_input(buy: &CpLeg, sell: &CpLeg, max_input: u64) -> Option<SizedTrade> {
+    let (g1, g2) = (buy.fee_factor(), sell.fee_factor());
+    let k = g1 * g2 * buy.reserve_out as f64 * sell.reserve_out as f64;
+    let m = buy.reserve_in as f64 * sell.reserve_in as f64;
+    let n = g1 * (sell.reserve_in as f64 + g2 * buy.reserve_out as f64);
+    if m <= 0.0 || n <= 0.0 || k <= m {
+        return None;
+    }
+
+    let input = (((k * m).sqrt() - m) / n).min(max_input as f64) as u64;
+    let output = sell.amount_out(buy.amount_out(input));
+    (input > 0 && output > input).then(|| SizedTrade {
+        input,
+        output,
+        profit: output - input,
+    })
+}
+
+/// Golden-section search over `[0, max_input]` for the input maximizing `quote(input) - input`.
+///
+/// `quote` returns the round-trip output for an input, or None when the route cannot
+/// fill it. Profit is assumed unimodal in the input, which holds for concentrated
+/// liquidity and bin curves as well as constant product. At most `iterations` quotes
+/// are taken besides the first two and the final one, so latency stays bounded.
+pub fn optimal_size_search<F>(mut quote: F, max_input: u64, iterations: u32) -> Option<SizedTrade>
+where
+    F: FnMut(u64) -> Option<u64>,
+{
+    let mut profit = |input: f64| -> i128 {
+        let input = input.round() as u64;
+        quote(input).map_or(i128::MIN, |output| output as i128 - input as i128)
+    };
+
+    let (mut lo, mut hi) = (0.0, max_input as f64);
+    let mut c = hi - INV_GOLDEN_RATIO * (hi - lo);
+    let mut d = lo + INV_GOLDEN_RATIO * (hi - lo);
+    let (mut fc, mut fd) = (profit(c), profit(d));
+    for _ in 0..iterations {
+        if hi - lo < 1.0 {
+            break;
+        }
+        if fc >= fd {
+            hi = d;
+            d = c;
+            fd = fc;
+            c = hi - INV_GOLDEN_RATIO * (hi - lo);
+            fc = profit(c);
+        } else {
+            lo = c;
+            c = d;
+            fc = fd;
+            d = lo + INV_GOLDEN_RATIO * (hi - lo);
+            fd = profit(d);
+        }
+    }
+
+    let input = ((lo + hi) / 2.0).round() as u64;
+    let output = quote(input)?;
+    (input > 0 && output > input).then(|| SizedTrade {
+        input,
+        output,
+        profit: output - input,
+    })
+}
+
+/// Sizes a buy-then-sell round trip: the closed form when both legs are constant
+/// product, otherwise a bounded search over `quote`
+pub fn size_round_trip<F>(
+    buy: Option<&CpLeg>,
+    sell: Option<&CpLeg>,
+    quote: F,
+    max_input: u64,
+    iterations: u32,
+) -> Option<SizedTrade>
+where
+    F: FnMut(u64) -> Option<u64>,
+{
+    match (buy, sell) {
+        (Some(buy), Some(sell)) => cp_optimal_input(buy, sell, max_input),
+        _ => optimal_size_search(quote, max_input, iterations),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leg(reserve_in: u64, reserve_out: u64, fee_bps: u64) -> CpLeg {
+        CpLeg {
+            reserve_in,
+            reserve_out,
+            fee_bps,
+        }
+    }
+
+    fn round_trip(buy: CpLeg, sell: CpLeg) -> impl FnMut(u64) -> Option<u64> {
+        move |input| Some(sell.amount_out(buy.amount_out(input)))
+    }
+
+    #[test]
+    fn default_iterations_narrow_u64_to_one_unit() {
+        let range = u64::MAX as f64;
+        let iterations = DEFAULT_SEARCH_ITERATIONS as i32;
+        assert!(range * INV_GOLDEN_RATIO.powi(iterations) < 1.0);
+        assert!(range * INV_GOLDEN_RATIO.powi(iterations - 1) >= 1.0);
+    }
+
+    #[test]
+    fn search_matches_the_closed_form_on_cp_legs() {
+        let sol = 1_000_000_000;
+        let cases = [
+            // 1% apart, 25 bps fees, deep pools
+            (
+                leg(1_000 * sol, 50_000_000_000, 25),
+                leg(49_500_000_000, 1_000 * sol, 25),
+            ),
+            // 5% apart, shallow buy pool
+            (
+                leg(20 * sol, 1_000_000_000, 30),
+                leg(95_000_000_000, 2_000 * sol, 25),
+            ),
+            // 10% apart, no fees
+            (leg(100 * sol, 10_000_000, 0), leg(9_000_000, 100 * sol, 0)),
+        ];
+        for (buy, sell) in cases {
+            for max_input in [u64::MAX, 5 * sol] {
+                let closed = cp_optimal_input(&buy, &sell, max_input).unwrap();
+                let searched = size_round_trip(
+                    None,
+                    None,
+                    round_trip(buy, sell),
+                    max_input.min(1_000_000 * sol),
+                    DEFAULT_SEARCH_ITERATIONS,
+                )
+                .unwrap();
+                // Profit is flat around the optimum, so the inputs may differ slightly
+                let tolerance = closed.profit / 10_000 + 2;
+                assert!(
+                    searched.profit + tolerance >= closed.profit,
+                    "{:?} {:?}: search {:?}, closed form {:?}",
+                    buy,
+                    sell,
+                    searched,
+                    closed
+                );
+                assert!(searched.input <= max_input);
+            }
+        }
+    }
+
+    #[test]
+    fn cp_legs_use_the_closed_form() {
+        let (buy, sell) = (leg(1_000_000, 1_000_000, 0), leg(900_000, 1_000_000, 0));
+        // The quote disagrees with the legs, so using it would change the result
+        let sized = size_round_trip(Some(&buy), Some(&sell), |_| None, 1_000_000, 8);
+        assert_eq!(sized, cp_optimal_input(&buy, &sell, 1_000_000));
+        assert!(sized.is_some());
+    }
+
+    #[test]
+    fn unprofitable_round_trips_are_none() {
+        let (buy, sell) = (
+            leg(1_000_000_000, 1_000_000_000, 25),
+            leg(1_000_000_000, 1_000_000_000, 25),
+        );
+        assert_eq!(cp_optimal_input(&buy, &sell, u64::MAX), None);
+        let searched = optimal_size_search(
+            round_trip(buy, sell),
+            1_000_000_000,
+            DEFAULT_SEARCH_ITERATIONS,
+        );
+        assert_eq!(searched, None);
+        assert_eq!(
+            optimal_size_search(Some, 1_000, DEFAULT_SEARCH_ITERATIONS),
+            None
+        );
+    }
+
+    #[test]
+    fn unfillable_quotes_are_none() {
+        assert_eq!(
+            optimal_size_search(|_| None, 1_000_000, DEFAULT_SEARCH_ITERATIONS),
+            None
+        );
+        assert_eq!(
+            optimal_size_search(|input| Some(input * 2), 0, DEFAULT_SEARCH_ITERATIONS),
+            None
+        );
+    }
+}
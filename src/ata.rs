@@ -1,4 +1,8 @@
-use crate::constants::{sol_mint, usdc_mint, usd1_mint};
+use crate::account_batcher::AccountBatcher;
+use crate::base_mints::BaseMints;
+use crate::pools::MintPoolData;
+use crate::token_program::classify_token_program;
+use crate::transaction::send_maintenance_transaction;
 use anyhow::{Context, Result};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
@@ -9,20 +13,29 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use spl_associated_token_account::{
-    get_associated_token_address,
+    get_associated_token_address, get_associated_token_address_with_program_id,
     instruction::create_associated_token_account_idempotent,
 };
-use tracing::info;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{info, warn};
 
-/// Ensures a single ATA exists, creating it if necessary
+/// Creator vault ATAs created per maintenance transaction
+const CREATOR_VAULT_ATAS_PER_TX: usize = 8;
+
+/// Ensures a single ATA exists, creating it through `send_client` if necessary
 fn ensure_ata_exists(
     rpc_client: &RpcClient,
+    send_client: &RpcClient,
     wallet_kp: &Keypair,
     mint: &Pubkey,
     mint_name: &str,
 ) -> Result<Pubkey> {
     let wallet = wallet_kp.pubkey();
-    let ata = get_associated_token_address(&wallet, mint);
+    let mint_account = rpc_client
+        .get_account(mint)
+        .with_context(|| format!("Failed to fetch {} mint account", mint_name))?;
+    let token_program = classify_token_program(&mint_account.owner)?.pubkey();
+    let ata = get_associated_token_address_with_program_id(&wallet, mint, &token_program);
 
     info!("Checking {} ATA: {}", mint_name, ata);
 
@@ -38,7 +51,7 @@ fn ensure_ata_exists(
                 &wallet,
                 &wallet,
                 mint,
-                &spl_token::id(),
+                &token_program,
             );
 
             let blockhash = rpc_client
@@ -55,9 +68,8 @@ fn ensure_ata_exists(
                 blockhash,
             );
 
-            let sig = rpc_client
-                .send_and_confirm_transaction(&tx)
-                .context(format!("Failed to create {} ATA", mint_name))?;
+            let what = format!("{} ATA creation", mint_name);
+            let sig = send_maintenance_transaction(send_client, &tx, &what)?;
 
             info!("{} ATA created successfully. Signature: {}", mint_name, sig);
             Ok(ata)
@@ -65,19 +77,102 @@ fn ensure_ata_exists(
     }
 }
 
-/// Ensures all base token ATAs (WSOL, USDC, USD1) exist.
+/// Ensures an ATA exists for every configured base mint.
 /// This should be called during bot initialization before processing pools.
-pub fn ensure_base_atas_exist(rpc_client: &RpcClient, wallet_kp: &Keypair) -> Result<()> {
+pub fn ensure_base_atas_exist(
+    rpc_client: &RpcClient,
+    send_client: &RpcClient,
+    wallet_kp: &Keypair,
+    base_mints: &BaseMints,
+) -> Result<()> {
     info!("Verifying base token ATAs...");
 
-    let wsol_ata = ensure_ata_exists(rpc_client, wallet_kp, &sol_mint(), "WSOL")?;
-    let usdc_ata = ensure_ata_exists(rpc_client, wallet_kp, &usdc_mint(), "USDC")?;
-    let usd1_ata = ensure_ata_exists(rpc_client, wallet_kp, &usd1_mint(), "USD1")?;
+    let mut atas = Vec::new();
+    for mint in base_mints.iter() {
+        let name = BaseMints::label(mint);
+        let ata = ensure_ata_exists(rpc_client, send_client, wallet_kp, mint, &name)?;
+        atas.push((name, ata));
+    }
 
     info!("All base token ATAs verified/created successfully");
-    info!("  WSOL ATA: {}", wsol_ata);
-    info!("  USDC ATA: {}", usdc_ata);
-    info!("  USD1 ATA: {}", usd1_ata);
+    for (name, ata) in atas {
+        info!("  {} ATA: {}", name, ata);
+    }
+
+    Ok(())
+}
+
+/// Checks that the coin creator vault ATA of every Pump pool exists and logs how many are
+/// missing. With `create`, missing ones are created idempotently through `send_client`,
+/// paid by `wallet_kp`.
+pub fn check_pump_creator_vault_atas(
+    rpc_client: &RpcClient,
+    send_client: &RpcClient,
+    wallet_kp: &Keypair,
+    pools: &HashMap<Pubkey, MintPoolData>,
+    create: bool,
+) -> Result<()> {
+    // ATA -> (owner, mint) for the idempotent create instruction
+    let mut atas: BTreeMap<Pubkey, (Pubkey, Pubkey)> = BTreeMap::new();
+    for pool in pools.values().flat_map(|pool_data| &pool_data.pump_pools) {
+        if pool.coin_creator == Pubkey::default() {
+            continue;
+        }
+        let owner = pool.coin_creator_vault_authority;
+        let quote_mint = [pool.base_mint, pool.token_mint]
+            .into_iter()
+            .find(|mint| get_associated_token_address(&owner, mint) == pool.coin_creator_vault_ata);
+        if let Some(quote_mint) = quote_mint {
+            atas.insert(pool.coin_creator_vault_ata, (owner, quote_mint));
+        }
+    }
+    if atas.is_empty() {
+        return Ok(());
+    }
 
+    let keys: Vec<Pubkey> = atas.keys().copied().collect();
+    let accounts = AccountBatcher::global().get_multiple_accounts(rpc_client, &keys)?;
+    let missing: Vec<Pubkey> = keys
+        .into_iter()
+        .zip(accounts)
+        .filter(|(_, account)| account.is_none())
+        .map(|(ata, _)| ata)
+        .collect();
+    if missing.is_empty() {
+        info!("All {} Pump coin creator vault ATAs exist", atas.len());
+        return Ok(());
+    }
+    warn!(
+        "{} of {} Pump coin creator vault ATAs do not exist",
+        missing.len(),
+        atas.len()
+    );
+    if !create {
+        return Ok(());
+    }
+
+    let wallet = wallet_kp.pubkey();
+    for chunk in missing.chunks(CREATOR_VAULT_ATAS_PER_TX) {
+        let mut instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_price(1_000_000),
+            ComputeBudgetInstruction::set_compute_unit_limit(30_000 * chunk.len() as u32),
+        ];
+        instructions.extend(chunk.iter().map(|ata| {
+            let (owner, mint) = atas[ata];
+            create_associated_token_account_idempotent(&wallet, &owner, &mint, &spl_token::ID)
+        }));
+        let blockhash = rpc_client
+            .get_latest_blockhash()
+            .context("Failed to get blockhash for creator vault ATA creation")?;
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&wallet),
+            &[wallet_kp],
+            blockhash,
+        );
+        let sig =
+            send_maintenance_transaction(send_client, &tx, "Creator vault ATA creation")?;
+        info!("Created {} Pump coin creator vault ATAs. Signature: {}", chunk.len(), sig);
+    }
     Ok(())
 }
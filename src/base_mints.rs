@@ -0,0 +1,102 @@
+use crate::config::RoutingConfig;
+use crate::constants::{sol_mint, usd1_mint, usdc_mint};
+use anyhow::{anyhow, Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Mints accepted as the quote side of a pool. SOL is always included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseMints {
+    mints: Vec<Pubkey>,
+    primary: Pubkey,
+    /// Base side of pools configured with `base_override`
+    overrides: HashMap<Pubkey, Pubkey>,
+}
+
+impl Default for BaseMints {
+    fn default() -> Self {
+        Self {
+            mints: vec![sol_mint(), usdc_mint(), usd1_mint()],
+            primary: sol_mint(),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl BaseMints {
+    /// Reads `routing.base_mints`, defaulting to SOL, USDC and USD1,
+    /// `routing.primary_base_mint`, defaulting to SOL, and the `base_override` of markets
+    pub fn from_config(routing: &RoutingConfig) -> Result<Self> {
+        let mut base_mints = Self::default();
+        if let Some(configured) = &routing.base_mints {
+            base_mints.mints = vec![sol_mint()];
+            for mint in configured {
+                let mint = Pubkey::from_str(mint)
+                    .with_context(|| format!("Invalid routing.base_mints entry {}", mint))?;
+                if !base_mints.mints.contains(&mint) {
+                    base_mints.mints.push(mint);
+                }
+            }
+        }
+        if let Some(primary) = &routing.primary_base_mint {
+            let primary = Pubkey::from_str(primary)
+                .with_context(|| format!("Invalid routing.primary_base_mint {}", primary))?;
+            if !base_mints.contains(&primary) {
+                return Err(anyhow!(
+                    "routing.primary_base_mint {} is not one of the base mints",
+                    primary
+                ));
+            }
+            base_mints.primary = primary;
+        }
+        for entry in &routing.markets.markets {
+            let Some(base) = entry.base_override() else {
+                continue;
+            };
+            let pool = Pubkey::from_str(entry.address())
+                .with_context(|| format!("Invalid market address {}", entry.address()))?;
+            let base = Pubkey::from_str(base).with_context(|| {
+                format!("Invalid base_override {} for market {}", base, entry.describe())
+            })?;
+            base_mints.overrides.insert(pool, base);
+        }
+        Ok(base_mints)
+    }
+
+    /// Base mint whose pools are kept when a mint's pools span several base mints
+    pub fn primary(&self) -> Pubkey {
+        self.primary
+    }
+
+    /// Configured base side of `pool`, if any
+    pub fn override_for(&self, pool: &Pubkey) -> Option<Pubkey> {
+        self.overrides.get(pool).copied()
+    }
+
+    /// Mint on the base side of `pool`: its `base_override`, otherwise SOL
+    pub fn base_side(&self, pool: &Pubkey) -> Pubkey {
+        self.override_for(pool).unwrap_or_else(sol_mint)
+    }
+
+    pub fn contains(&self, mint: &Pubkey) -> bool {
+        self.mints.contains(mint)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Pubkey> {
+        self.mints.iter()
+    }
+
+    /// Short name for logs: WSOL, USDC, USD1 or the address
+    pub fn label(mint: &Pubkey) -> String {
+        if *mint == sol_mint() {
+            "WSOL".to_string()
+        } else if *mint == usdc_mint() {
+            "USDC".to_string()
+        } else if *mint == usd1_mint() {
+            "USD1".to_string()
+        } else {
+            mint.to_string()
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::log_dedup::LogDedup;
+use anyhow::Result;
+use serde_json::json;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::{Response, RpcBlockhash};
+use solana_sdk::hash::Hash;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{watch, Mutex};
+use tracing::{error, info, warn, Level};
+
+/// Nominal slot time, used to estimate the current slot between fetches
+pub const SLOT_DURATION: Duration = Duration::from_millis(400);
+pub const DEFAULT_BLOCKHASH_GRACE: Duration = Duration::from_secs(30);
+/// How long the refresher may fail before an alert is logged
+const UNHEALTHY_ALERT_AFTER: Duration = Duration::from_secs(60);
+
+/// Blockhash used for signing, with the slot and time it was fetched at
+#[derive(Debug, Clone, Copy)]
+pub struct CachedBlockhash {
+    pub hash: Hash,
+    pub slot: u64,
+    pub fetched_at: SystemTime,
+}
+
+impl CachedBlockhash {
+    /// Slot estimate at `at`, counting nominal slots since the fetch
+    pub fn slot_at(&self, at: SystemTime) -> u64 {
+        let elapsed = at.duration_since(self.fetched_at).unwrap_or_default();
+        self.slot + (elapsed.as_millis() / SLOT_DURATION.as_millis()) as u64
+    }
+}
+
+/// Latest blockhash together with the slot of the response context
+pub fn fetch_blockhash(rpc_client: &RpcClient) -> Result<CachedBlockhash> {
+    let response: Response<RpcBlockhash> = rpc_client.send(
+        RpcRequest::GetLatestBlockhash,
+        json!([{ "commitment": rpc_client.commitment().commitment }]),
+    )?;
+    Ok(CachedBlockhash {
+        hash: Hash::from_str(&response.value.blockhash)?,
+        slot: response.context.slot,
+        fetched_at: SystemTime::now(),
+    })
+}
+
+/// State of the blockhash refresher, published to the workers on every attempt
+#[derive(Debug, Clone, Copy)]
+pub struct BlockhashHealth {
+    pub consecutive_failures: u32,
+    pub last_success: Instant,
+}
+
+impl BlockhashHealth {
+    pub fn healthy() -> Self {
+        Self {
+            consecutive_failures: 0,
+            last_success: Instant::now(),
+        }
+    }
+
+    /// Whether the refresher has been failing for longer than `grace`
+    pub fn is_stale(&self, grace: Duration) -> bool {
+        self.consecutive_failures > 0 && self.last_success.elapsed() > grace
+    }
+}
+
+/// Refreshes the cached blockhash every `refresh_interval`, trying `clients` in order until
+/// one answers, and publishes the refresher's health on `health`
+pub async fn run_blockhash_refresher(
+    clients: Vec<Arc<RpcClient>>,
+    cached_blockhash: Arc<Mutex<CachedBlockhash>>,
+    health: watch::Sender<BlockhashHealth>,
+    refresh_interval: Duration,
+) {
+    let mut state = BlockhashHealth::healthy();
+    let mut alerted = false;
+    loop {
+        match fetch_from_any(&clients) {
+            Ok(blockhash) => {
+                let mut guard = cached_blockhash.lock().await;
+                *guard = blockhash;
+                info!("Blockhash refreshed: {} (slot {})", blockhash.hash, blockhash.slot);
+                if alerted {
+                    info!(
+                        "Blockhash refresher recovered after {} failures",
+                        state.consecutive_failures
+                    );
+                    alerted = false;
+                }
+                state = BlockhashHealth::healthy();
+            }
+            Err(e) => {
+                state.consecutive_failures += 1;
+                static REFRESH_ERRORS: LogDedup = LogDedup::new(Level::ERROR);
+                REFRESH_ERRORS.log(
+                    "blockhash",
+                    format_args!(
+                        "Failed to refresh blockhash ({} consecutive failures): {:?}",
+                        state.consecutive_failures, e
+                    ),
+                );
+                let unhealthy_for = state.last_success.elapsed();
+                if unhealthy_for > UNHEALTHY_ALERT_AFTER && !alerted {
+                    error!(
+                        "ALERT: blockhash refresher has been failing for {}s",
+                        unhealthy_for.as_secs()
+                    );
+                    alerted = true;
+                }
+            }
+        }
+        let _ = health.send(state);
+        tokio::time::sleep(refresh_interval).await;
+    }
+}
+
+/// First blockhash any of `clients` returns, primary first
+fn fetch_from_any(clients: &[Arc<RpcClient>]) -> Result<CachedBlockhash> {
+    let mut last_error = None;
+    for (i, client) in clients.iter().enumerate() {
+        match fetch_blockhash(client) {
+            Ok(blockhash) => {
+                if i > 0 {
+                    warn!("Blockhash fetched from fallback RPC {}", i);
+                }
+                return Ok(blockhash);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no RPC configured for blockhashes")))
+}
+
+pub fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
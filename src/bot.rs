@@ -1,72 +1,427 @@
-use crate::ata::ensure_base_atas_exist;
-use crate::config::Config;
-use crate::pool_refreshers::PoolDataRefresher;
-use crate::refresh::initialize_pools_from_markets;
-use crate::transaction::build_and_send_transaction;
+use crate::account_batcher::AccountBatcher;
+use crate::accounting::{recent_executor_transactions, BaseMintPrices};
+use crate::amm_config::AmmConfigCache;
+use crate::ata::{check_pump_creator_vault_atas, ensure_base_atas_exist};
+use crate::base_mints::BaseMints;
+use crate::blockhash::{fetch_blockhash, run_blockhash_refresher, BlockhashHealth};
+use crate::cluster::{check_cluster_config, verify_cluster};
+use crate::config::{Config, RiskConfig, WsolStrategy};
+use crate::confirmation::LandingTracker;
+use crate::constants::{
+    init_canonical_mints, sol_mint, MAX_COMPUTE_UNIT_LIMIT, MAX_HEAP_FRAME_BYTES,
+    MIN_HEAP_FRAME_BYTES,
+};
+use crate::dex_rpc::DexRpcClients;
+use crate::endpoint_health::{run_slot_lag_probe, EndpointHealth};
+use crate::executor_errors::ExecutorErrors;
+use crate::geyser::spawn_geyser_stream;
+use crate::jito::JitoTips;
+use crate::journal::spawn_send_journal;
+use crate::keypair::load_keypair;
+use crate::log_dedup::run_log_dedup_flush;
+use crate::migration::MigrationWatcher;
+use crate::observations::spawn_observation_writer;
+use crate::paper_trade::PaperLedger;
+use crate::pools::MintPoolData;
+use crate::program_registry::ProgramRegistry;
+use crate::loader::initialize_pools_from_markets;
+use crate::risk::DailyLossBreaker;
+use crate::rpc_budget::RpcBudget;
+use crate::rpc_metrics::{new_rpc_client, new_sending_rpc_client, RpcMetrics};
+use crate::startup::StartupProgress;
+use crate::status::spawn_status_server;
+use crate::transaction::{build_route_account_metas, endpoint_label, AltCoverage, SendingClient};
+use crate::worker::{shard_for, MintWorker, WorkerContext, WorkerGroup};
 use anyhow::Context;
+use futures::future::join_all;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::address_lookup_table::state::AddressLookupTable;
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
-use solana_sdk::hash::Hash;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use std::collections::HashMap;
+use std::io::Write;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex, Semaphore};
 use tracing::{error, info, warn};
 
-pub async fn run_bot(config_path: &str) -> anyhow::Result<()> {
+const DEFAULT_RECONCILE_LOOKBACK_SECS: u64 = 24 * 60 * 60;
+const DEFAULT_MIN_ALT_COVERAGE_PCT: f64 = 90.0;
+
+/// Command line switches that change how the bot starts
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Refuse to start when lookup table coverage is below the configured minimum
+    pub strict_alt: bool,
+    /// Ask for confirmation on stdin after the wallet summary
+    pub confirm_start: bool,
+    /// Book opportunities against a virtual balance instead of sending transactions
+    pub paper_trade: bool,
+    /// Run one iteration per mint and return instead of looping
+    pub one_shot: bool,
+}
+
+/// Loads the config at `config_path`, initializes every mint's pools and runs the mint
+/// workers until the process is stopped, or for one iteration with `options.one_shot`
+pub async fn run_bot(config_path: &str, options: RunOptions) -> anyhow::Result<()> {
     let config = Config::load(config_path)?;
     info!("Configuration loaded successfully");
+    init_canonical_mints(config.mints.as_ref())?;
+    ProgramRegistry::init(config.program_overrides.as_ref())?;
+    check_compute_unit_limits(&config)?;
+    check_heap_frame(&config)?;
+    check_cluster_config(&config)?;
+    let base_mints = BaseMints::from_config(&config.routing)?;
+    let paper_trade = options.paper_trade
+        || config
+            .paper_trade
+            .as_ref()
+            .map_or(false, |paper_trade| paper_trade.enabled);
+    if paper_trade {
+        info!("Paper trading mode: no transactions will be sent");
+    }
 
-    let rpc_client = Arc::new(RpcClient::new(config.rpc.url.clone()));
+    let rpc_metrics = config
+        .metrics
+        .as_ref()
+        .filter(|metrics| metrics.enabled)
+        .map(|metrics| RpcMetrics::new(metrics.log_interval_secs));
+    if let Some(rpc_metrics) = &rpc_metrics {
+        info!("RPC metrics enabled");
+        rpc_metrics.spawn_reporter();
+    }
 
-    let sending_rpc_clients = if let Some(spam_config) = &config.spam {
-        if spam_config.enabled {
-            spam_config
+    let rpc_budget = RpcBudget::from_config(&config.rpc).map(Arc::new);
+    if rpc_budget.is_some() {
+        info!(
+            "RPC budget: {} calls per iteration, {} calls per minute",
+            config
+                .rpc
+                .max_calls_per_iteration
+                .map_or("unlimited".to_string(), |calls| calls.to_string()),
+            config
+                .rpc
+                .max_calls_per_minute
+                .map_or("unlimited".to_string(), |calls| calls.to_string())
+        );
+    }
+
+    AccountBatcher::init(&config.rpc);
+    DexRpcClients::init(&config.rpc, rpc_metrics.as_ref())?;
+    ExecutorErrors::init(config.executor.as_ref())?;
+    JitoTips::init(config.jito.as_ref())?;
+    let rpc_client = Arc::new(new_rpc_client(config.rpc.url.clone(), rpc_metrics.as_ref()));
+    let cluster = verify_cluster(&config.rpc, &rpc_client)?;
+
+    let sending_rpc_clients = match config.spam.as_ref().filter(|spam| spam.enabled) {
+        Some(spam_config) => {
+            let clients = spam_config
                 .sending_rpc_urls
                 .iter()
-                .map(|url| Arc::new(RpcClient::new(url.clone())))
-                .collect::<Vec<_>>()
-        } else {
-            vec![rpc_client.clone()]
+                .filter(|endpoint| endpoint.weight() > 0)
+                .map(|endpoint| {
+                    Ok(SendingClient {
+                        client: Arc::new(new_sending_rpc_client(endpoint, rpc_metrics.as_ref())?),
+                        skip_preflight: endpoint.skip_preflight(),
+                        weight: endpoint.weight(),
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            if clients.is_empty() {
+                anyhow::bail!(
+                    "spam is enabled but spam.sending_rpc_urls has no endpoint with weight above 0"
+                );
+            }
+            info!("Sending through {} spam endpoints", clients.len());
+            EndpointHealth::init(spam_config.max_slot_lag);
+            tokio::spawn(run_slot_lag_probe(rpc_client.clone(), clients.clone()));
+            clients
         }
-    } else {
-        vec![rpc_client.clone()]
+        None => vec![SendingClient::primary(rpc_client.clone())],
+    };
+
+    let maintenance_rpc_client = match config
+        .maintenance
+        .as_ref()
+        .and_then(|maintenance| maintenance.send_rpc_url.clone())
+    {
+        Some(url) => {
+            info!("Sending maintenance transactions through {}", endpoint_label(&url));
+            Arc::new(new_rpc_client(url, rpc_metrics.as_ref()))
+        }
+        None => rpc_client.clone(),
     };
 
-    let wallet_kp =
-        load_keypair(&config.wallet.private_key).context("Failed to load wallet keypair")?;
-    info!("Wallet loaded: {}", wallet_kp.pubkey());
+    let wallets = load_wallets(&config)?;
+    let wallet_pubkeys: Vec<Pubkey> = wallets.iter().map(|wallet| wallet.pubkey()).collect();
+    check_expected_wallets(&config, &wallet_pubkeys)?;
+    for wallet in &wallets {
+        log_wallet_summary(&rpc_client, &wallet.pubkey(), &cluster, &config);
+    }
+    if options.confirm_start || config.wallet.confirm_start.unwrap_or(false) {
+        confirm_start()?;
+    }
+
+    // Ensure base token ATAs (routing.base_mints) exist for every wallet before any worker
+    // can send. Route token ATAs are NOT created here - the on-chain program creates them
+    // as needed
+    if paper_trade {
+        info!("Paper trading: skipping base token ATA creation");
+    } else {
+        for wallet in &wallets {
+            ensure_base_atas_exist(&rpc_client, &maintenance_rpc_client, wallet, &base_mints)
+                .with_context(|| {
+                    format!(
+                        "Base token ATAs of wallet {} could not be created; every arbitrage \
+                         transaction would fail without them. Create them manually or fund \
+                         the wallet for the rent",
+                        wallet.pubkey()
+                    )
+                })?;
+        }
+    }
 
-    let initial_blockhash = rpc_client.get_latest_blockhash()?;
+    let initial_blockhash = fetch_blockhash(&rpc_client)?;
     let cached_blockhash = Arc::new(Mutex::new(initial_blockhash));
 
     let refresh_interval = Duration::from_secs(10);
-    let blockhash_client = rpc_client.clone();
-    let blockhash_cache = cached_blockhash.clone();
-    tokio::spawn(async move {
-        blockhash_refresher(blockhash_client, blockhash_cache, refresh_interval).await;
-    });
+    let mut blockhash_clients = vec![rpc_client.clone()];
+    blockhash_clients.extend(
+        config
+            .rpc
+            .blockhash_fallback_urls
+            .iter()
+            .map(|url| Arc::new(new_rpc_client(url.clone(), rpc_metrics.as_ref()))),
+    );
+    tokio::spawn(run_log_dedup_flush());
+    let (blockhash_health_tx, blockhash_health) = watch::channel(BlockhashHealth::healthy());
+    tokio::spawn(run_blockhash_refresher(
+        blockhash_clients,
+        cached_blockhash.clone(),
+        blockhash_health_tx,
+        refresh_interval,
+    ));
 
     // Initialize pools from markets config (auto-detect DEX types and group by mint)
-    let mint_pool_data_map = initialize_pools_from_markets(
+    let progress = StartupProgress::new(config.startup.as_ref());
+    progress.spawn_reporter(config.startup.as_ref());
+    let initialized = initialize_pools_from_markets(
         &config.routing.markets,
-        &wallet_kp.pubkey(),
+        &base_mints,
+        &wallets[0].pubkey(),
         rpc_client.clone(),
+        &progress,
     )
-    .await?;
+    .await;
+    progress.finish();
+    let mut mint_pool_data_map = initialized?;
+    let wallet_assignments = assign_wallets(&config, &mut mint_pool_data_map, &wallets)?;
 
     info!("Initialized {} mints from markets config", mint_pool_data_map.len());
+    info!("Cached {} shared AMM configs", AmmConfigCache::global().len());
+
+    check_pump_creator_vault_atas(
+        &rpc_client,
+        &maintenance_rpc_client,
+        &wallets[0],
+        &mint_pool_data_map,
+        config.bot.create_creator_vault_atas && !paper_trade,
+    )?;
+
+    let lookup_table_accounts_list = load_lookup_tables(&config, &rpc_client);
+
+    check_alt_coverage(
+        &config,
+        &mint_pool_data_map,
+        &lookup_table_accounts_list,
+        options.strict_alt,
+    )?;
+
+    let lookup_table_accounts_list = Arc::new(lookup_table_accounts_list);
+
+    if let Some(adaptive) = config
+        .routing
+        .markets
+        .adaptive_delay
+        .as_ref()
+        .filter(|adaptive| adaptive.enabled)
+    {
+        info!(
+            "Adaptive process delay enabled: {}ms - {}ms",
+            adaptive.min_delay, adaptive.max_delay
+        );
+    }
+    if MigrationWatcher::from_config(&config)?.is_some() {
+        info!("Migration watching enabled: drained pools are replaced by their migrated pool");
+    }
+    if let Some(cooldown) = config.routing.post_land_cooldown_ms {
+        info!("Post-land cooldown enabled: {}ms", cooldown);
+    }
 
-    // Ensure base token ATAs (WSOL, USDC, USD1) exist
-    // Route token ATAs are NOT created here - the on-chain program creates them as needed
-    ensure_base_atas_exist(&rpc_client, &wallet_kp)?;
+    if let Some(max_daily_loss) = config
+        .risk
+        .as_ref()
+        .and_then(|risk| risk.max_daily_loss_lamports)
+    {
+        info!("Daily loss limit: {} lamports per wallet", max_daily_loss);
+    }
+
+    let observation_sink = config
+        .observations
+        .as_ref()
+        .map(spawn_observation_writer)
+        .transpose()?;
+
+    let send_journal = config.journal.as_ref().map(spawn_send_journal).transpose()?;
+
+    let status = match &config.status {
+        Some(status_config) => Some(
+            spawn_status_server(
+                status_config,
+                rpc_metrics.clone(),
+                rpc_budget.clone(),
+                send_journal.clone(),
+            )
+            .await?,
+        ),
+        None => None,
+    };
 
-    // Load lookup tables (global config)
-    let mut lookup_table_addresses = config.routing.markets.lookup_table_accounts.clone().unwrap_or_default();
+    let paper_ledger = paper_trade.then(|| PaperLedger::new(config.paper_trade.as_ref()));
+    if let Some(paper_ledger) = &paper_ledger {
+        paper_ledger.spawn_reporter();
+    }
+
+    let base_prices = Arc::new(BaseMintPrices::new(
+        &base_mints,
+        config.routing.markets.sol_price_usd,
+    ));
+
+    // Each wallet gets its own loss accounting and landing tracker
+    let mut worker_contexts = Vec::with_capacity(wallets.len());
+    for (index, wallet) in wallets.into_iter().enumerate() {
+        let loss_breaker = config
+            .risk
+            .as_ref()
+            .and_then(DailyLossBreaker::from_config)
+            .map(Arc::new);
+        if let (Some(status), Some(loss_breaker)) = (&status, &loss_breaker) {
+            status.register_loss_breaker(wallet.pubkey(), loss_breaker.clone());
+        }
+        let landing_tracker = LandingTracker::new(
+            rpc_client.clone(),
+            wallet.pubkey(),
+            base_prices.clone(),
+            loss_breaker.clone(),
+            send_journal.clone(),
+            status.is_some(),
+        );
+        if let Some(risk) = &config.risk {
+            let mints: Vec<Pubkey> = wallet_assignments
+                .iter()
+                .filter(|(_, assigned)| **assigned == index)
+                .map(|(mint, _)| *mint)
+                .collect();
+            reconcile_wallet_history(&rpc_client, &landing_tracker, &mints, risk);
+        }
+
+        worker_contexts.push(WorkerContext {
+            config: config.clone(),
+            wallet_kp: Arc::new(wallet),
+            rpc_client: rpc_client.clone(),
+            sending_rpc_clients: sending_rpc_clients.clone(),
+            cached_blockhash: cached_blockhash.clone(),
+            blockhash_health: blockhash_health.clone(),
+            lookup_tables: lookup_table_accounts_list.clone(),
+            landing_tracker,
+            loss_breaker,
+            observation_sink: observation_sink.clone(),
+            status: status.clone(),
+            paper_ledger: paper_ledger.clone(),
+            rpc_budget: rpc_budget.clone(),
+            send_journal: send_journal.clone(),
+            in_flight: config
+                .bot
+                .max_in_flight_per_wallet
+                .map(|max| Arc::new(Semaphore::new(max))),
+        });
+    }
+
+    let one_shot = options.one_shot || config.bot.one_shot;
+    if one_shot {
+        info!("One-shot mode: running a single iteration per mint");
+    }
+    let mut one_shot_workers = Vec::new();
+
+    let worker_count = config.bot.worker_count.filter(|count| *count > 0);
+    let mut groups: Vec<Vec<MintWorker>> = (0..worker_count.unwrap_or(0))
+        .map(|_| Vec::new())
+        .collect();
+
+    let mut geyser_routes = config.geyser.as_ref().map(|_| HashMap::new());
+
+    // Spawn a processing task for each mint, or shard mints across worker_count tasks
+    for (mint, pool_data) in mint_pool_data_map {
+        let ctx = worker_contexts[wallet_assignments[&mint]].clone();
+        info!("Starting processing for mint: {} (wallet {})", mint, ctx.wallet_kp.pubkey());
+
+        let mut worker = MintWorker::new(mint, pool_data, ctx);
+        if one_shot {
+            // The single iteration refreshes the pools itself
+            one_shot_workers.push(worker);
+            continue;
+        }
+        if config.bot.warm_on_start.unwrap_or(true) {
+            worker.warm_pools();
+        }
+        if let Some(routes) = geyser_routes.as_mut() {
+            worker.subscribe_geyser(routes);
+        }
+        match worker_count {
+            Some(count) => groups[shard_for(&mint, count)].push(worker),
+            None => {
+                tokio::spawn(worker.run());
+            }
+        }
+    }
+    if one_shot {
+        let started = Instant::now();
+        let mints = one_shot_workers.len();
+        join_all(one_shot_workers.iter_mut().map(MintWorker::run_once)).await;
+        info!(
+            "One-shot iteration of {} mints finished in {}ms",
+            mints,
+            started.elapsed().as_millis()
+        );
+        return Ok(());
+    }
+    for (index, workers) in groups.into_iter().enumerate() {
+        let group = WorkerGroup::new(workers);
+        info!("Worker {} handles {} mints", index, group.len());
+        tokio::spawn(group.run());
+    }
+    if let (Some(geyser), Some(routes)) = (&config.geyser, geyser_routes) {
+        spawn_geyser_stream(geyser, routes)?;
+    }
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+/// Loads `routing.markets.lookup_table_accounts` plus the executor's own lookup table.
+/// Tables that cannot be fetched or parsed are logged and left out.
+pub(crate) fn load_lookup_tables(
+    config: &Config,
+    rpc_client: &RpcClient,
+) -> Vec<AddressLookupTableAccount> {
+    let mut lookup_table_addresses =
+        config.routing.markets.lookup_table_accounts.clone().unwrap_or_default();
     lookup_table_addresses.push("4sKLJ1Qoudh8PJyqBeuKocYdsZvxTcRShUt9aKqwhgvC".to_string());
 
     let mut lookup_table_accounts_list = vec![];
@@ -109,121 +464,390 @@ pub async fn run_bot(config_path: &str) -> anyhow::Result<()> {
         info!("   Loaded {} lookup tables successfully", lookup_table_accounts_list.len());
     }
 
-    let lookup_table_accounts_list = Arc::new(lookup_table_accounts_list);
-    let process_delay = Duration::from_millis(config.routing.markets.process_delay);
-    let pool_refresh_interval = Duration::from_secs(5);
+    lookup_table_accounts_list
+}
 
-    // Spawn processing task for each mint
-    for (mint, pool_data) in mint_pool_data_map {
-        info!("Starting processing for mint: {}", mint);
-
-        let mint_pool_data = Arc::new(Mutex::new(pool_data));
-        let config_clone = config.clone();
-        let sending_rpc_clients_clone = sending_rpc_clients.clone();
-        let cached_blockhash_clone = cached_blockhash.clone();
-        let wallet_bytes = wallet_kp.to_bytes();
-        let wallet_kp_clone = Keypair::from_bytes(&wallet_bytes).unwrap();
-        let lookup_tables = lookup_table_accounts_list.clone();
-        let mint_str = mint.to_string();
-        let rpc_client_clone = rpc_client.clone();
-
-        tokio::spawn(async move {
-            // Pool refresher for CLMM pools (DLMM, Whirlpool, Raydium CLMM, PancakeSwap, Byreal)
-            let pool_refresher = PoolDataRefresher::new();
-            let mut last_pool_refresh = Instant::now()
-                .checked_sub(pool_refresh_interval)
-                .unwrap_or_else(Instant::now);
-
-            loop {
-                // Check if pool refresh is needed (every 5 seconds)
-                let now = Instant::now();
-                if now.duration_since(last_pool_refresh) >= pool_refresh_interval {
-                    let mut guard = mint_pool_data.lock().await;
-                    match pool_refresher.refresh_all_pools(&mut guard, &rpc_client_clone, false) {
-                        Ok(_) => {
-                            last_pool_refresh = now;
-                            info!("Pool data refreshed for mint {}", mint_str);
-                        }
-                        Err(e) => {
-                            error!("Failed to refresh pool data for mint {}: {}", mint_str, e);
-                        }
-                    }
-                    drop(guard);
-                }
+/// Loads `wallet.private_key` followed by every `wallet.private_keys` entry
+pub(crate) fn load_wallets(config: &Config) -> anyhow::Result<Vec<Keypair>> {
+    let sources = config.wallet.key_sources();
+    if sources.is_empty() {
+        anyhow::bail!("No wallet configured: set wallet.private_key or wallet.private_keys");
+    }
+    let mut wallets: Vec<Keypair> = Vec::with_capacity(sources.len());
+    for (index, source) in sources.iter().enumerate() {
+        let wallet = load_keypair(source)
+            .with_context(|| format!("Failed to load wallet keypair #{}", index))?;
+        if wallets.iter().any(|loaded| loaded.pubkey() == wallet.pubkey()) {
+            anyhow::bail!("Wallet {} is configured more than once", wallet.pubkey());
+        }
+        info!("Wallet #{} loaded: {}", index, wallet.pubkey());
+        wallets.push(wallet);
+    }
+    Ok(wallets)
+}
 
-                let latest_blockhash = {
-                    let guard = cached_blockhash_clone.lock().await;
-                    *guard
-                };
-
-                let guard = mint_pool_data.lock().await;
-
-                match build_and_send_transaction(
-                    &wallet_kp_clone,
-                    &config_clone,
-                    &*guard,
-                    &sending_rpc_clients_clone,
-                    latest_blockhash,
-                    &lookup_tables,
-                )
-                .await
-                {
-                    Ok(signatures) => {
-                        info!("Transactions sent successfully for mint {}", mint_str);
-                        for signature in signatures {
-                            info!("  Signature: {}", signature);
-                        }
-                    }
-                    Err(e) => {
-                        error!("Error sending transaction for mint {}: {}", mint_str, e);
-                    }
-                }
+/// Assigns each mint a wallet index, explicitly via `routing.mint_overrides` or
+/// round-robin over the mints in address order, and re-derives its wallet accounts
+pub(crate) fn assign_wallets(
+    config: &Config,
+    mint_pool_data_map: &mut HashMap<Pubkey, MintPoolData>,
+    wallets: &[Keypair],
+) -> anyhow::Result<HashMap<Pubkey, usize>> {
+    let mut mints: Vec<Pubkey> = mint_pool_data_map.keys().copied().collect();
+    mints.sort();
 
-                drop(guard);
-                tokio::time::sleep(process_delay).await;
+    let mut assignments = HashMap::new();
+    for (position, mint) in mints.iter().enumerate() {
+        let index = match config.mint_override(mint).and_then(|entry| entry.wallet) {
+            Some(index) if index >= wallets.len() => anyhow::bail!(
+                "Mint {} is assigned wallet #{} but only {} wallets are configured",
+                mint,
+                index,
+                wallets.len()
+            ),
+            Some(index) => index,
+            None => position % wallets.len(),
+        };
+        if let Some(pool_data) = mint_pool_data_map.get_mut(mint) {
+            pool_data.set_wallet(&wallets[index].pubkey());
+        }
+        assignments.insert(*mint, index);
+    }
+    Ok(assignments)
+}
+
+/// Refuses to run unless every loaded wallet matches `wallet.expected_pubkeys` in load
+/// order, or the only loaded wallet matches `wallet.expected_pubkey`
+fn check_expected_wallets(config: &Config, wallets: &[Pubkey]) -> anyhow::Result<()> {
+    let expected: Vec<&String> = match (
+        &config.wallet.expected_pubkey,
+        &config.wallet.expected_pubkeys,
+    ) {
+        (None, None) => return Ok(()),
+        (Some(_), Some(_)) => {
+            anyhow::bail!("Set either wallet.expected_pubkey or wallet.expected_pubkeys, not both")
+        }
+        (Some(expected), None) => {
+            if wallets.len() > 1 {
+                anyhow::bail!(
+                    "wallet.expected_pubkey names one wallet but {} are loaded; list every \
+                     address in wallet.expected_pubkeys instead",
+                    wallets.len()
+                );
             }
-        });
+            vec![expected]
+        }
+        (None, Some(expected)) => {
+            if expected.len() != wallets.len() {
+                anyhow::bail!(
+                    "wallet.expected_pubkeys lists {} wallets but {} are loaded",
+                    expected.len(),
+                    wallets.len()
+                );
+            }
+            expected.iter().collect()
+        }
+    };
+    for (index, (wallet, expected)) in wallets.iter().zip(expected).enumerate() {
+        let expected = Pubkey::from_str(expected)
+            .with_context(|| format!("Invalid expected wallet address: {}", expected))?;
+        if expected != *wallet {
+            anyhow::bail!(
+                "Loaded wallet #{} {} does not match its expected address {}; refusing to trade",
+                index,
+                wallet,
+                expected
+            );
+        }
     }
+    info!("Every loaded wallet matches its expected address");
+    Ok(())
+}
 
-    loop {
-        tokio::time::sleep(Duration::from_secs(1)).await;
+/// Logs the wallet's balances and warns when it holds less than the trading float: the WSOL
+/// balance below `bot.min_wsol_balance` when pre-funded, the SOL balance below
+/// `bot.wsol_wrap_amount` when wrapping per trade
+fn log_wallet_summary(rpc_client: &RpcClient, wallet: &Pubkey, cluster: &str, config: &Config) {
+    let sol_balance = rpc_client.get_balance(wallet);
+    let sol = match &sol_balance {
+        Ok(lamports) => format!("{:.4} SOL", *lamports as f64 / LAMPORTS_PER_SOL as f64),
+        Err(e) => format!("unavailable ({})", e),
+    };
+    let wsol_ata = get_associated_token_address(wallet, &sol_mint());
+    let wsol_balance = rpc_client.get_token_account_balance(&wsol_ata);
+    let wsol = wsol_balance
+        .as_ref()
+        .map(|balance| format!("{} WSOL", balance.ui_amount_string))
+        .unwrap_or_else(|_| "no WSOL account".to_string());
+
+    info!("==================================================================");
+    info!("Cluster:        {}", cluster);
+    info!("Trading wallet: {}", wallet);
+    info!("  SOL balance:  {}", sol);
+    info!("  WSOL balance: {}", wsol);
+    match config.bot.wsol_strategy {
+        WsolStrategy::PreFunded => {
+            let wsol_lamports = wsol_balance
+                .ok()
+                .and_then(|balance| balance.amount.parse::<u64>().ok())
+                .unwrap_or(0);
+            if let Some(min) = config.bot.min_wsol_balance.filter(|min| wsol_lamports < *min) {
+                warn!(
+                    "  WSOL balance is below bot.min_wsol_balance ({:.4} WSOL); routes \
+                     needing more will fail",
+                    min as f64 / LAMPORTS_PER_SOL as f64
+                );
+            }
+        }
+        WsolStrategy::PerTrade => {
+            let wrap_amount = config.bot.wsol_wrap_amount.unwrap_or(0);
+            if sol_balance.map_or(false, |lamports| lamports < wrap_amount) {
+                warn!(
+                    "  SOL balance is below bot.wsol_wrap_amount ({:.4} SOL); wrapping will fail",
+                    wrap_amount as f64 / LAMPORTS_PER_SOL as f64
+                );
+            }
+        }
     }
+    info!("==================================================================");
 }
 
-async fn blockhash_refresher(
-    rpc_client: Arc<RpcClient>,
-    cached_blockhash: Arc<Mutex<Hash>>,
-    refresh_interval: Duration,
-) {
-    loop {
-        match rpc_client.get_latest_blockhash() {
-            Ok(blockhash) => {
-                let mut guard = cached_blockhash.lock().await;
-                *guard = blockhash;
-                info!("Blockhash refreshed: {}", blockhash);
+fn confirm_start() -> anyhow::Result<()> {
+    print!("Start trading with this wallet? Type 'yes' to continue: ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    if answer.trim() != "yes" {
+        anyhow::bail!("Start not confirmed");
+    }
+    Ok(())
+}
+
+/// Rejects compute unit limits the runtime would refuse and logs per-mint overrides
+fn check_compute_unit_limits(config: &Config) -> anyhow::Result<()> {
+    if config.bot.compute_unit_limit > MAX_COMPUTE_UNIT_LIMIT {
+        anyhow::bail!(
+            "bot.compute_unit_limit {} exceeds the {} CU per-transaction cap",
+            config.bot.compute_unit_limit,
+            MAX_COMPUTE_UNIT_LIMIT
+        );
+    }
+    for entry in config.routing.mint_overrides.iter().flatten() {
+        Pubkey::from_str(&entry.mint)
+            .with_context(|| format!("Invalid mint in routing.mint_overrides: {}", entry.mint))?;
+        if let Some(limit) = entry.compute_unit_limit {
+            if limit > MAX_COMPUTE_UNIT_LIMIT {
+                anyhow::bail!(
+                    "compute_unit_limit {} for mint {} exceeds the {} CU per-transaction cap",
+                    limit,
+                    entry.mint,
+                    MAX_COMPUTE_UNIT_LIMIT
+                );
             }
-            Err(e) => {
-                error!("Failed to refresh blockhash: {:?}", e);
+            info!("Compute unit limit for mint {}: {}", entry.mint, limit);
+        }
+    }
+    Ok(())
+}
+
+/// Rejects heap frame sizes `request_heap_frame` would fail with
+fn check_heap_frame(config: &Config) -> anyhow::Result<()> {
+    let Some(bytes) = config.bot.heap_frame_bytes else {
+        return Ok(());
+    };
+    if !(MIN_HEAP_FRAME_BYTES..=MAX_HEAP_FRAME_BYTES).contains(&bytes) || bytes % 1024 != 0 {
+        anyhow::bail!(
+            "bot.heap_frame_bytes {} must be a multiple of 1024 between {} and {}",
+            bytes,
+            MIN_HEAP_FRAME_BYTES,
+            MAX_HEAP_FRAME_BYTES
+        );
+    }
+    info!(
+        "Requesting a {} byte heap frame for routes with at least {} accounts",
+        bytes,
+        config.bot.heap_frame_min_accounts.unwrap_or_default()
+    );
+    Ok(())
+}
+
+/// Reports per mint how many route accounts the loaded lookup tables miss
+fn check_alt_coverage(
+    config: &Config,
+    mint_pool_data_map: &HashMap<Pubkey, MintPoolData>,
+    lookup_tables: &[AddressLookupTableAccount],
+    strict: bool,
+) -> anyhow::Result<()> {
+    let min_coverage = config
+        .routing
+        .markets
+        .min_alt_coverage_pct
+        .unwrap_or(DEFAULT_MIN_ALT_COVERAGE_PCT);
+
+    let mut below_minimum = Vec::new();
+    for (mint, pool_data) in mint_pool_data_map {
+        let accounts = build_route_account_metas(config, pool_data)?;
+        let coverage = AltCoverage::compute(&accounts, lookup_tables);
+        info!(
+            "Lookup table coverage for mint {}: {}/{} accounts ({:.1}%), {} missing",
+            mint,
+            coverage.covered,
+            coverage.total,
+            coverage.percent(),
+            coverage.missing.len()
+        );
+        if coverage.percent() < min_coverage {
+            for missing in &coverage.missing {
+                warn!("   Not in any lookup table: {}", missing);
             }
+            below_minimum.push(*mint);
         }
-        tokio::time::sleep(refresh_interval).await;
     }
+
+    if below_minimum.is_empty() {
+        return Ok(());
+    }
+    warn!("==================================================================");
+    warn!(
+        "{} mint(s) have lookup table coverage below {:.1}%: transactions will be oversized.",
+        below_minimum.len(),
+        min_coverage
+    );
+    warn!("Check that lookup_table_accounts were built for this wallet and pool set.");
+    warn!("==================================================================");
+    if strict {
+        anyhow::bail!(
+            "Lookup table coverage below {:.1}% for mints {:?} (--strict-alt)",
+            min_coverage,
+            below_minimum
+        );
+    }
+    Ok(())
 }
 
-fn load_keypair(private_key: &str) -> anyhow::Result<Keypair> {
-    if let Ok(keypair) = bs58::decode(private_key)
-        .into_vec()
-        .map_err(|e| anyhow::anyhow!("Failed to decode base58: {}", e))
-        .and_then(|bytes| {
-            Keypair::from_bytes(&bytes).map_err(|e| anyhow::anyhow!("Invalid keypair bytes: {}", e))
-        })
-    {
-        return Ok(keypair);
+/// Seeds the fee spend, loss breaker and send journal of the wallet's landing tracker with
+/// executor transactions that landed before this start. Each transaction's fees go to the one
+/// of `mints` it traded.
+fn reconcile_wallet_history(
+    rpc_client: &RpcClient,
+    landing_tracker: &LandingTracker,
+    mints: &[Pubkey],
+    risk: &RiskConfig,
+) {
+    let limit = match risk.reconcile_signatures {
+        Some(limit) if limit > 0 => limit,
+        _ => return,
+    };
+    let lookback_secs = risk.reconcile_lookback_secs.unwrap_or(DEFAULT_RECONCILE_LOOKBACK_SECS);
+
+    info!(
+        "Reconciling up to {} recent wallet transactions from the last {}s",
+        limit, lookback_secs
+    );
+    let transactions = recent_executor_transactions(
+        rpc_client,
+        &landing_tracker.wallet(),
+        mints,
+        limit,
+        lookback_secs,
+        landing_tracker.base_prices(),
+    );
+    let transactions = match transactions {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            warn!("Failed to reconcile wallet history: {}", e);
+            return;
+        }
+    };
+    landing_tracker.record_reconciled(&transactions);
+
+    let landed = transactions.iter().filter(|tx| tx.pnl.succeeded).count();
+    let fees: u64 = transactions.iter().map(|tx| tx.pnl.fee_lamports).sum();
+    let pnl: i64 = transactions.iter().filter_map(|tx| tx.pnl.pnl_lamports).sum();
+    let unpriced = transactions.iter().filter(|tx| tx.pnl.pnl_lamports.is_none()).count();
+    info!(
+        "Reconciled {} executor transactions ({} succeeded): fees {} lamports, net {} lamports",
+        transactions.len(),
+        landed,
+        fees,
+        pnl
+    );
+    if unpriced > 0 {
+        warn!(
+            "{} reconciled transactions moved a base mint without a SOL price and are not \
+             counted by the loss breaker",
+            unpriced
+        );
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(wallet: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+            [bot]
+            compute_unit_limit = 400000
+
+            [routing.markets]
+            markets = []
+            process_delay = 1000
 
-    if let Ok(keypair) = solana_sdk::signature::read_keypair_file(private_key) {
-        return Ok(keypair);
+            [rpc]
+            url = "http://127.0.0.1:8899"
+
+            [wallet]
+            {}
+            "#,
+            wallet
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn every_wallet_is_checked_against_its_expected_address() {
+        let wallets = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let expected = |keys: &[Pubkey]| {
+            let keys: Vec<String> = keys.iter().map(|key| format!("\"{}\"", key)).collect();
+            config(&format!("expected_pubkeys = [{}]", keys.join(", ")))
+        };
+
+        assert!(check_expected_wallets(&config(""), &wallets).is_ok());
+        assert!(check_expected_wallets(&expected(&wallets), &wallets).is_ok());
+
+        let error = check_expected_wallets(&expected(&[wallets[0], wallets[0]]), &wallets)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.starts_with(&format!("Loaded wallet #1 {}", wallets[1])),
+            "{}",
+            error
+        );
+
+        let error = check_expected_wallets(&expected(&wallets[..1]), &wallets)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "wallet.expected_pubkeys lists 1 wallets but 2 are loaded"
+        );
     }
 
-    anyhow::bail!("Failed to load keypair from: {}", private_key)
+    #[test]
+    fn expected_pubkey_only_covers_a_single_wallet() {
+        let wallets = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let single = config(&format!("expected_pubkey = \"{}\"", wallets[0]));
+        assert!(check_expected_wallets(&single, &wallets[..1]).is_ok());
+        assert!(check_expected_wallets(&single, &wallets[1..]).is_err());
+        // Ambiguous with several wallets, even when the first one matches
+        assert!(check_expected_wallets(&single, &wallets).is_err());
+
+        let both = config(&format!(
+            "expected_pubkey = \"{}\"\nexpected_pubkeys = [\"{}\"]",
+            wallets[0], wallets[0]
+        ));
+        assert!(check_expected_wallets(&both, &wallets[..1]).is_err());
+    }
 }
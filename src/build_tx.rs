@@ -0,0 +1,249 @@
+use crate::account_batcher::AccountBatcher;
+use crate::base_mints::BaseMints;
+use crate::bot::{assign_wallets, load_lookup_tables, load_wallets};
+use crate::config::Config;
+use crate::constants::init_canonical_mints;
+use crate::dex_rpc::DexRpcClients;
+use crate::executor_errors::ExecutorErrors;
+use crate::loader::initialize_pools_from_markets;
+use crate::program_registry::ProgramRegistry;
+use crate::startup::StartupProgress;
+use crate::transaction::build_transaction;
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::Arc;
+
+/// `ComputeBudgetInstruction::SetComputeUnitLimit` tag
+const SET_COMPUTE_UNIT_LIMIT_TAG: u8 = 2;
+
+/// Where a transaction account is read from
+enum AccountSource {
+    Static,
+    LookupTable { table: Pubkey, index: u8 },
+}
+
+/// One account of a compiled transaction, in message order
+struct ResolvedAccount {
+    pubkey: Pubkey,
+    signer: bool,
+    writable: bool,
+    source: AccountSource,
+}
+
+/// Accounts of `tx` in message order: the static keys, then the writable and the readonly
+/// lookup table entries. Entries of tables missing from `lookup_tables` resolve to the
+/// default pubkey.
+fn resolve_accounts(
+    tx: &VersionedTransaction,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Vec<ResolvedAccount> {
+    let header = tx.message.header();
+    let static_keys = tx.message.static_account_keys();
+    let signers = header.num_required_signatures as usize;
+    let writable_signers = signers - header.num_readonly_signed_accounts as usize;
+    let writable_unsigned = static_keys.len() - header.num_readonly_unsigned_accounts as usize;
+    let mut accounts: Vec<ResolvedAccount> = static_keys
+        .iter()
+        .enumerate()
+        .map(|(i, pubkey)| ResolvedAccount {
+            pubkey: *pubkey,
+            signer: i < signers,
+            writable: if i < signers {
+                i < writable_signers
+            } else {
+                i < writable_unsigned
+            },
+            source: AccountSource::Static,
+        })
+        .collect();
+
+    let lookups = tx.message.address_table_lookups().unwrap_or_default();
+    for writable in [true, false] {
+        for lookup in lookups {
+            let table = lookup_tables
+                .iter()
+                .find(|table| table.key == lookup.account_key);
+            let indexes = if writable {
+                &lookup.writable_indexes
+            } else {
+                &lookup.readonly_indexes
+            };
+            for &index in indexes {
+                accounts.push(ResolvedAccount {
+                    pubkey: table
+                        .and_then(|table| table.addresses.get(index as usize))
+                        .copied()
+                        .unwrap_or_default(),
+                    signer: false,
+                    writable,
+                    source: AccountSource::LookupTable {
+                        table: lookup.account_key,
+                        index,
+                    },
+                });
+            }
+        }
+    }
+    accounts
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Compute unit limit requested by the transaction's compute budget instruction
+fn compute_unit_limit(tx: &VersionedTransaction, accounts: &[ResolvedAccount]) -> Option<u32> {
+    tx.message.instructions().iter().find_map(|ix| {
+        let program = accounts.get(ix.program_id_index as usize)?.pubkey;
+        if program != compute_budget::id() || ix.data.first() != Some(&SET_COMPUTE_UNIT_LIMIT_TAG) {
+            return None;
+        }
+        Some(u32::from_le_bytes(ix.data.get(1..5)?.try_into().ok()?))
+    })
+}
+
+/// Builds the transaction the bot would send for `mint` right now, with only that mint's
+/// pools loaded, and prints it: base64, accounts with their lookup table, instruction data
+/// and size. It is signed by the mint's wallet, without pool flags. With `simulate` it is
+/// also simulated and its logs printed.
+pub async fn build_tx(config_path: &str, mint: &str, simulate: bool) -> Result<()> {
+    let mut config = Config::load(config_path)?;
+    init_canonical_mints(config.mints.as_ref())?;
+    ProgramRegistry::init(config.program_overrides.as_ref())?;
+    ExecutorErrors::init(config.executor.as_ref())?;
+    let mint: Pubkey = mint
+        .parse()
+        .map_err(|e| anyhow!("Invalid mint {}: {}", mint, e))?;
+    config.routing.markets.allowlist = Some(vec![mint.to_string()]);
+
+    AccountBatcher::init(&config.rpc);
+    DexRpcClients::init(&config.rpc, None)?;
+    let rpc_client = Arc::new(RpcClient::new(config.rpc.url.clone()));
+    let base_mints = BaseMints::from_config(&config.routing)?;
+    let wallets = load_wallets(&config)?;
+    let mut pools = initialize_pools_from_markets(
+        &config.routing.markets,
+        &base_mints,
+        &wallets[0].pubkey(),
+        rpc_client.clone(),
+        &StartupProgress::new(None),
+    )
+    .await?;
+    if !pools.contains_key(&mint) {
+        return Err(anyhow!("No pools found for mint {}", mint));
+    }
+    let assignments = assign_wallets(&config, &mut pools, &wallets)?;
+    let wallet = &wallets[assignments[&mint]];
+    let pool_data = &pools[&mint];
+
+    let lookup_tables = load_lookup_tables(&config, &rpc_client);
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    let tx = build_transaction(wallet, &config, pool_data, blockhash, &lookup_tables, None)?;
+    let serialized = bincode::serialize(&tx)?;
+    let accounts = resolve_accounts(&tx, &lookup_tables);
+
+    println!(
+        "Transaction for mint {} ({} pools)",
+        mint,
+        pool_data.pool_count()
+    );
+    println!("Wallet:    {}", wallet.pubkey());
+    println!("Blockhash: {}", blockhash);
+    println!(
+        "Size:      {} of {} bytes{}",
+        serialized.len(),
+        PACKET_DATA_SIZE,
+        if serialized.len() > PACKET_DATA_SIZE {
+            " (too large to send)"
+        } else {
+            ""
+        }
+    );
+    println!(
+        "CU limit:  {}",
+        compute_unit_limit(&tx, &accounts).map_or_else(|| "-".to_string(), |cu| cu.to_string())
+    );
+    println!();
+    println!("Base64:");
+    println!(
+        "{}",
+        base64::engine::general_purpose::STANDARD.encode(&serialized)
+    );
+    println!();
+    let through_tables = accounts
+        .iter()
+        .filter(|account| matches!(account.source, AccountSource::LookupTable { .. }))
+        .count();
+    println!(
+        "Accounts ({}, {} through lookup tables):",
+        accounts.len(),
+        through_tables
+    );
+    for (i, account) in accounts.iter().enumerate() {
+        let flags = match (account.signer, account.writable) {
+            (true, true) => "signer writable",
+            (true, false) => "signer",
+            (false, true) => "writable",
+            (false, false) => "readonly",
+        };
+        let source = match &account.source {
+            AccountSource::Static => "static".to_string(),
+            AccountSource::LookupTable { table, index } => format!("ALT {}[{}]", table, index),
+        };
+        println!("  {:>3} {:<44} {:<15} {}", i, account.pubkey, flags, source);
+    }
+    println!();
+    println!("Instructions:");
+    for (i, ix) in tx.message.instructions().iter().enumerate() {
+        let program = accounts
+            .get(ix.program_id_index as usize)
+            .map_or_else(Pubkey::default, |account| account.pubkey);
+        println!(
+            "  #{} {} ({} accounts) data {}",
+            i,
+            program,
+            ix.accounts.len(),
+            hex(&ix.data)
+        );
+    }
+
+    if simulate {
+        let result = rpc_client
+            .simulate_transaction_with_config(
+                &tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::processed()),
+                    ..Default::default()
+                },
+            )?
+            .value;
+        println!();
+        match &result.err {
+            None => println!("Simulation succeeded"),
+            Some(err) => println!(
+                "Simulation failed: {} ({})",
+                err,
+                ExecutorErrors::global().reason(err)
+            ),
+        }
+        if let Some(units) = result.units_consumed {
+            println!("Units consumed: {}", units);
+        }
+        println!("Logs:");
+        for line in result.logs.iter().flatten() {
+            println!("  {}", line);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use crate::config::{Config, RpcConfig};
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use solana_client::rpc_client::RpcClient;
+use std::fmt;
+use tracing::{info, warn};
+
+/// Solana cluster named by `rpc.cluster`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cluster {
+    MainnetBeta,
+    Devnet,
+    Testnet,
+}
+
+impl Cluster {
+    const ALL: [Cluster; 3] = [Cluster::MainnetBeta, Cluster::Devnet, Cluster::Testnet];
+
+    pub fn genesis_hash(self) -> &'static str {
+        match self {
+            Cluster::MainnetBeta => "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d",
+            Cluster::Devnet => "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG",
+            Cluster::Testnet => "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY",
+        }
+    }
+
+    fn from_genesis_hash(hash: &str) -> Option<Cluster> {
+        Self::ALL.into_iter().find(|cluster| cluster.genesis_hash() == hash)
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Cluster::MainnetBeta => "mainnet-beta",
+            Cluster::Devnet => "devnet",
+            Cluster::Testnet => "testnet",
+        })
+    }
+}
+
+/// Genesis hash `rpc.url` must report, from `rpc.expected_genesis_hash` or `rpc.cluster`
+fn expected_genesis_hash(config: &RpcConfig) -> Result<Option<String>> {
+    match (&config.expected_genesis_hash, config.cluster) {
+        (Some(hash), Some(cluster)) if hash != cluster.genesis_hash() => bail!(
+            "rpc.expected_genesis_hash {} is not the genesis hash of rpc.cluster {}",
+            hash,
+            cluster
+        ),
+        (Some(hash), _) => Ok(Some(hash.clone())),
+        (None, cluster) => Ok(cluster.map(|cluster| cluster.genesis_hash().to_string())),
+    }
+}
+
+/// Refuses a non-mainnet `rpc.cluster` unless the mints and DEX programs of that cluster
+/// are configured, since the mainnet defaults would be used otherwise
+pub fn check_cluster_config(config: &Config) -> Result<()> {
+    expected_genesis_hash(&config.rpc)?;
+    let Some(cluster) = config.rpc.cluster.filter(|c| *c != Cluster::MainnetBeta) else {
+        return Ok(());
+    };
+    if config.mints.is_none() {
+        bail!(
+            "rpc.cluster is {}, but [mints] is not set; the mainnet mints would be used",
+            cluster
+        );
+    }
+    if config.program_overrides.as_ref().map_or(true, |overrides| overrides.is_empty()) {
+        bail!(
+            "rpc.cluster is {}, but [program_overrides] is not set; the mainnet DEX programs \
+             would be used",
+            cluster
+        );
+    }
+    Ok(())
+}
+
+/// Reads the genesis hash of `rpc_client` and fails when it is not the expected one.
+/// Returns the detected cluster for the startup summary.
+pub fn verify_cluster(config: &RpcConfig, rpc_client: &RpcClient) -> Result<String> {
+    let expected = expected_genesis_hash(config)?;
+    let genesis_hash = match rpc_client.get_genesis_hash() {
+        Ok(hash) => hash.to_string(),
+        Err(e) if expected.is_some() => {
+            bail!("Failed to read the genesis hash to verify the cluster: {}", e);
+        }
+        Err(e) => {
+            warn!("Failed to read the genesis hash, cluster unknown: {}", e);
+            return Ok("unknown".to_string());
+        }
+    };
+    let detected = match Cluster::from_genesis_hash(&genesis_hash) {
+        Some(cluster) => cluster.to_string(),
+        None => format!("unknown cluster with genesis hash {}", genesis_hash),
+    };
+    if let Some(expected) = expected {
+        if genesis_hash != expected {
+            bail!(
+                "RPC is on {}, but the config expects genesis hash {}{}",
+                detected,
+                expected,
+                config
+                    .cluster
+                    .map_or(String::new(), |cluster| format!(" ({})", cluster))
+            );
+        }
+        info!("Genesis hash {} verified", genesis_hash);
+    }
+    Ok(detected)
+}
@@ -1,51 +1,384 @@
-use serde::{Deserialize, Deserializer};
-use std::{env, fs::File, io::Read};
+use crate::cluster::Cluster;
+use crate::config_include::apply_includes;
+use crate::config_migration::migrate;
+use crate::keypair::is_key_file;
+use crate::paths::{config_dir, resolve_path};
+use crate::loader::MarketPoolKind;
+use anyhow::Context;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::ops::RangeInclusive;
+use std::{
+    collections::{HashMap, HashSet},
+    env, fmt,
+    fs::File,
+    io::Read,
+    path::Path,
+    time::Duration,
+};
+use tracing::warn;
 
+/// Bot configuration, read from a TOML file by `Config::load`
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
+    /// Layout version of the file; older layouts are migrated when loaded
+    pub config_version: Option<i64>,
+    /// Files or glob patterns contributing markets, mint overrides and lookup tables,
+    /// relative to the config file
+    pub include: Option<Vec<String>>,
     pub bot: BotConfig,
     pub routing: RoutingConfig,
     pub rpc: RpcConfig,
     pub spam: Option<SpamConfig>,
     pub wallet: WalletConfig,
     pub flashloan: Option<FlashloanConfig>,
+    pub risk: Option<RiskConfig>,
+    pub observations: Option<ObservationsConfig>,
+    pub status: Option<StatusConfig>,
+    pub executor: Option<ExecutorConfig>,
+    pub prefilter: Option<PrefilterConfig>,
+    pub paper_trade: Option<PaperTradeConfig>,
+    pub metrics: Option<MetricsConfig>,
+    pub mints: Option<MintsConfig>,
+    /// Program IDs of forked or devnet DEX deployments by DEX name
+    pub program_overrides: Option<HashMap<String, String>>,
+    pub geyser: Option<GeyserConfig>,
+    pub journal: Option<JournalConfig>,
+    pub jito: Option<JitoConfig>,
+    pub maintenance: Option<MaintenanceConfig>,
+    pub startup: Option<StartupConfig>,
 }
 
+/// `[bot]`: transaction shape and wallet handling
 #[derive(Debug, Deserialize, Clone)]
 pub struct BotConfig {
     pub compute_unit_limit: u32,
+    #[serde(default)]
+    pub wsol_strategy: WsolStrategy,
+    pub wsol_wrap_amount: Option<u64>,
+    /// WSOL lamports a pre-funded wallet should hold; less is warned about at startup
+    pub min_wsol_balance: Option<u64>,
+    /// Shard mints across this many tasks instead of spawning one task per mint
+    pub worker_count: Option<usize>,
+    /// SPL Memo attached to every arbitrage transaction, e.g. a run id or strategy name
+    pub memo: Option<String>,
+    /// Refresh every mint's pools once before its send loop starts (default true)
+    pub warm_on_start: Option<bool>,
+    /// Create missing Pump coin creator vault ATAs at startup (default false)
+    #[serde(default)]
+    pub create_creator_vault_atas: bool,
+    /// Heap frame requested for routes with at least `heap_frame_min_accounts` accounts
+    pub heap_frame_bytes: Option<u32>,
+    /// Swap instruction accounts from which `heap_frame_bytes` is requested (default 0)
+    pub heap_frame_min_accounts: Option<usize>,
+    /// Run one iteration per mint and exit (same as `--one-shot`)
+    #[serde(default)]
+    pub one_shot: bool,
+    /// Unconfirmed sends a wallet may have at once across its mints (default unlimited)
+    pub max_in_flight_per_wallet: Option<usize>,
 }
 
+/// How the wallet's WSOL balance is funded for each trade
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WsolStrategy {
+    /// The WSOL ATA already holds enough; no wrap/unwrap in the transaction
+    #[default]
+    PreFunded,
+    /// Wrap `wsol_wrap_amount` at the start of the transaction and unwrap the remainder at the end
+    PerTrade,
+}
+
+/// `[routing]`: the markets to trade and how often
 #[derive(Debug, Deserialize, Clone)]
 pub struct RoutingConfig {
     pub markets: MarketsConfig,
+    #[serde(default, deserialize_with = "serde_opt_millis")]
+    pub post_land_cooldown_ms: Option<u64>,
+    /// Skip the send when refreshing, pricing and simulating a mint took longer than this
+    #[serde(default, deserialize_with = "serde_opt_millis")]
+    pub evaluation_timeout_ms: Option<u64>,
+    /// Slots a pool's bin/tick arrays may lag the current slot when a transaction is built
+    pub max_pool_slot_lag: Option<u64>,
+    /// Leave pools over `max_pool_slot_lag` out of the transaction instead of only warning
+    #[serde(default)]
+    pub skip_stale_pools: bool,
+    pub mint_overrides: Option<Vec<MintOverrideConfig>>,
+    /// Accepted quote mints (default SOL, USDC, USD1)
+    pub base_mints: Option<Vec<String>>,
+    /// Quote mint a mint's pools are routed in when they span several base mints
+    /// (default SOL). Must be one of `base_mints`.
+    pub primary_base_mint: Option<String>,
+    pub migration: Option<MigrationConfig>,
 }
 
+/// Replaces pools whose SOL side drained with the pool the token migrated to
+#[derive(Debug, Deserialize, Clone)]
+pub struct MigrationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// SOL reserve below which a pool counts as drained (default 1.0)
+    pub min_sol_reserve: Option<f64>,
+    /// Pool kinds searched for the new pool (default Pump, RaydiumCp, RaydiumV4)
+    pub search_kinds: Option<Vec<MarketPoolKind>>,
+    /// Wait before searching again for the same drained pool (default 60)
+    pub retry_after_secs: Option<u64>,
+}
+
+/// Settings that replace the global ones for a single mint
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct MintOverrideConfig {
+    pub mint: String,
+    pub compute_unit_limit: Option<u32>,
+    /// Index of the wallet trading this mint (`private_key` first, then `private_keys`)
+    pub wallet: Option<usize>,
+}
+
+/// `[routing.markets]`: pool addresses, lookup tables and loop timing
 #[derive(Debug, Deserialize, Clone)]
 pub struct MarketsConfig {
-    pub markets: Vec<String>,
+    pub markets: Vec<MarketEntry>,
     pub lookup_table_accounts: Option<Vec<String>>,
+    pub min_alt_coverage_pct: Option<f64>,
+    pub min_pool_liquidity_sol: Option<f64>,
+    /// USD price of SOL, converting USDC and USD1 reserves for `min_pool_liquidity_sol` and
+    /// balance changes for the loss breaker
+    pub sol_price_usd: Option<f64>,
+    /// Warn at startup about pools whose base per token reserve ratio is this many times
+    /// off the median of the mint's pools, or that have an empty side
+    pub reserve_skew_warn_factor: Option<f64>,
+    /// Log every market skipped at startup instead of only the per-reason summary
+    #[serde(default)]
+    pub log_skipped_markets: bool,
+    /// Sidecar file of detected pool kinds reused across restarts
+    pub pool_kind_cache_path: Option<String>,
+    /// Only trade these token mints (default: every mint found in `markets`)
+    pub allowlist: Option<Vec<String>>,
+    #[serde(deserialize_with = "serde_millis")]
     pub process_delay: u64,
+    /// Largest random change of each mint's delay and first start, in percent of the delay
+    /// (default 20)
+    pub delay_jitter_pct: Option<f64>,
+    pub adaptive_delay: Option<AdaptiveDelayConfig>,
+}
+
+/// A `markets` entry: either a bare pool address or a table with per-market metadata
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum MarketEntry {
+    Address(String),
+    Detailed(MarketDetails),
+}
+
+/// Table form of a `markets` entry
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MarketDetails {
+    pub address: String,
+    /// Name shown in logs next to the address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Expected pool kind; the market is skipped when detection disagrees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dex: Option<MarketPoolKind>,
+    /// Higher priority pools come first in their mint's route (default 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    /// Expected base mint; the market is skipped when the pool is quoted in another
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Mint traded as the pool's base side instead of the detected one, for pools where
+    /// neither side is SOL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_override: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+impl MarketEntry {
+    pub fn address(&self) -> &str {
+        match self {
+            MarketEntry::Address(address) => address,
+            MarketEntry::Detailed(details) => &details.address,
+        }
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        match self {
+            MarketEntry::Address(_) => None,
+            MarketEntry::Detailed(details) => details.label.as_deref(),
+        }
+    }
+
+    pub fn dex(&self) -> Option<MarketPoolKind> {
+        match self {
+            MarketEntry::Address(_) => None,
+            MarketEntry::Detailed(details) => details.dex,
+        }
+    }
+
+    pub fn priority(&self) -> i32 {
+        match self {
+            MarketEntry::Address(_) => 0,
+            MarketEntry::Detailed(details) => details.priority.unwrap_or_default(),
+        }
+    }
+
+    pub fn base(&self) -> Option<&str> {
+        match self {
+            MarketEntry::Address(_) => None,
+            MarketEntry::Detailed(details) => details.base.as_deref(),
+        }
+    }
+
+    pub fn base_override(&self) -> Option<&str> {
+        match self {
+            MarketEntry::Address(_) => None,
+            MarketEntry::Detailed(details) => details.base_override.as_deref(),
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        matches!(self, MarketEntry::Detailed(details) if details.disabled)
+    }
+
+    /// Address followed by the label, if any, for logs
+    pub fn describe(&self) -> String {
+        match self.label() {
+            Some(label) => format!("{} ({})", self.address(), label),
+            None => self.address().to_string(),
+        }
+    }
+}
+
+/// `[routing.markets.adaptive_delay]`: backs off the delay after failure streaks
+#[derive(Debug, Deserialize, Clone)]
+pub struct AdaptiveDelayConfig {
+    pub enabled: bool,
+    #[serde(deserialize_with = "serde_millis")]
+    pub min_delay: u64,
+    #[serde(deserialize_with = "serde_millis")]
+    pub max_delay: u64,
+    pub failure_streak: Option<u32>,
+    /// Widest spread of the latest pricing, in bps, above which the delay shrinks.
+    /// Unset, spreads leave the delay alone.
+    pub spread_threshold_bps: Option<f64>,
 }
 
+/// `[rpc]`: the primary RPC and how it is read
 #[derive(Debug, Deserialize, Clone)]
 pub struct RpcConfig {
     #[serde(deserialize_with = "serde_string_or_env")]
     pub url: String,
+    /// Accounts per getMultipleAccounts request (default and maximum 100)
+    pub gma_chunk_size: Option<usize>,
+    /// Pause between consecutive getMultipleAccounts requests
+    #[serde(default, deserialize_with = "serde_opt_millis")]
+    pub gma_chunk_delay_ms: Option<u64>,
+    /// Most RPC calls a single mint iteration may make before pool refreshes are deferred
+    pub max_calls_per_iteration: Option<u64>,
+    /// Most RPC calls per minute across all workers before pool refreshes are deferred
+    pub max_calls_per_minute: Option<u64>,
+    /// Tried in order when the blockhash cannot be fetched from `url`
+    #[serde(default, deserialize_with = "serde_strings_or_env")]
+    pub blockhash_fallback_urls: Vec<String>,
+    /// Sending pauses once the blockhash refresher has failed for this long (default 30)
+    #[serde(default, deserialize_with = "serde_opt_secs")]
+    pub blockhash_grace_secs: Option<u64>,
+    /// Read endpoints of single DEXes, used for their pools instead of `url`
+    pub dex_urls: Option<HashMap<MarketPoolKind, String>>,
+    /// Cluster `url` must be on, verified by genesis hash at startup
+    pub cluster: Option<Cluster>,
+    /// Genesis hash `url` must report; overrides the hash of `cluster`
+    pub expected_genesis_hash: Option<String>,
 }
 
+/// `[spam]`: sending through several RPC endpoints
 #[derive(Debug, Deserialize, Clone)]
 pub struct SpamConfig {
     pub enabled: bool,
-    pub sending_rpc_urls: Vec<String>,
+    pub sending_rpc_urls: Vec<SendingEndpoint>,
     pub compute_unit_price: u64,
     pub max_retries: Option<u64>,
+    /// Slots a sending endpoint may trail `rpc.url` before it is benched (default 10)
+    pub max_slot_lag: Option<u64>,
 }
 
+/// A `sending_rpc_urls` entry: either a bare URL or a table with per-endpoint options
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum SendingEndpoint {
+    Url(String),
+    Detailed(SendingEndpointDetails),
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SendingEndpointDetails {
+    pub url: String,
+    /// Extra header sent with every request, as `"name: value"`; a value starting with `$`
+    /// is read from that environment variable
+    pub api_key_header: Option<String>,
+    /// Default true
+    pub skip_preflight: Option<bool>,
+    /// Copies of each transaction sent through the endpoint (default 1, 0 disables it)
+    pub weight: Option<u32>,
+    #[serde(default, deserialize_with = "serde_opt_millis")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl SendingEndpoint {
+    pub fn url(&self) -> &str {
+        match self {
+            SendingEndpoint::Url(url) => url,
+            SendingEndpoint::Detailed(details) => &details.url,
+        }
+    }
+
+    pub fn api_key_header(&self) -> Option<&str> {
+        match self {
+            SendingEndpoint::Url(_) => None,
+            SendingEndpoint::Detailed(details) => details.api_key_header.as_deref(),
+        }
+    }
+
+    pub fn skip_preflight(&self) -> bool {
+        match self {
+            SendingEndpoint::Url(_) => true,
+            SendingEndpoint::Detailed(details) => details.skip_preflight.unwrap_or(true),
+        }
+    }
+
+    pub fn weight(&self) -> u32 {
+        match self {
+            SendingEndpoint::Url(_) => 1,
+            SendingEndpoint::Detailed(details) => details.weight.unwrap_or(1),
+        }
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        match self {
+            SendingEndpoint::Url(_) => None,
+            SendingEndpoint::Detailed(details) => details.timeout_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+/// `[wallet]`: trading wallet keys
 #[derive(Debug, Deserialize, Clone)]
 pub struct WalletConfig {
-    #[serde(deserialize_with = "serde_string_or_env")]
+    #[serde(default, deserialize_with = "serde_string_or_env")]
     pub private_key: String,
+    /// Additional trading wallets; mints are spread across all wallets
+    #[serde(default, deserialize_with = "serde_strings_or_env")]
+    pub private_keys: Vec<String>,
+    /// Refuse to start when the only loaded key belongs to a different wallet
+    pub expected_pubkey: Option<String>,
+    /// Address of every loaded wallet in load order, `private_key` first
+    pub expected_pubkeys: Option<Vec<String>>,
+    /// Ask for confirmation on stdin before trading (same as `--confirm-start`)
+    pub confirm_start: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -53,6 +386,140 @@ pub struct FlashloanConfig {
     pub enabled: bool,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct ExecutorConfig {
+    /// Instruction data layout understood by the deployed executor (default 1)
+    pub ix_version: Option<u8>,
+    /// Names for the executor's custom error codes, e.g. `{ 6000 = "no_profit" }`
+    pub error_codes: Option<HashMap<String, String>>,
+    /// Pass DLMM and CLMM bitmap extensions read-only (default false, writable)
+    pub readonly_bitmap_extensions: Option<bool>,
+    /// Accounts passed writable even where the route marks them read-only
+    pub force_writable: Option<Vec<String>>,
+    /// `force_writable` parsed by `Config::validate`
+    #[serde(skip)]
+    pub force_writable_keys: HashSet<Pubkey>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PrefilterConfig {
+    pub simulate_before_send: bool,
+    /// Only simulate these mints (default: every mint)
+    pub mints: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PaperTradeConfig {
+    /// Same as --paper-trade
+    #[serde(default)]
+    pub enabled: bool,
+    pub starting_balance_lamports: Option<u64>,
+    /// Fee assumed for pools whose own fee is not known (default 25)
+    pub fee_bps: Option<u64>,
+    pub log_interval_secs: Option<u64>,
+}
+
+/// Canonical mints of the target cluster, defaulting to the mainnet addresses
+#[derive(Debug, Deserialize, Clone)]
+pub struct MintsConfig {
+    pub sol: Option<String>,
+    pub usdc: Option<String>,
+    pub usd1: Option<String>,
+}
+
+/// Yellowstone gRPC Geyser stream of pool account updates. Requires the `geyser` feature.
+#[derive(Debug, Deserialize, Clone)]
+pub struct GeyserConfig {
+    pub endpoint: String,
+    /// Access token sent as `x-token`, when the provider requires one
+    pub x_token: Option<String>,
+    /// processed, confirmed or finalized (default processed)
+    pub commitment: Option<String>,
+}
+
+/// JSON lines journal of every send and landing
+#[derive(Debug, Deserialize, Clone)]
+pub struct JournalConfig {
+    pub path: String,
+}
+
+/// Progress logging and time limit of pool initialization
+#[derive(Debug, Deserialize, Clone)]
+pub struct StartupConfig {
+    /// Checked between mints; what happens then is set by `on_timeout`
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub on_timeout: StartupTimeoutAction,
+    /// Default 5
+    pub progress_interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupTimeoutAction {
+    /// Fail startup
+    #[default]
+    Abort,
+    /// Start with the mints initialized so far
+    StartPartial,
+}
+
+/// Where non-arbitrage transactions such as ATA creation are sent
+#[derive(Debug, Deserialize, Clone)]
+pub struct MaintenanceConfig {
+    /// Default: `rpc.url`
+    pub send_rpc_url: Option<String>,
+}
+
+/// Tip transfer to a Jito tip account appended to every transaction
+#[derive(Debug, Deserialize, Clone)]
+pub struct JitoConfig {
+    pub tip_lamports: u64,
+    /// Accounts tipped in turn (default: Jito's 8 mainnet tip accounts)
+    pub tip_accounts: Option<Vec<String>>,
+    #[serde(default)]
+    pub rotation: TipRotation,
+}
+
+/// How the tip account of each transaction is picked
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TipRotation {
+    #[default]
+    RoundRobin,
+    Random,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MetricsConfig {
+    /// Count and time every RPC request per method
+    #[serde(default)]
+    pub enabled: bool,
+    pub log_interval_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RiskConfig {
+    pub max_daily_loss_lamports: Option<u64>,
+    pub resume_after_secs: Option<u64>,
+    pub reconcile_signatures: Option<usize>,
+    pub reconcile_lookback_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ObservationsConfig {
+    pub path: String,
+    pub sample_rate: Option<f64>,
+    pub max_file_bytes: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StatusConfig {
+    pub listen_addr: String,
+    #[serde(default, deserialize_with = "serde_opt_millis")]
+    pub stale_after_ms: Option<u64>,
+}
+
 pub fn serde_string_or_env<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
@@ -66,13 +533,446 @@ where
     Ok(value)
 }
 
+pub fn serde_strings_or_env<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|value_or_env| match value_or_env.strip_prefix('$') {
+            Some(var) => {
+                env::var(var).map_err(|_| serde::de::Error::custom(format!("reading `{}` from env", var)))
+            }
+            None => Ok(value_or_env),
+        })
+        .collect()
+}
+
+/// Bounds of the timing settings checked by `Config::validate`
+const DELAY_MS: RangeInclusive<u64> = 10..=60_000;
+const COOLDOWN_MS: RangeInclusive<u64> = 0..=3_600_000;
+const EVALUATION_TIMEOUT_MS: RangeInclusive<u64> = 10..=60_000;
+const GMA_CHUNK_DELAY_MS: RangeInclusive<u64> = 0..=10_000;
+const SEND_TIMEOUT_MS: RangeInclusive<u64> = 100..=120_000;
+const STALE_AFTER_MS: RangeInclusive<u64> = 100..=3_600_000;
+/// The blockhash is refreshed every 10 seconds, so a shorter grace pauses sending after a
+/// single failed refresh
+const BLOCKHASH_GRACE_SECS: RangeInclusive<u64> = 10..=600;
+
+fn check_millis(
+    name: &str,
+    value: Option<u64>,
+    bounds: RangeInclusive<u64>,
+) -> anyhow::Result<()> {
+    match value {
+        Some(millis) if !bounds.contains(&millis) => anyhow::bail!(
+            "{} is {}ms, expected {}ms to {}ms (plain numbers are milliseconds, durations \
+             such as \"2s\" are also accepted)",
+            name,
+            millis,
+            bounds.start(),
+            bounds.end()
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Milliseconds from an integer or a duration string such as `"250ms"`, `"2s"` or `"1m"`
+pub fn serde_millis<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(DurationVisitor { unit_millis: 1 })
+}
+
+pub fn serde_opt_millis<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    serde_millis(deserializer).map(Some)
+}
+
+/// Whole seconds from an integer or a duration string such as `"30s"` or `"2m"`
+pub fn serde_opt_secs<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer
+        .deserialize_any(DurationVisitor { unit_millis: 1000 })
+        .map(Some)
+}
+
+/// Integers are taken in the field's unit, strings carry their own
+struct DurationVisitor {
+    unit_millis: u64,
+}
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = u64;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = if self.unit_millis == 1 { "milliseconds" } else { "seconds" };
+        write!(f, "a number of {} or a duration such as \"250ms\" or \"2s\"", unit)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<u64, E> {
+        Ok(value)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<u64, E> {
+        u64::try_from(value).map_err(|_| E::custom("durations cannot be negative"))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+        let millis = parse_duration_millis(value).map_err(E::custom)?;
+        if millis % self.unit_millis != 0 {
+            return Err(E::custom(format!("{} is not a whole number of seconds", value)));
+        }
+        Ok(millis / self.unit_millis)
+    }
+}
+
+/// Parses `"<number><unit>"` with unit ms, s, m or h into milliseconds
+fn parse_duration_millis(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let unit_millis = match unit.trim() {
+        "ms" => 1.0,
+        "s" => 1_000.0,
+        "m" => 60_000.0,
+        "h" => 3_600_000.0,
+        "" => return Err(format!("duration {:?} has no unit (ms, s, m or h)", value)),
+        other => return Err(format!("unknown duration unit {:?} in {:?}", other, value)),
+    };
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration {:?}", value))?;
+    let millis = number * unit_millis;
+    if millis.fract() != 0.0 {
+        return Err(format!("duration {:?} is not a whole number of milliseconds", value));
+    }
+    Ok(millis as u64)
+}
+
+impl WalletConfig {
+    /// Every configured key source, `private_key` first
+    pub fn key_sources(&self) -> Vec<&str> {
+        std::iter::once(self.private_key.as_str())
+            .filter(|key| !key.is_empty())
+            .chain(self.private_keys.iter().map(String::as_str))
+            .collect()
+    }
+}
+
 impl Config {
+    /// Parses the config file. Relative file paths in it are resolved against the config
+    /// file's directory and `~` is expanded.
     pub fn load(path: &str) -> anyhow::Result<Self> {
-        let mut file = File::open(path)?;
+        let base = config_dir(path)?;
+        let absolute = resolve_path(path, &env::current_dir()?);
+        let mut file = File::open(&absolute)
+            .with_context(|| format!("Failed to open config file {}", absolute.display()))?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
 
-        let config: Config = toml::from_str(&contents)?;
+        let mut value: toml::Value = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse config file {}", absolute.display()))?;
+        for change in migrate(&mut value)? {
+            warn!("Config {}: {}", absolute.display(), change);
+        }
+        let mut config: Config = serde_path_to_error::deserialize(value).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid config file {}: `{}`: {}",
+                absolute.display(),
+                e.path(),
+                e.inner()
+            )
+        })?;
+        config.resolve_paths(&base);
+        apply_includes(&mut config, &absolute, &base)?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config file {}", absolute.display()))?;
         Ok(config)
     }
+
+    /// Rejects timing settings outside sane bounds, such as a `process_delay` of 0 that
+    /// busy-loops the workers or one given in seconds that makes the bot look stalled.
+    /// Also parses `executor.force_writable` into `force_writable_keys`.
+    pub fn validate(&mut self) -> anyhow::Result<()> {
+        let markets = &self.routing.markets;
+        check_millis("routing.markets.process_delay", Some(markets.process_delay), DELAY_MS)?;
+        if let Some(adaptive) = &markets.adaptive_delay {
+            let (min, max) = (adaptive.min_delay, adaptive.max_delay);
+            check_millis("routing.markets.adaptive_delay.min_delay", Some(min), DELAY_MS)?;
+            check_millis("routing.markets.adaptive_delay.max_delay", Some(max), DELAY_MS)?;
+            if min > max {
+                anyhow::bail!(
+                    "routing.markets.adaptive_delay.min_delay {}ms exceeds max_delay {}ms",
+                    min,
+                    max
+                );
+            }
+            if let Some(threshold) = adaptive.spread_threshold_bps {
+                if !(threshold > 0.0 && threshold.is_finite()) {
+                    anyhow::bail!(
+                        "routing.markets.adaptive_delay.spread_threshold_bps is {}, expected a \
+                         positive number of bps",
+                        threshold
+                    );
+                }
+            }
+        }
+        if let Some(price) = markets.sol_price_usd {
+            if !(price > 0.0 && price.is_finite()) {
+                anyhow::bail!(
+                    "routing.markets.sol_price_usd is {}, expected a positive price",
+                    price
+                );
+            }
+        }
+        check_millis(
+            "routing.post_land_cooldown_ms",
+            self.routing.post_land_cooldown_ms,
+            COOLDOWN_MS,
+        )?;
+        check_millis(
+            "routing.evaluation_timeout_ms",
+            self.routing.evaluation_timeout_ms,
+            EVALUATION_TIMEOUT_MS,
+        )?;
+        check_millis("rpc.gma_chunk_delay_ms", self.rpc.gma_chunk_delay_ms, GMA_CHUNK_DELAY_MS)?;
+        if let Some(secs) = self.rpc.blockhash_grace_secs {
+            if !BLOCKHASH_GRACE_SECS.contains(&secs) {
+                anyhow::bail!(
+                    "rpc.blockhash_grace_secs is {}s, expected {}s to {}s",
+                    secs,
+                    BLOCKHASH_GRACE_SECS.start(),
+                    BLOCKHASH_GRACE_SECS.end()
+                );
+            }
+        }
+        for (i, endpoint) in self.spam.iter().flat_map(|spam| &spam.sending_rpc_urls).enumerate() {
+            if let SendingEndpoint::Detailed(details) = endpoint {
+                let name = format!("spam.sending_rpc_urls[{}].timeout_ms", i);
+                check_millis(&name, details.timeout_ms, SEND_TIMEOUT_MS)?;
+            }
+        }
+        if let Some(status) = &self.status {
+            check_millis("status.stale_after_ms", status.stale_after_ms, STALE_AFTER_MS)?;
+        }
+        if self.bot.max_in_flight_per_wallet == Some(0) {
+            anyhow::bail!("bot.max_in_flight_per_wallet must be at least 1");
+        }
+        if let Some(executor) = self.executor.as_mut() {
+            executor.force_writable_keys = executor
+                .force_writable
+                .iter()
+                .flatten()
+                .map(|address| {
+                    address.parse().map_err(|e| {
+                        anyhow::anyhow!("Invalid executor.force_writable {}: {}", address, e)
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?;
+        }
+        Ok(())
+    }
+
+    fn resolve_paths(&mut self, base: &Path) {
+        let resolve = |path: &mut String| {
+            *path = resolve_path(path, base).to_string_lossy().into_owned();
+        };
+        if is_key_file(&self.wallet.private_key) {
+            resolve(&mut self.wallet.private_key);
+        }
+        self.wallet
+            .private_keys
+            .iter_mut()
+            .filter(|key| is_key_file(key))
+            .for_each(resolve);
+        if let Some(path) = self.routing.markets.pool_kind_cache_path.as_mut() {
+            resolve(path);
+        }
+        if let Some(journal) = self.journal.as_mut() {
+            resolve(&mut journal.path);
+        }
+        if let Some(observations) = self.observations.as_mut() {
+            resolve(&mut observations.path);
+        }
+    }
+
+    pub fn mint_override(&self, mint: &Pubkey) -> Option<&MintOverrideConfig> {
+        let mint = mint.to_string();
+        self.routing
+            .mint_overrides
+            .as_ref()?
+            .iter()
+            .find(|entry| entry.mint == mint)
+    }
+
+    /// Compute unit limit for the mint's route, falling back to `bot.compute_unit_limit`
+    pub fn compute_unit_limit_for(&self, mint: &Pubkey) -> u32 {
+        self.mint_override(mint)
+            .and_then(|entry| entry.compute_unit_limit)
+            .unwrap_or(self.bot.compute_unit_limit)
+    }
+
+    /// Heap frame to request for a swap instruction with `route_accounts` accounts
+    pub fn heap_frame_bytes_for(&self, route_accounts: usize) -> Option<u32> {
+        let min_accounts = self.bot.heap_frame_min_accounts.unwrap_or_default();
+        self.bot.heap_frame_bytes.filter(|_| route_accounts >= min_accounts)
+    }
+
+    /// Whether DLMM and CLMM bitmap extensions are passed read-only
+    pub fn readonly_bitmap_extensions(&self) -> bool {
+        self.executor
+            .as_ref()
+            .and_then(|executor| executor.readonly_bitmap_extensions)
+            .unwrap_or(false)
+    }
+
+    /// Whether the executor accepts per-pool flags in its instruction data
+    pub fn encodes_pool_flags(&self) -> bool {
+        self.executor
+            .as_ref()
+            .and_then(|executor| executor.ix_version)
+            .map_or(false, |version| version >= 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Markets {
+        markets: Vec<MarketEntry>,
+    }
+
+    fn parse_markets(toml: &str) -> Result<Vec<MarketEntry>, toml::de::Error> {
+        toml::from_str::<Markets>(toml).map(|parsed| parsed.markets)
+    }
+
+    const POOL: &str = "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2";
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+
+    #[test]
+    fn bare_string_market() {
+        let markets = parse_markets(&format!("markets = [\"{}\"]", POOL)).unwrap();
+        assert_eq!(markets, vec![MarketEntry::Address(POOL.to_string())]);
+        let market = &markets[0];
+        assert_eq!(market.address(), POOL);
+        assert_eq!(market.label(), None);
+        assert_eq!(market.dex(), None);
+        assert_eq!(market.priority(), 0);
+        assert_eq!(market.base(), None);
+        assert_eq!(market.base_override(), None);
+        assert!(!market.is_disabled());
+        assert_eq!(market.describe(), POOL);
+    }
+
+    #[test]
+    fn table_market_with_every_field() {
+        // Inline tables must stay on one line
+        let markets = parse_markets(&format!(
+            "markets = [{{ address = \"{}\", label = \"SOL/USDC\", dex = \"RaydiumV4\", \
+             priority = 10, base = \"{}\", base_override = \"{}\", disabled = true }}]",
+            POOL, SOL, SOL
+        ))
+        .unwrap();
+        let expected = MarketDetails {
+            address: POOL.to_string(),
+            label: Some("SOL/USDC".to_string()),
+            dex: Some(MarketPoolKind::RaydiumV4),
+            priority: Some(10),
+            base: Some(SOL.to_string()),
+            base_override: Some(SOL.to_string()),
+            disabled: true,
+        };
+        assert_eq!(markets, vec![MarketEntry::Detailed(expected)]);
+        let market = &markets[0];
+        assert_eq!(market.label(), Some("SOL/USDC"));
+        assert_eq!(market.dex(), Some(MarketPoolKind::RaydiumV4));
+        assert_eq!(market.priority(), 10);
+        assert_eq!(market.base(), Some(SOL));
+        assert_eq!(market.base_override(), Some(SOL));
+        assert!(market.is_disabled());
+        assert_eq!(market.describe(), format!("{} (SOL/USDC)", POOL));
+    }
+
+    #[test]
+    fn table_market_defaults() {
+        let markets = parse_markets(&format!("markets = [{{ address = \"{}\" }}]", POOL)).unwrap();
+        let MarketEntry::Detailed(details) = &markets[0] else {
+            panic!("expected the table form, got {:?}", markets[0]);
+        };
+        assert_eq!(details.address, POOL);
+        assert!(!details.disabled);
+        assert!(!markets[0].is_disabled());
+        assert_eq!(markets[0].priority(), 0);
+    }
+
+    #[test]
+    fn mixed_market_list_keeps_order() {
+        let markets = parse_markets(&format!(
+            r#"markets = [
+                "{pool}",
+                {{ address = "{pool}", label = "off", disabled = true }},
+                {{ address = "{pool}", priority = -1, disabled = false }},
+                "{sol}",
+            ]"#,
+            pool = POOL,
+            sol = SOL
+        ))
+        .unwrap();
+        assert_eq!(markets.len(), 4);
+        assert!(matches!(markets[0], MarketEntry::Address(_)));
+        assert!(markets[1].is_disabled());
+        assert_eq!(markets[1].label(), Some("off"));
+        assert!(!markets[2].is_disabled());
+        assert_eq!(markets[2].priority(), -1);
+        assert_eq!(markets[3], MarketEntry::Address(SOL.to_string()));
+        let enabled: Vec<&MarketEntry> =
+            markets.iter().filter(|market| !market.is_disabled()).collect();
+        assert_eq!(enabled.len(), 3);
+    }
+
+    #[test]
+    fn table_market_rejects_unknown_fields_and_bad_types() {
+        let unknown = format!("markets = [{{ address = \"{}\", disabeld = true }}]", POOL);
+        assert!(parse_markets(&unknown).is_err());
+        let bad_dex = format!("markets = [{{ address = \"{}\", dex = \"Uniswap\" }}]", POOL);
+        assert!(parse_markets(&bad_dex).is_err());
+        assert!(parse_markets("markets = [{ label = \"no address\" }]").is_err());
+        assert!(parse_markets("markets = [42]").is_err());
+    }
+
+    #[test]
+    fn markets_round_trip_through_serialization() {
+        let markets = vec![
+            MarketEntry::Address(POOL.to_string()),
+            MarketEntry::Detailed(MarketDetails {
+                address: POOL.to_string(),
+                label: Some("pump".to_string()),
+                dex: Some(MarketPoolKind::Pump),
+                priority: None,
+                base: None,
+                base_override: None,
+                disabled: true,
+            }),
+        ];
+        for market in markets {
+            let value = toml::Value::try_from(&market).unwrap();
+            // Unset fields are left out, so the table form stays as short as it was written
+            if let toml::Value::Table(table) = &value {
+                assert!(!table.contains_key("priority"));
+                assert!(!table.contains_key("base"));
+            }
+            assert_eq!(value.try_into::<MarketEntry>().unwrap(), market);
+        }
+    }
 }
@@ -0,0 +1,349 @@
+use crate::config::{Config, MarketEntry, MintOverrideConfig};
+use crate::paths::resolve_path;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Keys an `include` file may set, merged into `[routing.markets]` and `[routing]`
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IncludedConfig {
+    #[serde(default)]
+    markets: Vec<MarketEntry>,
+    lookup_table_accounts: Option<Vec<String>>,
+    mint_overrides: Option<Vec<MintOverrideConfig>>,
+}
+
+impl IncludedConfig {
+    fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read included config file {}", path.display()))?;
+        let value: toml::Value = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse included config file {}", path.display()))?;
+        serde_path_to_error::deserialize(value).map_err(|e| {
+            anyhow!(
+                "Invalid included config file {}: `{}`: {}",
+                path.display(),
+                e.path(),
+                e.inner()
+            )
+        })
+    }
+}
+
+/// Files matched by the `include` patterns, relative ones resolved against `base`. Files
+/// come in pattern order, sorted by path within a pattern, and each file only once.
+fn included_files(patterns: &[String], base: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    for pattern in patterns {
+        let resolved = resolve_path(pattern, base);
+        let resolved = resolved.to_string_lossy();
+        let mut matched = glob::glob(&resolved)
+            .map_err(|e| anyhow!("Invalid include pattern {}: {}", pattern, e))?
+            .collect::<Result<Vec<PathBuf>, _>>()
+            .with_context(|| format!("Failed to read files matching include {}", pattern))?;
+        if matched.is_empty() {
+            bail!(
+                "include {} matches no file (resolved to {})",
+                pattern,
+                resolved
+            );
+        }
+        matched.sort();
+        files.extend(matched.into_iter().filter(|path| seen.insert(path.clone())));
+    }
+    Ok(files)
+}
+
+/// Merges the markets, mint overrides and lookup tables of the `include` files into
+/// `config`, after those of `main`, the config file itself. A market or mint override
+/// set in two files is an error naming both unless the entries are identical, in which
+/// case it is kept once.
+pub fn apply_includes(config: &mut Config, main: &Path, base: &Path) -> Result<()> {
+    let Some(patterns) = config
+        .include
+        .clone()
+        .filter(|patterns| !patterns.is_empty())
+    else {
+        return Ok(());
+    };
+    let mut markets: HashMap<String, (PathBuf, MarketEntry)> = HashMap::new();
+    for entry in &config.routing.markets.markets {
+        markets.insert(
+            entry.address().to_string(),
+            (main.to_path_buf(), entry.clone()),
+        );
+    }
+    let mut mint_overrides: HashMap<String, (PathBuf, MintOverrideConfig)> = HashMap::new();
+    for entry in config.routing.mint_overrides.iter().flatten() {
+        mint_overrides.insert(entry.mint.clone(), (main.to_path_buf(), entry.clone()));
+    }
+
+    for path in included_files(&patterns, base)? {
+        let included = IncludedConfig::load(&path)?;
+        let mut added = 0;
+        for entry in included.markets {
+            let address = entry.address().to_string();
+            if let Some((origin, existing)) = markets.get(&address) {
+                if *existing != entry {
+                    bail!(
+                        "Market {} is configured differently in {} and {}",
+                        address,
+                        origin.display(),
+                        path.display()
+                    );
+                }
+                continue;
+            }
+            markets.insert(address, (path.clone(), entry.clone()));
+            config.routing.markets.markets.push(entry);
+            added += 1;
+        }
+        for entry in included.mint_overrides.into_iter().flatten() {
+            if let Some((origin, existing)) = mint_overrides.get(&entry.mint) {
+                if *existing != entry {
+                    bail!(
+                        "Mint override of {} is configured differently in {} and {}",
+                        entry.mint,
+                        origin.display(),
+                        path.display()
+                    );
+                }
+                continue;
+            }
+            mint_overrides.insert(entry.mint.clone(), (path.clone(), entry.clone()));
+            config
+                .routing
+                .mint_overrides
+                .get_or_insert_with(Vec::new)
+                .push(entry);
+        }
+        if let Some(tables) = included.lookup_table_accounts {
+            let existing = config
+                .routing
+                .markets
+                .lookup_table_accounts
+                .get_or_insert_with(Vec::new);
+            for table in tables {
+                if !existing.contains(&table) {
+                    existing.push(table);
+                }
+            }
+        }
+        info!("Included {} markets from {}", added, path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MarketDetails;
+    use std::env;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("config-include-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Main config including `include`, with the `[routing.markets]` keys in `markets` and
+    /// `extra` appended at the end, e.g. `[[routing.mint_overrides]]` tables
+    fn config(include: &[&str], markets: &str, extra: &str) -> Config {
+        toml::from_str(&format!(
+            "include = {:?}\n\
+             [bot]\ncompute_unit_limit = 400000\n\
+             [routing.markets]\n{}\nprocess_delay = 1000\n\
+             [rpc]\nurl = \"http://127.0.0.1:8899\"\n\
+             [wallet]\n{}",
+            include, markets, extra
+        ))
+        .unwrap()
+    }
+
+    fn addresses(config: &Config) -> Vec<&str> {
+        config
+            .routing
+            .markets
+            .markets
+            .iter()
+            .map(MarketEntry::address)
+            .collect()
+    }
+
+    fn apply(config: &mut Config, dir: &Path) -> Result<()> {
+        apply_includes(config, &dir.join("config.toml"), dir)
+    }
+
+    #[test]
+    fn files_come_in_pattern_then_path_order() {
+        let dir = temp_dir("order");
+        fs::create_dir_all(dir.join("markets")).unwrap();
+        for (file, market) in [
+            ("markets/b.toml", "B"),
+            ("markets/a.toml", "A"),
+            ("c.toml", "C"),
+        ] {
+            fs::write(dir.join(file), format!("markets = [\"{}\"]", market)).unwrap();
+        }
+        let patterns = ["c.toml", "markets/*.toml", "markets/a.toml"];
+        assert_eq!(
+            included_files(&patterns.map(String::from), &dir).unwrap(),
+            vec![
+                dir.join("c.toml"),
+                dir.join("markets/a.toml"),
+                dir.join("markets/b.toml")
+            ]
+        );
+
+        let mut config = config(&patterns, "markets = [\"M\"]", "");
+        apply(&mut config, &dir).unwrap();
+        assert_eq!(addresses(&config), ["M", "C", "A", "B"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn identical_entries_are_kept_once() {
+        let dir = temp_dir("identical");
+        let override_table = "[[mint_overrides]]\nmint = \"Mint\"\ncompute_unit_limit = 300000\n";
+        fs::write(
+            dir.join("a.toml"),
+            format!(
+                "markets = [\"M\", {{ address = \"A\", priority = 1 }}]\n{}",
+                override_table
+            ),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.toml"),
+            format!(
+                "markets = [{{ address = \"A\", priority = 1 }}, \"B\"]\n{}",
+                override_table
+            ),
+        )
+        .unwrap();
+        let extra = "[[routing.mint_overrides]]\nmint = \"Mint\"\ncompute_unit_limit = 300000\n";
+        let mut config = config(&["*.toml"], "markets = [\"M\"]", extra);
+        apply(&mut config, &dir).unwrap();
+
+        assert_eq!(addresses(&config), ["M", "A", "B"]);
+        let detailed = MarketEntry::Detailed(MarketDetails {
+            address: "A".to_string(),
+            label: None,
+            dex: None,
+            priority: Some(1),
+            base: None,
+            base_override: None,
+            disabled: false,
+        });
+        assert_eq!(config.routing.markets.markets[1], detailed);
+        assert_eq!(config.routing.mint_overrides.unwrap().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn conflicting_entries_name_both_files() {
+        let dir = temp_dir("conflict");
+        let main = dir.join("config.toml");
+        let a = dir.join("a.toml");
+        let b = dir.join("b.toml");
+
+        fs::write(&a, "markets = [{ address = \"M\", priority = 2 }]").unwrap();
+        let message = apply(&mut config(&["a.toml"], "markets = [\"M\"]", ""), &dir)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            message,
+            format!(
+                "Market M is configured differently in {} and {}",
+                main.display(),
+                a.display()
+            )
+        );
+
+        fs::write(
+            &a,
+            "[[mint_overrides]]\nmint = \"Mint\"\ncompute_unit_limit = 300000",
+        )
+        .unwrap();
+        fs::write(
+            &b,
+            "[[mint_overrides]]\nmint = \"Mint\"\ncompute_unit_limit = 200000",
+        )
+        .unwrap();
+        let message = apply(&mut config(&["*.toml"], "markets = []", ""), &dir)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            message,
+            format!(
+                "Mint override of Mint is configured differently in {} and {}",
+                a.display(),
+                b.display()
+            )
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn pattern_matching_nothing_is_an_error() {
+        let dir = temp_dir("nothing");
+        let message = apply(&mut config(&["pools/*.toml"], "markets = []", ""), &dir)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.starts_with("include pools/*.toml matches no file"),
+            "{}",
+            message
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let dir = temp_dir("unknown");
+        let path = dir.join("a.toml");
+        fs::write(&path, "markets = []\nprocess_delay = 500").unwrap();
+        let message = apply(&mut config(&["a.toml"], "markets = []", ""), &dir)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.starts_with(&format!("Invalid included config file {}", path.display())),
+            "{}",
+            message
+        );
+        assert!(message.contains("process_delay"), "{}", message);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn lookup_tables_are_deduplicated() {
+        let dir = temp_dir("tables");
+        fs::write(
+            dir.join("a.toml"),
+            "lookup_table_accounts = [\"T1\", \"T2\"]",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.toml"),
+            "lookup_table_accounts = [\"T2\", \"T3\"]",
+        )
+        .unwrap();
+        let mut config = config(
+            &["*.toml"],
+            "markets = []\nlookup_table_accounts = [\"T1\"]",
+            "",
+        );
+        apply(&mut config, &dir).unwrap();
+        assert_eq!(
+            config.routing.markets.lookup_table_accounts.unwrap(),
+            ["T1", "T2", "T3"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,396 @@
+use crate::paths::create_parent_dirs;
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs;
+use std::path::Path;
+use toml::value::{Table, Value};
+use tracing::{info, warn};
+
+/// Layout written by this version of the bot
+pub const CURRENT_CONFIG_VERSION: i64 = 2;
+/// Per-mint `[[routing.mint_config_list]]` entries, each with one pool list per DEX
+const MINT_CONFIG_LIST_VERSION: i64 = 1;
+
+/// Upgrades a parsed config file to `CURRENT_CONFIG_VERSION` in place and describes every
+/// change made. Files without `config_version` are versioned by their layout.
+pub fn migrate(value: &mut Value) -> Result<Vec<String>> {
+    let root = value
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("Config file is not a TOML table"))?;
+    let version = match root.get("config_version") {
+        Some(Value::Integer(version)) => *version,
+        Some(other) => bail!("config_version must be an integer, found {}", other),
+        None if has_mint_config_list(root) => MINT_CONFIG_LIST_VERSION,
+        None => CURRENT_CONFIG_VERSION,
+    };
+    if version > CURRENT_CONFIG_VERSION {
+        bail!(
+            "config_version {} is newer than the supported version {}",
+            version,
+            CURRENT_CONFIG_VERSION
+        );
+    }
+    if version < MINT_CONFIG_LIST_VERSION {
+        bail!("Unknown config_version {}", version);
+    }
+
+    let mut changes = Vec::new();
+    if version == MINT_CONFIG_LIST_VERSION {
+        changes.extend(migrate_mint_config_list(root)?);
+    }
+    if version < CURRENT_CONFIG_VERSION {
+        root.insert("config_version".to_string(), Value::Integer(CURRENT_CONFIG_VERSION));
+        changes.push(format!(
+            "upgraded from config_version {} to {}",
+            version, CURRENT_CONFIG_VERSION
+        ));
+    }
+    Ok(changes)
+}
+
+fn has_mint_config_list(root: &Table) -> bool {
+    root.get("routing")
+        .and_then(Value::as_table)
+        .map_or(false, |routing| routing.contains_key("mint_config_list"))
+}
+
+/// Version 1 to 2: the pool lists of every `mint_config_list` entry become
+/// `routing.markets.markets`, where the DEX is detected instead of configured
+fn migrate_mint_config_list(root: &mut Table) -> Result<Vec<String>> {
+    let routing = root
+        .get_mut("routing")
+        .and_then(Value::as_table_mut)
+        .ok_or_else(|| anyhow!("config_version 1 requires a [routing] table"))?;
+    let entries = match routing.remove("mint_config_list") {
+        Some(Value::Array(entries)) => entries,
+        Some(_) => bail!("routing.mint_config_list must be an array of tables"),
+        None => Vec::new(),
+    };
+
+    let markets_table = routing
+        .entry("markets".to_string())
+        .or_insert_with(|| Value::Table(Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("routing.markets must be a table"))?;
+    let mut markets = string_list(markets_table.get("markets"), "routing.markets.markets")?;
+    let mut lookup_tables = string_list(
+        markets_table.get("lookup_table_accounts"),
+        "routing.markets.lookup_table_accounts",
+    )?;
+    let mut process_delay = markets_table.get("process_delay").and_then(Value::as_integer);
+
+    let mut changes = Vec::new();
+    let mut moved_pools = 0;
+    for (i, entry) in entries.into_iter().enumerate() {
+        let Value::Table(entry) = entry else {
+            bail!("routing.mint_config_list[{}] must be a table", i);
+        };
+        let mint = entry.get("mint").and_then(Value::as_str).unwrap_or("?").to_string();
+        for (key, value) in &entry {
+            let path = format!("routing.mint_config_list[{}].{}", i, key);
+            match key.as_str() {
+                "mint" => {}
+                "lookup_table_accounts" => {
+                    for table in string_list(Some(value), &path)? {
+                        if !lookup_tables.contains(&table) {
+                            lookup_tables.push(table);
+                        }
+                    }
+                }
+                "process_delay" => {
+                    let delay = value
+                        .as_integer()
+                        .ok_or_else(|| anyhow!("{} must be an integer", path))?;
+                    if process_delay.map_or(false, |current| current != delay) {
+                        changes.push(format!(
+                            "mint {} had process_delay {}, routing.markets.process_delay keeps {}",
+                            mint,
+                            delay,
+                            process_delay.unwrap_or_default()
+                        ));
+                    }
+                    process_delay.get_or_insert(delay);
+                }
+                key if key.ends_with("_pool_list") => {
+                    for pool in string_list(Some(value), &path)? {
+                        if !markets.contains(&pool) {
+                            markets.push(pool);
+                            moved_pools += 1;
+                        }
+                    }
+                }
+                _ => changes.push(format!("dropped {}, which has no equivalent", path)),
+            }
+        }
+    }
+    changes.insert(
+        0,
+        format!(
+            "moved {} pools from routing.mint_config_list to routing.markets.markets",
+            moved_pools
+        ),
+    );
+
+    markets_table.insert("markets".to_string(), strings_value(markets));
+    if !lookup_tables.is_empty() {
+        markets_table.insert("lookup_table_accounts".to_string(), strings_value(lookup_tables));
+    }
+    if let Some(delay) = process_delay {
+        markets_table.insert("process_delay".to_string(), Value::Integer(delay));
+    }
+    Ok(changes)
+}
+
+fn string_list(value: Option<&Value>, path: &str) -> Result<Vec<String>> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    value
+        .as_array()
+        .ok_or_else(|| anyhow!("{} must be an array", path))?
+        .iter()
+        .map(|item| {
+            item.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("{} must only contain strings", path))
+        })
+        .collect()
+}
+
+fn strings_value(strings: Vec<String>) -> Value {
+    Value::Array(strings.into_iter().map(Value::String).collect())
+}
+
+/// Writes the upgraded config to `output`, or over `config_path` after copying the original
+/// to `<config_path>.bak`. Comments of the original file are not carried over.
+pub fn migrate_config_file(config_path: &str, output: Option<&str>) -> Result<()> {
+    let contents = fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read config file {}", config_path))?;
+    let mut value: Value = toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse config file {}", config_path))?;
+    let changes = migrate(&mut value)?;
+    if changes.is_empty() {
+        info!("{} is already at config_version {}", config_path, CURRENT_CONFIG_VERSION);
+        return Ok(());
+    }
+    for change in &changes {
+        info!("Migration: {}", change);
+    }
+
+    let migrated = toml::to_string_pretty(&value).context("Failed to serialize the config")?;
+    let output = match output {
+        Some(output) => output.to_string(),
+        None => {
+            let backup = format!("{}.bak", config_path);
+            fs::copy(config_path, &backup)
+                .with_context(|| format!("Failed to back up the config to {}", backup))?;
+            info!("Original config saved to {}", backup);
+            config_path.to_string()
+        }
+    };
+    create_parent_dirs(Path::new(&output))?;
+    fs::write(&output, migrated)
+        .with_context(|| format!("Failed to write migrated config {}", output))?;
+    warn!("Wrote migrated config to {}; comments of the original are not kept", output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    /// Version 1 layout with an explicit `config_version`. Keys are sorted, as the TOML
+    /// table visits them in that order.
+    const V1_CONFIG: &str = r#"
+config_version = 1
+
+[bot]
+compute_unit_limit = 600000
+
+[rpc]
+url = "https://api.mainnet-beta.solana.com"
+
+[wallet]
+private_key = "/path/to/keypair.json"
+
+[[routing.mint_config_list]]
+enabled = true
+lookup_table_accounts = ["ALT_A"]
+mint = "MINT_A"
+process_delay = 400
+pump_pool_list = ["PUMP_A"]
+raydium_pool_list = ["RAY_A", "RAY_SHARED"]
+
+[[routing.mint_config_list]]
+lookup_table_accounts = ["ALT_A", "ALT_B"]
+meteora_dlmm_pool_list = ["DLMM_B"]
+mint = "MINT_B"
+process_delay = 200
+raydium_pool_list = ["RAY_SHARED"]
+"#;
+
+    /// Version 1 layout without `config_version`, recognized by its `mint_config_list`,
+    /// next to a `[routing.markets]` table already holding a market
+    const UNVERSIONED_V1_CONFIG: &str = r#"
+[bot]
+compute_unit_limit = 600000
+
+[rpc]
+url = "https://api.mainnet-beta.solana.com"
+
+[wallet]
+private_key = "/path/to/keypair.json"
+
+[routing.markets]
+markets = ["WHIRL_EXISTING"]
+process_delay = 300
+
+[[routing.mint_config_list]]
+mint = "MINT_A"
+process_delay = 300
+whirlpool_pool_list = ["WHIRL_EXISTING", "WHIRL_A"]
+"#;
+
+    /// Current layout
+    const V2_CONFIG: &str = r#"
+config_version = 2
+
+[bot]
+compute_unit_limit = 600000
+
+[rpc]
+url = "https://api.mainnet-beta.solana.com"
+
+[wallet]
+private_key = "/path/to/keypair.json"
+
+[routing.markets]
+markets = ["PUMP_A"]
+process_delay = 400
+"#;
+
+    fn parse(toml: &str) -> Value {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn get<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+        path.split('.').try_fold(value, |value, key| value.get(key))
+    }
+
+    fn strings(value: &Value, path: &str) -> Vec<String> {
+        string_list(get(value, path), path).unwrap()
+    }
+
+    #[test]
+    fn migrates_v1() {
+        let mut value = parse(V1_CONFIG);
+        let changes = migrate(&mut value).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                "moved 4 pools from routing.mint_config_list to routing.markets.markets",
+                "dropped routing.mint_config_list[0].enabled, which has no equivalent",
+                "mint MINT_B had process_delay 200, routing.markets.process_delay keeps 400",
+                "upgraded from config_version 1 to 2",
+            ]
+        );
+
+        assert_eq!(get(&value, "config_version"), Some(&Value::Integer(2)));
+        assert!(get(&value, "routing.mint_config_list").is_none());
+        assert_eq!(
+            strings(&value, "routing.markets.markets"),
+            vec!["PUMP_A", "RAY_A", "RAY_SHARED", "DLMM_B"]
+        );
+        assert_eq!(
+            strings(&value, "routing.markets.lookup_table_accounts"),
+            vec!["ALT_A", "ALT_B"]
+        );
+        assert_eq!(get(&value, "routing.markets.process_delay"), Some(&Value::Integer(400)));
+
+        let config: Config = value.try_into().unwrap();
+        assert_eq!(config.config_version, Some(CURRENT_CONFIG_VERSION));
+        assert_eq!(config.routing.markets.markets.len(), 4);
+        assert_eq!(config.routing.markets.process_delay, 400);
+    }
+
+    #[test]
+    fn migrates_unversioned_v1_into_existing_markets() {
+        let mut value = parse(UNVERSIONED_V1_CONFIG);
+        let changes = migrate(&mut value).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                "moved 1 pools from routing.mint_config_list to routing.markets.markets",
+                "upgraded from config_version 1 to 2",
+            ]
+        );
+
+        assert_eq!(get(&value, "config_version"), Some(&Value::Integer(2)));
+        assert!(get(&value, "routing.mint_config_list").is_none());
+        assert_eq!(
+            strings(&value, "routing.markets.markets"),
+            vec!["WHIRL_EXISTING", "WHIRL_A"]
+        );
+        assert!(get(&value, "routing.markets.lookup_table_accounts").is_none());
+        assert_eq!(get(&value, "routing.markets.process_delay"), Some(&Value::Integer(300)));
+
+        let config: Config = value.try_into().unwrap();
+        assert_eq!(config.routing.markets.markets.len(), 2);
+    }
+
+    #[test]
+    fn current_layouts_are_left_alone() {
+        let mut value = parse(V2_CONFIG);
+        assert!(migrate(&mut value).unwrap().is_empty());
+        assert_eq!(value, parse(V2_CONFIG));
+
+        let unversioned = V2_CONFIG.replace("config_version = 2", "");
+        let mut value = parse(&unversioned);
+        assert!(migrate(&mut value).unwrap().is_empty());
+        assert!(get(&value, "config_version").is_none());
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        for (version, error) in [
+            ("3", "config_version 3 is newer than the supported version 2"),
+            ("0", "Unknown config_version 0"),
+            ("\"1\"", "config_version must be an integer"),
+        ] {
+            let line = format!("config_version = {}", version);
+            let source = V2_CONFIG.replace("config_version = 2", &line);
+            let message = migrate(&mut parse(&source)).unwrap_err().to_string();
+            assert!(message.starts_with(error), "{}", message);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_v1_entries() {
+        let source = V1_CONFIG.replace(r#"["PUMP_A"]"#, r#""PUMP_A""#);
+        let message = migrate(&mut parse(&source)).unwrap_err().to_string();
+        assert_eq!(message, "routing.mint_config_list[0].pump_pool_list must be an array");
+
+        let source = V1_CONFIG.replace("process_delay = 200", r#"process_delay = "200ms""#);
+        let message = migrate(&mut parse(&source)).unwrap_err().to_string();
+        assert_eq!(message, "routing.mint_config_list[1].process_delay must be an integer");
+    }
+
+    #[test]
+    fn migrate_config_file_keeps_a_backup() {
+        let dir = std::env::temp_dir().join(format!("config-migration-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, V1_CONFIG).unwrap();
+
+        migrate_config_file(path.to_str().unwrap(), None).unwrap();
+        let backup = fs::read_to_string(dir.join("config.toml.bak")).unwrap();
+        assert_eq!(backup, V1_CONFIG);
+        let migrated = parse(&fs::read_to_string(&path).unwrap());
+        assert_eq!(get(&migrated, "config_version"), Some(&Value::Integer(2)));
+        assert_eq!(strings(&migrated, "routing.markets.markets").len(), 4);
+
+        // A second run finds nothing to change and leaves the file alone
+        migrate_config_file(path.to_str().unwrap(), None).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
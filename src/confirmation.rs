@@ -0,0 +1,243 @@
+use crate::accounting::{fetch_transaction_pnl, BaseMintPrices, ReconciledTransaction};
+use crate::executor_errors::{ExecutorErrors, FailureSource};
+use crate::fee_spend::FeeSpend;
+use crate::journal::SendJournal;
+use crate::risk::{unix_now, DailyLossBreaker};
+use crate::transaction::SendReport;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::OwnedSemaphorePermit;
+use tracing::{debug, info, warn};
+
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+pub const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A transaction observed on chain for a mint
+#[derive(Debug, Clone)]
+pub struct LandedTransaction {
+    pub mint: Pubkey,
+    pub signature: Signature,
+    pub slot: u64,
+    pub succeeded: bool,
+    /// Failure reason of a transaction that landed but failed
+    pub failure: Option<String>,
+}
+
+/// Follows sent transactions until they land, reporting landings to the mint's worker,
+/// realized results to the loss breaker and fee spend, and landing slots to the send journal
+#[derive(Clone)]
+pub struct LandingTracker {
+    rpc_client: Arc<RpcClient>,
+    wallet: Pubkey,
+    base_prices: Arc<BaseMintPrices>,
+    loss_breaker: Option<Arc<DailyLossBreaker>>,
+    journal: Option<SendJournal>,
+    track_fees: bool,
+    timeout: Duration,
+}
+
+impl LandingTracker {
+    pub fn new(
+        rpc_client: Arc<RpcClient>,
+        wallet: Pubkey,
+        base_prices: Arc<BaseMintPrices>,
+        loss_breaker: Option<Arc<DailyLossBreaker>>,
+        journal: Option<SendJournal>,
+        track_fees: bool,
+    ) -> Self {
+        Self {
+            rpc_client,
+            wallet,
+            base_prices,
+            loss_breaker,
+            journal,
+            track_fees,
+            timeout: DEFAULT_CONFIRMATION_TIMEOUT,
+        }
+    }
+
+    /// Whether landed transactions are fetched for their realized result and fees
+    pub fn tracks_pnl(&self) -> bool {
+        self.loss_breaker.is_some() || self.track_fees
+    }
+
+    pub fn tracks_journal(&self) -> bool {
+        self.journal.is_some()
+    }
+
+    pub fn wallet(&self) -> Pubkey {
+        self.wallet
+    }
+
+    pub fn base_prices(&self) -> &BaseMintPrices {
+        &self.base_prices
+    }
+
+    /// Feeds transactions that landed before this start to the same fee spend, loss breaker
+    /// and send journal as live landings. Transactions without a mint are left out of the fee
+    /// spend, and those already in the journal are not journaled again.
+    pub fn record_reconciled(&self, transactions: &[ReconciledTransaction]) {
+        if transactions.is_empty() {
+            return;
+        }
+        let journaled = self
+            .journal
+            .as_ref()
+            .map(SendJournal::journaled_signatures)
+            .unwrap_or_default();
+        for transaction in transactions {
+            let pnl = &transaction.pnl;
+            let timestamp = pnl.block_time.unwrap_or_else(unix_now);
+            if let Some(mint) = transaction.mint.filter(|_| self.track_fees) {
+                FeeSpend::global().record(&mint, timestamp, pnl);
+            }
+            if let (Some(breaker), Some(lamports)) = (&self.loss_breaker, pnl.pnl_lamports) {
+                breaker.record(timestamp, lamports);
+            }
+            if let Some(journal) = &self.journal {
+                if !journaled.contains(&pnl.signature.to_string()) {
+                    journal.record_reconciled(transaction);
+                }
+            }
+        }
+        let unattributed = transactions.iter().filter(|tx| tx.mint.is_none()).count();
+        if self.track_fees && unattributed > 0 {
+            warn!(
+                "{} reconciled transactions of wallet {} could not be attributed to one mint and \
+                 are left out of the fee spend",
+                unattributed, self.wallet
+            );
+        }
+    }
+
+    /// Follows the signatures of `report` in the background. `in_flight`, the wallet's
+    /// in-flight slot taken for the send, is released once one of them lands or the
+    /// confirmation timeout passes.
+    pub fn track(
+        &self,
+        mint: Pubkey,
+        report: SendReport,
+        sender: UnboundedSender<LandedTransaction>,
+        in_flight: Option<OwnedSemaphorePermit>,
+    ) {
+        let mut signatures = report.signatures();
+        signatures.sort();
+        signatures.dedup();
+        if signatures.is_empty() {
+            return;
+        }
+
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            let landed = tracker.wait_for_landing(&mint, &signatures).await;
+            drop(in_flight);
+            match landed {
+                Some(landed) => {
+                    tracker.record_pnl(&landed);
+                    if let Some(journal) = &tracker.journal {
+                        journal.record_landing(&report, &landed);
+                    }
+                    let _ = sender.send(landed);
+                }
+                None => debug!(
+                    "Transactions for mint {} did not land within {:?}",
+                    mint, tracker.timeout
+                ),
+            }
+        });
+    }
+
+    async fn wait_for_landing(
+        &self,
+        mint: &Pubkey,
+        signatures: &[Signature],
+    ) -> Option<LandedTransaction> {
+        let started = Instant::now();
+        while started.elapsed() < self.timeout {
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+
+            let statuses = match self.rpc_client.get_signature_statuses(signatures) {
+                Ok(response) => response.value,
+                Err(e) => {
+                    warn!("Failed to fetch signature statuses for mint {}: {}", mint, e);
+                    continue;
+                }
+            };
+
+            for (signature, status) in signatures.iter().zip(statuses) {
+                if let Some(status) = status {
+                    let failure = status.err.as_ref().map(|err| {
+                        let reason = ExecutorErrors::global().reason(err);
+                        ExecutorErrors::global().record(FailureSource::Landed, &reason);
+                        reason
+                    });
+                    debug!(
+                        "Transaction {} for mint {} landed in slot {} (succeeded: {})",
+                        signature,
+                        mint,
+                        status.slot,
+                        status.err.is_none()
+                    );
+                    return Some(LandedTransaction {
+                        mint: *mint,
+                        signature: *signature,
+                        slot: status.slot,
+                        succeeded: status.err.is_none(),
+                        failure,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Fetches the landed transaction once and feeds its fee, tip and net result to the fee
+    /// spend and the loss breaker, so both account from the same status meta
+    fn record_pnl(&self, landed: &LandedTransaction) {
+        if !self.tracks_pnl() {
+            return;
+        }
+        let pnl = fetch_transaction_pnl(
+            &self.rpc_client,
+            &landed.signature,
+            &self.wallet,
+            &self.base_prices,
+        );
+        match pnl {
+            Ok(pnl) => {
+                match pnl.pnl_lamports {
+                    Some(pnl_lamports) => info!(
+                        "Realized {} lamports (fee {}, tip {}) on {} for mint {}",
+                        pnl_lamports,
+                        pnl.fee_lamports,
+                        pnl.tip_lamports,
+                        landed.signature,
+                        landed.mint
+                    ),
+                    None => warn!(
+                        "Result of {} for mint {} moved a base mint without a SOL price \
+                         (fee {}, tip {}); not counted by the loss breaker",
+                        landed.signature, landed.mint, pnl.fee_lamports, pnl.tip_lamports
+                    ),
+                }
+                let timestamp = pnl.block_time.unwrap_or_else(unix_now);
+                if self.track_fees {
+                    FeeSpend::global().record(&landed.mint, timestamp, &pnl);
+                }
+                if let (Some(loss_breaker), Some(pnl_lamports)) =
+                    (&self.loss_breaker, pnl.pnl_lamports)
+                {
+                    loss_breaker.record(timestamp, pnl_lamports);
+                }
+            }
+            Err(e) => warn!(
+                "Failed to fetch result of transaction {}: {}",
+                landed.signature, e
+            ),
+        }
+    }
+}
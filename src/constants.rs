@@ -1,18 +1,135 @@
+use crate::config::MintsConfig;
+use anyhow::{anyhow, Context, Result};
 use solana_program::pubkey::Pubkey;
 use std::str::FromStr;
+use std::sync::OnceLock;
 
 pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
 pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
 pub const USD1_MINT: &str = "USD1ttGY1N17NEEHLmELoaybftRBUSErhqYiQzvEmuB";
+pub const EXECUTOR_PROGRAM_ID: &str = "MEViEnscUm6tsQRoGd9h6nLQaQspKj7DB2M5FwM3Xvz";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+/// Per-transaction compute unit cap enforced by the runtime
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+/// Heap frame sizes the runtime accepts in `request_heap_frame`, in multiples of 1024
+pub const MIN_HEAP_FRAME_BYTES: u32 = 32 * 1024;
+pub const MAX_HEAP_FRAME_BYTES: u32 = 256 * 1024;
+
+static CANONICAL_MINTS: OnceLock<CanonicalMints> = OnceLock::new();
+
+/// Wrapped SOL, USDC and USD1 mints of the cluster the bot runs against
+#[derive(Debug, Clone, Copy)]
+struct CanonicalMints {
+    sol: Pubkey,
+    usdc: Pubkey,
+    usd1: Pubkey,
+}
+
+fn canonical_mints() -> &'static CanonicalMints {
+    CANONICAL_MINTS.get_or_init(|| CanonicalMints {
+        sol: Pubkey::from_str(SOL_MINT).unwrap(),
+        usdc: Pubkey::from_str(USDC_MINT).unwrap(),
+        usd1: Pubkey::from_str(USD1_MINT).unwrap(),
+    })
+}
+
+/// Replaces the mainnet mints returned by `sol_mint`, `usdc_mint` and `usd1_mint` with
+/// the `[mints]` overrides. Must run before any of them is first called.
+pub fn init_canonical_mints(config: Option<&MintsConfig>) -> Result<()> {
+    let Some(config) = config else {
+        return Ok(());
+    };
+    let parse = |name: &str, value: &Option<String>, default: &str| -> Result<Pubkey> {
+        let value = value.as_deref().unwrap_or(default);
+        Pubkey::from_str(value).with_context(|| format!("Invalid mints.{} {}", name, value))
+    };
+    let mints = CanonicalMints {
+        sol: parse("sol", &config.sol, SOL_MINT)?,
+        usdc: parse("usdc", &config.usdc, USDC_MINT)?,
+        usd1: parse("usd1", &config.usd1, USD1_MINT)?,
+    };
+    CANONICAL_MINTS
+        .set(mints)
+        .map_err(|_| anyhow!("Canonical mints were read before the [mints] overrides were applied"))
+}
 
 pub fn sol_mint() -> Pubkey {
-    Pubkey::from_str(SOL_MINT).unwrap()
+    canonical_mints().sol
 }
 
 pub fn usdc_mint() -> Pubkey {
-    Pubkey::from_str(USDC_MINT).unwrap()
+    canonical_mints().usdc
 }
 
 pub fn usd1_mint() -> Pubkey {
-    Pubkey::from_str(USD1_MINT).unwrap()
+    canonical_mints().usd1
+}
+
+pub fn executor_program_id() -> Pubkey {
+    Pubkey::from_str(EXECUTOR_PROGRAM_ID).unwrap()
+}
+
+pub fn token_2022_program_id() -> Pubkey {
+    Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap()
+}
+
+pub fn memo_program_id() -> Pubkey {
+    Pubkey::from_str(MEMO_PROGRAM_ID).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pubkey(address: &str) -> Pubkey {
+        Pubkey::from_str(address).unwrap()
+    }
+
+    #[test]
+    fn mints_match_the_known_mainnet_addresses() {
+        assert_eq!(sol_mint(), spl_token::native_mint::id());
+        assert_eq!(
+            sol_mint(),
+            pubkey("So11111111111111111111111111111111111111112")
+        );
+        assert_eq!(
+            usdc_mint(),
+            pubkey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")
+        );
+        assert_eq!(
+            usd1_mint(),
+            pubkey("USD1ttGY1N17NEEHLmELoaybftRBUSErhqYiQzvEmuB")
+        );
+    }
+
+    #[test]
+    fn programs_match_the_known_addresses() {
+        assert_eq!(
+            executor_program_id(),
+            pubkey("MEViEnscUm6tsQRoGd9h6nLQaQspKj7DB2M5FwM3Xvz")
+        );
+        assert_eq!(
+            token_2022_program_id(),
+            pubkey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb")
+        );
+        assert_eq!(
+            memo_program_id(),
+            pubkey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr")
+        );
+        assert_ne!(token_2022_program_id(), spl_token::id());
+    }
+
+    #[test]
+    fn mint_overrides_are_validated() {
+        let config = MintsConfig {
+            sol: None,
+            usdc: Some("not a pubkey".to_string()),
+            usd1: None,
+        };
+        let err = init_canonical_mints(Some(&config)).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid mints.usdc not a pubkey");
+        // Nothing was applied
+        assert_eq!(usdc_mint(), pubkey(USDC_MINT));
+    }
 }
@@ -2,6 +2,7 @@ pub mod info;
 
 pub use info::*;
 
+use crate::pda::find_program_address;
 use solana_program::pubkey::Pubkey;
 use std::str::FromStr;
 
@@ -12,3 +13,11 @@ pub fn futarchy_program_id() -> Pubkey {
 pub fn futarchy_event_authority() -> Pubkey {
     Pubkey::from_str("DGEympSS4qLvdr9r3uGHTfACdN8snShk4iGdJtZPxuBC").unwrap()
 }
+
+/// Event authority of the given deployment
+pub fn futarchy_event_authority_for(program_id: &Pubkey) -> Pubkey {
+    if *program_id == futarchy_program_id() {
+        return futarchy_event_authority();
+    }
+    find_program_address(&[b"__event_authority"], program_id).0
+}
@@ -73,3 +73,63 @@ fn read_u64_le(data: &[u8], offset: usize) -> Option<u64> {
     bytes.copy_from_slice(&data[offset..offset + 8]);
     Some(u64::from_le_bytes(bytes))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Inverse of `decode_pubkey`: writes `pubkey` at `offset` as 4 XOR'd little-endian u64s
+    fn encode_pubkey(data: &mut [u8], offset: usize, pubkey: &Pubkey) {
+        for (i, chunk) in pubkey.to_bytes().chunks(8).enumerate() {
+            let plain = u64::from_le_bytes(chunk.try_into().unwrap());
+            let start = offset + i * 8;
+            data[start..start + 8].copy_from_slice(&(plain ^ XOR_KEYS[i]).to_le_bytes());
+        }
+    }
+
+    fn known_pubkey(value: &str) -> Pubkey {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn load_checked_recovers_xor_encoded_pubkeys() {
+        let quote_mint = known_pubkey("So11111111111111111111111111111111111111112");
+        let base_mint = known_pubkey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        let quote_vault = known_pubkey("9H6tua7jkLhdm3w8BvgpTn5LZNU7g4ZynDmCiNN3q6Rp");
+        let base_vault = known_pubkey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+        // Non-zero filler, so a wrong offset cannot decode to the expected keys by accident
+        let mut data = vec![0xa5u8; BASE_VAULT_OFFSET + 32];
+        encode_pubkey(&mut data, QUOTE_MINT_OFFSET, &quote_mint);
+        encode_pubkey(&mut data, BASE_MINT_OFFSET, &base_mint);
+        encode_pubkey(&mut data, QUOTE_VAULT_OFFSET, &quote_vault);
+        encode_pubkey(&mut data, BASE_VAULT_OFFSET, &base_vault);
+
+        let info = HumidifiInfo::load_checked(&data).unwrap();
+        assert_eq!(info.quote_mint, quote_mint);
+        assert_eq!(info.base_mint, base_mint);
+        assert_eq!(info.quote_vault, quote_vault);
+        assert_eq!(info.base_vault, base_vault);
+    }
+
+    #[test]
+    fn encoded_bytes_differ_from_plaintext() {
+        let pubkey = known_pubkey("So11111111111111111111111111111111111111112");
+        let mut data = vec![0u8; BASE_VAULT_OFFSET + 32];
+        encode_pubkey(&mut data, QUOTE_MINT_OFFSET, &pubkey);
+        assert_ne!(&data[QUOTE_MINT_OFFSET..QUOTE_MINT_OFFSET + 32], pubkey.as_ref());
+    }
+
+    #[test]
+    fn load_checked_rejects_short_buffer() {
+        let data = vec![0u8; BASE_VAULT_OFFSET + 31];
+        assert!(HumidifiInfo::load_checked(&data).is_err());
+        assert!(HumidifiInfo::load_checked(&[]).is_err());
+    }
+
+    #[test]
+    fn decode_pubkey_rejects_truncated_chunk() {
+        let data = vec![0u8; QUOTE_MINT_OFFSET + 31];
+        assert!(decode_pubkey(&data, QUOTE_MINT_OFFSET).is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::pda::find_program_address;
 use solana_program::pubkey::Pubkey;
 use std::str::FromStr;
 
@@ -9,6 +10,14 @@ pub fn dlmm_event_authority() -> Pubkey {
     Pubkey::from_str("D1ZN9Wj1fRSUQfCjhvnu1hqDMT7hzjzBBpi12nVniYD6").unwrap()
 }
 
+/// DLMM event authority of the given deployment
+pub fn dlmm_event_authority_for(program_id: &Pubkey) -> Pubkey {
+    if *program_id == dlmm_program_id() {
+        return dlmm_event_authority();
+    }
+    find_program_address(&[b"__event_authority"], program_id).0
+}
+
 pub fn damm_program_id() -> Pubkey {
     Pubkey::from_str("Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB").unwrap()
 }
@@ -29,4 +38,20 @@ pub fn damm_v2_pool_authority() -> Pubkey {
     Pubkey::from_str("HLnpSz9h2S4hiLQ43rnSD9XkcUThA7B8hQMKmDaiTLcC").unwrap()
 }
 
+/// DAMM V2 event authority of the given deployment
+pub fn damm_v2_event_authority_for(program_id: &Pubkey) -> Pubkey {
+    if *program_id == damm_v2_program_id() {
+        return damm_v2_event_authority();
+    }
+    find_program_address(&[b"__event_authority"], program_id).0
+}
+
+/// DAMM V2 pool authority of the given deployment
+pub fn damm_v2_pool_authority_for(program_id: &Pubkey) -> Pubkey {
+    if *program_id == damm_v2_program_id() {
+        return damm_v2_pool_authority();
+    }
+    find_program_address(&[b"pool_authority"], program_id).0
+}
+
 pub const BIN_ARRAY: &[u8] = b"bin_array";
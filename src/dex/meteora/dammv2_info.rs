@@ -1,24 +1,162 @@
 use anyhow::Result;
 use solana_sdk::pubkey::Pubkey;
 
+// Base fee (fee scheduler)
+const CLIFF_FEE_NUMERATOR_OFFSET: usize = 8; // pool_fees.base_fee.cliff_fee_numerator
+const FEE_SCHEDULER_MODE_OFFSET: usize = 16; // pool_fees.base_fee.fee_scheduler_mode
+const NUMBER_OF_PERIOD_OFFSET: usize = 22; // pool_fees.base_fee.number_of_period
+const PERIOD_FREQUENCY_OFFSET: usize = 24; // pool_fees.base_fee.period_frequency
+const REDUCTION_FACTOR_OFFSET: usize = 32; // pool_fees.base_fee.reduction_factor
+// Dynamic fee
+const DYNAMIC_FEE_INITIALIZED_OFFSET: usize = 56; // pool_fees.dynamic_fee.initialized
+const VARIABLE_FEE_CONTROL_OFFSET: usize = 68; // pool_fees.dynamic_fee.variable_fee_control
+const BIN_STEP_OFFSET: usize = 72; // pool_fees.dynamic_fee.bin_step
+const VOLATILITY_ACCUMULATOR_OFFSET: usize = 120; // pool_fees.dynamic_fee.volatility_accumulator
+const TOKEN_A_MINT_OFFSET: usize = 168; // token_a_mint
+const TOKEN_B_MINT_OFFSET: usize = 200; // token_b_mint
+const TOKEN_A_VAULT_OFFSET: usize = 232; // token_a_vault
+const TOKEN_B_VAULT_OFFSET: usize = 264; // token_b_vault
+const ACTIVATION_POINT_OFFSET: usize = 472; // activation_point
+const ACTIVATION_TYPE_OFFSET: usize = 480; // activation_type
+
+/// Denominator of DAMM v2 fee numerators
+pub const FEE_DENOMINATOR: u64 = 1_000_000_000;
+/// Highest total fee numerator the program charges
+pub const MAX_FEE_NUMERATOR: u64 = 500_000_000;
+const BASIS_POINT_MAX: u64 = 10_000;
+const FEE_SCHEDULER_MODE_EXPONENTIAL: u8 = 1;
+const ACTIVATION_TYPE_TIMESTAMP: u8 = 1;
+
+/// Fee scheduler and dynamic fee state of a DAMM v2 pool. Both live in the pool account
+/// itself, so a swap needs no accounts beyond the pool to apply them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DammV2Fees {
+    pub cliff_fee_numerator: u64,
+    /// 0 for a linear schedule, 1 for an exponential one
+    pub fee_scheduler_mode: u8,
+    pub number_of_period: u16,
+    pub period_frequency: u64,
+    pub reduction_factor: u64,
+    pub dynamic_fee_initialized: bool,
+    pub variable_fee_control: u32,
+    pub bin_step: u16,
+    pub volatility_accumulator: u128,
+    pub activation_point: u64,
+    /// 0 when `activation_point` is a slot, 1 when it is a unix timestamp
+    pub activation_type: u8,
+}
+
+impl DammV2Fees {
+    /// Scheduled base fee numerator at `current_point`, a slot or a timestamp depending on
+    /// `activation_type`. Before activation the schedule sits at its last period.
+    pub fn base_fee_numerator(&self, current_point: u64) -> u64 {
+        if self.period_frequency == 0 {
+            return self.cliff_fee_numerator;
+        }
+        let number_of_period = self.number_of_period as u64;
+        let period = if current_point < self.activation_point {
+            number_of_period
+        } else {
+            ((current_point - self.activation_point) / self.period_frequency).min(number_of_period)
+        };
+        if self.fee_scheduler_mode == FEE_SCHEDULER_MODE_EXPONENTIAL {
+            // The program uses a fixed-point power; the float result differs in the last digits
+            let remaining = 1.0 - self.reduction_factor as f64 / BASIS_POINT_MAX as f64;
+            (self.cliff_fee_numerator as f64 * remaining.max(0.0).powi(period as i32)) as u64
+        } else {
+            self.cliff_fee_numerator
+                .saturating_sub(self.reduction_factor.saturating_mul(period))
+        }
+    }
+
+    /// Volatility-driven fee numerator on top of the base fee, 0 without a dynamic fee
+    pub fn variable_fee_numerator(&self) -> u64 {
+        if !self.dynamic_fee_initialized {
+            return 0;
+        }
+        let square_vfa_bin = self
+            .volatility_accumulator
+            .saturating_mul(self.bin_step as u128)
+            .saturating_pow(2);
+        let fee = square_vfa_bin
+            .saturating_mul(self.variable_fee_control as u128)
+            .saturating_add(99_999_999_999)
+            / 100_000_000_000;
+        fee.min(u64::MAX as u128) as u64
+    }
+
+    /// Total fee numerator charged at `slot` and `timestamp`, out of `FEE_DENOMINATOR`
+    pub fn fee_numerator(&self, slot: u64, timestamp: u64) -> u64 {
+        let current_point = if self.activation_type == ACTIVATION_TYPE_TIMESTAMP {
+            timestamp
+        } else {
+            slot
+        };
+        self.base_fee_numerator(current_point)
+            .saturating_add(self.variable_fee_numerator())
+            .min(MAX_FEE_NUMERATOR)
+    }
+
+    /// `fee_numerator` in basis points
+    pub fn fee_bps(&self, slot: u64, timestamp: u64) -> f64 {
+        self.fee_numerator(slot, timestamp) as f64 * BASIS_POINT_MAX as f64
+            / FEE_DENOMINATOR as f64
+    }
+}
+
 pub struct MeteoraDAmmV2Info {
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
+    pub fees: DammV2Fees,
 }
 
 impl MeteoraDAmmV2Info {
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        let base_mint = Pubkey::new(&data[168..200]);
-        let quote_mint = Pubkey::new(&data[200..232]);
-        let base_vault = Pubkey::new(&data[232..264]);
-        let quote_vault = Pubkey::new(&data[264..296]);
+        if data.len() < ACTIVATION_TYPE_OFFSET + 1 {
+            return Err(anyhow::anyhow!("Invalid data length for MeteoraDAmmV2Info"));
+        }
+
+        let base_mint = Pubkey::new(&data[TOKEN_A_MINT_OFFSET..TOKEN_A_MINT_OFFSET + 32]);
+        let quote_mint = Pubkey::new(&data[TOKEN_B_MINT_OFFSET..TOKEN_B_MINT_OFFSET + 32]);
+        let base_vault = Pubkey::new(&data[TOKEN_A_VAULT_OFFSET..TOKEN_A_VAULT_OFFSET + 32]);
+        let quote_vault = Pubkey::new(&data[TOKEN_B_VAULT_OFFSET..TOKEN_B_VAULT_OFFSET + 32]);
+        let fees = DammV2Fees {
+            cliff_fee_numerator: read_u64(data, CLIFF_FEE_NUMERATOR_OFFSET),
+            fee_scheduler_mode: data[FEE_SCHEDULER_MODE_OFFSET],
+            number_of_period: read_u16(data, NUMBER_OF_PERIOD_OFFSET),
+            period_frequency: read_u64(data, PERIOD_FREQUENCY_OFFSET),
+            reduction_factor: read_u64(data, REDUCTION_FACTOR_OFFSET),
+            dynamic_fee_initialized: data[DYNAMIC_FEE_INITIALIZED_OFFSET] != 0,
+            variable_fee_control: read_u32(data, VARIABLE_FEE_CONTROL_OFFSET),
+            bin_step: read_u16(data, BIN_STEP_OFFSET),
+            volatility_accumulator: u128::from_le_bytes(
+                data[VOLATILITY_ACCUMULATOR_OFFSET..VOLATILITY_ACCUMULATOR_OFFSET + 16]
+                    .try_into()
+                    .unwrap(),
+            ),
+            activation_point: read_u64(data, ACTIVATION_POINT_OFFSET),
+            activation_type: data[ACTIVATION_TYPE_OFFSET],
+        };
         Ok(Self {
             base_mint,
             quote_mint,
             base_vault,
             quote_vault,
+            fees,
         })
     }
 }
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
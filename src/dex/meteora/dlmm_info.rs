@@ -1,4 +1,6 @@
-use crate::dex::meteora::constants::{dlmm_program_id, BIN_ARRAY};
+use crate::dex::meteora::constants::BIN_ARRAY;
+use crate::pda::find_program_address;
+use crate::program_registry::ProgramRegistry;
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
 use std::mem::size_of;
@@ -94,6 +96,7 @@ pub struct DlmmInfo {
     pub token_y_vault: Pubkey,
     pub oracle: Pubkey,
     pub active_id: i32,
+    pub bin_step: u16,
     pub lb_pair: LbPair,
 }
 
@@ -117,6 +120,7 @@ impl DlmmInfo {
             token_y_vault: lb_pair.reserve_y,
             oracle: lb_pair.oracle,
             active_id: lb_pair.active_id,
+            bin_step: lb_pair.bin_step,
             lb_pair,
         })
     }
@@ -144,6 +148,11 @@ impl DlmmInfo {
         (token_vault, sol_vault)
     }
 
+    /// Price of token X in token Y (raw units) implied by the active bin
+    pub fn price_from_active_id(&self) -> f64 {
+        price_from_bin_id(self.active_id, self.bin_step)
+    }
+
     pub fn calculate_bin_arrays(&self, pair_pubkey: &Pubkey) -> Result<Vec<Pubkey>> {
         let bin_array_index = self.bin_id_to_bin_array_index(self.active_id)?;
 
@@ -176,12 +185,17 @@ impl DlmmInfo {
     fn derive_bin_array_pda(&self, lb_pair: &Pubkey, index: i64) -> Result<Pubkey> {
         let seeds = [BIN_ARRAY, lb_pair.as_ref(), &index.to_le_bytes()[0..8]];
 
-        let (pda, _) = Pubkey::find_program_address(&seeds, &dlmm_program_id());
+        let (pda, _) = find_program_address(&seeds, &ProgramRegistry::global().meteora_dlmm);
 
         Ok(pda)
     }
 }
 
+/// `(1 + bin_step / 10000) ^ bin_id`
+pub fn price_from_bin_id(bin_id: i32, bin_step: u16) -> f64 {
+    (1.0 + bin_step as f64 / 10_000.0).powi(bin_id)
+}
+
 impl LbPair {
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
         if data.len() < size_of::<Self>() {
@@ -193,3 +207,54 @@ impl LbPair {
         Ok(lb_pair)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::offset_of;
+
+    /// Account data of an LbPair with only the active bin and bin step set
+    fn lb_pair_account(active_id: i32, bin_step: u16) -> Vec<u8> {
+        let mut data = vec![0u8; 8 + size_of::<LbPair>()];
+        let active_id_offset = 8 + offset_of!(LbPair, active_id);
+        let bin_step_offset = 8 + offset_of!(LbPair, bin_step);
+        data[active_id_offset..active_id_offset + 4].copy_from_slice(&active_id.to_le_bytes());
+        data[bin_step_offset..bin_step_offset + 2].copy_from_slice(&bin_step.to_le_bytes());
+        data
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            ((actual - expected) / expected).abs() < 1e-12,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn price_of_a_sol_usdc_pool() {
+        // SOL/USDC with bin step 4 at active bin -4744: 1.0004^-4744 USDC raw units per
+        // lamport, about $149.98 once scaled by the 9 - 6 decimals
+        let info = DlmmInfo::load_checked(&lb_pair_account(-4744, 4)).unwrap();
+        assert_eq!((info.active_id, info.bin_step), (-4744, 4));
+        assert_close(info.price_from_active_id(), 0.14998491131561187);
+        assert_eq!(
+            (info.price_from_active_id() * 1e3 * 100.0).round() / 100.0,
+            149.98
+        );
+    }
+
+    #[test]
+    fn price_from_bin_id_matches_reference_values() {
+        assert_eq!(price_from_bin_id(0, 25), 1.0);
+        assert_close(price_from_bin_id(1, 25), 1.0025);
+        assert_close(price_from_bin_id(-1000, 25), 0.08234148740757744);
+        assert_close(price_from_bin_id(12345, 1), 3.4364476540328197);
+        // Bins at opposite ids quote reciprocal prices
+        assert_close(
+            price_from_bin_id(-300, 80) * price_from_bin_id(300, 80),
+            1.0,
+        );
+    }
+}
@@ -1,10 +1,19 @@
+use crate::pda::find_program_address;
+use crate::program_registry::ProgramRegistry;
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
 
-use super::constants::pump_program_id;
+use super::constants::{pump_fee_wallet, pump_mayhem_fee_wallet};
 
 const COIN_CREATOR_VAULT_SEED: &[u8] = b"creator_vault";
 
+/// Pool layouts, each appending one field to the previous: the original pool, then
+/// `coin_creator`, `is_mayhem_mode` and `is_cashback_coin`
+pub const PUMP_LAYOUT_ORIGINAL: u8 = 1;
+pub const PUMP_LAYOUT_COIN_CREATOR: u8 = 2;
+pub const PUMP_LAYOUT_MAYHEM: u8 = 3;
+pub const PUMP_LAYOUT_CASHBACK: u8 = 4;
+
 #[derive(Debug)]
 pub struct PumpAmmInfo {
     pub base_mint: Pubkey,
@@ -15,6 +24,8 @@ pub struct PumpAmmInfo {
     pub coin_creator_vault_authority: Pubkey,
     pub is_mayhem_mode: bool,
     pub is_cashback_coin: bool,
+    /// Newest `PUMP_LAYOUT_*` the account is large enough to hold
+    pub layout_version: u8,
 }
 
 impl PumpAmmInfo {
@@ -60,12 +71,22 @@ impl PumpAmmInfo {
             data[is_cashback_coin_offset] != 0
         };
 
+        let layout_version = if is_cashback_coin_offset < data.len() {
+            PUMP_LAYOUT_CASHBACK
+        } else if is_mayhem_mode_offset < data.len() {
+            PUMP_LAYOUT_MAYHEM
+        } else if coin_creator_offset + 32 <= data.len() {
+            PUMP_LAYOUT_COIN_CREATOR
+        } else {
+            PUMP_LAYOUT_ORIGINAL
+        };
+
         let coin_creator_vault_authority = if coin_creator == Pubkey::default() {
             Pubkey::default()
         } else {
-            Pubkey::find_program_address(
+            find_program_address(
                 &[COIN_CREATOR_VAULT_SEED, coin_creator.as_ref()],
-                &pump_program_id(),
+                &ProgramRegistry::global().pump,
             )
             .0
         };
@@ -79,6 +100,22 @@ impl PumpAmmInfo {
             coin_creator_vault_authority,
             is_mayhem_mode,
             is_cashback_coin,
+            layout_version,
         })
     }
 }
+
+impl PumpAmmInfo {
+    /// Picks a protocol fee wallet for the pool's fee mode and its quote token account
+    pub fn fee_wallets(&self) -> (Pubkey, Pubkey) {
+        let wallet = if self.is_mayhem_mode {
+            pump_mayhem_fee_wallet()
+        } else {
+            pump_fee_wallet()
+        };
+        (
+            wallet,
+            spl_associated_token_account::get_associated_token_address(&wallet, &self.quote_mint),
+        )
+    }
+}
@@ -1,3 +1,4 @@
+use crate::pda::find_program_address;
 use solana_program::pubkey::Pubkey;
 use std::str::FromStr;
 
@@ -23,11 +24,29 @@ pub const PUMP_MAYHEM_FEE_WALLETS: [&str; 8] = [
     "6AUH3WEHucYZyC61hqpqYUWVto5qA5hjHuNQ32GNnNxA",
 ];
 pub const PUMP_SWAP_FEE_RECIPIENT: &str = "EHAAiTxcdDwQ3U4bU6YcMsQGaekdzLS3B5SmYo46kJtL";
+pub const PUMP_GLOBAL_CONFIG: &str = "ADyA8hdefvWN2dbGGWFotbzWxrAvLW83WG6QCVXvJKqw";
+pub const PUMP_EVENT_AUTHORITY: &str = "GS4CU59F31iL7aR2Q8zVS8DRrcRnXX1yjQ66TqNVQnaR";
 
 pub fn pump_program_id() -> Pubkey {
     Pubkey::from_str(PUMP_PROGRAM_ID).unwrap()
 }
 
+/// Global config of the given deployment
+pub fn pump_global_config_for(program_id: &Pubkey) -> Pubkey {
+    if *program_id == pump_program_id() {
+        return Pubkey::from_str(PUMP_GLOBAL_CONFIG).unwrap();
+    }
+    find_program_address(&[b"global_config"], program_id).0
+}
+
+/// Event authority of the given deployment
+pub fn pump_event_authority_for(program_id: &Pubkey) -> Pubkey {
+    if *program_id == pump_program_id() {
+        return Pubkey::from_str(PUMP_EVENT_AUTHORITY).unwrap();
+    }
+    find_program_address(&[b"__event_authority"], program_id).0
+}
+
 pub fn pump_fee_wallet() -> Pubkey {
     let idx = rand::random::<usize>() % PUMP_FEE_WALLETS.len();
     Pubkey::from_str(PUMP_FEE_WALLETS[idx]).unwrap()
@@ -5,6 +5,13 @@ const COIN_VAULT_OFFSET: usize = 336; // coinVault/tokenVaultA
 const PC_VAULT_OFFSET: usize = 368; // pcVault/tokenVaultB
 const COIN_MINT_OFFSET: usize = 400; // coinMint/tokenMintA
 const PC_MINT_OFFSET: usize = 432; // pcMint/tokenMintB
+/// Size of the AMM V4 `AmmInfo` account
+const AMM_INFO_V4_LEN: usize = 752;
+
+/// Layout of an `AmmInfo` account of the V4 size
+pub const RAYDIUM_LAYOUT_V4: u8 = 4;
+/// Any other size: the vault and mint offsets are assumed to match V4
+pub const RAYDIUM_LAYOUT_UNKNOWN: u8 = 0;
 
 #[derive(Debug)]
 pub struct RaydiumAmmInfo {
@@ -12,6 +19,8 @@ pub struct RaydiumAmmInfo {
     pub pc_mint: Pubkey,
     pub coin_vault: Pubkey,
     pub pc_vault: Pubkey,
+    /// `RAYDIUM_LAYOUT_V4`, or `RAYDIUM_LAYOUT_UNKNOWN` for accounts of another size
+    pub layout_version: u8,
 }
 
 impl RaydiumAmmInfo {
@@ -25,11 +34,18 @@ impl RaydiumAmmInfo {
         let coin_mint = Pubkey::new(&data[COIN_MINT_OFFSET..COIN_MINT_OFFSET + 32]);
         let pc_mint = Pubkey::new(&data[PC_MINT_OFFSET..PC_MINT_OFFSET + 32]);
         
+        let layout_version = if data.len() == AMM_INFO_V4_LEN {
+            RAYDIUM_LAYOUT_V4
+        } else {
+            RAYDIUM_LAYOUT_UNKNOWN
+        };
+
         Ok(Self {
             coin_mint,
             pc_mint,
             coin_vault,
             pc_vault,
+            layout_version,
         })
     }
 }
@@ -1,3 +1,4 @@
+use crate::pda::find_program_address;
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
 
@@ -166,9 +167,14 @@ impl PoolState {
         let tick_spacing = u16::from_le_bytes(tick_spacing_bytes);
         offset += 2;
 
+        let mut liquidity_bytes = [0u8; 16];
+        liquidity_bytes.copy_from_slice(&data[offset..offset + 16]);
+        let liquidity = u128::from_le_bytes(liquidity_bytes);
         offset += 16;
 
-        // Skip sqrt_price_x64
+        let mut sqrt_price_bytes = [0u8; 16];
+        sqrt_price_bytes.copy_from_slice(&data[offset..offset + 16]);
+        let sqrt_price_x64 = u128::from_le_bytes(sqrt_price_bytes);
         offset += 16;
 
         let mut tick_current_bytes = [0u8; 4];
@@ -193,6 +199,8 @@ impl PoolState {
             token_vault_1,
             observation_key,
             tick_spacing,
+            liquidity,
+            sqrt_price_x64,
             tick_current,
             tick_array_bitmap,
             ..Default::default()
@@ -272,7 +280,7 @@ pub fn get_tick_array_pubkeys(
             &offset_start_index.to_be_bytes(),
         ];
 
-        let (pubkey, _) = Pubkey::find_program_address(seeds, raydium_clmm_program_id);
+        let (pubkey, _) = find_program_address(seeds, raydium_clmm_program_id);
         result.push(pubkey);
     }
 
@@ -471,7 +479,7 @@ fn derive_tick_array_pubkeys(
                 pool_pubkey.as_ref(),
                 &start.to_be_bytes(),
             ];
-            Pubkey::find_program_address(seeds, program_id).0
+            find_program_address(seeds, program_id).0
         })
         .collect()
 }
@@ -1,3 +1,4 @@
+use crate::pda::find_program_address;
 use solana_program::pubkey::Pubkey;
 use std::str::FromStr;
 
@@ -9,7 +10,18 @@ pub fn raydium_authority() -> Pubkey {
     Pubkey::from_str("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1").unwrap()
 }
 
+/// AMM V4 devnet deployment
+pub fn raydium_devnet_program_id() -> Pubkey {
+    Pubkey::from_str("HWy1jotHpo6UqeQxx49dpYYdQB8wj9Qk9MdxwjLvDHB8").unwrap()
+}
 
+/// AMM V4 authority of the given deployment
+pub fn raydium_authority_for(program_id: &Pubkey) -> Pubkey {
+    if *program_id == raydium_program_id() {
+        return raydium_authority();
+    }
+    find_program_address(&[b"amm authority"], program_id).0
+}
 
 pub fn raydium_cp_program_id() -> Pubkey {
     Pubkey::from_str("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C").unwrap()
@@ -19,6 +31,24 @@ pub fn raydium_cp_authority() -> Pubkey {
     Pubkey::from_str("GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL").unwrap()
 }
 
+/// CP-Swap devnet deployment
+pub fn raydium_cp_devnet_program_id() -> Pubkey {
+    Pubkey::from_str("CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW").unwrap()
+}
+
+/// CP-Swap vault authority of the given deployment
+pub fn raydium_cp_authority_for(program_id: &Pubkey) -> Pubkey {
+    if *program_id == raydium_cp_program_id() {
+        return raydium_cp_authority();
+    }
+    find_program_address(&[b"vault_and_lp_mint_auth_seed"], program_id).0
+}
+
 pub fn raydium_clmm_program_id() -> Pubkey {
     Pubkey::from_str("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK").unwrap()
 }
+
+/// CLMM devnet deployment
+pub fn raydium_clmm_devnet_program_id() -> Pubkey {
+    Pubkey::from_str("devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH").unwrap()
+}
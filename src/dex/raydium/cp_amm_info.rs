@@ -11,6 +11,10 @@ const TOKEN_1_MINT_OFFSET: usize = 200; // token_1_mint
 const TOKEN_0_PROGRAM_OFFSET: usize = 232; // token_0_program
 const TOKEN_1_PROGRAM_OFFSET: usize = 264; // token_1_program
 const OBSERVATION_KEY_OFFSET: usize = 296; // observation_key
+const PROTOCOL_FEES_TOKEN_0_OFFSET: usize = 341; // protocol_fees_token_0
+const PROTOCOL_FEES_TOKEN_1_OFFSET: usize = 349; // protocol_fees_token_1
+const FUND_FEES_TOKEN_0_OFFSET: usize = 357; // fund_fees_token_0
+const FUND_FEES_TOKEN_1_OFFSET: usize = 365; // fund_fees_token_1
 
 #[derive(Debug)]
 pub struct RaydiumCpAmmInfo {
@@ -20,11 +24,16 @@ pub struct RaydiumCpAmmInfo {
     pub token_1_vault: Pubkey,
     pub amm_config: Pubkey,
     pub observation_key: Pubkey,
+    /// Protocol and fund fees accrued in the vaults and not yet collected
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
 }
 
 impl RaydiumCpAmmInfo {
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        if data.len() < OBSERVATION_KEY_OFFSET + 32 {
+        if data.len() < FUND_FEES_TOKEN_1_OFFSET + 8 {
             return Err(anyhow::anyhow!("Invalid data length for RaydiumCpAmmInfo"));
         }
         
@@ -34,6 +43,10 @@ impl RaydiumCpAmmInfo {
         let token_1_mint = Pubkey::new(&data[TOKEN_1_MINT_OFFSET..TOKEN_1_MINT_OFFSET + 32]);
         let amm_config = Pubkey::new(&data[AMM_CONFIG_OFFSET..AMM_CONFIG_OFFSET + 32]);
         let observation_key = Pubkey::new(&data[OBSERVATION_KEY_OFFSET..OBSERVATION_KEY_OFFSET + 32]);
+        let protocol_fees_token_0 = read_u64(data, PROTOCOL_FEES_TOKEN_0_OFFSET);
+        let protocol_fees_token_1 = read_u64(data, PROTOCOL_FEES_TOKEN_1_OFFSET);
+        let fund_fees_token_0 = read_u64(data, FUND_FEES_TOKEN_0_OFFSET);
+        let fund_fees_token_1 = read_u64(data, FUND_FEES_TOKEN_1_OFFSET);
         
         Ok(Self {
             token_0_mint,
@@ -42,6 +55,25 @@ impl RaydiumCpAmmInfo {
             token_1_vault,
             amm_config,
             observation_key,
+            protocol_fees_token_0,
+            protocol_fees_token_1,
+            fund_fees_token_0,
+            fund_fees_token_1,
         })
     }
+
+    /// Fees accrued in `vault` that are not part of the swappable reserve
+    pub fn accrued_fees(&self, vault: &Pubkey) -> u64 {
+        if *vault == self.token_0_vault {
+            self.protocol_fees_token_0.saturating_add(self.fund_fees_token_0)
+        } else if *vault == self.token_1_vault {
+            self.protocol_fees_token_1.saturating_add(self.fund_fees_token_1)
+        } else {
+            0
+        }
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
 }
@@ -3,7 +3,7 @@ pub mod constants;
 pub mod cp_amm_info;
 pub mod clmm_info;
 
-pub use amm_info::RaydiumAmmInfo;
+pub use amm_info::{RaydiumAmmInfo, RAYDIUM_LAYOUT_UNKNOWN, RAYDIUM_LAYOUT_V4};
 pub use constants::*;
 pub use cp_amm_info::RaydiumCpAmmInfo;
 pub use clmm_info::{
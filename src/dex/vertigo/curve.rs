@@ -0,0 +1,147 @@
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// Vertigo's bonding curve: constant product over the real token reserve and the base
+/// reserve plus a virtual `shift`, so a launch starts at a non-zero price before any base
+/// is deposited. The fee is charged on the base side of the swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VertigoCurve {
+    pub enabled: bool,
+    /// Real base (SOL) reserve held by the pool
+    pub base_reserve: u64,
+    pub token_reserve: u64,
+    /// Virtual base reserve added to `base_reserve` on the curve
+    pub shift: u128,
+    pub fee_bps: u64,
+}
+
+impl VertigoCurve {
+    /// Disabled pools, pools with an empty side and pools charging a fee above 100% cannot
+    /// be traded against
+    fn tradeable(&self) -> bool {
+        self.enabled
+            && self.token_reserve > 0
+            && self.virtual_base() > 0
+            && self.fee_bps as u128 <= BPS_DENOMINATOR
+    }
+
+    fn virtual_base(&self) -> u128 {
+        self.base_reserve as u128 + self.shift
+    }
+
+    fn fee(&self, amount: u128) -> u128 {
+        (amount * self.fee_bps as u128).div_ceil(BPS_DENOMINATOR)
+    }
+
+    /// Tokens received for `base_in`, with the fee taken from the input
+    pub fn buy(&self, base_in: u64) -> Option<u64> {
+        if !self.tradeable() {
+            return None;
+        }
+        let base_in = base_in as u128;
+        let net_in = base_in - self.fee(base_in);
+        let virtual_base = self.virtual_base();
+        let token_reserve = self.token_reserve as u128;
+        let token_out = token_reserve * net_in / (virtual_base + net_in);
+        (token_out < token_reserve).then_some(token_out as u64)
+    }
+
+    /// Base received for `token_in`, with the fee taken from the output. None when the
+    /// curve output exceeds the real base reserve the pool holds.
+    pub fn sell(&self, token_in: u64) -> Option<u64> {
+        if !self.tradeable() {
+            return None;
+        }
+        let token_in = token_in as u128;
+        let virtual_base = self.virtual_base();
+        let gross_out = virtual_base * token_in / (self.token_reserve as u128 + token_in);
+        if gross_out > self.base_reserve as u128 {
+            return None;
+        }
+        Some((gross_out - self.fee(gross_out)) as u64)
+    }
+
+    /// Spot price in raw base units per raw token unit, before fees
+    pub fn spot_price(&self) -> Option<f64> {
+        self.tradeable().then(|| self.virtual_base() as f64 / self.token_reserve as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1,000 real + 9,000 virtual base against 1,000,000 tokens, 1% fee
+    fn curve() -> VertigoCurve {
+        VertigoCurve {
+            enabled: true,
+            base_reserve: 1_000,
+            token_reserve: 1_000_000,
+            shift: 9_000,
+            fee_bps: 100,
+        }
+    }
+
+    #[test]
+    fn buy_takes_the_fee_from_the_input() {
+        // fee ceil(1,000 * 1%) = 10, 1,000,000 * 990 / (10,000 + 990) = 90,081.89
+        assert_eq!(curve().buy(1_000), Some(90_081));
+        // The fee rounds up, leaving nothing of a 1 lamport input
+        assert_eq!(curve().buy(1), Some(0));
+    }
+
+    #[test]
+    fn sell_takes_the_fee_from_the_output() {
+        // 10,000 * 100,000 / 1,100,000 = 909.09, fee ceil(909 * 1%) = 10
+        assert_eq!(curve().sell(100_000), Some(899));
+        // 10,000 * 200,000 / 1,200,000 = 1,666 is more than the 1,000 real base reserve
+        assert_eq!(curve().sell(200_000), None);
+    }
+
+    #[test]
+    fn spot_price_includes_the_shift() {
+        assert_eq!(curve().spot_price(), Some(0.01));
+    }
+
+    #[test]
+    fn full_fee_leaves_no_output() {
+        let curve = VertigoCurve {
+            fee_bps: 10_000,
+            ..curve()
+        };
+        assert_eq!(curve.buy(1_000), Some(0));
+        assert_eq!(curve.sell(100_000), Some(0));
+    }
+
+    #[test]
+    fn fee_above_100_percent_is_not_tradeable() {
+        let curve = VertigoCurve {
+            fee_bps: 10_001,
+            ..curve()
+        };
+        assert_eq!(curve.buy(1_000), None);
+        assert_eq!(curve.sell(100_000), None);
+        assert_eq!(curve.spot_price(), None);
+    }
+
+    #[test]
+    fn disabled_or_empty_pools_are_not_tradeable() {
+        let disabled = VertigoCurve {
+            enabled: false,
+            ..curve()
+        };
+        let no_tokens = VertigoCurve {
+            token_reserve: 0,
+            ..curve()
+        };
+        let no_base = VertigoCurve {
+            base_reserve: 0,
+            shift: 0,
+            ..curve()
+        };
+        for curve in [disabled, no_tokens, no_base] {
+            assert_eq!(curve.buy(1_000), None);
+            assert_eq!(curve.sell(1_000), None);
+            assert_eq!(curve.spot_price(), None);
+        }
+    }
+}
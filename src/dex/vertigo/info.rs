@@ -1,3 +1,5 @@
+use crate::pda::find_program_address;
+use crate::program_registry::ProgramRegistry;
 use anyhow::Result;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
@@ -55,7 +57,5 @@ impl VertigoInfo {
 
 /// Helper function to derive vault PDA
 pub fn derive_vault_address(pool: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
-    use crate::dex::vertigo::constants::vertigo_program_id;
-
-    Pubkey::find_program_address(&[pool.as_ref(), mint.as_ref()], &vertigo_program_id())
+    find_program_address(&[pool.as_ref(), mint.as_ref()], &ProgramRegistry::global().vertigo)
 }
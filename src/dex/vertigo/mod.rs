@@ -1,5 +1,9 @@
 pub mod constants;
+pub mod curve;
 pub mod info;
+pub mod vaults;
 
 pub use constants::*;
+pub use curve::*;
 pub use info::*;
+pub use vaults::*;
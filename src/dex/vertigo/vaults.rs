@@ -0,0 +1,144 @@
+use crate::account_batcher::AccountBatcher;
+use crate::pricing::{token_account_mint, token_amount};
+use crate::token_program::classify_token_program;
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+
+/// Vaults holding fewer raw units than this are treated as drained
+pub const VERTIGO_DUST_AMOUNT: u64 = 1_000;
+
+/// Fetches a pool's derived vaults, given as `(vault, expected mint)`, in one request and
+/// returns why the pool cannot be traded: a vault that was never created, is not a token
+/// account of the expected mint, or holds only dust
+pub fn vertigo_vault_problem(
+    rpc_client: &RpcClient,
+    vaults: &[(Pubkey, Pubkey); 2],
+) -> Result<Option<String>> {
+    let addresses = [vaults[0].0, vaults[1].0];
+    let accounts = AccountBatcher::global().get_multiple_accounts(rpc_client, &addresses)?;
+    Ok(vault_problem(vaults, &accounts))
+}
+
+/// `vertigo_vault_problem` over already fetched vault accounts
+fn vault_problem(vaults: &[(Pubkey, Pubkey); 2], accounts: &[Option<Account>]) -> Option<String> {
+    for ((vault, expected_mint), account) in vaults.iter().zip(accounts) {
+        let Some(account) = account else {
+            return Some(format!("vault {} does not exist", vault));
+        };
+        if classify_token_program(&account.owner).is_err() {
+            return Some(format!(
+                "vault {} is owned by {}, not a token program",
+                vault, account.owner
+            ));
+        }
+        match token_account_mint(&account.data) {
+            Some(mint) if mint == *expected_mint => {}
+            Some(mint) => {
+                return Some(format!(
+                    "vault {} holds mint {}, expected {}",
+                    vault, mint, expected_mint
+                ))
+            }
+            None => return Some(format!("vault {} is not a token account", vault)),
+        }
+        let amount = token_amount(&account.data).unwrap_or(0);
+        if amount < VERTIGO_DUST_AMOUNT {
+            return Some(format!("vault {} is drained ({} units)", vault, amount));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SPL token account of `mint` holding `amount`
+    fn token_account(mint: &Pubkey, amount: u64) -> Option<Account> {
+        let mut data = vec![0u8; 165];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        Some(Account {
+            lamports: 1,
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        })
+    }
+
+    fn vaults() -> [(Pubkey, Pubkey); 2] {
+        [
+            (Pubkey::new_unique(), Pubkey::new_unique()),
+            (Pubkey::new_unique(), Pubkey::new_unique()),
+        ]
+    }
+
+    #[test]
+    fn funded_vaults_of_the_expected_mints_are_tradeable() {
+        let vaults = vaults();
+        let accounts = [
+            token_account(&vaults[0].1, VERTIGO_DUST_AMOUNT),
+            token_account(&vaults[1].1, 5_000_000),
+        ];
+        assert_eq!(vault_problem(&vaults, &accounts), None);
+    }
+
+    #[test]
+    fn missing_vault_is_reported() {
+        let vaults = vaults();
+        let accounts = [token_account(&vaults[0].1, 5_000_000), None];
+        assert_eq!(
+            vault_problem(&vaults, &accounts),
+            Some(format!("vault {} does not exist", vaults[1].0))
+        );
+    }
+
+    #[test]
+    fn vault_of_the_wrong_mint_is_reported() {
+        let vaults = vaults();
+        let other_mint = Pubkey::new_unique();
+        let accounts = [
+            token_account(&other_mint, 5_000_000),
+            token_account(&vaults[1].1, 5_000_000),
+        ];
+        assert_eq!(
+            vault_problem(&vaults, &accounts),
+            Some(format!(
+                "vault {} holds mint {}, expected {}",
+                vaults[0].0, other_mint, vaults[0].1
+            ))
+        );
+    }
+
+    #[test]
+    fn vault_owned_by_another_program_is_reported() {
+        let vaults = vaults();
+        let owner = Pubkey::new_unique();
+        let mut account = token_account(&vaults[0].1, 5_000_000).unwrap();
+        account.owner = owner;
+        let accounts = [Some(account), token_account(&vaults[1].1, 5_000_000)];
+        assert_eq!(
+            vault_problem(&vaults, &accounts),
+            Some(format!(
+                "vault {} is owned by {}, not a token program",
+                vaults[0].0, owner
+            ))
+        );
+    }
+
+    #[test]
+    fn drained_vault_is_reported() {
+        let vaults = vaults();
+        let accounts = [
+            token_account(&vaults[0].1, 5_000_000),
+            token_account(&vaults[1].1, VERTIGO_DUST_AMOUNT - 1),
+        ];
+        assert_eq!(
+            vault_problem(&vaults, &accounts),
+            Some(format!("vault {} is drained (999 units)", vaults[1].0))
+        );
+    }
+}
@@ -3,11 +3,15 @@ pub mod state;
 
 use crate::dex::whirlpool::state::{Whirlpool, TICK_ARRAY_SIZE};
 use crate::dex::whirlpool::constants::{MAX_TICK_INDEX, MIN_TICK_INDEX};
+use crate::pda::find_program_address;
 use solana_program::instruction::AccountMeta;
 use solana_program::pubkey::Pubkey;
 
 pub type TickArrayStartIndexes = (i32, Option<i32>, Option<i32>);
 
+/// Start index of the tick array holding `curr_tick`, Orca's
+/// `floor(tick / (tick_spacing * 88)) * tick_spacing * 88`. `%` keeps the sign of the tick,
+/// so negative ticks off an array boundary step down one more array.
 pub fn derive_start_tick(curr_tick: i32, tick_spacing: u16) -> i32 {
     let num_of_ticks_in_array = TICK_ARRAY_SIZE as i32 * tick_spacing as i32;
     let rem = curr_tick % num_of_ticks_in_array;
@@ -69,7 +73,7 @@ pub fn get_tick_array_address(
         start_tick_index_str.as_bytes(),
     ];
 
-    Pubkey::find_program_address(seeds, program_id).0
+    find_program_address(seeds, program_id).0
 }
 
 pub fn update_tick_array_accounts_for_onchain(
@@ -111,3 +115,83 @@ pub fn update_tick_array_accounts_for_onchain(
     ];
     tick_array_pks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Orca's reference: `floor(tick / (tick_spacing * 88)) * tick_spacing * 88`
+    fn reference_start_tick(tick: i32, tick_spacing: u16) -> i32 {
+        let ticks_in_array = tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+        tick.div_euclid(ticks_in_array) * ticks_in_array
+    }
+
+    #[test]
+    fn start_tick_matches_orca_reference() {
+        // (tick_current_index, tick_spacing, expected start index)
+        let cases: [(i32, u16, i32); 24] = [
+            (0, 1, 0),
+            (1, 1, 0),
+            (87, 1, 0),
+            (88, 1, 88),
+            (-1, 1, -88),
+            (-88, 1, -88),
+            (-89, 1, -176),
+            (100, 8, 0),
+            (-100, 8, -704),
+            (704, 8, 704),
+            (-704, 8, -704),
+            (-705, 8, -1408),
+            (5000, 64, 0),
+            (-5000, 64, -5632),
+            (12345, 128, 11264),
+            (-12345, 128, -22528),
+            (22528, 256, 22528),
+            (-1, 256, -22528),
+            (MAX_TICK_INDEX, 1, 443608),
+            (MIN_TICK_INDEX, 1, -443696),
+            (MAX_TICK_INDEX, 64, 439296),
+            (MIN_TICK_INDEX, 64, -444928),
+            (MAX_TICK_INDEX, 32768, 0),
+            (MIN_TICK_INDEX, 32768, -2883584),
+        ];
+        for (tick, tick_spacing, expected) in cases {
+            assert_eq!(
+                derive_start_tick(tick, tick_spacing),
+                expected,
+                "tick {} spacing {}",
+                tick,
+                tick_spacing
+            );
+            assert_eq!(expected, reference_start_tick(tick, tick_spacing));
+        }
+    }
+
+    #[test]
+    fn start_tick_matches_reference_across_tick_range() {
+        for tick_spacing in [1u16, 2, 4, 8, 16, 32, 64, 96, 128, 256, 32768] {
+            for tick in (MIN_TICK_INDEX..=MAX_TICK_INDEX).step_by(997) {
+                assert_eq!(
+                    derive_start_tick(tick, tick_spacing),
+                    reference_start_tick(tick, tick_spacing),
+                    "tick {} spacing {}",
+                    tick,
+                    tick_spacing
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tick_array_sequence_steps_by_one_array() {
+        let (first, second, third) = derive_tick_array_start_indexes(-100, 8, true);
+        assert_eq!(first, -704);
+        assert_eq!(second, Some(-1408));
+        assert_eq!(third, Some(-2112));
+
+        // b to a shifts the current tick by one spacing before taking its array
+        let (first, second, _) = derive_tick_array_start_indexes(703, 8, false);
+        assert_eq!(first, 704);
+        assert_eq!(second, Some(1408));
+    }
+}
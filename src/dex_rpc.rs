@@ -0,0 +1,47 @@
+use crate::config::RpcConfig;
+use crate::loader::MarketPoolKind;
+use crate::rpc_metrics::{new_rpc_client, RpcMetrics};
+use crate::transaction::endpoint_label;
+use anyhow::{anyhow, Result};
+use solana_client::rpc_client::RpcClient;
+use std::collections::HashMap;
+use std::env;
+use std::sync::OnceLock;
+use tracing::info;
+
+static GLOBAL: OnceLock<DexRpcClients> = OnceLock::new();
+
+/// Read clients of the DEXes with their own endpoint in `rpc.dex_urls`. Pools of every
+/// other DEX are read through the primary client.
+#[derive(Default)]
+pub struct DexRpcClients {
+    clients: HashMap<MarketPoolKind, RpcClient>,
+}
+
+impl DexRpcClients {
+    /// Connects the `rpc.dex_urls` endpoints. Only the first call takes effect.
+    pub fn init(config: &RpcConfig, metrics: Option<&RpcMetrics>) -> Result<()> {
+        let mut clients = HashMap::new();
+        for (kind, url) in config.dex_urls.iter().flatten() {
+            let url = match url.strip_prefix('$') {
+                Some(var) => env::var(var).map_err(|_| {
+                    anyhow!("reading `{}` from env for rpc.dex_urls.{:?}", var, kind)
+                })?,
+                None => url.clone(),
+            };
+            info!("Reading {:?} pools through {}", kind, endpoint_label(&url));
+            clients.insert(*kind, new_rpc_client(url, metrics));
+        }
+        let _ = GLOBAL.set(Self { clients });
+        Ok(())
+    }
+
+    pub fn global() -> &'static DexRpcClients {
+        GLOBAL.get_or_init(DexRpcClients::default)
+    }
+
+    /// Client reading pools of `kind`: its own endpoint when configured, else `primary`
+    pub fn client<'a>(&'a self, kind: MarketPoolKind, primary: &'a RpcClient) -> &'a RpcClient {
+        self.clients.get(&kind).unwrap_or(primary)
+    }
+}
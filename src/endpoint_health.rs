@@ -0,0 +1,201 @@
+use crate::transaction::{endpoint_label, SendingClient};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+};
+use solana_client::rpc_request::RpcError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+static GLOBAL: OnceLock<EndpointHealth> = OnceLock::new();
+
+/// Slots a sending endpoint may trail the primary RPC before it is benched
+pub const DEFAULT_MAX_SLOT_LAG: u64 = 10;
+/// Interval of the slot lag probe, which also keeps the sending connections alive
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Lag and bench state of one sending endpoint, keyed by its host
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EndpointState {
+    /// Slots behind the primary RPC at the last probe, None before the first answer
+    pub slot_lag: Option<u64>,
+    pub benched: bool,
+    pub bench_events: u64,
+    pub unbench_events: u64,
+    pub node_behind_errors: u64,
+}
+
+/// Slot lag of the sending endpoints. Endpoints lagging more than `spam.max_slot_lag`
+/// slots, or answering a send with a node-behind error, are benched until the probe sees
+/// them caught up.
+#[derive(Debug)]
+pub struct EndpointHealth {
+    max_slot_lag: u64,
+    endpoints: Mutex<BTreeMap<String, EndpointState>>,
+}
+
+impl Default for EndpointHealth {
+    fn default() -> Self {
+        Self {
+            max_slot_lag: DEFAULT_MAX_SLOT_LAG,
+            endpoints: Mutex::default(),
+        }
+    }
+}
+
+impl EndpointHealth {
+    /// Reads `spam.max_slot_lag`. Only the first call takes effect.
+    pub fn init(max_slot_lag: Option<u64>) {
+        let _ = GLOBAL.set(Self {
+            max_slot_lag: max_slot_lag.unwrap_or(DEFAULT_MAX_SLOT_LAG),
+            endpoints: Mutex::default(),
+        });
+    }
+
+    pub fn global() -> &'static EndpointHealth {
+        GLOBAL.get_or_init(EndpointHealth::default)
+    }
+
+    pub fn state(&self, endpoint: &str) -> EndpointState {
+        self.endpoints.lock().unwrap().get(endpoint).copied().unwrap_or_default()
+    }
+
+    pub fn is_benched(&self, endpoint: &str) -> bool {
+        self.state(endpoint).benched
+    }
+
+    /// Records a probe of `endpoint`, benching it above the lag limit and unbenching it once
+    /// it is back within the limit
+    pub fn record_lag(&self, endpoint: &str, slot_lag: u64) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let state = endpoints.entry(endpoint.to_string()).or_default();
+        state.slot_lag = Some(slot_lag);
+        if slot_lag > self.max_slot_lag && !state.benched {
+            state.benched = true;
+            state.bench_events += 1;
+            warn!(
+                "Benching sending endpoint {}: {} slots behind the primary RPC (limit {})",
+                endpoint, slot_lag, self.max_slot_lag
+            );
+        } else if slot_lag <= self.max_slot_lag && state.benched {
+            state.benched = false;
+            state.unbench_events += 1;
+            info!(
+                "Sending endpoint {} caught up ({} slots behind), unbenching",
+                endpoint, slot_lag
+            );
+        }
+    }
+
+    /// Records a node-behind answer of `endpoint`, benching it until the next probe finds
+    /// it within the lag limit
+    pub fn record_node_behind(&self, endpoint: &str) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let state = endpoints.entry(endpoint.to_string()).or_default();
+        state.node_behind_errors += 1;
+        if !state.benched {
+            state.benched = true;
+            state.bench_events += 1;
+            warn!("Benching sending endpoint {}: it reports being behind", endpoint);
+        }
+    }
+
+    /// Prometheus text exposition of the lag, bench state and bench events per endpoint
+    pub fn render_prometheus(&self) -> String {
+        let endpoints = self.endpoints.lock().unwrap();
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE sending_endpoint_slot_lag gauge");
+        for (endpoint, state) in endpoints.iter() {
+            if let Some(slot_lag) = state.slot_lag {
+                let _ = writeln!(
+                    out,
+                    "sending_endpoint_slot_lag{{endpoint=\"{}\"}} {}",
+                    endpoint, slot_lag
+                );
+            }
+        }
+        let _ = writeln!(out, "# TYPE sending_endpoint_benched gauge");
+        for (endpoint, state) in endpoints.iter() {
+            let _ = writeln!(
+                out,
+                "sending_endpoint_benched{{endpoint=\"{}\"}} {}",
+                endpoint, state.benched as u8
+            );
+        }
+        let _ = writeln!(out, "# TYPE sending_endpoint_bench_events_total counter");
+        for (endpoint, state) in endpoints.iter() {
+            for (event, count) in [("bench", state.bench_events), ("unbench", state.unbench_events)]
+            {
+                let _ = writeln!(
+                    out,
+                    "sending_endpoint_bench_events_total{{endpoint=\"{}\",event=\"{}\"}} {}",
+                    endpoint, event, count
+                );
+            }
+        }
+        let _ = writeln!(out, "# TYPE sending_endpoint_node_behind_errors_total counter");
+        for (endpoint, state) in endpoints.iter() {
+            let _ = writeln!(
+                out,
+                "sending_endpoint_node_behind_errors_total{{endpoint=\"{}\"}} {}",
+                endpoint, state.node_behind_errors
+            );
+        }
+        out
+    }
+}
+
+/// Whether a send failed because the node is behind: an unhealthy node or a
+/// `minContextSlot` it has not reached yet
+pub fn is_node_behind(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. }) => {
+            *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                || *code == JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+                || is_node_behind_message(message)
+        }
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(message)) => {
+            is_node_behind_message(message)
+        }
+        _ => false,
+    }
+}
+
+fn is_node_behind_message(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("node is behind") || message.contains("minimum context slot")
+}
+
+/// Compares the slot of every sending endpoint with the primary RPC every few seconds and
+/// benches the ones lagging too far behind. The requests also keep idle connections open.
+pub async fn run_slot_lag_probe(primary: Arc<RpcClient>, clients: Vec<SendingClient>) {
+    let commitment = CommitmentConfig::processed();
+    loop {
+        tokio::time::sleep(PROBE_INTERVAL).await;
+        let primary_slot = match primary.get_slot_with_commitment(commitment) {
+            Ok(slot) => slot,
+            Err(e) => {
+                warn!("Slot lag probe could not read the primary slot: {}", e);
+                continue;
+            }
+        };
+        for sending in &clients {
+            let endpoint = endpoint_label(&sending.client.url());
+            match sending.client.get_slot_with_commitment(commitment) {
+                Ok(slot) => {
+                    EndpointHealth::global()
+                        .record_lag(&endpoint, primary_slot.saturating_sub(slot));
+                }
+                Err(e) if is_node_behind(&e) => {
+                    EndpointHealth::global().record_node_behind(&endpoint);
+                }
+                Err(e) => warn!("Slot lag probe of {} failed: {}", endpoint, e),
+            }
+        }
+    }
+}
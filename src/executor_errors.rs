@@ -0,0 +1,121 @@
+use crate::config::ExecutorConfig;
+use anyhow::{anyhow, Result};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use tracing::info;
+
+static GLOBAL: OnceLock<ExecutorErrors> = OnceLock::new();
+
+/// Reasons after which the mint's pools are refreshed right away instead of on schedule
+const POOL_REFRESH_REASONS: [&str; 3] =
+    ["missing_tick_array", "stale_tick_array", "bin_array_mismatch"];
+
+/// Where a failed executor transaction was observed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FailureSource {
+    Simulation,
+    Landed,
+}
+
+impl FailureSource {
+    fn label(self) -> &'static str {
+        match self {
+            FailureSource::Simulation => "simulation",
+            FailureSource::Landed => "landed",
+        }
+    }
+}
+
+/// Names for the executor's `InstructionError::Custom` codes from `executor.error_codes`,
+/// and a count of failures per source and reason
+#[derive(Debug, Default)]
+pub struct ExecutorErrors {
+    codes: HashMap<u32, String>,
+    failures: Mutex<BTreeMap<(FailureSource, String), u64>>,
+    triggered_refreshes: Mutex<BTreeMap<String, u64>>,
+}
+
+impl ExecutorErrors {
+    /// Reads `executor.error_codes`. Only the first call takes effect.
+    pub fn init(config: Option<&ExecutorConfig>) -> Result<()> {
+        let mut codes = HashMap::new();
+        for (code, reason) in config.and_then(|c| c.error_codes.as_ref()).into_iter().flatten() {
+            let code = code
+                .parse::<u32>()
+                .map_err(|_| anyhow!("Invalid executor.error_codes key {}", code))?;
+            codes.insert(code, reason.clone());
+        }
+        if !codes.is_empty() {
+            info!("Mapping {} executor error codes to reasons", codes.len());
+        }
+        let _ = GLOBAL.set(Self {
+            codes,
+            failures: Mutex::default(),
+            triggered_refreshes: Mutex::default(),
+        });
+        Ok(())
+    }
+
+    pub fn global() -> &'static ExecutorErrors {
+        GLOBAL.get_or_init(ExecutorErrors::default)
+    }
+
+    /// Reason for a failed transaction: the configured name of a custom error code, the
+    /// code itself when unmapped, or the error's description for non-custom errors
+    pub fn reason(&self, err: &TransactionError) -> String {
+        match err {
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => self
+                .codes
+                .get(code)
+                .cloned()
+                .unwrap_or_else(|| code.to_string()),
+            other => other.to_string(),
+        }
+    }
+
+    pub fn record(&self, source: FailureSource, reason: &str) {
+        let mut failures = self.failures.lock().unwrap();
+        *failures.entry((source, reason.to_string())).or_default() += 1;
+    }
+
+    /// Counts a pool refresh triggered by a failure with `reason`
+    pub fn record_triggered_refresh(&self, reason: &str) {
+        let mut triggered_refreshes = self.triggered_refreshes.lock().unwrap();
+        *triggered_refreshes.entry(reason.to_string()).or_default() += 1;
+    }
+
+    /// Prometheus text exposition of the failure and triggered refresh counts
+    pub fn render_prometheus(&self) -> String {
+        let failures = self.failures.lock().unwrap();
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE executor_failures_total counter");
+        for ((source, reason), count) in failures.iter() {
+            let _ = writeln!(
+                out,
+                "executor_failures_total{{source=\"{}\",reason=\"{}\"}} {}",
+                source.label(),
+                reason.replace('"', "'"),
+                count
+            );
+        }
+        let triggered_refreshes = self.triggered_refreshes.lock().unwrap();
+        let _ = writeln!(out, "# TYPE executor_triggered_refreshes_total counter");
+        for (reason, count) in triggered_refreshes.iter() {
+            let _ = writeln!(
+                out,
+                "executor_triggered_refreshes_total{{reason=\"{}\"}} {}",
+                reason.replace('"', "'"),
+                count
+            );
+        }
+        out
+    }
+}
+
+/// Whether a failure for `reason` calls for an immediate pool refresh of the mint
+pub fn triggers_pool_refresh(reason: &str) -> bool {
+    POOL_REFRESH_REASONS.contains(&reason)
+}
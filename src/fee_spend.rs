@@ -0,0 +1,95 @@
+use crate::accounting::TransactionPnl;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+
+static GLOBAL: OnceLock<FeeSpend> = OnceLock::new();
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+/// Days of per-day totals kept, today included
+const DAYS_KEPT: i64 = 7;
+
+/// Fees and tips of one mint's landed transactions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MintFeeSpend {
+    pub transactions: u64,
+    pub fee_lamports: u64,
+    pub tip_lamports: u64,
+    /// Fees and tips of transactions whose block time falls on the current UTC day
+    pub today_lamports: u64,
+}
+
+#[derive(Debug, Default)]
+struct FeeSpendState {
+    totals: BTreeMap<Pubkey, MintFeeSpend>,
+    /// Fees and tips by (UTC day number, mint)
+    daily: BTreeMap<(i64, Pubkey), u64>,
+}
+
+/// On-chain fee payer cost per mint, from the status meta of landed transactions. The
+/// transactions are attributed to the mint they were sent for, so wallets shared by several
+/// mints are split correctly.
+#[derive(Debug, Default)]
+pub struct FeeSpend {
+    state: Mutex<FeeSpendState>,
+}
+
+impl FeeSpend {
+    pub fn global() -> &'static FeeSpend {
+        GLOBAL.get_or_init(FeeSpend::default)
+    }
+
+    /// Adds the fee and tip of a transaction landed at unix time `timestamp` for `mint`
+    pub fn record(&self, mint: &Pubkey, timestamp: i64, pnl: &TransactionPnl) {
+        let spent = pnl.fee_lamports + pnl.tip_lamports;
+        let day = timestamp.div_euclid(SECS_PER_DAY);
+        let mut state = self.state.lock().unwrap();
+        let totals = state.totals.entry(*mint).or_default();
+        totals.transactions += 1;
+        totals.fee_lamports += pnl.fee_lamports;
+        totals.tip_lamports += pnl.tip_lamports;
+        *state.daily.entry((day, *mint)).or_default() += spent;
+        state.daily.retain(|(kept_day, _), _| day - kept_day < DAYS_KEPT);
+    }
+
+    /// Totals of `mint`, None before its first landed transaction
+    pub fn mint(&self, mint: &Pubkey, now: i64) -> Option<MintFeeSpend> {
+        let state = self.state.lock().unwrap();
+        let mut spend = *state.totals.get(mint)?;
+        let today = now.div_euclid(SECS_PER_DAY);
+        spend.today_lamports = state.daily.get(&(today, *mint)).copied().unwrap_or_default();
+        Some(spend)
+    }
+
+    /// Prometheus text exposition of the per-mint totals and today's spend
+    pub fn render_prometheus(&self, now: i64) -> String {
+        let state = self.state.lock().unwrap();
+        let today = now.div_euclid(SECS_PER_DAY);
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE fee_spend_lamports_total counter");
+        for (mint, spend) in &state.totals {
+            for (kind, lamports) in [("fee", spend.fee_lamports), ("tip", spend.tip_lamports)] {
+                let _ = writeln!(
+                    out,
+                    "fee_spend_lamports_total{{mint=\"{}\",kind=\"{}\"}} {}",
+                    mint, kind, lamports
+                );
+            }
+        }
+        let _ = writeln!(out, "# TYPE fee_spend_transactions_total counter");
+        for (mint, spend) in &state.totals {
+            let _ = writeln!(
+                out,
+                "fee_spend_transactions_total{{mint=\"{}\"}} {}",
+                mint, spend.transactions
+            );
+        }
+        let _ = writeln!(out, "# TYPE fee_spend_today_lamports gauge");
+        for mint in state.totals.keys() {
+            let lamports = state.daily.get(&(today, *mint)).copied().unwrap_or_default();
+            let _ = writeln!(out, "fee_spend_today_lamports{{mint=\"{}\"}} {}", mint, lamports);
+        }
+        out
+    }
+}
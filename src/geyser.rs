@@ -0,0 +1,146 @@
+use crate::config::GeyserConfig;
+use crate::pools::MintPoolData;
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Account update received from the Geyser stream
+#[derive(Debug, Clone)]
+pub struct PoolAccountUpdate {
+    pub pubkey: Pubkey,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+    pub slot: u64,
+}
+
+/// Accounts of `pool_data` whose updates can be applied without RPC: the pool accounts
+/// handled by `recompute_from_account` and the CLMM tick array bitmap extensions
+pub fn streamed_accounts(pool_data: &MintPoolData) -> Vec<Pubkey> {
+    let mut accounts: Vec<Pubkey> = pool_data.pump_pools.iter().map(|pool| pool.pool).collect();
+    accounts.extend(pool_data.raydium_cp_pools.iter().map(|pool| pool.pool));
+    accounts.extend(pool_data.dlmm_pairs.iter().map(|pool| pool.pair));
+    accounts.extend(pool_data.whirlpool_pools.iter().map(|pool| pool.pool));
+    for pool in &pool_data.raydium_clmm_pools {
+        accounts.extend([pool.pool, pool.bitmap_extension]);
+    }
+    for pool in &pool_data.pancakeswap_pools {
+        accounts.extend([pool.pool, pool.bitmap_extension]);
+    }
+    for pool in &pool_data.byreal_pools {
+        accounts.extend([pool.pool, pool.bitmap_extension]);
+    }
+    accounts.extend(pool_data.meteora_damm_v2_pools.iter().map(|pool| pool.pool));
+    accounts
+}
+
+/// Subscribes to every account in `routes` on the configured Yellowstone gRPC endpoint and
+/// forwards each update to the worker owning the account. Reconnects after stream errors.
+#[cfg(feature = "geyser")]
+pub fn spawn_geyser_stream(
+    config: &GeyserConfig,
+    routes: HashMap<Pubkey, UnboundedSender<PoolAccountUpdate>>,
+) -> Result<()> {
+    stream::spawn(config, routes)
+}
+
+#[cfg(not(feature = "geyser"))]
+pub fn spawn_geyser_stream(
+    _config: &GeyserConfig,
+    _routes: HashMap<Pubkey, UnboundedSender<PoolAccountUpdate>>,
+) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "[geyser] is configured but the bot was built without the `geyser` feature"
+    ))
+}
+
+#[cfg(feature = "geyser")]
+mod stream {
+    use super::PoolAccountUpdate;
+    use crate::config::GeyserConfig;
+    use anyhow::{anyhow, Result};
+    use futures::StreamExt;
+    use solana_sdk::pubkey::Pubkey;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tokio::sync::mpsc::UnboundedSender;
+    use tracing::{info, warn};
+    use yellowstone_grpc_client::GeyserGrpcClient;
+    use yellowstone_grpc_proto::prelude::{
+        subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+        SubscribeRequestFilterAccounts,
+    };
+
+    const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+    pub fn spawn(
+        config: &GeyserConfig,
+        routes: HashMap<Pubkey, UnboundedSender<PoolAccountUpdate>>,
+    ) -> Result<()> {
+        let commitment = match config.commitment.as_deref().unwrap_or("processed") {
+            "processed" => CommitmentLevel::Processed,
+            "confirmed" => CommitmentLevel::Confirmed,
+            "finalized" => CommitmentLevel::Finalized,
+            other => return Err(anyhow!("Invalid geyser.commitment {}", other)),
+        };
+        let request = SubscribeRequest {
+            accounts: HashMap::from([(
+                "pools".to_string(),
+                SubscribeRequestFilterAccounts {
+                    account: routes.keys().map(Pubkey::to_string).collect(),
+                    ..Default::default()
+                },
+            )]),
+            commitment: Some(commitment as i32),
+            ..Default::default()
+        };
+        let endpoint = config.endpoint.clone();
+        let x_token = config.x_token.clone();
+        info!("Streaming {} pool accounts from Geyser endpoint {}", routes.len(), endpoint);
+
+        tokio::spawn(async move {
+            loop {
+                match run(&endpoint, x_token.clone(), request.clone(), &routes).await {
+                    Ok(()) => warn!("Geyser stream {} ended, reconnecting", endpoint),
+                    Err(e) => warn!("Geyser stream {} failed: {}, reconnecting", endpoint, e),
+                }
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+        Ok(())
+    }
+
+    async fn run(
+        endpoint: &str,
+        x_token: Option<String>,
+        request: SubscribeRequest,
+        routes: &HashMap<Pubkey, UnboundedSender<PoolAccountUpdate>>,
+    ) -> Result<()> {
+        let mut client = GeyserGrpcClient::connect(endpoint.to_string(), x_token, None)?;
+        let (_sink, mut updates) = client.subscribe_with_request(Some(request)).await?;
+        while let Some(update) = updates.next().await {
+            let Some(UpdateOneof::Account(update)) = update?.update_oneof else {
+                continue;
+            };
+            let Some(account) = update.account else {
+                continue;
+            };
+            let (Ok(pubkey), Ok(owner)) = (
+                Pubkey::try_from(account.pubkey.as_slice()),
+                Pubkey::try_from(account.owner.as_slice()),
+            ) else {
+                continue;
+            };
+            if let Some(route) = routes.get(&pubkey) {
+                // The worker is gone when its receiver is dropped; nothing to deliver to
+                let _ = route.send(PoolAccountUpdate {
+                    pubkey,
+                    owner,
+                    data: account.data,
+                    slot: update.slot,
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use crate::pools::MintPoolData;
+use crate::pricing::{priced_from_reserves, PriceSnapshot};
+use anyhow::{bail, Result};
+
+/// Executor instruction discriminator: the single-byte instruction index the deployed
+/// executor dispatches its arbitrage instruction on. The per-DEX swap discriminators are
+/// encoded by the executor's own CPIs, so the bot never writes them.
+pub const ARB_IX_DISCRIMINATOR: u8 = 28;
+/// Length of the version 1 instruction data
+const ARB_IX_V1_LEN: usize = 17;
+
+/// Per-pool hint for the executor, one byte per pool in account-meta order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PoolFlags(pub u8);
+
+impl PoolFlags {
+    pub const NONE: PoolFlags = PoolFlags(0);
+    /// Leave the pool out of the route search
+    pub const SKIP: u8 = 1 << 0;
+    /// Off-chain pricing sees this pool as the cheapest venue
+    pub const PREFER_BUY: u8 = 1 << 1;
+    /// Off-chain pricing sees this pool as the most expensive venue
+    pub const PREFER_SELL: u8 = 1 << 2;
+
+    pub fn insert(&mut self, flag: u8) {
+        self.0 |= flag;
+    }
+}
+
+/// Instruction data of the executor's arbitrage instruction.
+///
+/// Version 1 is the fixed 17-byte layout. Version 2 appends a `u8` pool count
+/// followed by one `PoolFlags` byte per pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArbIxData {
+    pub minimum_profit: u64,
+    pub compute_unit_limit: u32,
+    /// When true the executor succeeds without trading when no route is profitable
+    pub no_failure_mode: bool,
+    pub use_flashloan: bool,
+    /// Only encoded when set (ix_version 2)
+    pub pool_flags: Option<Vec<PoolFlags>>,
+}
+
+impl ArbIxData {
+    /// Encodes the instruction data. Errors when more pool flags are set than the `u8`
+    /// pool count can announce.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut data = vec![ARB_IX_DISCRIMINATOR];
+        data.extend_from_slice(&self.minimum_profit.to_le_bytes());
+        data.extend_from_slice(&self.compute_unit_limit.to_le_bytes());
+        data.push(self.no_failure_mode as u8);
+        data.extend_from_slice(&0u16.to_le_bytes()); // reserved
+        data.push(self.use_flashloan as u8);
+        if let Some(flags) = &self.pool_flags {
+            let Ok(count) = u8::try_from(flags.len()) else {
+                bail!("{} pool flags, at most {} can be encoded", flags.len(), u8::MAX);
+            };
+            data.push(count);
+            data.extend(flags.iter().map(|flags| flags.0));
+        }
+        Ok(data)
+    }
+
+    /// Decodes instruction data written by `to_bytes`
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.len() < ARB_IX_V1_LEN || data[0] != ARB_IX_DISCRIMINATOR {
+            bail!("not an executor arbitrage instruction");
+        }
+        let pool_flags = match data.get(ARB_IX_V1_LEN) {
+            None => None,
+            Some(&count) => {
+                let flags = &data[ARB_IX_V1_LEN + 1..];
+                if flags.len() != count as usize {
+                    bail!("{} pool flags announced, {} present", count, flags.len());
+                }
+                Some(flags.iter().map(|&flags| PoolFlags(flags)).collect())
+            }
+        };
+        Ok(Self {
+            minimum_profit: u64::from_le_bytes(data[1..9].try_into()?),
+            compute_unit_limit: u32::from_le_bytes(data[9..13].try_into()?),
+            no_failure_mode: data[13] != 0,
+            use_flashloan: data[16] != 0,
+            pool_flags,
+        })
+    }
+}
+
+/// Flags for every pool of the mint, aligned with `MintPoolData::pool_vaults()`.
+/// Constant-product pools missing from the snapshot (empty or unreadable vaults) are
+/// skipped, and the widest spread's venues get direction hints.
+pub fn pool_flags_from_snapshot(
+    pool_data: &MintPoolData,
+    snapshot: Option<&PriceSnapshot>,
+) -> Vec<PoolFlags> {
+    let pools = pool_data.pool_vaults();
+    let Some(snapshot) = snapshot else {
+        return vec![PoolFlags::NONE; pools.len()];
+    };
+    let best_spread = snapshot.best_spread();
+
+    pools
+        .iter()
+        .map(|pool| {
+            let mut flags = PoolFlags::NONE;
+            let priced = snapshot.prices.iter().any(|price| price.pool == pool.pool);
+            if priced_from_reserves(pool.kind) && !priced {
+                flags.insert(PoolFlags::SKIP);
+            }
+            if let Some(spread) = &best_spread {
+                if spread.buy.pool == pool.pool {
+                    flags.insert(PoolFlags::PREFER_BUY);
+                } else if spread.sell.pool == pool.pool {
+                    flags.insert(PoolFlags::PREFER_SELL);
+                }
+            }
+            flags
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ix_data(pool_flags: Option<Vec<PoolFlags>>) -> ArbIxData {
+        ArbIxData {
+            minimum_profit: 1_234_567,
+            compute_unit_limit: 400_000,
+            no_failure_mode: true,
+            use_flashloan: false,
+            pool_flags,
+        }
+    }
+
+    #[test]
+    fn v1_round_trips() {
+        let data = ix_data(None);
+        let bytes = data.to_bytes().unwrap();
+        assert_eq!(bytes.len(), ARB_IX_V1_LEN);
+        assert_eq!(bytes[0], ARB_IX_DISCRIMINATOR);
+        assert_eq!(ArbIxData::from_bytes(&bytes).unwrap(), data);
+    }
+
+    #[test]
+    fn v2_round_trips() {
+        let flags = vec![
+            PoolFlags(PoolFlags::SKIP),
+            PoolFlags::NONE,
+            PoolFlags(PoolFlags::PREFER_BUY),
+            PoolFlags(PoolFlags::PREFER_SELL),
+        ];
+        let data = ix_data(Some(flags));
+        let bytes = data.to_bytes().unwrap();
+        assert_eq!(bytes.len(), ARB_IX_V1_LEN + 1 + 4);
+        assert_eq!(bytes[ARB_IX_V1_LEN], 4);
+        assert_eq!(ArbIxData::from_bytes(&bytes).unwrap(), data);
+    }
+
+    #[test]
+    fn encodes_up_to_255_pool_flags() {
+        let data = ix_data(Some(vec![PoolFlags::NONE; 255]));
+        let bytes = data.to_bytes().unwrap();
+        assert_eq!(bytes[ARB_IX_V1_LEN], 255);
+        assert_eq!(ArbIxData::from_bytes(&bytes).unwrap(), data);
+    }
+
+    #[test]
+    fn rejects_more_than_255_pool_flags() {
+        assert!(ix_data(Some(vec![PoolFlags::NONE; 256])).to_bytes().is_err());
+    }
+
+    #[test]
+    fn from_bytes_rejects_malformed_data() {
+        let mut bytes = ix_data(Some(vec![PoolFlags::NONE; 3])).to_bytes().unwrap();
+        bytes.pop();
+        assert!(ArbIxData::from_bytes(&bytes).is_err());
+        assert!(ArbIxData::from_bytes(&bytes[..ARB_IX_V1_LEN - 1]).is_err());
+        bytes[0] = ARB_IX_DISCRIMINATOR + 1;
+        assert!(ArbIxData::from_bytes(&bytes[..ARB_IX_V1_LEN]).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use crate::config::{JitoConfig, TipRotation};
+use anyhow::{anyhow, Result};
+use solana_program::instruction::Instruction;
+use solana_program::system_instruction;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use tracing::info;
+
+static GLOBAL: OnceLock<JitoTips> = OnceLock::new();
+
+/// Jito's official mainnet tip accounts
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// Tip transfer appended to every transaction when `[jito]` is configured. Each
+/// transaction tips the next account of the list, so consecutive sends do not all
+/// write-lock the same tip account.
+#[derive(Debug)]
+pub struct JitoTips {
+    lamports: u64,
+    accounts: Vec<Pubkey>,
+    rotation: TipRotation,
+    next: AtomicUsize,
+}
+
+impl JitoTips {
+    /// Reads `[jito]`. Only the first call takes effect.
+    pub fn init(config: Option<&JitoConfig>) -> Result<()> {
+        let Some(config) = config.filter(|config| config.tip_lamports > 0) else {
+            return Ok(());
+        };
+        let accounts = match &config.tip_accounts {
+            Some(accounts) => accounts
+                .iter()
+                .map(|account| {
+                    Pubkey::from_str(account)
+                        .map_err(|e| anyhow!("Invalid jito.tip_accounts {}: {}", account, e))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => JITO_TIP_ACCOUNTS
+                .iter()
+                .map(|account| Pubkey::from_str(account).unwrap())
+                .collect(),
+        };
+        if accounts.is_empty() {
+            return Err(anyhow!("jito.tip_accounts is empty"));
+        }
+        info!(
+            "Tipping {} lamports per transaction across {} Jito tip accounts ({:?})",
+            config.tip_lamports,
+            accounts.len(),
+            config.rotation
+        );
+        let _ = GLOBAL.set(Self {
+            lamports: config.tip_lamports,
+            accounts,
+            rotation: config.rotation,
+            next: AtomicUsize::new(0),
+        });
+        Ok(())
+    }
+
+    /// None when tipping is not configured
+    pub fn global() -> Option<&'static JitoTips> {
+        GLOBAL.get()
+    }
+
+    fn next_account(&self) -> Pubkey {
+        let index = match self.rotation {
+            TipRotation::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed),
+            TipRotation::Random => rand::random::<usize>(),
+        };
+        self.accounts[index % self.accounts.len()]
+    }
+
+    pub fn is_tip_account(&self, account: &Pubkey) -> bool {
+        self.accounts.contains(account)
+    }
+
+    /// Transfer of the tip from `payer` to the next tip account
+    pub fn tip_instruction(&self, payer: &Pubkey) -> Instruction {
+        system_instruction::transfer(payer, &self.next_account(), self.lamports)
+    }
+}
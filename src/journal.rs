@@ -0,0 +1,214 @@
+use crate::accounting::ReconciledTransaction;
+use crate::blockhash::unix_millis;
+use crate::config::JournalConfig;
+use crate::confirmation::LandedTransaction;
+use crate::paths::create_parent_dirs;
+use crate::transaction::SendReport;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::{error, info, warn};
+
+/// Upper bounds of the send-to-land histogram buckets, in slots
+const SLOT_BUCKETS: [u64; 9] = [0, 1, 2, 3, 5, 8, 13, 21, 34];
+
+#[derive(Debug, Clone, Default)]
+struct SlotHistogram {
+    count: u64,
+    sum: u64,
+    /// Landings per bucket of `SLOT_BUCKETS`, plus one overflow bucket
+    buckets: [u64; SLOT_BUCKETS.len() + 1],
+}
+
+impl SlotHistogram {
+    fn record(&mut self, slots: u64) {
+        self.count += 1;
+        self.sum += slots;
+        let bucket = SLOT_BUCKETS
+            .iter()
+            .position(|bound| slots <= *bound)
+            .unwrap_or(SLOT_BUCKETS.len());
+        self.buckets[bucket] += 1;
+    }
+}
+
+/// JSON lines journal of every send and landing, plus the send-to-land slot latency
+/// histogram per sending endpoint
+#[derive(Debug, Clone)]
+pub struct SendJournal {
+    path: PathBuf,
+    sender: UnboundedSender<Value>,
+    latency: Arc<Mutex<BTreeMap<String, SlotHistogram>>>,
+}
+
+impl SendJournal {
+    pub fn record_send(&self, mint: &Pubkey, report: &SendReport) {
+        let endpoints: Vec<Value> = report
+            .endpoints
+            .iter()
+            .map(|send| {
+                json!({
+                    "endpoint": send.endpoint,
+                    "sent_at_ms": unix_millis(send.sent_at),
+                    "slot_estimate": send.slot_estimate,
+                    "slot_lag": send.slot_lag,
+                    "signature": send.result.as_ref().ok().map(|s| s.to_string()),
+                    "error": send.result.as_ref().err(),
+                })
+            })
+            .collect();
+        let _ = self.sender.send(json!({
+            "type": "sent",
+            "timestamp_ms": unix_millis(SystemTime::now()),
+            "mint": mint.to_string(),
+            "blockhash": report.blockhash.hash.to_string(),
+            "blockhash_slot": report.blockhash.slot,
+            "blockhash_fetched_at_ms": unix_millis(report.blockhash.fetched_at),
+            "slot_estimate": report.slot_estimate,
+            "endpoints": endpoints,
+        }));
+    }
+
+    /// Joins the landing slot with each endpoint's send slot estimate
+    pub fn record_landing(&self, report: &SendReport, landed: &LandedTransaction) {
+        let mut endpoints = Vec::new();
+        {
+            let mut latency = self.latency.lock().unwrap();
+            for send in &report.endpoints {
+                if send.result.as_ref().ok() != Some(&landed.signature) {
+                    continue;
+                }
+                let slots = landed.slot.saturating_sub(send.slot_estimate);
+                latency.entry(send.endpoint.clone()).or_default().record(slots);
+                endpoints.push(json!({
+                    "endpoint": send.endpoint,
+                    "send_slot_estimate": send.slot_estimate,
+                    "slots_to_land": slots,
+                }));
+            }
+        }
+        let _ = self.sender.send(json!({
+            "type": "landed",
+            "timestamp_ms": unix_millis(SystemTime::now()),
+            "mint": landed.mint.to_string(),
+            "signature": landed.signature.to_string(),
+            "succeeded": landed.succeeded,
+            "failure_reason": landed.failure,
+            "land_slot": landed.slot,
+            "blockhash_slot": report.blockhash.slot,
+            "endpoints": endpoints,
+        }));
+    }
+
+    /// Journals a transaction found in the wallet history at startup. It has no send to
+    /// join, so only its landing and realized result are recorded.
+    pub fn record_reconciled(&self, transaction: &ReconciledTransaction) {
+        let pnl = &transaction.pnl;
+        let _ = self.sender.send(json!({
+            "type": "reconciled",
+            "timestamp_ms": unix_millis(SystemTime::now()),
+            "mint": transaction.mint.map(|mint| mint.to_string()),
+            "signature": pnl.signature.to_string(),
+            "succeeded": pnl.succeeded,
+            "land_slot": pnl.slot,
+            "block_time": pnl.block_time,
+            "fee_lamports": pnl.fee_lamports,
+            "tip_lamports": pnl.tip_lamports,
+            "pnl_lamports": pnl.pnl_lamports,
+        }));
+    }
+
+    /// Signatures of the `landed` and `reconciled` entries already in the journal file
+    pub fn journaled_signatures(&self) -> HashSet<String> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!("Failed to read send journal {}: {}", self.path.display(), e);
+                return HashSet::new();
+            }
+        };
+        BufReader::new(file)
+            .lines()
+            .map_while(|line| line.ok())
+            .filter_map(|line| serde_json::from_str::<Value>(&line).ok())
+            .filter(|entry| matches!(entry["type"].as_str(), Some("landed" | "reconciled")))
+            .filter_map(|entry| entry["signature"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// Prometheus text exposition of the send-to-land slot histogram per endpoint
+    pub fn render_prometheus(&self) -> String {
+        let latency = self.latency.lock().unwrap();
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE send_to_land_slots histogram");
+        for (endpoint, histogram) in latency.iter() {
+            let mut cumulative = 0;
+            for (bound, count) in SLOT_BUCKETS.iter().zip(histogram.buckets.iter()) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "send_to_land_slots_bucket{{endpoint=\"{}\",le=\"{}\"}} {}",
+                    endpoint, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "send_to_land_slots_bucket{{endpoint=\"{}\",le=\"+Inf\"}} {}",
+                endpoint, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "send_to_land_slots_sum{{endpoint=\"{}\"}} {}",
+                endpoint, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "send_to_land_slots_count{{endpoint=\"{}\"}} {}",
+                endpoint, histogram.count
+            );
+        }
+        out
+    }
+}
+
+/// Opens the journal for appending and writes entries in the background
+pub fn spawn_send_journal(config: &JournalConfig) -> Result<SendJournal> {
+    create_parent_dirs(Path::new(&config.path))?;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&config.path)
+        .with_context(|| format!("Failed to open send journal {}", config.path))?;
+    let (sender, receiver) = unbounded_channel();
+    info!("Journaling sends and landings to {}", config.path);
+    tokio::spawn(write_entries(BufWriter::new(file), receiver));
+    Ok(SendJournal {
+        path: PathBuf::from(&config.path),
+        sender,
+        latency: Arc::default(),
+    })
+}
+
+async fn write_entries<W: Write>(mut writer: W, mut receiver: UnboundedReceiver<Value>) {
+    while let Some(entry) = receiver.recv().await {
+        let mut batch = vec![entry];
+        while let Ok(entry) = receiver.try_recv() {
+            batch.push(entry);
+        }
+        let result = batch
+            .iter()
+            .try_for_each(|entry| writeln!(writer, "{}", entry))
+            .and_then(|_| writer.flush());
+        if let Err(e) = result {
+            error!("Failed to write send journal: {}", e);
+        }
+    }
+}
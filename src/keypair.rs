@@ -0,0 +1,437 @@
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::env;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+const KEYPAIR_LENGTH: usize = 64;
+/// Environment variable holding the keystore passphrase
+pub const PASSPHRASE_ENV: &str = "WALLET_PASSPHRASE";
+const KEYSTORE_EXTENSION: &str = ".keystore";
+const KEYSTORE_VERSION: u32 = 1;
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// Passphrase-encrypted keypair at rest: scrypt key derivation, AES-256-GCM encryption.
+/// Byte fields are base58 encoded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u32,
+    pub pubkey: String,
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Why a private key could not be loaded. Messages never contain the secret itself.
+#[derive(Debug, Error)]
+pub enum KeypairError {
+    #[error("environment variable {0} is not set")]
+    EnvVarMissing(String),
+    #[error("private key is empty")]
+    Empty,
+    #[error("invalid base58 character at position {position}")]
+    InvalidBase58Character { position: usize },
+    #[error("private key is not valid base58")]
+    InvalidBase58,
+    #[error("JSON array private key is malformed at element {index}")]
+    InvalidJsonArray { index: usize },
+    #[error("private key must be 64 bytes, got {0}")]
+    WrongLength(usize),
+    #[error("keypair file {0} not found")]
+    FileNotFound(String),
+    #[error("failed to read keypair file {path}: {source}")]
+    FileUnreadable {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("private key bytes are not a valid ed25519 keypair")]
+    InvalidKeypair,
+    #[error("keystore {0} requires the WALLET_PASSPHRASE environment variable")]
+    PassphraseMissing(String),
+    #[error("keystore {path} is malformed: {reason}")]
+    KeystoreMalformed { path: String, reason: String },
+    #[error("failed to decrypt keystore {0}: wrong passphrase or corrupted file")]
+    DecryptionFailed(String),
+}
+
+/// Loads a keypair from a base58 string, a JSON byte array, a keypair file path,
+/// an encrypted `.keystore` file, or `env:VAR_NAME` holding any of those
+pub fn load_keypair(private_key: &str) -> Result<Keypair, KeypairError> {
+    let private_key = private_key.trim();
+    if let Some(var) = private_key.strip_prefix("env:") {
+        let value = env::var(var).map_err(|_| KeypairError::EnvVarMissing(var.to_string()))?;
+        return load_literal_or_file(value.trim());
+    }
+    load_literal_or_file(private_key)
+}
+
+fn load_literal_or_file(private_key: &str) -> Result<Keypair, KeypairError> {
+    if private_key.is_empty() {
+        return Err(KeypairError::Empty);
+    }
+    if private_key.starts_with('[') {
+        return keypair_from_bytes(&parse_json_array(private_key)?);
+    }
+    if private_key.ends_with(KEYSTORE_EXTENSION) {
+        return load_keystore(private_key);
+    }
+    if looks_like_path(private_key) {
+        return load_keypair_file(private_key);
+    }
+    keypair_from_bytes(&decode_base58(private_key)?)
+}
+
+/// Whether a key source names a keypair or keystore file rather than holding the key
+pub fn is_key_file(private_key: &str) -> bool {
+    let private_key = private_key.trim();
+    !private_key.is_empty()
+        && !private_key.starts_with('[')
+        && !private_key.starts_with("env:")
+        && (private_key.starts_with('~')
+            || private_key.ends_with(KEYSTORE_EXTENSION)
+            || looks_like_path(private_key))
+}
+
+fn looks_like_path(value: &str) -> bool {
+    value.contains('/')
+        || value.contains('\\')
+        || value.ends_with(".json")
+        || Path::new(value).exists()
+}
+
+fn load_keypair_file(path: &str) -> Result<Keypair, KeypairError> {
+    let contents = read_file(path)?;
+    keypair_from_bytes(&parse_json_array(contents.trim())?)
+}
+
+fn read_file(path: &str) -> Result<String, KeypairError> {
+    fs::read_to_string(path).map_err(|source| {
+        if source.kind() == std::io::ErrorKind::NotFound {
+            KeypairError::FileNotFound(path.to_string())
+        } else {
+            KeypairError::FileUnreadable {
+                path: path.to_string(),
+                source,
+            }
+        }
+    })
+}
+
+/// Decrypts a keystore with the passphrase from `WALLET_PASSPHRASE`
+pub fn load_keystore(path: &str) -> Result<Keypair, KeypairError> {
+    let malformed = |reason: &str| KeypairError::KeystoreMalformed {
+        path: path.to_string(),
+        reason: reason.to_string(),
+    };
+    let keystore: Keystore =
+        toml::from_str(&read_file(path)?).map_err(|e| malformed(&e.to_string()))?;
+    if keystore.version != KEYSTORE_VERSION {
+        return Err(malformed("unsupported version"));
+    }
+    let passphrase =
+        env::var(PASSPHRASE_ENV).map_err(|_| KeypairError::PassphraseMissing(path.to_string()))?;
+
+    let salt = bs58::decode(&keystore.salt)
+        .into_vec()
+        .map_err(|_| malformed("salt is not base58"))?;
+    let nonce = bs58::decode(&keystore.nonce)
+        .into_vec()
+        .map_err(|_| malformed("nonce is not base58"))?;
+    if nonce.len() != 12 {
+        return Err(malformed("nonce must be 12 bytes"));
+    }
+    let ciphertext = bs58::decode(&keystore.ciphertext)
+        .into_vec()
+        .map_err(|_| malformed("ciphertext is not base58"))?;
+
+    let key = derive_key(&passphrase, &salt, keystore.log_n, keystore.r, keystore.p)
+        .map_err(|_| malformed("invalid scrypt parameters"))?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| malformed("invalid key length"))?;
+    let secret = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| KeypairError::DecryptionFailed(path.to_string()))?;
+
+    let keypair = keypair_from_bytes(&secret)?;
+    if keypair.pubkey().to_string() != keystore.pubkey {
+        return Err(KeypairError::DecryptionFailed(path.to_string()));
+    }
+    Ok(keypair)
+}
+
+/// Encrypts `keypair` with `passphrase` into keystore file contents
+pub fn encrypt_keystore(keypair: &Keypair, passphrase: &str) -> anyhow::Result<String> {
+    encrypt_keystore_with_cost(keypair, passphrase, SCRYPT_LOG_N)
+}
+
+/// `encrypt_keystore` with a scrypt cost of `2^log_n`
+fn encrypt_keystore_with_cost(
+    keypair: &Keypair,
+    passphrase: &str,
+    log_n: u8,
+) -> anyhow::Result<String> {
+    let salt: [u8; 32] = rand::random();
+    let nonce: [u8; 12] = rand::random();
+    let key = derive_key(passphrase, &salt, log_n, SCRYPT_R, SCRYPT_P)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), keypair.to_bytes().as_ref())
+        .map_err(|e| anyhow::anyhow!("Failed to encrypt keypair: {}", e))?;
+
+    let keystore = Keystore {
+        version: KEYSTORE_VERSION,
+        pubkey: keypair.pubkey().to_string(),
+        log_n,
+        r: SCRYPT_R,
+        p: SCRYPT_P,
+        salt: bs58::encode(salt).into_string(),
+        nonce: bs58::encode(nonce).into_string(),
+        ciphertext: bs58::encode(ciphertext).into_string(),
+    };
+    Ok(toml::to_string(&keystore)?)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], log_n: u8, r: u32, p: u32) -> anyhow::Result<[u8; 32]> {
+    let params = scrypt::Params::new(log_n, r, p, 32)
+        .map_err(|e| anyhow::anyhow!("Invalid scrypt parameters: {}", e))?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+fn decode_base58(value: &str) -> Result<Vec<u8>, KeypairError> {
+    bs58::decode(value).into_vec().map_err(|e| match e {
+        bs58::decode::Error::InvalidCharacter { index, .. } => {
+            KeypairError::InvalidBase58Character { position: index }
+        }
+        _ => KeypairError::InvalidBase58,
+    })
+}
+
+fn parse_json_array(value: &str) -> Result<Vec<u8>, KeypairError> {
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or(KeypairError::InvalidJsonArray { index: 0 })?;
+    if inner.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    inner
+        .split(',')
+        .enumerate()
+        .map(|(index, element)| {
+            element
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| KeypairError::InvalidJsonArray { index })
+        })
+        .collect()
+}
+
+fn keypair_from_bytes(bytes: &[u8]) -> Result<Keypair, KeypairError> {
+    if bytes.len() != KEYPAIR_LENGTH {
+        return Err(KeypairError::WrongLength(bytes.len()));
+    }
+    Keypair::from_bytes(bytes).map_err(|_| KeypairError::InvalidKeypair)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Fast scrypt cost for test keystores
+    const TEST_LOG_N: u8 = 4;
+
+    fn base58_secret(keypair: &Keypair) -> String {
+        bs58::encode(keypair.to_bytes()).into_string()
+    }
+
+    fn json_secret(keypair: &Keypair) -> String {
+        let bytes: Vec<String> = keypair.to_bytes().iter().map(u8::to_string).collect();
+        format!("[{}]", bytes.join(","))
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("keypair-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Neither the message nor the debug output carries the secret or a piece of it
+    fn assert_secret_absent(error: &KeypairError, secret: &str) {
+        for rendered in [error.to_string(), format!("{:?}", error)] {
+            let chars: Vec<char> = secret.chars().collect();
+            for window in chars.windows(8) {
+                let piece: String = window.iter().collect();
+                assert!(!rendered.contains(&piece), "{:?} leaks {:?}", rendered, piece);
+            }
+        }
+    }
+
+    #[test]
+    fn loads_every_key_format() {
+        let keypair = Keypair::new();
+        let dir = temp_dir("formats");
+        let path = dir.join("id.json");
+        fs::write(&path, json_secret(&keypair)).unwrap();
+        env::set_var("KEYPAIR_TEST_VALID_KEY", base58_secret(&keypair));
+
+        for source in [
+            base58_secret(&keypair),
+            format!("  {}\n", base58_secret(&keypair)),
+            json_secret(&keypair),
+            path.to_str().unwrap().to_string(),
+            "env:KEYPAIR_TEST_VALID_KEY".to_string(),
+        ] {
+            assert_eq!(load_keypair(&source).unwrap().pubkey(), keypair.pubkey());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bad_base58_character() {
+        let mut secret = base58_secret(&Keypair::new());
+        // 0 is not in the base58 alphabet
+        secret.replace_range(10..11, "0");
+        let error = load_keypair(&secret).unwrap_err();
+        assert!(
+            matches!(error, KeypairError::InvalidBase58Character { position: 10 }),
+            "{:?}",
+            error
+        );
+        assert_secret_absent(&error, &secret);
+    }
+
+    #[test]
+    fn wrong_length() {
+        let keypair = Keypair::new();
+        let short = bs58::encode(&keypair.to_bytes()[..32]).into_string();
+        let error = load_keypair(&short).unwrap_err();
+        assert!(matches!(error, KeypairError::WrongLength(32)), "{:?}", error);
+        assert_secret_absent(&error, &short);
+
+        let json = json_secret(&keypair).replacen('[', "[7,", 1);
+        let error = load_keypair(&json).unwrap_err();
+        assert!(matches!(error, KeypairError::WrongLength(65)), "{:?}", error);
+        assert_secret_absent(&error, &json);
+
+        assert!(matches!(load_keypair("   "), Err(KeypairError::Empty)));
+    }
+
+    #[test]
+    fn malformed_json_array() {
+        let json = json_secret(&Keypair::new());
+        let mut elements: Vec<&str> = json[1..json.len() - 1].split(',').collect();
+        elements[5] = "x";
+        let secret = format!("[{}]", elements.join(","));
+        let error = load_keypair(&secret).unwrap_err();
+        assert!(matches!(error, KeypairError::InvalidJsonArray { index: 5 }), "{:?}", error);
+        assert_secret_absent(&error, &secret);
+
+        let unclosed = &json[..json.len() - 1];
+        let error = load_keypair(unclosed).unwrap_err();
+        assert!(matches!(error, KeypairError::InvalidJsonArray { index: 0 }), "{:?}", error);
+        assert_secret_absent(&error, unclosed);
+
+        let out_of_range = json.replacen('[', "[256,", 1);
+        let error = load_keypair(&out_of_range).unwrap_err();
+        assert!(matches!(error, KeypairError::InvalidJsonArray { index: 0 }), "{:?}", error);
+    }
+
+    #[test]
+    fn missing_and_malformed_keypair_files() {
+        let dir = temp_dir("files");
+        let missing = dir.join("missing.json");
+        let error = load_keypair(missing.to_str().unwrap()).unwrap_err();
+        assert!(
+            matches!(&error, KeypairError::FileNotFound(path) if path == missing.to_str().unwrap()),
+            "{:?}",
+            error
+        );
+
+        let json = json_secret(&Keypair::new());
+        let malformed = dir.join("malformed.json");
+        let secret = json.replacen(',', ",,", 1);
+        fs::write(&malformed, &secret).unwrap();
+        let error = load_keypair(malformed.to_str().unwrap()).unwrap_err();
+        assert!(matches!(error, KeypairError::InvalidJsonArray { index: 1 }), "{:?}", error);
+        assert_secret_absent(&error, &secret);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_and_invalid_env_vars() {
+        env::remove_var("KEYPAIR_TEST_UNSET_KEY");
+        let error = load_keypair("env:KEYPAIR_TEST_UNSET_KEY").unwrap_err();
+        assert!(
+            matches!(&error, KeypairError::EnvVarMissing(var) if var == "KEYPAIR_TEST_UNSET_KEY"),
+            "{:?}",
+            error
+        );
+
+        let mut secret = base58_secret(&Keypair::new());
+        secret.replace_range(3..4, "I");
+        env::set_var("KEYPAIR_TEST_BAD_KEY", &secret);
+        let error = load_keypair("env:KEYPAIR_TEST_BAD_KEY").unwrap_err();
+        assert!(
+            matches!(error, KeypairError::InvalidBase58Character { position: 3 }),
+            "{:?}",
+            error
+        );
+        assert_secret_absent(&error, &secret);
+    }
+
+    /// One test, since the passphrase variable is shared by the whole process
+    #[test]
+    fn keystore_errors() {
+        let keypair = Keypair::new();
+        let secret = base58_secret(&keypair);
+        let dir = temp_dir("keystore");
+        let path = dir.join("wallet.keystore");
+        let path_str = path.to_str().unwrap();
+        let contents = encrypt_keystore_with_cost(&keypair, "correct horse", TEST_LOG_N).unwrap();
+
+        let error = load_keypair(path_str).unwrap_err();
+        assert!(matches!(error, KeypairError::FileNotFound(_)), "{:?}", error);
+
+        fs::write(&path, "not = [toml").unwrap();
+        let error = load_keypair(path_str).unwrap_err();
+        assert!(matches!(error, KeypairError::KeystoreMalformed { .. }), "{:?}", error);
+
+        fs::write(&path, contents.replace("version = 1", "version = 9")).unwrap();
+        let error = load_keypair(path_str).unwrap_err();
+        assert!(
+            matches!(&error, KeypairError::KeystoreMalformed { reason, .. }
+                if reason == "unsupported version"),
+            "{:?}",
+            error
+        );
+
+        fs::write(&path, &contents).unwrap();
+        env::remove_var(PASSPHRASE_ENV);
+        let error = load_keypair(path_str).unwrap_err();
+        assert!(matches!(error, KeypairError::PassphraseMissing(_)), "{:?}", error);
+
+        env::set_var(PASSPHRASE_ENV, "wrong horse");
+        let error = load_keypair(path_str).unwrap_err();
+        assert!(matches!(error, KeypairError::DecryptionFailed(_)), "{:?}", error);
+        assert_secret_absent(&error, &secret);
+        assert!(!error.to_string().contains("wrong horse"));
+
+        env::set_var(PASSPHRASE_ENV, "correct horse");
+        assert_eq!(load_keypair(path_str).unwrap().pubkey(), keypair.pubkey());
+        env::remove_var(PASSPHRASE_ENV);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
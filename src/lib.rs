@@ -1,9 +1,93 @@
+//! Solana onchain arbitrage bot.
+//!
+//! The binary runs the bot from a config file with `run_bot`. Embedders use the items
+//! re-exported here, or `prelude` for all of them at once; these paths are the public API.
+//! The modules stay public for tooling, but items not re-exported may change between
+//! releases.
+
+pub mod account_batcher;
+pub mod accounting;
+pub mod adaptive_delay;
+pub mod amm_config;
+pub mod arb_math;
 pub mod ata;
+pub mod base_mints;
+pub mod blockhash;
 pub mod bot;
+pub mod build_tx;
+pub mod cluster;
 pub mod config;
+pub mod config_include;
+pub mod config_migration;
+pub mod confirmation;
 pub mod constants;
 pub mod dex;
-pub mod pool_refreshers;
+pub mod dex_rpc;
+pub mod endpoint_health;
+pub mod executor_errors;
+pub mod fee_spend;
+pub mod geyser;
+pub mod ix_data;
+pub mod jito;
+pub mod journal;
+pub mod keypair;
+pub mod list_pools;
+pub mod loader;
+pub mod log_dedup;
+pub mod migration;
+pub mod observations;
+pub mod paper_trade;
+pub mod paths;
+pub mod pda;
+pub mod pool_accounts;
+pub mod pool_kind_cache;
 pub mod pools;
-pub mod refresh;
+pub mod prefilter;
+pub mod pricing;
+pub mod program_registry;
+pub mod refresher;
+pub mod repro;
+pub mod risk;
+pub mod rpc_budget;
+pub mod rpc_metrics;
+pub mod snapshot;
+pub mod startup;
+pub mod status;
+pub mod token_program;
 pub mod transaction;
+pub mod worker;
+
+pub use bot::{run_bot, RunOptions};
+pub use config::{
+    BotConfig, Config, MarketDetails, MarketEntry, MarketsConfig, RoutingConfig, RpcConfig,
+    SpamConfig, WalletConfig,
+};
+pub use loader::{initialize_pools_from_markets, known_programs, MarketPoolKind};
+pub use pools::{
+    ByrealPool, DlmmPool, FutarchyPool, HeavenPool, HumidifiPool, MeteoraDAmmPool,
+    MeteoraDAmmV2Pool, MintPoolData, PancakeswapPool, PoolVaults, PumpPool, RaydiumClmmPool,
+    RaydiumCpPool, RaydiumPool, VertigoPool, WhirlpoolPool,
+};
+pub use refresher::PoolDataRefresher;
+pub use worker::{MintWorker, WorkerContext};
+
+/// Old path of `loader`, kept for one release
+#[deprecated(note = "pool initialization moved to `loader`")]
+pub mod refresh {
+    pub use crate::loader::*;
+}
+
+/// Old path of `refresher`, kept for one release
+#[deprecated(note = "pool refreshing moved to `refresher`")]
+pub mod pool_refreshers {
+    pub use crate::refresher::*;
+}
+
+/// The public API in one import: `use solana_onchain_arbitrage_bot::prelude::*;`
+pub mod prelude {
+    pub use crate::{
+        initialize_pools_from_markets, known_programs, run_bot, BotConfig, Config, MarketEntry,
+        MarketPoolKind, MarketsConfig, MintPoolData, MintWorker, PoolDataRefresher,
+        RoutingConfig, RpcConfig, RunOptions, SpamConfig, WalletConfig, WorkerContext,
+    };
+}
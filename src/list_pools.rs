@@ -0,0 +1,134 @@
+use crate::account_batcher::AccountBatcher;
+use crate::amm_config::AmmConfigCache;
+use crate::base_mints::BaseMints;
+use crate::config::Config;
+use crate::constants::init_canonical_mints;
+use crate::dex_rpc::DexRpcClients;
+use crate::pricing::token_amount;
+use crate::program_registry::ProgramRegistry;
+use crate::loader::initialize_pools_from_markets;
+use crate::startup::StartupProgress;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One row of `list-pools`
+#[derive(Debug, Serialize)]
+pub struct PoolSummary {
+    pub kind: String,
+    pub pool: String,
+    pub base_mint: String,
+    /// Trade fee from the pool's AMM config when it has one, or the current scheduled and
+    /// dynamic fee of a DAMM v2 pool
+    pub fee_bps: Option<f64>,
+    /// Base vault balance in SOL; only SOL-quoted pools, no SOL price source is configured
+    pub liquidity_sol: Option<f64>,
+    /// Slot the vault balances were read at
+    pub slot: u64,
+    /// Whether the pool is part of the mint's transaction. Every pool that passes
+    /// startup filtering is routed, so this is true for all listed pools.
+    pub included: bool,
+}
+
+/// Loads the pools `mint` would trade with the given config and prints their fee and liquidity
+pub async fn list_pools(config_path: &str, mint: &str, json: bool) -> Result<()> {
+    let mut config = Config::load(config_path)?;
+    init_canonical_mints(config.mints.as_ref())?;
+    ProgramRegistry::init(config.program_overrides.as_ref())?;
+    let mint: Pubkey = mint.parse().map_err(|e| anyhow!("Invalid mint {}: {}", mint, e))?;
+    config.routing.markets.allowlist = Some(vec![mint.to_string()]);
+
+    AccountBatcher::init(&config.rpc);
+    DexRpcClients::init(&config.rpc, None)?;
+    let rpc_client = Arc::new(RpcClient::new(config.rpc.url.clone()));
+    let base_mints = BaseMints::from_config(&config.routing)?;
+    // Only read-only accounts are derived from the wallet, so any address will do
+    let mut pools = initialize_pools_from_markets(
+        &config.routing.markets,
+        &base_mints,
+        &Pubkey::default(),
+        rpc_client.clone(),
+        &StartupProgress::new(None),
+    )
+    .await?;
+    let pool_data = pools
+        .remove(&mint)
+        .ok_or_else(|| anyhow!("No pools found for mint {}", mint))?;
+
+    let mut fees: HashMap<Pubkey, f64> = pool_data
+        .amm_configs()
+        .into_iter()
+        .filter_map(|(pool, amm_config, kind)| {
+            let config = AmmConfigCache::global()
+                .get_or_fetch(&rpc_client, &amm_config, kind)
+                .ok()?;
+            Some((pool, config.trade_fee_rate as f64 / 100.0))
+        })
+        .collect();
+
+    let vaults = pool_data.pool_vaults();
+    let base_vaults: Vec<Pubkey> = vaults.iter().map(|pool| pool.base_vault).collect();
+    let fetched = AccountBatcher::global().get_multiple_accounts_with_commitment(
+        &rpc_client,
+        &base_vaults,
+        CommitmentConfig::processed(),
+    )?;
+    let slot = fetched.slot;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    for pool in &pool_data.meteora_damm_v2_pools {
+        fees.insert(pool.pool, pool.fees.fee_bps(slot, now));
+    }
+    let balances: Vec<Option<u64>> = fetched
+        .accounts
+        .into_iter()
+        .map(|account| account.and_then(|account| token_amount(&account.data)))
+        .collect();
+
+    let summaries: Vec<PoolSummary> = vaults
+        .iter()
+        .zip(balances)
+        .map(|(pool, balance)| PoolSummary {
+            kind: format!("{:?}", pool.kind),
+            pool: pool.pool.to_string(),
+            base_mint: BaseMints::label(&pool.base_mint),
+            fee_bps: fees.get(&pool.pool).copied(),
+            liquidity_sol: balance.and_then(|balance| pool.liquidity_sol(balance, None)),
+            slot,
+            included: true,
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+        return Ok(());
+    }
+
+    println!("Pools for mint {} (slot {})", mint, slot);
+    println!(
+        "{:<14} {:<44} {:<6} {:>9} {:>14} {:>8}",
+        "DEX", "POOL", "BASE", "FEE BPS", "LIQUIDITY SOL", "INCLUDED"
+    );
+    for summary in &summaries {
+        println!(
+            "{:<14} {:<44} {:<6} {:>9} {:>14} {:>8}",
+            summary.kind,
+            summary.pool,
+            summary.base_mint,
+            summary
+                .fee_bps
+                .map_or_else(|| "-".to_string(), |fee| format!("{:.2}", fee)),
+            summary
+                .liquidity_sol
+                .map_or_else(|| "-".to_string(), |sol| format!("{:.3}", sol)),
+            if summary.included { "yes" } else { "no" }
+        );
+    }
+    Ok(())
+}
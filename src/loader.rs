@@ -0,0 +1,2435 @@
+use crate::account_batcher::AccountBatcher;
+use crate::amm_config::{AmmConfigCache, AmmConfigKind};
+use crate::base_mints::BaseMints;
+use crate::config::{MarketEntry, MarketsConfig, StartupTimeoutAction};
+use crate::constants::{memo_program_id, sol_mint, usd1_mint, usdc_mint};
+use crate::dex::futarchy::{futarchy_event_authority_for, FutarchyInfo};
+use crate::dex::heaven::HeavenPoolState;
+use crate::dex::humidifi::HumidifiInfo;
+use crate::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
+use crate::dex::meteora::dlmm_info::DlmmInfo;
+use crate::dex::pump::PumpAmmInfo;
+use crate::dex::raydium::{PoolState, RaydiumAmmInfo, RaydiumCpAmmInfo, RAYDIUM_LAYOUT_UNKNOWN};
+use crate::dex::vertigo::{derive_vault_address, vertigo_vault_problem, VertigoInfo};
+use crate::dex::whirlpool::{state::Whirlpool, update_tick_array_accounts_for_onchain};
+use crate::dex_rpc::DexRpcClients;
+use crate::pda::find_program_address;
+use crate::pool_accounts::{
+    check_owner, clmm_bitmap_extension_address, clmm_vaults, fetch_bitmap_extension,
+    load_clmm_pool, ClmmPoolAccount,
+};
+use crate::pool_kind_cache::{CachedMarket, PoolKindCache};
+use crate::pools::*;
+use crate::pricing::{priced_from_reserves, token_amount, SolPrice};
+use crate::program_registry::ProgramRegistry;
+use crate::startup::StartupProgress;
+use crate::token_program::classify_token_program;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use spl_associated_token_account;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+
+/// DEX of a pool, detected from the program owning the pool account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MarketPoolKind {
+    Pump,
+    RaydiumV4,
+    RaydiumCp,
+    RaydiumClmm,
+    MeteoraDlmm,
+    MeteoraDamm,
+    MeteoraDammV2,
+    Whirlpool,
+    Vertigo,
+    Heaven,
+    Futarchy,
+    Humidifi,
+    PancakeSwap,
+    Byreal,
+}
+
+impl MarketPoolKind {
+    /// DEX name used in logs
+    pub fn name(self) -> &'static str {
+        match self {
+            MarketPoolKind::Pump => "Pump",
+            MarketPoolKind::RaydiumV4 => "Raydium",
+            MarketPoolKind::RaydiumCp => "Raydium CP",
+            MarketPoolKind::RaydiumClmm => "Raydium CLMM",
+            MarketPoolKind::MeteoraDlmm => "Meteora DLMM",
+            MarketPoolKind::MeteoraDamm => "Meteora DAMM",
+            MarketPoolKind::MeteoraDammV2 => "Meteora DAMM v2",
+            MarketPoolKind::Whirlpool => "Whirlpool",
+            MarketPoolKind::Vertigo => "Vertigo",
+            MarketPoolKind::Heaven => "Heaven",
+            MarketPoolKind::Futarchy => "Futarchy",
+            MarketPoolKind::Humidifi => "HumidiFi",
+            MarketPoolKind::PancakeSwap => "PancakeSwap",
+            MarketPoolKind::Byreal => "Byreal",
+        }
+    }
+}
+
+/// Internal structure for grouping pools by mint during detection
+#[derive(Default)]
+struct MintPoolsBuilder {
+    pump_pools: Vec<Pubkey>,
+    raydium_pools: Vec<Pubkey>,
+    raydium_cp_pools: Vec<Pubkey>,
+    raydium_clmm_pools: Vec<Pubkey>,
+    dlmm_pools: Vec<Pubkey>,
+    damm_pools: Vec<Pubkey>,
+    damm_v2_pools: Vec<Pubkey>,
+    whirlpool_pools: Vec<Pubkey>,
+    vertigo_pools: Vec<Pubkey>,
+    heaven_pools: Vec<Pubkey>,
+    futarchy_pools: Vec<Pubkey>,
+    humidifi_pools: Vec<Pubkey>,
+    pancakeswap_pools: Vec<Pubkey>,
+    byreal_pools: Vec<Pubkey>,
+}
+
+impl MintPoolsBuilder {
+    fn push(&mut self, kind: MarketPoolKind, pool_pubkey: Pubkey) {
+        match kind {
+            MarketPoolKind::Pump => self.pump_pools.push(pool_pubkey),
+            MarketPoolKind::RaydiumV4 => self.raydium_pools.push(pool_pubkey),
+            MarketPoolKind::RaydiumCp => self.raydium_cp_pools.push(pool_pubkey),
+            MarketPoolKind::RaydiumClmm => self.raydium_clmm_pools.push(pool_pubkey),
+            MarketPoolKind::MeteoraDlmm => self.dlmm_pools.push(pool_pubkey),
+            MarketPoolKind::MeteoraDamm => self.damm_pools.push(pool_pubkey),
+            MarketPoolKind::MeteoraDammV2 => self.damm_v2_pools.push(pool_pubkey),
+            MarketPoolKind::Whirlpool => self.whirlpool_pools.push(pool_pubkey),
+            MarketPoolKind::Vertigo => self.vertigo_pools.push(pool_pubkey),
+            MarketPoolKind::Heaven => self.heaven_pools.push(pool_pubkey),
+            MarketPoolKind::Futarchy => self.futarchy_pools.push(pool_pubkey),
+            MarketPoolKind::Humidifi => self.humidifi_pools.push(pool_pubkey),
+            MarketPoolKind::PancakeSwap => self.pancakeswap_pools.push(pool_pubkey),
+            MarketPoolKind::Byreal => self.byreal_pools.push(pool_pubkey),
+        }
+    }
+
+    async fn initialize(
+        self,
+        mint: Pubkey,
+        wallet_account: &Pubkey,
+        base_mints: &BaseMints,
+        rpc_client: Arc<RpcClient>,
+    ) -> anyhow::Result<MintPoolData> {
+        initialize_pool_data(
+            mint,
+            wallet_account,
+            Some(&self.raydium_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.raydium_cp_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.pump_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.dlmm_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.whirlpool_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.raydium_clmm_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.damm_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.damm_v2_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.vertigo_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.heaven_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.futarchy_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.humidifi_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.pancakeswap_pools).filter(|pools| !pools.is_empty()),
+            Some(&self.byreal_pools).filter(|pools| !pools.is_empty()),
+            base_mints,
+            rpc_client,
+        )
+        .await
+    }
+}
+
+/// Initializes `pools` of `mint` outside the startup flow, e.g. pools discovered at runtime
+pub(crate) async fn initialize_discovered_pools(
+    mint: Pubkey,
+    wallet_account: &Pubkey,
+    pools: &[(Pubkey, MarketPoolKind)],
+    base_mints: &BaseMints,
+    rpc_client: Arc<RpcClient>,
+) -> anyhow::Result<MintPoolData> {
+    let mut builder = MintPoolsBuilder::default();
+    for &(pool, kind) in pools {
+        builder.push(kind, pool);
+    }
+    builder.initialize(mint, wallet_account, base_mints, rpc_client).await
+}
+
+/// Loads the pool's AMM config through the shared cache and logs its trade fee
+fn log_amm_config(rpc_client: &RpcClient, amm_config: &Pubkey, kind: AmmConfigKind) {
+    match AmmConfigCache::global().get_or_fetch(rpc_client, amm_config, kind) {
+        Ok(config) => info!("    Trade fee rate: {} / 1000000", config.trade_fee_rate),
+        Err(e) => warn!("    Failed to load AMM config {}: {}", amm_config, e),
+    }
+}
+
+/// A pool with the Raydium CLMM layout read for one mint
+struct LoadedClmmPool {
+    program_id: Pubkey,
+    account: ClmmPoolAccount,
+    token_vault: Pubkey,
+    base_vault: Pubkey,
+    token_mint: Pubkey,
+    base_mint: Pubkey,
+}
+
+/// Reads a Raydium CLMM, PancakeSwap or Byreal pool of `mint` quoted in `base` and derives
+/// its tick arrays. Logs why and returns None when the pool cannot be used.
+fn load_clmm_pool_for_mint(
+    rpc_client: &RpcClient,
+    kind: MarketPoolKind,
+    programs: &[Pubkey],
+    pool: &Pubkey,
+    mint: &Pubkey,
+    base: &Pubkey,
+) -> Option<LoadedClmmPool> {
+    let account = match rpc_client.get_account(pool) {
+        Ok(account) => account,
+        Err(e) => {
+            error!("Error fetching {} pool account {}: {:?}", kind.name(), pool, e);
+            return None;
+        }
+    };
+    if let Err(e) = check_owner(kind, pool, &account.owner, programs) {
+        error!("{}, skipping", e);
+        return None;
+    }
+    let program_id = account.owner;
+    let bitmap_extension =
+        fetch_bitmap_extension(rpc_client, &clmm_bitmap_extension_address(pool, &program_id));
+    let loaded = load_clmm_pool(
+        kind,
+        pool,
+        &account.data,
+        &program_id,
+        bitmap_extension.as_ref(),
+        Some(rpc_client),
+    );
+    let account = match loaded {
+        Ok(account) => account,
+        Err(e) => {
+            error!("Error loading {} pool {}: {:?}", kind.name(), pool, e);
+            return None;
+        }
+    };
+    let state = &account.state;
+    if state.token_mint_0 != *mint && state.token_mint_1 != *mint {
+        error!("Mint {} is not present in {} pool {}, skipping", mint, kind.name(), pool);
+        return None;
+    }
+    let Some((token_vault, base_vault)) = clmm_vaults(state, base) else {
+        error!("Base mint {} is not present in {} pool {}", base, kind.name(), pool);
+        return None;
+    };
+    let (token_mint, base_mint) = if *mint == state.token_mint_0 {
+        (state.token_mint_0, state.token_mint_1)
+    } else {
+        (state.token_mint_1, state.token_mint_0)
+    };
+    Some(LoadedClmmPool {
+        program_id,
+        account,
+        token_vault,
+        base_vault,
+        token_mint,
+        base_mint,
+    })
+}
+
+/// Logs a CLMM pool added by `load_clmm_pool_for_mint`
+fn log_clmm_pool(kind: MarketPoolKind, pool: &Pubkey, loaded: &LoadedClmmPool) {
+    let state = &loaded.account.state;
+    info!("{} pool added: {}", kind.name(), pool);
+    info!("    Token mint 0: {}", state.token_mint_0);
+    info!("    Token mint 1: {}", state.token_mint_1);
+    info!("    Token vault: {}", loaded.token_vault);
+    info!("    Sol vault: {}", loaded.base_vault);
+    info!("    AMM config: {}", state.amm_config);
+    // Fetched while loading the pool, which logs a failed fetch
+    if let Some(config) = AmmConfigCache::global().get(&state.amm_config) {
+        info!("    Trade fee rate: {} / 1000000", config.trade_fee_rate);
+    }
+    info!("    Observation key: {}", state.observation_key);
+    for (i, array) in loaded.account.tick_arrays.iter().enumerate() {
+        info!("    Tick Array {}: {}", i, array);
+    }
+    info!("");
+}
+
+/// Every DEX program id the crate recognizes, paired with its pool kind.
+/// Raydium programs are listed for both the configured and the devnet deployment.
+pub fn known_programs() -> Vec<(Pubkey, MarketPoolKind)> {
+    let registry = ProgramRegistry::global();
+    let mut programs = vec![(registry.pump, MarketPoolKind::Pump)];
+    programs.extend(
+        registry
+            .raydium_programs()
+            .into_iter()
+            .map(|id| (id, MarketPoolKind::RaydiumV4)),
+    );
+    programs.extend(
+        registry
+            .raydium_cp_programs()
+            .into_iter()
+            .map(|id| (id, MarketPoolKind::RaydiumCp)),
+    );
+    programs.extend(
+        registry
+            .raydium_clmm_programs()
+            .into_iter()
+            .map(|id| (id, MarketPoolKind::RaydiumClmm)),
+    );
+    programs.extend([
+        (registry.meteora_dlmm, MarketPoolKind::MeteoraDlmm),
+        (registry.meteora_damm, MarketPoolKind::MeteoraDamm),
+        (registry.meteora_damm_v2, MarketPoolKind::MeteoraDammV2),
+        (registry.whirlpool, MarketPoolKind::Whirlpool),
+        (registry.vertigo, MarketPoolKind::Vertigo),
+        (registry.heaven, MarketPoolKind::Heaven),
+        (registry.futarchy, MarketPoolKind::Futarchy),
+        (registry.humidifi, MarketPoolKind::Humidifi),
+        (registry.pancakeswap, MarketPoolKind::PancakeSwap),
+        (registry.byreal, MarketPoolKind::Byreal),
+    ]);
+    programs
+}
+
+/// Detect the pool kind based on the account owner (program ID)
+pub(crate) fn detect_pool_kind(owner: &Pubkey) -> Option<MarketPoolKind> {
+    known_programs()
+        .into_iter()
+        .find(|(program_id, _)| program_id == owner)
+        .map(|(_, kind)| kind)
+}
+
+/// Both mints of a pool, in the order the pool stores them
+fn pool_mints(
+    kind: MarketPoolKind,
+    data: &[u8],
+    pool_pubkey: &Pubkey,
+) -> anyhow::Result<(Pubkey, Pubkey)> {
+    Ok(match kind {
+        MarketPoolKind::Pump => {
+            let info = PumpAmmInfo::load_checked(data)?;
+            (info.base_mint, info.quote_mint)
+        }
+        MarketPoolKind::RaydiumV4 => {
+            let info = RaydiumAmmInfo::load_checked(data)?;
+            (info.coin_mint, info.pc_mint)
+        }
+        MarketPoolKind::RaydiumCp => {
+            let info = RaydiumCpAmmInfo::load_checked(data)?;
+            (info.token_0_mint, info.token_1_mint)
+        }
+        // PancakeSwap and Byreal share the same CLMM layout as Raydium
+        MarketPoolKind::RaydiumClmm | MarketPoolKind::PancakeSwap | MarketPoolKind::Byreal => {
+            let info = PoolState::load_checked(data)?;
+            (info.token_mint_0, info.token_mint_1)
+        }
+        MarketPoolKind::MeteoraDlmm => {
+            let info = DlmmInfo::load_checked(data)?;
+            (info.token_x_mint, info.token_y_mint)
+        }
+        MarketPoolKind::MeteoraDamm => {
+            let pool = meteora_damm_cpi::Pool::deserialize_unchecked(data)?;
+            (pool.token_a_mint, pool.token_b_mint)
+        }
+        MarketPoolKind::MeteoraDammV2 => {
+            let info = MeteoraDAmmV2Info::load_checked(data)?;
+            (info.base_mint, info.quote_mint)
+        }
+        MarketPoolKind::Whirlpool => {
+            let whirlpool = Whirlpool::try_deserialize(data)?;
+            (whirlpool.token_mint_a, whirlpool.token_mint_b)
+        }
+        MarketPoolKind::Vertigo => {
+            let info = VertigoInfo::load_checked(data, pool_pubkey)?;
+            (info.mint_a, info.mint_b)
+        }
+        MarketPoolKind::Heaven => {
+            let info = HeavenPoolState::parse(data).ok_or_else(|| {
+                anyhow::anyhow!("Failed to parse Heaven pool")
+            })?;
+            (info.mint_a, info.mint_b)
+        }
+        MarketPoolKind::Futarchy => {
+            let info = FutarchyInfo::load_checked(data)?;
+            (info.base_mint, info.quote_mint)
+        }
+        MarketPoolKind::Humidifi => {
+            let info = HumidifiInfo::load_checked(data)?;
+            (info.base_mint, info.quote_mint)
+        }
+    })
+}
+
+/// Extract the non-base token mint and the base mint from a pool based on its kind.
+/// Heaven and Pump pools accept any configured base mint (e.g. USD1 launchpad pools), the
+/// other DEXes SOL only, unless the market sets `base_override`. Pools whose two mints are identical are rejected.
+fn extract_token_and_base_mint(
+    kind: MarketPoolKind,
+    data: &[u8],
+    pool_pubkey: &Pubkey,
+    base_mints: &BaseMints,
+) -> anyhow::Result<Option<(Pubkey, Pubkey)>> {
+    let (mint_a, mint_b) = pool_mints(kind, data, pool_pubkey)?;
+    if mint_a == mint_b {
+        return Err(anyhow::anyhow!(
+            "Pool {} has identical mints on both sides ({})",
+            pool_pubkey,
+            mint_a
+        ));
+    }
+
+    let base_override = base_mints.override_for(pool_pubkey);
+    if let Some(base) = base_override.filter(|base| *base != mint_a && *base != mint_b) {
+        return Err(anyhow::anyhow!(
+            "base_override {} is not a mint of pool {} ({}, {})",
+            base,
+            pool_pubkey,
+            mint_a,
+            mint_b
+        ));
+    }
+
+    let sol = sol_mint();
+    let is_base = |mint: &Pubkey| match (base_override, kind) {
+        (Some(base), _) => *mint == base,
+        (None, MarketPoolKind::Heaven | MarketPoolKind::Pump) => base_mints.contains(mint),
+        (None, _) => *mint == sol,
+    };
+    let mints = if is_base(&mint_a) {
+        (mint_b, mint_a)
+    } else if is_base(&mint_b) {
+        (mint_a, mint_b)
+    } else {
+        return Ok(None); // Neither side is a base mint
+    };
+    Ok(Some(mints))
+}
+
+/// Detects the kind, token mint and base mint of a fetched market account, or why it is
+/// skipped
+fn detect_market(
+    pool_pubkey: &Pubkey,
+    account: &Account,
+    base_mints: &BaseMints,
+) -> Result<CachedMarket, (SkipReason, String)> {
+    let Some(kind) = detect_pool_kind(&account.owner) else {
+        return Err((
+            SkipReason::UnknownProgram,
+            format!("Unknown pool program {} for market {}", account.owner, pool_pubkey),
+        ));
+    };
+    match extract_token_and_base_mint(kind, &account.data, pool_pubkey, base_mints) {
+        Ok(Some((token_mint, base_mint))) => Ok(CachedMarket {
+            owner: account.owner,
+            kind,
+            token_mint,
+            base_mint,
+        }),
+        Ok(None) => Err((
+            SkipReason::NoBaseSide,
+            format!("Pool {} does not have a base mint as one side, skipping", pool_pubkey),
+        )),
+        Err(e) => Err((
+            SkipReason::ParseError,
+            format!("Failed to parse pool {}: {}", pool_pubkey, e),
+        )),
+    }
+}
+
+/// Initialize pools from a simplified markets config
+/// This function:
+/// 1. Fetches all market accounts
+/// 2. Detects the pool kind for each
+/// 3. Extracts the token and base mints
+/// 4. Groups pools by mint and base mint, keeping the primary base partition
+/// 5. Initializes MintPoolData for each mint
+pub async fn initialize_pools_from_markets(
+    markets_config: &MarketsConfig,
+    base_mints: &BaseMints,
+    wallet_account: &Pubkey,
+    rpc_client: Arc<RpcClient>,
+    progress: &StartupProgress,
+) -> anyhow::Result<HashMap<Pubkey, MintPoolData>> {
+    info!("Initializing pools from {} markets", markets_config.markets.len());
+    let mut skipped = SkippedMarkets::new(markets_config.log_skipped_markets);
+
+    let mut entries: Vec<&MarketEntry> = Vec::with_capacity(markets_config.markets.len());
+    for entry in &markets_config.markets {
+        if entry.is_disabled() {
+            skipped.skip(SkipReason::Disabled, || {
+                format!("Market {} is disabled, skipping", entry.describe())
+            });
+        } else {
+            entries.push(entry);
+        }
+    }
+    // Stable, so equal priorities keep their config order
+    entries.sort_by_key(|entry| Reverse(entry.priority()));
+
+    // Parse all market addresses
+    let mut market_entries: HashMap<Pubkey, MarketConstraints> = HashMap::new();
+    let mut market_pubkeys: Vec<Pubkey> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let pubkey = match entry.address().parse::<Pubkey>() {
+            Ok(pk) => pk,
+            Err(e) => {
+                error!("Invalid market address {}: {}", entry.address(), e);
+                skipped.count(SkipReason::InvalidAddress);
+                continue;
+            }
+        };
+        let constraints = MarketConstraints::from_entry(entry)?;
+        if market_entries.insert(pubkey, constraints).is_none() {
+            market_pubkeys.push(pubkey);
+        }
+    }
+
+    if market_pubkeys.is_empty() {
+        skipped.log_summary(markets_config.markets.len());
+        return Ok(HashMap::new());
+    }
+    progress.set_markets_total(market_pubkeys.len());
+
+    let allowlist = markets_config
+        .allowlist
+        .as_ref()
+        .map(|mints| {
+            mints
+                .iter()
+                .map(|mint| {
+                    mint.parse::<Pubkey>()
+                        .map_err(|e| anyhow::anyhow!("Invalid allowlist mint {}: {}", mint, e))
+                })
+                .collect::<anyhow::Result<HashSet<Pubkey>>>()
+        })
+        .transpose()?;
+    if let Some(allowlist) = &allowlist {
+        info!("Only trading {} allowlisted mints", allowlist.len());
+    }
+
+    // Markets detected on a previous start skip the detection fetch, unless their base side
+    // is overridden in the config
+    let cache_path = markets_config.pool_kind_cache_path.as_deref();
+    let mut kind_cache = cache_path.map(PoolKindCache::load).unwrap_or_default();
+    let (cached_markets, uncached_markets): (Vec<Pubkey>, Vec<Pubkey>) = market_pubkeys
+        .iter()
+        .copied()
+        .partition(|market| {
+            kind_cache.get(market).is_some() && base_mints.override_for(market).is_none()
+        });
+    if !cached_markets.is_empty() {
+        info!("Skipping detection for {} cached markets", cached_markets.len());
+    }
+    let mut detected: Vec<(Pubkey, CachedMarket)> = cached_markets
+        .iter()
+        .filter_map(|market| Some((*market, *kind_cache.get(market)?)))
+        .collect();
+    progress.add_markets_fetched(cached_markets.len());
+
+    // Fetch the remaining accounts in batches
+    let accounts = AccountBatcher::global().get_multiple_accounts(&rpc_client, &uncached_markets)?;
+    progress.add_markets_fetched(uncached_markets.len());
+    for (pool_pubkey, maybe_account) in uncached_markets.iter().copied().zip(&accounts) {
+        let account = match maybe_account {
+            Some(acc) => acc,
+            None => {
+                skipped.skip(SkipReason::NotFound, || {
+                    format!("Market account {} not found", pool_pubkey)
+                });
+                continue;
+            }
+        };
+
+        let market = match detect_market(&pool_pubkey, account, base_mints) {
+            Ok(market) => market,
+            Err((reason, message)) => {
+                skipped.skip(reason, || message);
+                continue;
+            }
+        };
+        info!("Detected {:?} pool: {}", market.kind, market_entries[&pool_pubkey].name);
+        info!("  Token mint: {}", market.token_mint);
+        kind_cache.insert(pool_pubkey, market);
+        detected.push((pool_pubkey, market));
+    }
+
+    // Group by (mint, base mint). The executor routes every pool of a mint through a
+    // single base, so only the primary base partition is traded for now.
+    let primary_base = base_mints.primary();
+    let order: HashMap<Pubkey, usize> =
+        market_pubkeys.iter().enumerate().map(|(i, market)| (*market, i)).collect();
+    detected.sort_by_key(|(pool_pubkey, _)| order[pool_pubkey]);
+    let mut partitions: HashMap<(Pubkey, Pubkey), MintPoolsBuilder> = HashMap::new();
+    for (pool_pubkey, market) in detected {
+        if let Err(reason) = market_entries[&pool_pubkey].check(&market) {
+            skipped.skip(SkipReason::ConfigMismatch, || {
+                format!("Skipping market {}: {}", market_entries[&pool_pubkey].name, reason)
+            });
+            continue;
+        }
+        if let Some(allowlist) = &allowlist {
+            if !allowlist.contains(&market.token_mint) {
+                skipped.skip(SkipReason::NotAllowlisted, || {
+                    format!("Mint {} is not allowlisted, skipping pool", market.token_mint)
+                });
+                continue;
+            }
+        }
+        if market.base_mint != primary_base {
+            skipped.skip(SkipReason::OtherBase, || {
+                format!(
+                    "Excluding {:?} pool {} of mint {}: quoted in {}, only {} pools are routed",
+                    market.kind,
+                    pool_pubkey,
+                    market.token_mint,
+                    BaseMints::label(&market.base_mint),
+                    BaseMints::label(&primary_base)
+                )
+            });
+        }
+        partitions
+            .entry((market.token_mint, market.base_mint))
+            .or_default()
+            .push(market.kind, pool_pubkey);
+    }
+    let mint_pools: HashMap<Pubkey, MintPoolsBuilder> = partitions
+        .into_iter()
+        .filter(|((_, base), _)| *base == primary_base)
+        .map(|((mint, _), builder)| (mint, builder))
+        .collect();
+
+    skipped.log_summary(markets_config.markets.len());
+    info!("Found {} unique token mints", mint_pools.len());
+    if let Some(allowlist) = &allowlist {
+        for mint in allowlist.iter().filter(|mint| !mint_pools.contains_key(mint)) {
+            warn!("Allowlisted mint {} has no pools in the markets list", mint);
+        }
+    }
+
+    // Initialize MintPoolData for each mint
+    let mut result: HashMap<Pubkey, MintPoolData> = HashMap::new();
+    progress.set_mints_total(mint_pools.len());
+
+    let mut mint_pools = mint_pools.into_iter();
+    while let Some((mint, builder)) = mint_pools.next() {
+        if let Some(action) = progress.timed_out() {
+            let skipped: Vec<String> = std::iter::once(mint)
+                .chain(mint_pools.by_ref().map(|(mint, _)| mint))
+                .map(|mint| mint.to_string())
+                .collect();
+            if action == StartupTimeoutAction::Abort {
+                return Err(anyhow::anyhow!(
+                    "startup.timeout_secs exceeded with {} mints left ({})",
+                    skipped.len(),
+                    progress.summary()
+                ));
+            }
+            warn!(
+                "startup.timeout_secs exceeded, starting without {} mints: {}",
+                skipped.len(),
+                skipped.join(", ")
+            );
+            break;
+        }
+        info!("Initializing pools for mint: {}", mint);
+
+        let mut pool_data = builder
+            .initialize(mint, wallet_account, base_mints, rpc_client.clone())
+            .await?;
+        if pool_data.pool_count() == 0 {
+            warn!(
+                "No pools left for mint {} after the pool checks, skipping",
+                mint
+            );
+            continue;
+        }
+
+        if let Some(min_liquidity_sol) = markets_config.min_pool_liquidity_sol {
+            drop_low_liquidity_pools(
+                &mut pool_data,
+                &rpc_client,
+                min_liquidity_sol,
+                markets_config.sol_price_usd,
+            )?;
+            if pool_data.pool_count() == 0 {
+                warn!("No pools left for mint {} after the liquidity filter, skipping", mint);
+                continue;
+            }
+        }
+
+        if let Some(max_skew) = markets_config.reserve_skew_warn_factor {
+            warn_reserve_imbalance(&pool_data, &rpc_client, max_skew)?;
+        }
+
+        result.insert(mint, pool_data);
+        progress.mint_initialized();
+    }
+    warn_duplicate_vaults(&result);
+
+    if let Some(path) = cache_path {
+        let current: HashSet<Pubkey> = market_pubkeys.iter().copied().collect();
+        kind_cache.retain(|market| current.contains(market));
+        match kind_cache.save(path) {
+            Ok(()) => info!("Saved {} pool kinds to {}", kind_cache.len(), path),
+            Err(e) => warn!("{:#}", e),
+        }
+        kind_cache.spawn_verification(path.to_string(), cached_markets, rpc_client.clone());
+    }
+
+    Ok(result)
+}
+
+/// Why a market was left out at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SkipReason {
+    Disabled,
+    InvalidAddress,
+    NotFound,
+    UnknownProgram,
+    ParseError,
+    NoBaseSide,
+    ConfigMismatch,
+    NotAllowlisted,
+    OtherBase,
+}
+
+impl SkipReason {
+    fn label(self) -> &'static str {
+        match self {
+            SkipReason::Disabled => "disabled",
+            SkipReason::InvalidAddress => "invalid address",
+            SkipReason::NotFound => "account not found",
+            SkipReason::UnknownProgram => "unknown program",
+            SkipReason::ParseError => "parse error",
+            SkipReason::NoBaseSide => "no base side",
+            SkipReason::ConfigMismatch => "dex/base mismatch",
+            SkipReason::NotAllowlisted => "not allowlisted",
+            SkipReason::OtherBase => "non-primary base",
+        }
+    }
+}
+
+/// Skipped markets counted by reason, summarized in one line once detection is done.
+/// Each skip is only logged on its own with `log_skipped_markets`.
+struct SkippedMarkets {
+    counts: BTreeMap<SkipReason, usize>,
+    log_each: bool,
+}
+
+impl SkippedMarkets {
+    fn new(log_each: bool) -> Self {
+        Self {
+            counts: BTreeMap::new(),
+            log_each,
+        }
+    }
+
+    fn count(&mut self, reason: SkipReason) {
+        *self.counts.entry(reason).or_default() += 1;
+    }
+
+    fn skip<F: FnOnce() -> String>(&mut self, reason: SkipReason, message: F) {
+        self.count(reason);
+        if self.log_each {
+            warn!("{}", message());
+        } else {
+            debug!("{}", message());
+        }
+    }
+
+    fn log_summary(&self, total: usize) {
+        let skipped: usize = self.counts.values().sum();
+        if skipped == 0 {
+            return;
+        }
+        let reasons: Vec<String> = self
+            .counts
+            .iter()
+            .map(|(reason, count)| format!("{} {}", count, reason.label()))
+            .collect();
+        warn!(
+            "Skipped {} of {} markets: {}{}",
+            skipped,
+            total,
+            reasons.join(", "),
+            if self.log_each { "" } else { " (set log_skipped_markets for details)" }
+        );
+    }
+}
+
+/// Expectations a `markets` entry places on its detected pool
+struct MarketConstraints {
+    name: String,
+    dex: Option<MarketPoolKind>,
+    base: Option<Pubkey>,
+}
+
+impl MarketConstraints {
+    fn from_entry(entry: &MarketEntry) -> anyhow::Result<Self> {
+        let base = entry
+            .base()
+            .map(|base| {
+                base.parse::<Pubkey>().map_err(|e| {
+                    anyhow::anyhow!("Invalid base {} for market {}: {}", base, entry.describe(), e)
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            name: entry.describe(),
+            dex: entry.dex(),
+            base,
+        })
+    }
+
+    fn check(&self, market: &CachedMarket) -> Result<(), String> {
+        if let Some(dex) = self.dex.filter(|dex| *dex != market.kind) {
+            return Err(format!("configured as {:?} but detected as {:?}", dex, market.kind));
+        }
+        if let Some(base) = self.base.filter(|base| *base != market.base_mint) {
+            return Err(format!(
+                "configured with base {} but quoted in {}",
+                BaseMints::label(&base),
+                BaseMints::label(&market.base_mint)
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Decimals of the USDC and USD1 mints
+const STABLECOIN_DECIMALS: u8 = 6;
+
+/// SOL price in `base_mint` from the configured USD price of SOL, for the USD stablecoin
+/// base mints (USDC, USD1)
+pub(crate) fn stablecoin_sol_price(
+    base_mint: &Pubkey,
+    sol_price_usd: Option<f64>,
+) -> Option<SolPrice> {
+    if *base_mint != usdc_mint() && *base_mint != usd1_mint() {
+        return None;
+    }
+    sol_price_usd.map(|price| SolPrice {
+        price,
+        base_decimals: STABLECOIN_DECIMALS,
+    })
+}
+
+/// Base-side liquidity in SOL of `pool` when it is below `min_liquidity_sol`. None for pools
+/// at or above the minimum, with an unreadable vault, or quoted in a base mint without a
+/// SOL price.
+fn liquidity_below_min(
+    pool: &PoolVaults,
+    base_balance: Option<u64>,
+    min_liquidity_sol: f64,
+    sol_price_usd: Option<f64>,
+) -> Option<f64> {
+    let sol_price = stablecoin_sol_price(&pool.base_mint, sol_price_usd);
+    let liquidity = pool.liquidity_sol(base_balance?, sol_price.as_ref())?;
+    (liquidity < min_liquidity_sol).then_some(liquidity)
+}
+
+/// Removes pools whose base vault holds less than `min_liquidity_sol` worth of SOL.
+/// USDC and USD1 pools are converted with `sol_price_usd` and kept without it; pools quoted
+/// in other base mints and unreadable vaults are kept.
+fn drop_low_liquidity_pools(
+    pool_data: &mut MintPoolData,
+    rpc_client: &RpcClient,
+    min_liquidity_sol: f64,
+    sol_price_usd: Option<f64>,
+) -> anyhow::Result<()> {
+    let sol = sol_mint();
+    let base_vaults: Vec<Pubkey> = pool_data
+        .pool_vaults()
+        .iter()
+        .filter(|pool| {
+            pool.base_mint == sol || stablecoin_sol_price(&pool.base_mint, sol_price_usd).is_some()
+        })
+        .map(|pool| pool.base_vault)
+        .collect();
+
+    let mut balances = HashMap::new();
+    let accounts = AccountBatcher::global().get_multiple_accounts(rpc_client, &base_vaults)?;
+    for (vault, account) in base_vaults.iter().zip(accounts) {
+        if let Some(amount) = account.and_then(|account| token_amount(&account.data)) {
+            balances.insert(*vault, amount);
+        }
+    }
+
+    pool_data.retain_pools(|pool| {
+        let balance = balances.get(&pool.base_vault).copied();
+        match liquidity_below_min(pool, balance, min_liquidity_sol, sol_price_usd) {
+            Some(liquidity) => {
+                info!(
+                    "Skipping {:?} pool {}: {:.3} SOL liquidity is below the {} SOL minimum",
+                    pool.kind, pool.pool, liquidity, min_liquidity_sol
+                );
+                false
+            }
+            None => true,
+        }
+    });
+    Ok(())
+}
+
+/// Warns about reserve-priced pools with an empty side, or whose base per token reserve
+/// ratio is more than `max_skew` times off the median of the mint's reserve-priced pools.
+/// Such pools are usually brand new, being drained, or honeypots. Nothing is removed.
+fn warn_reserve_imbalance(
+    pool_data: &MintPoolData,
+    rpc_client: &RpcClient,
+    max_skew: f64,
+) -> anyhow::Result<()> {
+    let pools: Vec<PoolVaults> = pool_data
+        .pool_vaults()
+        .into_iter()
+        .filter(|pool| priced_from_reserves(pool.kind))
+        .collect();
+    let vaults: Vec<Pubkey> = pools
+        .iter()
+        .flat_map(|pool| [pool.base_vault, pool.token_vault])
+        .collect();
+    if vaults.is_empty() {
+        return Ok(());
+    }
+    let accounts = AccountBatcher::global().get_multiple_accounts(rpc_client, &vaults)?;
+    let balances: HashMap<Pubkey, u64> = vaults
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(vault, account)| Some((vault, token_amount(&account?.data)?)))
+        .collect();
+
+    let mut ratios = Vec::new();
+    for pool in &pools {
+        let (Some(&base), Some(&token)) =
+            (balances.get(&pool.base_vault), balances.get(&pool.token_vault))
+        else {
+            continue;
+        };
+        if base == 0 || token == 0 {
+            warn!(
+                "{:?} pool {} of mint {} has an empty side (base {}, token {})",
+                pool.kind, pool.pool, pool_data.mint, base, token
+            );
+            continue;
+        }
+        ratios.push((pool, base as f64 / token as f64));
+    }
+    if ratios.len() < 2 {
+        return Ok(());
+    }
+    let mut sorted: Vec<f64> = ratios.iter().map(|(_, ratio)| *ratio).collect();
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted[sorted.len() / 2];
+    for (pool, ratio) in ratios {
+        let skew = (ratio / median).max(median / ratio);
+        if skew > max_skew {
+            warn!(
+                "{:?} pool {} of mint {} has a skewed reserve ratio: {:.1}x off the median of \
+                 the mint's pools",
+                pool.kind, pool.pool, pool_data.mint, skew
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Warns about vaults referenced by more than one distinct pool, across all mints. Two
+/// pools never share a vault on chain, so a collision points at a misparsed or
+/// misconfigured market whose routes would trade against someone else's reserves.
+fn warn_duplicate_vaults(pools: &HashMap<Pubkey, MintPoolData>) {
+    let mut owners: HashMap<Pubkey, Vec<(Pubkey, PoolVaults)>> = HashMap::new();
+    for (mint, pool_data) in pools {
+        for pool in pool_data.pool_vaults() {
+            for vault in [pool.token_vault, pool.base_vault] {
+                let entries = owners.entry(vault).or_default();
+                if !entries.iter().any(|(_, other)| other.pool == pool.pool) {
+                    entries.push((*mint, pool));
+                }
+            }
+        }
+    }
+    for (vault, entries) in owners.iter().filter(|(_, entries)| entries.len() > 1) {
+        let described: Vec<String> = entries
+            .iter()
+            .map(|(mint, pool)| format!("{:?} pool {} of mint {}", pool.kind, pool.pool, mint))
+            .collect();
+        warn!("Vault {} is shared by {}", vault, described.join(", "));
+    }
+}
+
+/// Removes pools whose token vault is owned by another token program than the one their
+/// swap is built with. When the vaults cannot be read every pool is kept.
+fn drop_token_program_mismatches(pool_data: &mut MintPoolData, rpc_client: &RpcClient) {
+    let token_vaults: Vec<Pubkey> = pool_data
+        .pool_vaults()
+        .iter()
+        .map(|pool| pool.token_vault)
+        .collect();
+    let batcher = AccountBatcher::global();
+    let accounts = match batcher.get_multiple_accounts(rpc_client, &token_vaults) {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            warn!(
+                "Could not read the token vaults of mint {}, keeping its {} pools without \
+                 checking their token program: {}",
+                pool_data.mint,
+                token_vaults.len(),
+                e
+            );
+            return;
+        }
+    };
+    let owners: HashMap<Pubkey, Pubkey> = token_vaults
+        .into_iter()
+        .zip(accounts)
+        .filter_map(|(vault, account)| Some((vault, account?.owner)))
+        .collect();
+    retain_token_program_matches(pool_data, &owners);
+}
+
+/// Keeps the pools whose token vault is owned, per `owners`, by the token program their
+/// swap uses: the Heaven pool's own `token_program`, `MintPoolData.token_program` for the
+/// others. Vaults missing from `owners` are kept.
+fn retain_token_program_matches(pool_data: &mut MintPoolData, owners: &HashMap<Pubkey, Pubkey>) {
+    let heaven_programs: HashMap<Pubkey, Pubkey> = pool_data
+        .heaven_pools
+        .iter()
+        .map(|pool| (pool.pool, pool.token_program))
+        .collect();
+    let token_program = pool_data.token_program;
+    let mint = pool_data.mint;
+    pool_data.retain_pools(|pool| {
+        let expected = heaven_programs.get(&pool.pool).unwrap_or(&token_program);
+        match owners.get(&pool.token_vault) {
+            Some(owner) if owner != expected => {
+                error!(
+                    "Skipping {:?} pool {}: its vault of mint {} belongs to token program {}, \
+                     but the swap uses {}",
+                    pool.kind, pool.pool, mint, owner, expected
+                );
+                false
+            }
+            _ => true,
+        }
+    });
+}
+
+pub(crate) async fn initialize_pool_data(
+    mint: Pubkey,
+    wallet_account: &Pubkey,
+    raydium_pools: Option<&Vec<Pubkey>>,
+    raydium_cp_pools: Option<&Vec<Pubkey>>,
+    pump_pools: Option<&Vec<Pubkey>>,
+    dlmm_pools: Option<&Vec<Pubkey>>,
+    whirlpool_pools: Option<&Vec<Pubkey>>,
+    raydium_clmm_pools: Option<&Vec<Pubkey>>,
+    meteora_damm_pools: Option<&Vec<Pubkey>>,
+    meteora_damm_v2_pools: Option<&Vec<Pubkey>>,
+    vertigo_pools: Option<&Vec<Pubkey>>,
+    heaven_pools: Option<&Vec<Pubkey>>,
+    futarchy_pools: Option<&Vec<Pubkey>>,
+    humidifi_pools: Option<&Vec<Pubkey>>,
+    pancakeswap_pools: Option<&Vec<Pubkey>>,
+    byreal_pools: Option<&Vec<Pubkey>>,
+    base_mints: &BaseMints,
+    rpc_client: Arc<RpcClient>,
+) -> anyhow::Result<MintPoolData> {
+    info!("Initializing pool data for mint: {}", mint);
+    let registry = ProgramRegistry::global();
+
+    // Fetch mint account to determine token program
+    let mint_account = rpc_client.get_account(&mint)?;
+
+    // Determine token program based on mint account owner
+    let token_program_kind = classify_token_program(&mint_account.owner)
+        .map_err(|e| anyhow::anyhow!("{} for mint: {}", e, mint))?;
+    let token_program = token_program_kind.pubkey();
+
+    info!("Detected token program: {}", token_program);
+
+    // Determine memo_program based on whether token uses Token 2022
+    // Token 2022 pools require the memo program in swap accounts
+    let memo_program_id: Option<Pubkey> = if token_program_kind.is_token_2022() {
+        Some(memo_program_id())
+    } else {
+        None
+    };
+
+    let mut pool_data = MintPoolData::new(mint, wallet_account, token_program);
+    info!("Pool data initialized for mint: {}", mint);
+
+    if let Some(pools) = pump_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::Pump, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::Pump,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.pump],
+                    ) {
+                        error!("Error: {}", e);
+                        return Err(e);
+                    }
+
+                    match PumpAmmInfo::load_checked(&account.data) {
+                        Ok(amm_info) => {
+                            let (token_vault, sol_vault) = if mint == amm_info.base_mint {
+                                (
+                                    amm_info.pool_base_token_account,
+                                    amm_info.pool_quote_token_account,
+                                )
+                            } else if mint == amm_info.quote_mint {
+                                (
+                                    amm_info.pool_quote_token_account,
+                                    amm_info.pool_base_token_account,
+                                )
+                            } else {
+                                error!(
+                                    "Pump pool {} does not contain mint {} (base {}, quote {})",
+                                    pool_pubkey, mint, amm_info.base_mint, amm_info.quote_mint
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Pump pool does not contain configured mint"
+                                ));
+                            };
+
+                            let (fee_wallet, fee_token_wallet) = amm_info.fee_wallets();
+
+                            let coin_creator_vault_ata =
+                                spl_associated_token_account::get_associated_token_address(
+                                    &amm_info.coin_creator_vault_authority,
+                                    &amm_info.quote_mint,
+                                );
+
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == amm_info.base_mint {
+                                (amm_info.base_mint, amm_info.quote_mint)
+                            } else {
+                                (amm_info.quote_mint, amm_info.base_mint)
+                            };
+
+                            pool_data.add_pump_pool(
+                                pool_pubkey,
+                                token_vault,
+                                sol_vault,
+                                fee_wallet,
+                                fee_token_wallet,
+                                coin_creator_vault_ata,
+                                amm_info.coin_creator_vault_authority,
+                                amm_info.coin_creator,
+                                token_mint,
+                                base_mint,
+                                amm_info.is_mayhem_mode,
+                                amm_info.is_cashback_coin,
+                                amm_info.layout_version,
+                            );
+                            info!("Pump pool added: {}", pool_pubkey);
+                            info!("    Base mint: {}", amm_info.base_mint);
+                            info!("    Quote mint: {}", amm_info.quote_mint);
+                            info!("    Token vault: {}", token_vault);
+                            info!("    Sol vault: {}", sol_vault);
+                            info!("    Fee wallet: {}", fee_wallet);
+                            info!("    Fee token wallet: {}", fee_token_wallet);
+                            info!(
+                                "    Coin creator vault ata: {}",
+                                coin_creator_vault_ata
+                            );
+                            info!(
+                                "    Coin creator vault authority: {}",
+                                amm_info.coin_creator_vault_authority
+                            );
+                            info!("    Coin creator: {}", amm_info.coin_creator);
+                            info!("    Mayhem mode: {}", amm_info.is_mayhem_mode);
+                            info!("    Cashback coin: {}", amm_info.is_cashback_coin);
+                            info!("    Layout version: {}", amm_info.layout_version);
+                            info!("    Initialized Pump pool: {}\n", pool_pubkey);
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing AmmInfo from Pump pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            return Err(e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Pump pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    return Err(anyhow::anyhow!("Error fetching Pump pool account"));
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = raydium_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::RaydiumV4, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::RaydiumV4,
+                        &pool_pubkey,
+                        &account.owner,
+                        &registry.raydium_programs(),
+                    ) {
+                        error!("Error: {}", e);
+                        return Err(e);
+                    }
+
+                    match RaydiumAmmInfo::load_checked(&account.data) {
+                        Ok(amm_info) => {
+                            if amm_info.coin_mint != pool_data.mint
+                                && amm_info.pc_mint != pool_data.mint
+                            {
+                                error!(
+                                    "Mint {} is not present in Raydium pool {}, skipping",
+                                    pool_data.mint, pool_pubkey
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Invalid Raydium pool: {}",
+                                    pool_pubkey
+                                ));
+                            }
+
+                            let base = base_mints.base_side(&pool_pubkey);
+                            if amm_info.coin_mint != base && amm_info.pc_mint != base {
+                                error!(
+                                    "Base mint {} is not present in Raydium pool {}",
+                                    base, pool_pubkey
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Base mint {} is not present in Raydium pool: {}",
+                                    base, pool_pubkey
+                                ));
+                            }
+
+                            let (sol_vault, token_vault) = if base == amm_info.coin_mint {
+                                (amm_info.coin_vault, amm_info.pc_vault)
+                            } else {
+                                (amm_info.pc_vault, amm_info.coin_vault)
+                            };
+
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == amm_info.coin_mint {
+                                (amm_info.coin_mint, amm_info.pc_mint)
+                            } else {
+                                (amm_info.pc_mint, amm_info.coin_mint)
+                            };
+
+                            pool_data.add_raydium_pool(
+                                account.owner,
+                                pool_pubkey,
+                                token_vault,
+                                sol_vault,
+                                token_mint,
+                                base_mint,
+                                amm_info.layout_version,
+                            );
+                            if amm_info.layout_version == RAYDIUM_LAYOUT_UNKNOWN {
+                                warn!(
+                                    "Raydium pool {} has an unknown AmmInfo layout ({} bytes)",
+                                    pool_pubkey,
+                                    account.data.len()
+                                );
+                            }
+                            info!("Raydium pool added: {}", pool_pubkey);
+                            info!("    Coin mint: {}", amm_info.coin_mint);
+                            info!("    PC mint: {}", amm_info.pc_mint);
+                            info!("    Layout version: {}", amm_info.layout_version);
+                            info!("    Token vault: {}", token_vault);
+                            info!("    Sol vault: {}", sol_vault);
+                            info!("    Initialized Raydium pool: {}\n", pool_pubkey);
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing AmmInfo from Raydium pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            return Err(e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Raydium pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    return Err(anyhow::anyhow!("Error fetching Raydium pool account"));
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = raydium_cp_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::RaydiumCp, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::RaydiumCp,
+                        &pool_pubkey,
+                        &account.owner,
+                        &registry.raydium_cp_programs(),
+                    ) {
+                        error!("Error: {}", e);
+                        return Err(e);
+                    }
+
+                    match RaydiumCpAmmInfo::load_checked(&account.data) {
+                        Ok(amm_info) => {
+                            if amm_info.token_0_mint != pool_data.mint
+                                && amm_info.token_1_mint != pool_data.mint
+                            {
+                                error!(
+                                    "Mint {} is not present in Raydium CP pool {}, skipping",
+                                    pool_data.mint, pool_pubkey
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Invalid Raydium CP pool: {}",
+                                    pool_pubkey
+                                ));
+                            }
+
+                            let base = base_mints.base_side(&pool_pubkey);
+                            let (sol_vault, token_vault) = if base == amm_info.token_0_mint {
+                                (amm_info.token_0_vault, amm_info.token_1_vault)
+                            } else if base == amm_info.token_1_mint {
+                                (amm_info.token_1_vault, amm_info.token_0_vault)
+                            } else {
+                                error!(
+                                    "Base mint {} is not present in Raydium CP pool {}",
+                                    base, pool_pubkey
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Base mint {} is not present in Raydium CP pool: {}",
+                                    base, pool_pubkey
+                                ));
+                            };
+
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == amm_info.token_0_mint {
+                                (amm_info.token_0_mint, amm_info.token_1_mint)
+                            } else {
+                                (amm_info.token_1_mint, amm_info.token_0_mint)
+                            };
+
+                            pool_data.add_raydium_cp_pool(
+                                account.owner,
+                                pool_pubkey,
+                                token_vault,
+                                sol_vault,
+                                amm_info.amm_config,
+                                amm_info.observation_key,
+                                token_mint,
+                                base_mint,
+                                amm_info.accrued_fees(&token_vault),
+                                amm_info.accrued_fees(&sol_vault),
+                            );
+                            info!("Raydium CP pool added: {}", pool_pubkey);
+                            info!("    Token vault: {}", token_vault);
+                            info!("    Sol vault: {}", sol_vault);
+                            info!("    AMM Config: {}", amm_info.amm_config);
+                            log_amm_config(&rpc_client, &amm_info.amm_config, AmmConfigKind::Cp);
+                            info!(
+                                "    Observation Key: {}\n",
+                                amm_info.observation_key
+                            );
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing AmmInfo from Raydium CP pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            return Err(e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Raydium CP pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    return Err(anyhow::anyhow!("Error fetching Raydium CP pool account"));
+                }
+            }
+        }
+    }
+    if let Some(pools) = dlmm_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::MeteoraDlmm, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::MeteoraDlmm,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.meteora_dlmm],
+                    ) {
+                        error!("Error: {}", e);
+                        return Err(e);
+                    }
+
+                    match DlmmInfo::load_checked(&account.data) {
+                        Ok(amm_info) => {
+                            let base = base_mints.base_side(&pool_pubkey);
+                            let (token_vault, sol_vault) =
+                                amm_info.get_token_and_sol_vaults(&pool_data.mint, &base);
+
+                            let bin_arrays = match amm_info.calculate_bin_arrays(&pool_pubkey)
+                            {
+                                Ok(arrays) => arrays,
+                                Err(e) => {
+                                    error!(
+                                        "Error calculating bin arrays for DLMM pool {}: {:?}",
+                                        pool_pubkey, e
+                                    );
+                                    return Err(e);
+                                }
+                            };
+
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == amm_info.token_x_mint {
+                                (amm_info.token_x_mint, amm_info.token_y_mint)
+                            } else {
+                                (amm_info.token_y_mint, amm_info.token_x_mint)
+                            };
+
+                            let (bitmap_extension, _) = find_program_address(
+                                &[b"bitmap", pool_pubkey.as_ref()],
+                                &registry.meteora_dlmm,
+                            );
+                            let bin_array_bitmap_extension =
+                                match rpc_client.get_account(&bitmap_extension) {
+                                    Ok(extension_account)
+                                        if extension_account.owner == registry.meteora_dlmm =>
+                                    {
+                                        Some(bitmap_extension)
+                                    }
+                                    _ => None,
+                                };
+
+                            pool_data.add_dlmm_pool(
+                                pool_pubkey,
+                                token_vault,
+                                sol_vault,
+                                amm_info.oracle,
+                                bin_array_bitmap_extension,
+                                bin_arrays.clone(),
+                                amm_info.bin_step,
+                                memo_program_id, // memo_program for Token 2022
+                                token_mint,
+                                base_mint,
+                            );
+
+                            info!("DLMM pool added: {}", pool_pubkey);
+                            info!("    Token X Mint: {}", amm_info.token_x_mint);
+                            info!("    Token Y Mint: {}", amm_info.token_y_mint);
+                            info!("    Token vault: {}", token_vault);
+                            info!("    Sol vault: {}", sol_vault);
+                            info!("    Oracle: {}", amm_info.oracle);
+                            info!("    Bin step: {}", amm_info.bin_step);
+                            if let Some(bitmap_extension) = bin_array_bitmap_extension {
+                                info!("    Bin Array Bitmap Extension: {}", bitmap_extension);
+                            }
+                            info!("    Active ID: {}", amm_info.active_id);
+
+                            for (i, array) in bin_arrays.iter().enumerate() {
+                                info!("    Bin Array {}: {}", i, array);
+                            }
+                            info!("");
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing AmmInfo from DLMM pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            return Err(e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching DLMM pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    return Err(anyhow::anyhow!("Error fetching DLMM pool account"));
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = whirlpool_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::Whirlpool, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::Whirlpool,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.whirlpool],
+                    ) {
+                        error!("Error: {}", e);
+                        return Err(e);
+                    }
+
+                    match Whirlpool::try_deserialize(&account.data) {
+                        Ok(whirlpool) => {
+                            if whirlpool.token_mint_a != pool_data.mint
+                                && whirlpool.token_mint_b != pool_data.mint
+                            {
+                                error!(
+                                    "Mint {} is not present in Whirlpool pool {}, skipping",
+                                    pool_data.mint, pool_pubkey
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Invalid Whirlpool pool: {}",
+                                    pool_pubkey
+                                ));
+                            }
+
+                            let base = base_mints.base_side(&pool_pubkey);
+                            let (sol_vault, token_vault) = if base == whirlpool.token_mint_a {
+                                (whirlpool.token_vault_a, whirlpool.token_vault_b)
+                            } else if base == whirlpool.token_mint_b {
+                                (whirlpool.token_vault_b, whirlpool.token_vault_a)
+                            } else {
+                                error!(
+                                    "Base mint {} is not present in Whirlpool pool {}",
+                                    base, pool_pubkey
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Base mint {} is not present in Whirlpool pool: {}",
+                                    base, pool_pubkey
+                                ));
+                            };
+
+                            let whirlpool_oracle = find_program_address(
+                                &[b"oracle", pool_pubkey.as_ref()],
+                                &registry.whirlpool,
+                            )
+                            .0;
+
+                            let whirlpool_tick_arrays = update_tick_array_accounts_for_onchain(
+                                &whirlpool,
+                                &pool_pubkey,
+                                &registry.whirlpool,
+                            );
+
+                            let tick_arrays: Vec<Pubkey> = whirlpool_tick_arrays
+                                .iter()
+                                .map(|meta| meta.pubkey)
+                                .collect();
+
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == whirlpool.token_mint_a {
+                                (whirlpool.token_mint_a, whirlpool.token_mint_b)
+                            } else {
+                                (whirlpool.token_mint_b, whirlpool.token_mint_a)
+                            };
+
+                            pool_data.add_whirlpool_pool(
+                                pool_pubkey,
+                                whirlpool_oracle,
+                                token_vault,
+                                sol_vault,
+                                tick_arrays.clone(),
+                                memo_program_id, // memo_program for Token 2022
+                                token_mint,
+                                base_mint,
+                            );
+
+                            info!("Whirlpool pool added: {}", pool_pubkey);
+                            info!("    Token mint A: {}", whirlpool.token_mint_a);
+                            info!("    Token mint B: {}", whirlpool.token_mint_b);
+                            info!("    Token vault: {}", token_vault);
+                            info!("    Sol vault: {}", sol_vault);
+                            info!("    Oracle: {}", whirlpool_oracle);
+
+                            for (i, array) in tick_arrays.iter().enumerate() {
+                                info!("    Tick Array {}: {}", i, array);
+                            }
+                            info!("");
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing Whirlpool data from pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            return Err(anyhow::anyhow!("Error parsing Whirlpool data"));
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Whirlpool pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    return Err(anyhow::anyhow!("Error fetching Whirlpool pool account"));
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = raydium_clmm_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::RaydiumClmm, &rpc_client);
+        let programs = registry.raydium_clmm_programs();
+        for &pool_pubkey in pools {
+            let base = base_mints.base_side(&pool_pubkey);
+            let Some(loaded) = load_clmm_pool_for_mint(
+                rpc_client,
+                MarketPoolKind::RaydiumClmm,
+                &programs,
+                &pool_pubkey,
+                &mint,
+                &base,
+            ) else {
+                continue;
+            };
+            let state = &loaded.account.state;
+            pool_data.add_raydium_clmm_pool(
+                loaded.program_id,
+                pool_pubkey,
+                state.amm_config,
+                state.observation_key,
+                loaded.token_vault,
+                loaded.base_vault,
+                loaded.account.tick_arrays.clone(),
+                memo_program_id, // memo_program for Token 2022
+                loaded.token_mint,
+                loaded.base_mint,
+                state.liquidity,
+                state.sqrt_price_x64,
+                state.tick_current,
+            );
+            log_clmm_pool(MarketPoolKind::RaydiumClmm, &pool_pubkey, &loaded);
+        }
+    }
+
+    if let Some(pools) = meteora_damm_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::MeteoraDamm, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::MeteoraDamm,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.meteora_damm],
+                    ) {
+                        error!("Error: {}", e);
+                        return Err(e);
+                    }
+
+                    match meteora_damm_cpi::Pool::deserialize_unchecked(&account.data) {
+                        Ok(pool) => {
+                            if pool.token_a_mint != pool_data.mint
+                                && pool.token_b_mint != pool_data.mint
+                            {
+                                error!(
+                                    "Mint {} is not present in Meteora DAMM pool {}, skipping",
+                                    pool_data.mint, pool_pubkey
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Invalid Meteora DAMM pool: {}",
+                                    pool_pubkey
+                                ));
+                            }
+
+                            let base = base_mints.base_side(&pool_pubkey);
+                            if pool.token_a_mint != base && pool.token_b_mint != base {
+                                error!(
+                                    "Base mint {} is not present in Meteora DAMM pool {}",
+                                    base, pool_pubkey
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Base mint {} is not present in Meteora DAMM pool: {}",
+                                    base, pool_pubkey
+                                ));
+                            }
+
+                            let (x_vault, sol_vault) = if base == pool.token_a_mint {
+                                (pool.b_vault, pool.a_vault)
+                            } else {
+                                (pool.a_vault, pool.b_vault)
+                            };
+
+                            // Fetch vault accounts
+                            let x_vault_data = rpc_client.get_account(&x_vault)?;
+                            let sol_vault_data = rpc_client.get_account(&sol_vault)?;
+
+                            let x_vault_obj = meteora_vault_cpi::Vault::deserialize_unchecked(
+                                &mut x_vault_data.data.as_slice(),
+                            )?;
+                            let sol_vault_obj = meteora_vault_cpi::Vault::deserialize_unchecked(
+                                &mut sol_vault_data.data.as_slice(),
+                            )?;
+
+                            let x_token_vault = x_vault_obj.token_vault;
+                            let sol_token_vault = sol_vault_obj.token_vault;
+                            let x_lp_mint = x_vault_obj.lp_mint;
+                            let sol_lp_mint = sol_vault_obj.lp_mint;
+
+                            let (x_pool_lp, sol_pool_lp) = if base == pool.token_a_mint {
+                                (pool.b_vault_lp, pool.a_vault_lp)
+                            } else {
+                                (pool.a_vault_lp, pool.b_vault_lp)
+                            };
+
+                            let (x_admin_fee, sol_admin_fee) = if base == pool.token_a_mint {
+                                (pool.admin_token_b_fee, pool.admin_token_a_fee)
+                            } else {
+                                (pool.admin_token_a_fee, pool.admin_token_b_fee)
+                            };
+
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == pool.token_a_mint {
+                                (pool.token_a_mint, pool.token_b_mint)
+                            } else {
+                                (pool.token_b_mint, pool.token_a_mint)
+                            };
+
+                            pool_data.add_meteora_damm_pool(
+                                pool_pubkey,
+                                x_vault,
+                                sol_vault,
+                                x_token_vault,
+                                sol_token_vault,
+                                x_lp_mint,
+                                sol_lp_mint,
+                                x_pool_lp,
+                                sol_pool_lp,
+                                x_admin_fee,
+                                sol_admin_fee,
+                                token_mint,
+                                base_mint,
+                            );
+
+                            info!("Meteora DAMM pool added: {}", pool_pubkey);
+                            info!("    Token X vault: {}", x_token_vault);
+                            info!("    SOL vault: {}", sol_token_vault);
+                            info!("    Token X LP mint: {}", x_lp_mint);
+                            info!("    SOL LP mint: {}", sol_lp_mint);
+                            info!("    Token X pool LP: {}", x_pool_lp);
+                            info!("    SOL pool LP: {}", sol_pool_lp);
+                            info!("    Token X admin fee: {}", x_admin_fee);
+                            info!("    SOL admin fee: {}", sol_admin_fee);
+                            info!("");
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing Meteora DAMM pool data from pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            return Err(anyhow::anyhow!("Error parsing Meteora DAMM pool data"));
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Meteora DAMM pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    return Err(anyhow::anyhow!("Error fetching Meteora DAMM pool account"));
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = meteora_damm_v2_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::MeteoraDammV2, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::MeteoraDammV2,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.meteora_damm_v2],
+                    ) {
+                        error!("{}, skipping", e);
+                        continue;
+                    }
+
+                    match MeteoraDAmmV2Info::load_checked(&account.data) {
+                        Ok(meteora_damm_v2_info) => {
+                            info!("Meteora DAMM V2 pool added: {}", pool_pubkey);
+                            info!(
+                                "    Base mint: {}",
+                                meteora_damm_v2_info.base_mint
+                            );
+                            info!(
+                                "    Quote mint: {}",
+                                meteora_damm_v2_info.quote_mint
+                            );
+                            info!(
+                                "    Base vault: {}",
+                                meteora_damm_v2_info.base_vault
+                            );
+                            info!(
+                                "    Quote vault: {}",
+                                meteora_damm_v2_info.quote_vault
+                            );
+                            info!(
+                                "    Cliff fee: {}, scheduler mode: {}, dynamic fee: {}",
+                                meteora_damm_v2_info.fees.cliff_fee_numerator,
+                                meteora_damm_v2_info.fees.fee_scheduler_mode,
+                                meteora_damm_v2_info.fees.dynamic_fee_initialized
+                            );
+                            info!("");
+                            let base = base_mints.base_side(&pool_pubkey);
+                            let token_x_vault = if base == meteora_damm_v2_info.base_mint {
+                                meteora_damm_v2_info.quote_vault
+                            } else {
+                                meteora_damm_v2_info.base_vault
+                            };
+
+                            let token_sol_vault = if base == meteora_damm_v2_info.base_mint {
+                                meteora_damm_v2_info.base_vault
+                            } else {
+                                meteora_damm_v2_info.quote_vault
+                            };
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == meteora_damm_v2_info.base_mint {
+                                (meteora_damm_v2_info.base_mint, meteora_damm_v2_info.quote_mint)
+                            } else {
+                                (meteora_damm_v2_info.quote_mint, meteora_damm_v2_info.base_mint)
+                            };
+
+                            pool_data.add_meteora_damm_v2_pool(
+                                pool_pubkey,
+                                token_x_vault,
+                                token_sol_vault,
+                                token_mint,
+                                base_mint,
+                                meteora_damm_v2_info.fees,
+                            );
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing Meteora DAMM V2 pool data from pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            continue;
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Meteora DAMM V2 pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    continue;
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = vertigo_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::Vertigo, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::Vertigo,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.vertigo],
+                    ) {
+                        error!("Error: {}", e);
+                        return Err(e);
+                    }
+
+                    match VertigoInfo::load_checked(&account.data, &pool_pubkey) {
+                        Ok(vertigo_info) => {
+                            info!("Vertigo pool added: {}", pool_pubkey);
+                            info!("    Mint A: {}", vertigo_info.mint_a);
+                            info!("    Mint B: {}", vertigo_info.mint_b);
+
+                            // Following the original loading pattern from user's code:
+                            let non_base_vault = if pool_data.mint == vertigo_info.mint_a {
+                                derive_vault_address(&pool_pubkey, &vertigo_info.mint_b).0
+                            } else {
+                                derive_vault_address(&pool_pubkey, &vertigo_info.mint_a).0
+                            };
+                            let base_vault = if pool_data.mint == vertigo_info.mint_a {
+                                derive_vault_address(&pool_pubkey, &vertigo_info.mint_a).0
+                            } else {
+                                derive_vault_address(&pool_pubkey, &vertigo_info.mint_b).0
+                            };
+
+                            // Map to transaction expected fields:
+                            // base_mint is our trading token, non-base should be SOL
+                            let token_x_vault = base_vault; // vault for our trading token
+                            let token_sol_vault = non_base_vault; // vault for SOL
+
+                            info!("    Token X Vault: {}", token_x_vault);
+                            info!("    Token SOL Vault: {}", token_sol_vault);
+                            info!("");
+
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == vertigo_info.mint_a {
+                                (vertigo_info.mint_a, vertigo_info.mint_b)
+                            } else {
+                                (vertigo_info.mint_b, vertigo_info.mint_a)
+                            };
+
+                            // Vaults are derived, not read from the pool: make sure they exist
+                            let vaults =
+                                [(token_x_vault, token_mint), (token_sol_vault, base_mint)];
+                            match vertigo_vault_problem(&rpc_client, &vaults) {
+                                Ok(None) => {}
+                                Ok(Some(problem)) => {
+                                    warn!("Skipping Vertigo pool {}: {}", pool_pubkey, problem);
+                                    continue;
+                                }
+                                Err(e) => {
+                                    error!(
+                                        "Error fetching Vertigo vaults for pool {}: {:?}",
+                                        pool_pubkey, e
+                                    );
+                                    continue;
+                                }
+                            }
+
+                            pool_data.add_vertigo_pool(
+                                pool_pubkey,
+                                vertigo_info.pool,
+                                token_x_vault,
+                                token_sol_vault,
+                                token_mint,
+                                base_mint,
+                            );
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing Vertigo pool data from pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            continue;
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Vertigo pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    continue;
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = heaven_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::Heaven, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::Heaven,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.heaven],
+                    ) {
+                        error!("Error: {}", e);
+                        return Err(e);
+                    }
+
+                    match HeavenPoolState::parse(&account.data) {
+                        Some(heaven_info) => {
+                            info!("Heaven pool added: {}", pool_pubkey);
+                            info!("    Mint A: {}", heaven_info.mint_a);
+                            info!("    Mint B: {}", heaven_info.mint_b);
+                            info!("    Vault A: {}", heaven_info.vault_a);
+                            info!("    Vault B: {}", heaven_info.vault_b);
+                            info!("    Protocol Config: {}", heaven_info.protocol_config);
+                            info!("    Reserve A: {}", heaven_info.reserve_a);
+                            info!("    Reserve B: {}", heaven_info.reserve_b);
+
+                            // Determine which vault corresponds to token and base
+                            let (token_x_vault, token_base_vault) =
+                                if mint == heaven_info.mint_a {
+                                    (heaven_info.vault_a, heaven_info.vault_b)
+                                } else {
+                                    (heaven_info.vault_b, heaven_info.vault_a)
+                                };
+
+                            // Determine token_mint and base_mint
+                            let (token_mint, base_mint) = if mint == heaven_info.mint_a {
+                                (heaven_info.mint_a, heaven_info.mint_b)
+                            } else {
+                                (heaven_info.mint_b, heaven_info.mint_a)
+                            };
+
+                            // Validate that the base mint is one of the configured base mints
+                            if !base_mints.contains(&base_mint) {
+                                error!(
+                                    "Invalid Heaven pool: Expected a configured base mint, but found {}",
+                                    base_mint
+                                );
+                                return Err(anyhow::anyhow!(
+                                    "Invalid Heaven pool: Expected a configured base mint"
+                                ));
+                            }
+
+                            pool_data.add_heaven_pool(
+                                pool_pubkey,
+                                heaven_info.protocol_config,
+                                token_x_vault,
+                                token_base_vault,
+                                token_mint,
+                                base_mint,
+                                token_program,
+                            );
+
+                            info!("    Initialized Heaven pool: {}\n", pool_pubkey);
+                        }
+                        None => {
+                            error!(
+                                "Error parsing Heaven pool data from pool {}",
+                                pool_pubkey
+                            );
+                            return Err(anyhow::anyhow!("Failed to parse Heaven pool data"));
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Heaven pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    continue;
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = futarchy_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::Futarchy, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::Futarchy,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.futarchy],
+                    ) {
+                        error!("{}, skipping", e);
+                        continue;
+                    }
+
+                    match FutarchyInfo::load_checked(&account.data) {
+                        Ok(futarchy_info) => {
+                            info!("Futarchy pool added: {}", pool_pubkey);
+                            info!("    Base mint: {}", futarchy_info.base_mint);
+                            info!("    Quote mint: {}", futarchy_info.quote_mint);
+                            info!("    Base vault: {}", futarchy_info.base_vault);
+                            info!("    Quote vault: {}", futarchy_info.quote_vault);
+
+                            let (token_x_vault, token_base_vault, token_mint, base_mint) =
+                                if mint == futarchy_info.base_mint {
+                                    (
+                                        futarchy_info.base_vault,
+                                        futarchy_info.quote_vault,
+                                        futarchy_info.base_mint,
+                                        futarchy_info.quote_mint,
+                                    )
+                                } else if mint == futarchy_info.quote_mint {
+                                    (
+                                        futarchy_info.quote_vault,
+                                        futarchy_info.base_vault,
+                                        futarchy_info.quote_mint,
+                                        futarchy_info.base_mint,
+                                    )
+                                } else {
+                                    warn!(
+                                        "{} is not present in Futarchy pool {}, skipping",
+                                        mint, pool_pubkey
+                                    );
+                                    continue;
+                                };
+
+                            pool_data.add_futarchy_pool(
+                                futarchy_event_authority_for(&registry.futarchy),
+                                pool_pubkey,
+                                token_x_vault,
+                                token_base_vault,
+                                token_mint,
+                                base_mint,
+                            );
+
+                            info!("    Initialized Futarchy pool: {}\n", pool_pubkey);
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing Futarchy pool data from pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            continue;
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Futarchy pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    continue;
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = humidifi_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::Humidifi, &rpc_client);
+        for &pool_pubkey in pools {
+            match rpc_client.get_account(&pool_pubkey) {
+                Ok(account) => {
+                    if let Err(e) = check_owner(
+                        MarketPoolKind::Humidifi,
+                        &pool_pubkey,
+                        &account.owner,
+                        &[registry.humidifi],
+                    ) {
+                        error!("{}, skipping", e);
+                        continue;
+                    }
+
+                    match HumidifiInfo::load_checked(&account.data) {
+                        Ok(humidifi_info) => {
+                            info!("Humidifi pool added: {}", pool_pubkey);
+                            info!("    Base mint: {}", humidifi_info.base_mint);
+                            info!("    Quote mint: {}", humidifi_info.quote_mint);
+                            info!("    Base vault: {}", humidifi_info.base_vault);
+                            info!("    Quote vault: {}", humidifi_info.quote_vault);
+
+                            let base = base_mints.base_side(&pool_pubkey);
+                            let (token_x_vault, token_sol_vault) = if base == humidifi_info.base_mint {
+                                (humidifi_info.quote_vault, humidifi_info.base_vault)
+                            } else {
+                                (humidifi_info.base_vault, humidifi_info.quote_vault)
+                            };
+
+                            let (token_mint, base_mint) = if mint == humidifi_info.base_mint {
+                                (humidifi_info.base_mint, humidifi_info.quote_mint)
+                            } else {
+                                (humidifi_info.quote_mint, humidifi_info.base_mint)
+                            };
+
+                            pool_data.add_humidifi_pool(
+                                pool_pubkey,
+                                token_x_vault,
+                                token_sol_vault,
+                                token_mint,
+                                base_mint,
+                            );
+
+                            info!("    Initialized Humidifi pool: {}\n", pool_pubkey);
+                        }
+                        Err(e) => {
+                            error!(
+                                "Error parsing Humidifi pool data from pool {}: {:?}",
+                                pool_pubkey, e
+                            );
+                            continue;
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error fetching Humidifi pool account {}: {:?}",
+                        pool_pubkey, e
+                    );
+                    continue;
+                }
+            }
+        }
+    }
+
+    if let Some(pools) = pancakeswap_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::PancakeSwap, &rpc_client);
+        for &pool_pubkey in pools {
+            let base = base_mints.base_side(&pool_pubkey);
+            let Some(loaded) = load_clmm_pool_for_mint(
+                rpc_client,
+                MarketPoolKind::PancakeSwap,
+                &[registry.pancakeswap],
+                &pool_pubkey,
+                &mint,
+                &base,
+            ) else {
+                continue;
+            };
+            let state = &loaded.account.state;
+            pool_data.add_pancakeswap_pool(
+                pool_pubkey,
+                state.amm_config,
+                state.observation_key,
+                loaded.token_vault,
+                loaded.base_vault,
+                loaded.account.tick_arrays.clone(),
+                memo_program_id, // memo_program for Token 2022
+                loaded.token_mint,
+                loaded.base_mint,
+            );
+            log_clmm_pool(MarketPoolKind::PancakeSwap, &pool_pubkey, &loaded);
+        }
+    }
+
+    if let Some(pools) = byreal_pools {
+        let rpc_client = DexRpcClients::global().client(MarketPoolKind::Byreal, &rpc_client);
+        for &pool_pubkey in pools {
+            let base = base_mints.base_side(&pool_pubkey);
+            let Some(loaded) = load_clmm_pool_for_mint(
+                rpc_client,
+                MarketPoolKind::Byreal,
+                &[registry.byreal],
+                &pool_pubkey,
+                &mint,
+                &base,
+            ) else {
+                continue;
+            };
+            let state = &loaded.account.state;
+            pool_data.add_byreal_pool(
+                pool_pubkey,
+                state.amm_config,
+                state.observation_key,
+                loaded.token_vault,
+                loaded.base_vault,
+                loaded.account.tick_arrays.clone(),
+                memo_program_id, // memo_program for Token 2022
+                loaded.token_mint,
+                loaded.base_mint,
+            );
+            log_clmm_pool(MarketPoolKind::Byreal, &pool_pubkey, &loaded);
+        }
+    }
+
+    // Guard every add_* path: a pool quoting the mint against itself cannot be traded
+    pool_data.retain_pools(|pool| {
+        if pool.base_mint == mint {
+            error!(
+                "Skipping {:?} pool {}: token and base mint are both {}",
+                pool.kind, pool.pool, mint
+            );
+            return false;
+        }
+        true
+    });
+
+    drop_token_program_mismatches(&mut pool_data, &rpc_client);
+
+    Ok(pool_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Offsets of the mints in a Pump AMM pool account, after the discriminator, bump, index
+    /// and creator
+    const PUMP_BASE_MINT_OFFSET: usize = 8 + 1 + 2 + 32;
+    const PUMP_QUOTE_MINT_OFFSET: usize = PUMP_BASE_MINT_OFFSET + 32;
+    const PUMP_POOL_LEN: usize = PUMP_QUOTE_MINT_OFFSET + 32 * 4;
+    /// Offsets of the mints in a Meteora DAMM v2 pool account, and its shortest valid length
+    const DAMM_V2_TOKEN_A_MINT_OFFSET: usize = 168;
+    const DAMM_V2_TOKEN_B_MINT_OFFSET: usize = 200;
+    const DAMM_V2_POOL_LEN: usize = 481;
+
+    /// Pump AMM pool account trading `base_mint` against `quote_mint`
+    fn pump_pool(base_mint: &Pubkey, quote_mint: &Pubkey) -> Account {
+        let mut data = vec![0u8; PUMP_POOL_LEN];
+        data[PUMP_BASE_MINT_OFFSET..PUMP_BASE_MINT_OFFSET + 32].copy_from_slice(base_mint.as_ref());
+        data[PUMP_QUOTE_MINT_OFFSET..PUMP_QUOTE_MINT_OFFSET + 32]
+            .copy_from_slice(quote_mint.as_ref());
+        Account {
+            lamports: 1,
+            data,
+            owner: ProgramRegistry::global().pump,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn pools_with_identical_mints_are_skipped() {
+        let pool = Pubkey::new_unique();
+        let account = pump_pool(&sol_mint(), &sol_mint());
+        let (reason, message) = detect_market(&pool, &account, &BaseMints::default()).unwrap_err();
+        assert_eq!(reason, SkipReason::ParseError);
+        assert_eq!(
+            message,
+            format!(
+                "Failed to parse pool {}: Pool {} has identical mints on both sides ({})",
+                pool,
+                pool,
+                sol_mint()
+            )
+        );
+    }
+
+    #[test]
+    fn sol_quoted_pools_are_detected() {
+        let pool = Pubkey::new_unique();
+        let token = Pubkey::new_unique();
+        let account = pump_pool(&token, &sol_mint());
+        let market = detect_market(&pool, &account, &BaseMints::default()).unwrap();
+        assert_eq!(
+            market,
+            CachedMarket {
+                owner: ProgramRegistry::global().pump,
+                kind: MarketPoolKind::Pump,
+                token_mint: token,
+                base_mint: sol_mint(),
+            }
+        );
+    }
+
+    #[test]
+    fn pump_pools_quoted_in_usd1_are_detected() {
+        let token = Pubkey::new_unique();
+        // USD1 on either side of the pool is the base
+        for account in [
+            pump_pool(&token, &usd1_mint()),
+            pump_pool(&usd1_mint(), &token),
+        ] {
+            let market =
+                detect_market(&Pubkey::new_unique(), &account, &BaseMints::default()).unwrap();
+            assert_eq!((market.token_mint, market.base_mint), (token, usd1_mint()));
+        }
+    }
+
+    #[test]
+    fn usd1_quoted_pools_of_sol_only_dexes_have_no_base_side() {
+        let mut data = vec![0u8; DAMM_V2_POOL_LEN];
+        data[DAMM_V2_TOKEN_A_MINT_OFFSET..DAMM_V2_TOKEN_A_MINT_OFFSET + 32]
+            .copy_from_slice(Pubkey::new_unique().as_ref());
+        data[DAMM_V2_TOKEN_B_MINT_OFFSET..DAMM_V2_TOKEN_B_MINT_OFFSET + 32]
+            .copy_from_slice(usd1_mint().as_ref());
+        let account = Account {
+            owner: ProgramRegistry::global().meteora_damm_v2,
+            data,
+            ..pump_pool(&Pubkey::new_unique(), &sol_mint())
+        };
+        let (reason, _) =
+            detect_market(&Pubkey::new_unique(), &account, &BaseMints::default()).unwrap_err();
+        assert_eq!(reason, SkipReason::NoBaseSide);
+    }
+
+    #[test]
+    fn pools_of_unknown_programs_are_skipped() {
+        let account = Account {
+            owner: Pubkey::new_unique(),
+            ..pump_pool(&Pubkey::new_unique(), &sol_mint())
+        };
+        let (reason, _) =
+            detect_market(&Pubkey::new_unique(), &account, &BaseMints::default()).unwrap_err();
+        assert_eq!(reason, SkipReason::UnknownProgram);
+    }
+
+    fn pool_quoted_in(base_mint: Pubkey) -> PoolVaults {
+        PoolVaults {
+            kind: MarketPoolKind::Pump,
+            pool: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            base_mint,
+        }
+    }
+
+    #[test]
+    fn sol_pools_below_the_minimum_liquidity_are_dropped() {
+        let pool = pool_quoted_in(sol_mint());
+        assert_eq!(
+            liquidity_below_min(&pool, Some(4_000_000_000), 5.0, None),
+            Some(4.0)
+        );
+        assert_eq!(
+            liquidity_below_min(&pool, Some(5_000_000_000), 5.0, None),
+            None
+        );
+        // Unreadable vaults are kept
+        assert_eq!(liquidity_below_min(&pool, None, 5.0, None), None);
+    }
+
+    #[test]
+    fn stablecoin_pools_are_converted_with_the_sol_price() {
+        for base_mint in [usdc_mint(), usd1_mint()] {
+            let pool = pool_quoted_in(base_mint);
+            // 800 and 1,000 units at $200 per SOL are 4 and 5 SOL
+            assert_eq!(
+                liquidity_below_min(&pool, Some(800_000_000), 5.0, Some(200.0)),
+                Some(4.0)
+            );
+            assert_eq!(
+                liquidity_below_min(&pool, Some(1_000_000_000), 5.0, Some(200.0)),
+                None
+            );
+            // Kept without a SOL price
+            assert_eq!(liquidity_below_min(&pool, Some(1), 5.0, None), None);
+        }
+    }
+
+    #[test]
+    fn pools_of_other_base_mints_are_kept() {
+        let pool = pool_quoted_in(Pubkey::new_unique());
+        assert_eq!(liquidity_below_min(&pool, Some(1), 5.0, Some(200.0)), None);
+    }
+
+    #[test]
+    fn pools_with_vaults_of_another_token_program_are_dropped() {
+        let key = Pubkey::new_unique;
+        let (mint, sol) = (key(), sol_mint());
+        let token_2022 = crate::constants::token_2022_program_id();
+        let mut pool_data = MintPoolData::new(mint, &key(), spl_token::ID);
+        let (kept_vault, mismatched_vault, unread_vault) = (key(), key(), key());
+        for vault in [kept_vault, mismatched_vault, unread_vault] {
+            pool_data.add_raydium_pool(key(), key(), vault, key(), mint, sol, 4);
+        }
+        // Heaven pools are checked against their own token program
+        let (heaven_vault, heaven_mismatched_vault) = (key(), key());
+        pool_data.add_heaven_pool(key(), key(), heaven_vault, key(), mint, sol, token_2022);
+        let heaven_mismatched = key();
+        pool_data.add_heaven_pool(
+            heaven_mismatched,
+            key(),
+            heaven_mismatched_vault,
+            key(),
+            mint,
+            sol,
+            token_2022,
+        );
+        let owners = HashMap::from([
+            (kept_vault, spl_token::ID),
+            (mismatched_vault, token_2022),
+            (heaven_vault, token_2022),
+            (heaven_mismatched_vault, spl_token::ID),
+        ]);
+
+        retain_token_program_matches(&mut pool_data, &owners);
+        let token_vaults: Vec<Pubkey> = pool_data
+            .pool_vaults()
+            .iter()
+            .map(|pool| pool.token_vault)
+            .collect();
+        assert_eq!(token_vaults, [kept_vault, unread_vault, heaven_vault]);
+        assert_eq!(pool_data.heaven_pools.len(), 1);
+        assert_ne!(pool_data.heaven_pools[0].pool, heaven_mismatched);
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{error, warn, Level};
+
+/// Window repeats of a deduplicated line are suppressed for after it was logged
+pub const DEDUP_WINDOW: Duration = Duration::from_secs(10);
+/// Interval of the sweep reporting windows that closed without a new occurrence
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Every `LogDedup` that logged at least once, for the flush sweep. Locked once per
+/// callsite and by the sweep, never on the logging path.
+static REGISTRY: Mutex<Vec<&'static LogDedup>> = Mutex::new(Vec::new());
+
+#[derive(Debug)]
+struct Window {
+    opened: Instant,
+    suppressed: u64,
+    message: String,
+}
+
+/// Outcome of one occurrence of a deduplicated line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Occurrence {
+    /// Log the line. `repeated` is the number of repeats suppressed in the window that
+    /// closed before it, with the line logged at its start.
+    Log { repeated: Option<(u64, String)> },
+    /// Inside the window of an earlier occurrence of the same key, counted and not logged
+    Suppressed,
+}
+
+/// Log dedup of one callsite. The first occurrence per key (an endpoint, a pool) is logged;
+/// repeats within the window are only counted, and reported as "previous message repeated N
+/// times" when the window closes. Declared as a `static` per callsite, so callsites never
+/// share a lock.
+#[derive(Debug)]
+pub struct LogDedup {
+    level: Level,
+    window: Duration,
+    windows: Mutex<BTreeMap<String, Window>>,
+    registered: OnceLock<()>,
+}
+
+impl LogDedup {
+    /// Dedup logging at `level` (WARN or ERROR) with the default window
+    pub const fn new(level: Level) -> Self {
+        Self::with_window(level, DEDUP_WINDOW)
+    }
+
+    pub const fn with_window(level: Level, window: Duration) -> Self {
+        Self {
+            level,
+            window,
+            windows: Mutex::new(BTreeMap::new()),
+            registered: OnceLock::new(),
+        }
+    }
+
+    /// Logs `message` unless `key` was logged within the window, reporting the repeats of
+    /// the previous window first
+    pub fn log(&'static self, key: &str, message: fmt::Arguments<'_>) {
+        self.registered
+            .get_or_init(|| REGISTRY.lock().unwrap().push(self));
+        let Occurrence::Log { repeated } =
+            self.observe(key, Instant::now(), || message.to_string())
+        else {
+            return;
+        };
+        if let Some((count, previous)) = repeated {
+            self.emit(format_args!(
+                "Previous message repeated {} times: {}",
+                count, previous
+            ));
+        }
+        self.emit(message);
+    }
+
+    /// Records an occurrence of `key` at `now`. `message` is only rendered when the
+    /// occurrence opens a new window.
+    pub fn observe(&self, key: &str, now: Instant, message: impl FnOnce() -> String) -> Occurrence {
+        let mut windows = self.windows.lock().unwrap();
+        if let Some(window) = windows.get_mut(key) {
+            if now.saturating_duration_since(window.opened) < self.window {
+                window.suppressed += 1;
+                return Occurrence::Suppressed;
+            }
+        }
+        let previous = windows.insert(
+            key.to_string(),
+            Window {
+                opened: now,
+                suppressed: 0,
+                message: message(),
+            },
+        );
+        Occurrence::Log {
+            repeated: previous
+                .filter(|window| window.suppressed > 0)
+                .map(|window| (window.suppressed, window.message)),
+        }
+    }
+
+    /// Drops the windows closed at `now` and returns the repeats they suppressed with the
+    /// line logged at their start, skipping windows without repeats
+    pub fn take_closed(&self, now: Instant) -> Vec<(u64, String)> {
+        let mut closed = Vec::new();
+        self.windows.lock().unwrap().retain(|_, window| {
+            if now.saturating_duration_since(window.opened) < self.window {
+                return true;
+            }
+            if window.suppressed > 0 {
+                closed.push((window.suppressed, std::mem::take(&mut window.message)));
+            }
+            false
+        });
+        closed
+    }
+
+    fn emit(&self, message: fmt::Arguments<'_>) {
+        if self.level == Level::ERROR {
+            error!("{}", message);
+        } else {
+            warn!("{}", message);
+        }
+    }
+}
+
+/// Reports the repeats of windows that closed without a new occurrence, once a second
+pub async fn run_log_dedup_flush() {
+    loop {
+        tokio::time::sleep(FLUSH_INTERVAL).await;
+        let dedups: Vec<&'static LogDedup> = REGISTRY.lock().unwrap().clone();
+        let now = Instant::now();
+        for dedup in dedups {
+            for (count, message) in dedup.take_closed(now) {
+                dedup.emit(format_args!(
+                    "Previous message repeated {} times: {}",
+                    count, message
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(10);
+
+    fn dedup() -> LogDedup {
+        LogDedup::with_window(Level::WARN, WINDOW)
+    }
+
+    fn message(text: &str) -> impl FnOnce() -> String + '_ {
+        move || text.to_string()
+    }
+
+    #[test]
+    fn first_occurrence_is_logged() {
+        let dedup = dedup();
+        let now = Instant::now();
+        assert_eq!(
+            dedup.observe("endpoint", now, message("send failed")),
+            Occurrence::Log { repeated: None }
+        );
+    }
+
+    #[test]
+    fn repeats_within_the_window_are_counted() {
+        let dedup = dedup();
+        let start = Instant::now();
+        dedup.observe("endpoint", start, message("send failed"));
+        for i in 1..=3 {
+            let at = start + Duration::from_secs(i);
+            let occurrence = dedup.observe("endpoint", at, || panic!("suppressed repeat rendered"));
+            assert_eq!(occurrence, Occurrence::Suppressed);
+        }
+        // Nothing closed yet
+        assert!(dedup.take_closed(start + WINDOW - Duration::from_millis(1)).is_empty());
+
+        // The next occurrence after the window reports the repeats of the window before it
+        let next = dedup.observe("endpoint", start + WINDOW, message("send failed again"));
+        assert_eq!(
+            next,
+            Occurrence::Log {
+                repeated: Some((3, "send failed".to_string()))
+            }
+        );
+    }
+
+    #[test]
+    fn closed_windows_are_summarized_by_take_closed() {
+        let dedup = dedup();
+        let start = Instant::now();
+        dedup.observe("endpoint", start, message("send failed"));
+        dedup.observe("endpoint", start + Duration::from_secs(1), message("send failed"));
+        dedup.observe("endpoint", start + Duration::from_secs(2), message("send failed"));
+
+        assert_eq!(dedup.take_closed(start + WINDOW), vec![(2, "send failed".to_string())]);
+        // The window is gone: the summary is not repeated and the next occurrence starts over
+        assert!(dedup.take_closed(start + WINDOW * 2).is_empty());
+        assert_eq!(
+            dedup.observe("endpoint", start + WINDOW * 2, message("send failed")),
+            Occurrence::Log { repeated: None }
+        );
+    }
+
+    #[test]
+    fn windows_without_repeats_close_silently() {
+        let dedup = dedup();
+        let start = Instant::now();
+        dedup.observe("endpoint", start, message("send failed"));
+        assert!(dedup.take_closed(start + WINDOW).is_empty());
+        assert_eq!(
+            dedup.observe("endpoint", start + WINDOW, message("send failed")),
+            Occurrence::Log { repeated: None }
+        );
+    }
+
+    #[test]
+    fn keys_are_deduplicated_independently() {
+        let dedup = dedup();
+        let start = Instant::now();
+        assert_eq!(
+            dedup.observe("a", start, message("a failed")),
+            Occurrence::Log { repeated: None }
+        );
+        assert_eq!(
+            dedup.observe("b", start + Duration::from_secs(1), message("b failed")),
+            Occurrence::Log { repeated: None }
+        );
+        assert_eq!(
+            dedup.observe("a", start + Duration::from_secs(2), message("a failed")),
+            Occurrence::Suppressed
+        );
+
+        // Only a's window had repeats, and b's window opened later and is still open
+        assert_eq!(dedup.take_closed(start + WINDOW), vec![(1, "a failed".to_string())]);
+        assert_eq!(
+            dedup.observe("b", start + WINDOW, message("b failed")),
+            Occurrence::Suppressed
+        );
+        assert_eq!(
+            dedup.take_closed(start + WINDOW + Duration::from_secs(1)),
+            vec![(1, "b failed".to_string())]
+        );
+    }
+}
@@ -1,12 +1,54 @@
+mod account_batcher;
+mod accounting;
+mod adaptive_delay;
+mod amm_config;
+mod arb_math;
 mod ata;
+mod base_mints;
+mod blockhash;
 mod bot;
+mod build_tx;
+mod cluster;
 mod config;
+mod config_include;
+mod config_migration;
+mod confirmation;
 mod constants;
 mod dex;
-mod pool_refreshers;
+mod dex_rpc;
+mod endpoint_health;
+mod executor_errors;
+mod fee_spend;
+mod geyser;
+mod ix_data;
+mod jito;
+mod journal;
+mod keypair;
+mod list_pools;
+mod loader;
+mod log_dedup;
+mod migration;
+mod observations;
+mod paper_trade;
+mod paths;
+mod pda;
+mod pool_accounts;
+mod pool_kind_cache;
 mod pools;
-mod refresh;
+mod prefilter;
+mod pricing;
+mod program_registry;
+mod refresher;
+mod repro;
+mod risk;
+mod rpc_budget;
+mod rpc_metrics;
+mod snapshot;
+mod startup;
+mod status;
+mod token_program;
 mod transaction;
+mod worker;
 
 use clap::{App, Arg};
 use tracing::{info, Level};
@@ -14,14 +56,6 @@ use tracing_subscriber::FmtSubscriber;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed to set global default subscriber");
-
-    info!("Starting Solana Onchain Bot");
-
     let matches = App::new("Solana Onchain Arbitrage Bot")
         .version("0.1.0")
         .author("Cetipo")
@@ -35,12 +69,245 @@ async fn main() -> anyhow::Result<()> {
                 .takes_value(true)
                 .default_value("config.toml"),
         )
+        .arg(
+            Arg::with_name("strict-alt")
+                .long("strict-alt")
+                .help("Refuse to start when lookup table coverage is below routing.markets.min_alt_coverage_pct"),
+        )
+        .arg(
+            Arg::with_name("confirm-start")
+                .long("confirm-start")
+                .help("Show the wallet summary and ask for confirmation before trading"),
+        )
+        .arg(
+            Arg::with_name("paper-trade")
+                .long("paper-trade")
+                .help("Track simulated P&L on live prices without sending transactions"),
+        )
+        .arg(
+            Arg::with_name("one-shot")
+                .long("one-shot")
+                .help("Run a single refresh and send iteration for every mint, then exit"),
+        )
+        .subcommand(
+            App::new("analyze-spreads")
+                .about("Prints per-mint spread percentiles from a spread observations file")
+                .arg(
+                    Arg::with_name("file")
+                        .value_name("FILE")
+                        .help("Spread observations CSV file")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new("build-tx")
+                .about("Builds and prints the transaction the bot would send for one mint")
+                .arg(
+                    Arg::with_name("mint")
+                        .value_name("MINT")
+                        .help("Token mint")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("simulate")
+                        .long("simulate")
+                        .help("Also simulate the transaction and print its logs"),
+                ),
+        )
+        .subcommand(
+            App::new("list-pools")
+                .about("Lists the pools a mint would trade with their fee and SOL liquidity")
+                .arg(
+                    Arg::with_name("mint")
+                        .value_name("MINT")
+                        .help("Token mint")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("json")
+                        .long("json")
+                        .help("Print JSON instead of a table"),
+                ),
+        )
+        .subcommand(
+            App::new("migrate-config")
+                .about("Upgrades the config file to the current layout")
+                .arg(
+                    Arg::with_name("output")
+                        .value_name("OUTPUT")
+                        .help("File to write; the config file itself, with a .bak backup, when omitted"),
+                ),
+        )
+        .subcommand(
+            App::new("record-snapshot")
+                .about("Records every account read while initializing the config's pools")
+                .arg(
+                    Arg::with_name("output")
+                        .value_name("OUTPUT")
+                        .help("Snapshot file to write")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new("replay")
+                .about("Builds every mint's route from a snapshot and compares it with a golden file")
+                .arg(
+                    Arg::with_name("snapshot")
+                        .value_name("SNAPSHOT")
+                        .help("Snapshot file written by record-snapshot")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("golden")
+                        .long("golden")
+                        .value_name("FILE")
+                        .help("Expected routes; written when missing")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("update")
+                        .long("update")
+                        .help("Overwrite the golden file with the produced routes"),
+                ),
+        )
+        .subcommand(
+            App::new("capture-repro")
+                .about("Saves one mint's route with every account needed to rebuild it offline")
+                .arg(
+                    Arg::with_name("mint")
+                        .value_name("MINT")
+                        .help("Token mint")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .value_name("OUTPUT")
+                        .help("Repro file to write")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new("replay-repro")
+                .about("Rebuilds a captured route offline and compares it with the captured one")
+                .arg(
+                    Arg::with_name("repro")
+                        .value_name("REPRO")
+                        .help("Repro file written by capture-repro")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new("encrypt-keystore")
+                .about("Encrypts a private key into a .keystore file using the WALLET_PASSPHRASE passphrase")
+                .arg(
+                    Arg::with_name("key")
+                        .value_name("KEY")
+                        .help("Private key source: keypair file path or env:VAR_NAME")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .value_name("OUTPUT")
+                        .help("Keystore file to write (must end with .keystore)")
+                        .required(true),
+                ),
+        )
         .get_matches();
 
+    let subscriber = FmtSubscriber::builder().with_max_level(Level::INFO);
+    let installed = if matches!(matches.subcommand_name(), Some("list-pools" | "build-tx")) {
+        // Keep stdout for the pool table, JSON or transaction
+        tracing::subscriber::set_global_default(subscriber.with_writer(std::io::stderr).finish())
+    } else {
+        tracing::subscriber::set_global_default(subscriber.finish())
+    };
+    installed.expect("Failed to set global default subscriber");
+
+    info!("Starting Solana Onchain Bot");
+
+    if let Some(analyze) = matches.subcommand_matches("analyze-spreads") {
+        return observations::analyze_spreads(analyze.value_of("file").unwrap());
+    }
+
+    if let Some(encrypt) = matches.subcommand_matches("encrypt-keystore") {
+        return encrypt_keystore(
+            encrypt.value_of("key").unwrap(),
+            encrypt.value_of("output").unwrap(),
+        );
+    }
+
     let config_path = matches.value_of("config").unwrap();
+
+    if let Some(migrate) = matches.subcommand_matches("migrate-config") {
+        return config_migration::migrate_config_file(config_path, migrate.value_of("output"));
+    }
+
+    if let Some(build) = matches.subcommand_matches("build-tx") {
+        return build_tx::build_tx(
+            config_path,
+            build.value_of("mint").unwrap(),
+            build.is_present("simulate"),
+        )
+        .await;
+    }
+    if let Some(list) = matches.subcommand_matches("list-pools") {
+        return list_pools::list_pools(
+            config_path,
+            list.value_of("mint").unwrap(),
+            list.is_present("json"),
+        )
+        .await;
+    }
+
+    if let Some(record) = matches.subcommand_matches("record-snapshot") {
+        return snapshot::record_snapshot(config_path, record.value_of("output").unwrap()).await;
+    }
+
+    if let Some(replay) = matches.subcommand_matches("replay") {
+        return snapshot::replay_snapshot(
+            config_path,
+            replay.value_of("snapshot").unwrap(),
+            replay.value_of("golden").unwrap(),
+            replay.is_present("update"),
+        )
+        .await;
+    }
+
+    if let Some(capture) = matches.subcommand_matches("capture-repro") {
+        return repro::capture_repro(
+            config_path,
+            capture.value_of("mint").unwrap(),
+            capture.value_of("output").unwrap(),
+        )
+        .await;
+    }
+
+    if let Some(replay) = matches.subcommand_matches("replay-repro") {
+        return repro::replay_repro(config_path, replay.value_of("repro").unwrap()).await;
+    }
+
     info!("Using config file: {}", config_path);
 
-    bot::run_bot(config_path).await?;
+    let options = bot::RunOptions {
+        strict_alt: matches.is_present("strict-alt"),
+        confirm_start: matches.is_present("confirm-start"),
+        paper_trade: matches.is_present("paper-trade"),
+        one_shot: matches.is_present("one-shot"),
+    };
+    bot::run_bot(config_path, options).await?;
+
+    Ok(())
+}
 
+fn encrypt_keystore(key: &str, output: &str) -> anyhow::Result<()> {
+    if !output.ends_with(".keystore") {
+        anyhow::bail!("Keystore file name must end with .keystore");
+    }
+    let keypair = keypair::load_keypair(key)?;
+    let passphrase = std::env::var(keypair::PASSPHRASE_ENV)
+        .map_err(|_| anyhow::anyhow!("Set {} to the keystore passphrase", keypair::PASSPHRASE_ENV))?;
+    std::fs::write(output, keypair::encrypt_keystore(&keypair, &passphrase)?)?;
+    info!("Wrote keystore for {} to {}", solana_sdk::signer::Signer::pubkey(&keypair), output);
     Ok(())
 }
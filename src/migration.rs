@@ -0,0 +1,239 @@
+use crate::account_batcher::AccountBatcher;
+use crate::base_mints::BaseMints;
+use crate::config::{Config, MigrationConfig};
+use crate::constants::sol_mint;
+use crate::pools::{MintPoolData, PoolVaults};
+use crate::pricing::token_amount;
+use crate::loader::{initialize_discovered_pools, known_programs, MarketPoolKind};
+use anyhow::{anyhow, Result};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Log target of migration events, so they can be routed on their own
+/// (e.g. `RUST_LOG=info,migration=warn`)
+pub const MIGRATION_LOG_TARGET: &str = "migration";
+
+const DEFAULT_MIN_SOL_RESERVE: f64 = 1.0;
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(60);
+const DEFAULT_SEARCH_KINDS: [MarketPoolKind; 3] = [
+    MarketPoolKind::Pump,
+    MarketPoolKind::RaydiumCp,
+    MarketPoolKind::RaydiumV4,
+];
+
+/// Offsets of the two mint fields in a pool account, discriminator included
+fn mint_offsets(kind: MarketPoolKind) -> Option<[usize; 2]> {
+    match kind {
+        // discriminator, bump, index, creator, base_mint, quote_mint
+        MarketPoolKind::Pump => Some([43, 75]),
+        MarketPoolKind::RaydiumV4 => Some([400, 432]),
+        MarketPoolKind::RaydiumCp => Some([168, 200]),
+        _ => None,
+    }
+}
+
+/// Watches a mint's SOL-quoted pools for drained reserves and looks for the pool the
+/// token migrated to, swapping it in for the drained one
+pub struct MigrationWatcher {
+    min_sol_reserve: f64,
+    retry_after: Duration,
+    search_kinds: Vec<MarketPoolKind>,
+    base_mints: BaseMints,
+    last_search: HashMap<Pubkey, Instant>,
+}
+
+impl MigrationWatcher {
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(migration) = config
+            .routing
+            .migration
+            .as_ref()
+            .filter(|migration| migration.enabled)
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            min_sol_reserve: migration.min_sol_reserve.unwrap_or(DEFAULT_MIN_SOL_RESERVE),
+            retry_after: migration
+                .retry_after_secs
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_RETRY_AFTER),
+            search_kinds: search_kinds(migration)?,
+            base_mints: BaseMints::from_config(&config.routing)?,
+            last_search: HashMap::new(),
+        }))
+    }
+
+    /// Checks the SOL reserves after a pool refresh and handles every drained pool
+    pub async fn check(&mut self, pool_data: &mut MintPoolData, rpc_client: Arc<RpcClient>) {
+        let drained = match self.drained_pools(pool_data, &rpc_client) {
+            Ok(drained) => drained,
+            Err(e) => {
+                warn!("Failed to read SOL reserves of mint {}: {}", pool_data.mint, e);
+                return;
+            }
+        };
+        for pool in drained {
+            self.last_search.insert(pool.pool, Instant::now());
+            if let Err(e) = self.replace(pool_data, &pool, rpc_client.clone()).await {
+                warn!(
+                    "Migration search for drained {:?} pool {} failed: {}",
+                    pool.kind, pool.pool, e
+                );
+            }
+        }
+    }
+
+    /// SOL-quoted pools below `min_sol_reserve` that were not searched for recently
+    fn drained_pools(
+        &self,
+        pool_data: &MintPoolData,
+        rpc_client: &RpcClient,
+    ) -> Result<Vec<PoolVaults>> {
+        let sol = sol_mint();
+        let pools: Vec<PoolVaults> = pool_data
+            .pool_vaults()
+            .into_iter()
+            .filter(|pool| pool.base_mint == sol)
+            .filter(|pool| {
+                self.last_search
+                    .get(&pool.pool)
+                    .map_or(true, |last| last.elapsed() >= self.retry_after)
+            })
+            .collect();
+        let base_vaults: Vec<Pubkey> = pools.iter().map(|pool| pool.base_vault).collect();
+        let accounts = AccountBatcher::global().get_multiple_accounts(rpc_client, &base_vaults)?;
+        Ok(pools
+            .into_iter()
+            .zip(accounts)
+            .filter(|(pool, account)| {
+                account
+                    .as_ref()
+                    .and_then(|account| token_amount(&account.data))
+                    .and_then(|balance| pool.liquidity_sol(balance, None))
+                    .map_or(false, |liquidity| liquidity < self.min_sol_reserve)
+            })
+            .map(|(pool, _)| pool)
+            .collect())
+    }
+
+    async fn replace(
+        &self,
+        pool_data: &mut MintPoolData,
+        drained: &PoolVaults,
+        rpc_client: Arc<RpcClient>,
+    ) -> Result<()> {
+        let mint = pool_data.mint;
+        info!(
+            "{:?} pool {} of mint {} holds less than {} SOL, searching for a migrated pool",
+            drained.kind, drained.pool, mint, self.min_sol_reserve
+        );
+        let known: HashSet<Pubkey> = pool_data.pool_vaults().iter().map(|pool| pool.pool).collect();
+        let found: Vec<(Pubkey, MarketPoolKind)> =
+            discover_pools(&rpc_client, &mint, &self.search_kinds)?
+                .into_iter()
+                .filter(|(pool, _)| !known.contains(pool))
+                .collect();
+        if found.is_empty() {
+            info!("No migrated pool found for mint {} yet", mint);
+            return Ok(());
+        }
+
+        let discovered = initialize_discovered_pools(
+            mint,
+            &pool_data.wallet_account,
+            &found,
+            &self.base_mints,
+            rpc_client,
+        )
+        .await?;
+        let added: Vec<PoolVaults> = discovered.pool_vaults();
+        if added.is_empty() {
+            return Err(anyhow!(
+                "none of the {} discovered pools could be initialized",
+                found.len()
+            ));
+        }
+        if discovered.token_program != pool_data.token_program {
+            return Err(anyhow!("discovered pools use a different token program"));
+        }
+        pool_data.merge_pools(discovered);
+        pool_data.retain_pools(|pool| pool.pool != drained.pool);
+
+        for pool in &added {
+            warn!(
+                target: MIGRATION_LOG_TARGET,
+                "Mint {} migrated: drained {:?} pool {} replaced by {:?} pool {}",
+                mint,
+                drained.kind,
+                drained.pool,
+                pool.kind,
+                pool.pool
+            );
+        }
+        Ok(())
+    }
+}
+
+fn search_kinds(config: &MigrationConfig) -> Result<Vec<MarketPoolKind>> {
+    let kinds = config
+        .search_kinds
+        .clone()
+        .unwrap_or_else(|| DEFAULT_SEARCH_KINDS.to_vec());
+    for kind in &kinds {
+        if mint_offsets(*kind).is_none() {
+            return Err(anyhow!(
+                "routing.migration.search_kinds: {:?} pools cannot be searched by mint",
+                kind
+            ));
+        }
+    }
+    Ok(kinds)
+}
+
+/// Pools of the given kinds holding `mint` on either side, found with memcmp
+/// getProgramAccounts filters. Only addresses are fetched.
+pub fn discover_pools(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+    kinds: &[MarketPoolKind],
+) -> Result<Vec<(Pubkey, MarketPoolKind)>> {
+    let mut found = Vec::new();
+    for (program_id, kind) in known_programs()
+        .into_iter()
+        .filter(|(_, kind)| kinds.contains(kind))
+    {
+        let Some(offsets) = mint_offsets(kind) else {
+            continue;
+        };
+        for offset in offsets {
+            let config = RpcProgramAccountsConfig {
+                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    offset,
+                    &mint.to_bytes(),
+                ))]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    data_slice: Some(UiDataSliceConfig {
+                        offset: 0,
+                        length: 0,
+                    }),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            };
+            let accounts = rpc_client.get_program_accounts_with_config(&program_id, config)?;
+            found.extend(accounts.into_iter().map(|(pool, _)| (pool, kind)));
+        }
+    }
+    found.sort_by_key(|(pool, _)| *pool);
+    found.dedup_by_key(|(pool, _)| *pool);
+    Ok(found)
+}
@@ -0,0 +1,215 @@
+use crate::config::ObservationsConfig;
+use crate::paths::create_parent_dirs;
+use crate::pricing::{spread_between, PriceSnapshot};
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::{error, info};
+
+const HEADER: &str = "timestamp_ms,mint,pool_a,pool_b,spread_bps,best_direction";
+const DEFAULT_MAX_FILE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Spread between two pools of a mint at one point in time
+#[derive(Debug, Clone)]
+pub struct SpreadObservation {
+    pub timestamp_ms: u128,
+    pub mint: Pubkey,
+    pub pool_a: Pubkey,
+    pub pool_b: Pubkey,
+    pub spread_bps: f64,
+    /// True when buying on pool_a and selling on pool_b is the profitable direction
+    pub buy_a_sell_b: bool,
+}
+
+impl SpreadObservation {
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{:.2},{}",
+            self.timestamp_ms,
+            self.mint,
+            self.pool_a,
+            self.pool_b,
+            self.spread_bps,
+            if self.buy_a_sell_b { "buy_a_sell_b" } else { "buy_b_sell_a" }
+        )
+    }
+}
+
+/// Every pool pair of a snapshot that shares a base mint, pools ordered by address
+pub fn observations_from_snapshot(snapshot: &PriceSnapshot) -> Vec<SpreadObservation> {
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+
+    let mut observations = Vec::new();
+    for (i, first) in snapshot.prices.iter().enumerate() {
+        for second in &snapshot.prices[i + 1..] {
+            let (a, b) = if first.pool <= second.pool {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            if let Some(spread) = spread_between(a, b) {
+                observations.push(SpreadObservation {
+                    timestamp_ms,
+                    mint: snapshot.mint,
+                    pool_a: a.pool,
+                    pool_b: b.pool,
+                    spread_bps: spread.spread_bps,
+                    buy_a_sell_b: spread.buy.pool == a.pool,
+                });
+            }
+        }
+    }
+    observations
+}
+
+/// Sampled, fire-and-forget handle into the observations writer task
+#[derive(Clone)]
+pub struct ObservationSink {
+    sender: UnboundedSender<SpreadObservation>,
+    sample_rate: f64,
+}
+
+impl ObservationSink {
+    pub fn should_sample(&self) -> bool {
+        rand::random::<f64>() < self.sample_rate
+    }
+
+    pub fn record(&self, snapshot: &PriceSnapshot) {
+        for observation in observations_from_snapshot(snapshot) {
+            let _ = self.sender.send(observation);
+        }
+    }
+}
+
+/// Starts the writer task and returns the sink workers record into
+pub fn spawn_observation_writer(config: &ObservationsConfig) -> Result<ObservationSink> {
+    let path = PathBuf::from(&config.path);
+    let max_file_bytes = config.max_file_bytes.unwrap_or(DEFAULT_MAX_FILE_BYTES);
+    let writer = open_observations_file(&path)?;
+    let (sender, receiver) = unbounded_channel();
+
+    info!(
+        "Recording spread observations to {} (sample rate {})",
+        path.display(),
+        config.sample_rate.unwrap_or(1.0)
+    );
+    tokio::spawn(write_observations(path, max_file_bytes, writer, receiver));
+
+    Ok(ObservationSink {
+        sender,
+        sample_rate: config.sample_rate.unwrap_or(1.0).clamp(0.0, 1.0),
+    })
+}
+
+async fn write_observations(
+    path: PathBuf,
+    max_file_bytes: u64,
+    mut writer: BufWriter<File>,
+    mut receiver: UnboundedReceiver<SpreadObservation>,
+) {
+    while let Some(observation) = receiver.recv().await {
+        let mut batch = vec![observation];
+        while let Ok(observation) = receiver.try_recv() {
+            batch.push(observation);
+        }
+
+        let result = batch
+            .iter()
+            .try_for_each(|observation| writeln!(writer, "{}", observation.to_csv()))
+            .and_then(|_| writer.flush());
+        if let Err(e) = result {
+            error!("Failed to write spread observations: {}", e);
+            continue;
+        }
+
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or_default();
+        if size >= max_file_bytes {
+            match rotate(&path) {
+                Ok(rotated) => writer = rotated,
+                Err(e) => error!("Failed to rotate spread observations file: {}", e),
+            }
+        }
+    }
+}
+
+fn open_observations_file(path: &Path) -> Result<BufWriter<File>> {
+    create_parent_dirs(path)?;
+    let is_new = !path.exists();
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open observations file {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    if is_new {
+        writeln!(writer, "{}", HEADER)?;
+        writer.flush()?;
+    }
+    Ok(writer)
+}
+
+/// Moves the current file to `<path>.1` and starts a fresh one
+fn rotate(path: &Path) -> Result<BufWriter<File>> {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    fs::rename(path, &rotated)?;
+    open_observations_file(path)
+}
+
+/// Prints spread percentiles per mint from an observations file
+pub fn analyze_spreads(path: &str) -> Result<()> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open observations file {}", path))?;
+
+    let mut spreads_by_mint: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.is_empty() || line.starts_with("timestamp_ms") {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.len() < 5 {
+            continue;
+        }
+        if let Ok(spread_bps) = fields[4].parse::<f64>() {
+            spreads_by_mint
+                .entry(fields[1].to_string())
+                .or_default()
+                .push(spread_bps);
+        }
+    }
+
+    println!(
+        "{:<44} {:>8} {:>9} {:>9} {:>9} {:>9}",
+        "mint", "samples", "p50_bps", "p90_bps", "p99_bps", "max_bps"
+    );
+    for (mint, mut spreads) in spreads_by_mint {
+        spreads.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        println!(
+            "{:<44} {:>8} {:>9.2} {:>9.2} {:>9.2} {:>9.2}",
+            mint,
+            spreads.len(),
+            percentile(&spreads, 50.0),
+            percentile(&spreads, 90.0),
+            percentile(&spreads, 99.0),
+            spreads.last().copied().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+fn percentile(sorted: &[f64], percentile: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let index = (percentile / 100.0 * (sorted.len() - 1) as f64).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
@@ -0,0 +1,308 @@
+use crate::arb_math::{size_round_trip, CpLeg, SizedTrade, DEFAULT_SEARCH_ITERATIONS};
+use crate::config::PaperTradeConfig;
+use crate::constants::sol_mint;
+use crate::pricing::{priced_from_reserves, PoolPrice, PriceSnapshot};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info};
+
+const DEFAULT_STARTING_BALANCE_LAMPORTS: u64 = 10 * LAMPORTS_PER_SOL;
+const DEFAULT_FEE_BPS: u64 = 25;
+const DEFAULT_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Simulated P&L of a single mint
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MintPaperPnl {
+    pub opportunities: u64,
+    pub profit_lamports: u64,
+    last_slot: u64,
+}
+
+#[derive(Debug)]
+struct Ledger {
+    balance_lamports: u64,
+    mints: BTreeMap<Pubkey, MintPaperPnl>,
+}
+
+/// Virtual SOL balance credited with the estimated profit of every opportunity
+/// found on live prices. Nothing is ever sent.
+#[derive(Debug, Clone)]
+pub struct PaperLedger {
+    ledger: Arc<Mutex<Ledger>>,
+    starting_balance_lamports: u64,
+    fee_bps: u64,
+    log_interval: Duration,
+}
+
+impl PaperLedger {
+    pub fn new(config: Option<&PaperTradeConfig>) -> Self {
+        let starting_balance_lamports = config
+            .and_then(|config| config.starting_balance_lamports)
+            .unwrap_or(DEFAULT_STARTING_BALANCE_LAMPORTS);
+        Self {
+            ledger: Arc::new(Mutex::new(Ledger {
+                balance_lamports: starting_balance_lamports,
+                mints: BTreeMap::new(),
+            })),
+            starting_balance_lamports,
+            fee_bps: config.and_then(|config| config.fee_bps).unwrap_or(DEFAULT_FEE_BPS),
+            log_interval: config
+                .and_then(|config| config.log_interval_secs)
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_LOG_INTERVAL),
+        }
+    }
+
+    /// Applies the estimated profit of the snapshot's widest spread when it is SOL-quoted,
+    /// sized by `size_round_trip` and capped at the virtual balance. Each mint is counted at
+    /// most once per slot so an untouched spread is not booked repeatedly.
+    pub fn record(&self, snapshot: &PriceSnapshot) {
+        let mut guard = self.ledger.lock().unwrap();
+        let ledger = &mut *guard;
+        let balance = ledger.balance_lamports;
+        let entry = ledger.mints.entry(snapshot.mint).or_default();
+        if snapshot.slot <= entry.last_slot {
+            return;
+        }
+        entry.last_slot = snapshot.slot;
+
+        let Some(trade) = estimate_trade(snapshot, self.fee_bps, balance) else {
+            return;
+        };
+        entry.opportunities += 1;
+        entry.profit_lamports += trade.profit;
+        ledger.balance_lamports += trade.profit;
+        debug!(
+            "Paper trade on mint {}: {} lamports in, {} lamports profit (slot {})",
+            snapshot.mint, trade.input, trade.profit, snapshot.slot
+        );
+    }
+
+    pub fn log_summary(&self) {
+        let ledger = self.ledger.lock().unwrap();
+        let total = ledger.balance_lamports as i128 - self.starting_balance_lamports as i128;
+        info!(
+            "Paper trading: balance {:.6} SOL, cumulative P&L {:+.6} SOL",
+            ledger.balance_lamports as f64 / LAMPORTS_PER_SOL as f64,
+            total as f64 / LAMPORTS_PER_SOL as f64
+        );
+        for (mint, pnl) in ledger.mints.iter().filter(|(_, pnl)| pnl.opportunities > 0) {
+            info!(
+                "  {}: {} opportunities, {:+.6} SOL",
+                mint,
+                pnl.opportunities,
+                pnl.profit_lamports as f64 / LAMPORTS_PER_SOL as f64
+            );
+        }
+    }
+
+    /// Logs the cumulative P&L every `log_interval_secs`
+    pub fn spawn_reporter(&self) {
+        let ledger = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ledger.log_interval);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                ledger.log_summary();
+            }
+        });
+    }
+}
+
+/// One side of a round trip through a pool, oriented in the direction of the swap
+#[derive(Debug, Clone, Copy)]
+struct Leg {
+    cp: CpLeg,
+    /// Output per input unit at the pool's spot price, for pools that are not constant
+    /// product
+    spot_rate: Option<f64>,
+}
+
+impl Leg {
+    /// Leg buying the token with the base mint, or selling it for the base mint. Pools
+    /// without a known fee are charged `default_fee_bps`.
+    fn new(pool: &PoolPrice, buying: bool, default_fee_bps: u64) -> Self {
+        let (reserve_in, reserve_out, rate) = if buying {
+            (pool.base_reserve, pool.token_reserve, 1.0 / pool.price)
+        } else {
+            (pool.token_reserve, pool.base_reserve, pool.price)
+        };
+        Self {
+            cp: CpLeg {
+                reserve_in,
+                reserve_out,
+                fee_bps: pool
+                    .fee_bps
+                    .map_or(default_fee_bps, |fee| fee.round() as u64),
+            },
+            spot_rate: (!priced_from_reserves(pool.kind)).then_some(rate),
+        }
+    }
+
+    fn constant_product(&self) -> Option<&CpLeg> {
+        self.spot_rate.is_none().then_some(&self.cp)
+    }
+
+    /// Constant-product output, or the spot price net of the fee up to the output reserve
+    fn amount_out(&self, amount_in: u64) -> u64 {
+        match self.spot_rate {
+            None => self.cp.amount_out(amount_in),
+            Some(rate) => {
+                let out = amount_in as f64 * (1.0 - self.cp.fee_bps as f64 / 10_000.0) * rate;
+                (out.max(0.0) as u64).min(self.cp.reserve_out)
+            }
+        }
+    }
+}
+
+/// Round trip across the snapshot's widest spread, if it is quoted in SOL
+fn estimate_trade(
+    snapshot: &PriceSnapshot,
+    default_fee_bps: u64,
+    max_input: u64,
+) -> Option<SizedTrade> {
+    let spread = snapshot.best_spread()?;
+    if spread.buy.base_mint != sol_mint() {
+        return None;
+    }
+    let buy = Leg::new(&spread.buy, true, default_fee_bps);
+    let sell = Leg::new(&spread.sell, false, default_fee_bps);
+    let quote = |input| Some(sell.amount_out(buy.amount_out(input)));
+    size_round_trip(
+        buy.constant_product(),
+        sell.constant_product(),
+        quote,
+        max_input,
+        DEFAULT_SEARCH_ITERATIONS,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::usdc_mint;
+    use crate::loader::MarketPoolKind;
+    use std::time::Instant;
+
+    const SOL: u64 = LAMPORTS_PER_SOL;
+
+    /// Pool holding `base_sol` SOL of `base_mint` against a million tokens
+    fn pool(kind: MarketPoolKind, base_mint: Pubkey, base_sol: u64) -> PoolPrice {
+        let (token_reserve, base_reserve) = (1_000_000 * SOL, base_sol * SOL);
+        PoolPrice {
+            kind,
+            pool: Pubkey::new_unique(),
+            base_mint,
+            token_reserve,
+            base_reserve,
+            price: base_reserve as f64 / token_reserve as f64,
+            ui_price: None,
+            fee_bps: None,
+        }
+    }
+
+    /// Two constant-product pools 10% apart
+    fn snapshot(mint: Pubkey, base_mint: Pubkey, slot: u64) -> PriceSnapshot {
+        PriceSnapshot {
+            mint,
+            slot,
+            taken_at: Instant::now(),
+            prices: vec![
+                pool(MarketPoolKind::RaydiumV4, base_mint, 1_000),
+                pool(MarketPoolKind::Pump, base_mint, 1_100),
+            ],
+        }
+    }
+
+    fn ledger(starting_balance_lamports: u64) -> PaperLedger {
+        PaperLedger::new(Some(&PaperTradeConfig {
+            enabled: true,
+            starting_balance_lamports: Some(starting_balance_lamports),
+            fee_bps: None,
+            log_interval_secs: None,
+        }))
+    }
+
+    fn state(ledger: &PaperLedger, mint: &Pubkey) -> (u64, MintPaperPnl) {
+        let ledger = ledger.ledger.lock().unwrap();
+        (
+            ledger.balance_lamports,
+            ledger.mints.get(mint).copied().unwrap_or_default(),
+        )
+    }
+
+    #[test]
+    fn books_each_mint_once_per_slot() {
+        let ledger = ledger(10 * SOL);
+        let mint = Pubkey::new_unique();
+        ledger.record(&snapshot(mint, sol_mint(), 5));
+        let (balance, pnl) = state(&ledger, &mint);
+        assert_eq!(pnl.opportunities, 1);
+        assert!(pnl.profit_lamports > 0);
+        assert_eq!(balance, 10 * SOL + pnl.profit_lamports);
+
+        // Same or older slot: nothing more is booked
+        ledger.record(&snapshot(mint, sol_mint(), 5));
+        ledger.record(&snapshot(mint, sol_mint(), 4));
+        let (balance_after, pnl_after) = state(&ledger, &mint);
+        assert_eq!(balance_after, balance);
+        assert_eq!(pnl_after.opportunities, 1);
+        assert_eq!(pnl_after.profit_lamports, pnl.profit_lamports);
+
+        ledger.record(&snapshot(mint, sol_mint(), 6));
+        assert_eq!(state(&ledger, &mint).1.opportunities, 2);
+    }
+
+    #[test]
+    fn trades_are_capped_at_the_balance() {
+        let snapshot = snapshot(Pubkey::new_unique(), sol_mint(), 1);
+        // The uncapped optimum is well above one SOL
+        let uncapped = estimate_trade(&snapshot, DEFAULT_FEE_BPS, u64::MAX).unwrap();
+        assert!(uncapped.input > SOL);
+        let capped = estimate_trade(&snapshot, DEFAULT_FEE_BPS, SOL).unwrap();
+        assert_eq!(capped.input, SOL);
+
+        let ledger = ledger(SOL);
+        ledger.record(&snapshot);
+        assert_eq!(state(&ledger, &snapshot.mint).0, SOL + capped.profit);
+    }
+
+    #[test]
+    fn spreads_not_quoted_in_sol_are_skipped() {
+        let ledger = ledger(10 * SOL);
+        let snapshot = snapshot(Pubkey::new_unique(), usdc_mint(), 1);
+        ledger.record(&snapshot);
+        let (balance, pnl) = state(&ledger, &snapshot.mint);
+        assert_eq!(balance, 10 * SOL);
+        assert_eq!(pnl.opportunities, 0);
+    }
+
+    #[test]
+    fn known_pool_fees_replace_the_default() {
+        let mut snapshot = snapshot(Pubkey::new_unique(), sol_mint(), 1);
+        let default = estimate_trade(&snapshot, DEFAULT_FEE_BPS, 10 * SOL).unwrap();
+        for pool in &mut snapshot.prices {
+            pool.fee_bps = Some(100.0);
+        }
+        let expensive = estimate_trade(&snapshot, DEFAULT_FEE_BPS, 10 * SOL).unwrap();
+        assert!(expensive.profit < default.profit);
+    }
+
+    #[test]
+    fn non_constant_product_legs_are_searched() {
+        let mut snapshot = snapshot(Pubkey::new_unique(), sol_mint(), 1);
+        snapshot.prices[1].kind = MarketPoolKind::MeteoraDlmm;
+        let trade = estimate_trade(&snapshot, DEFAULT_FEE_BPS, 10 * SOL).unwrap();
+        // Selling at the spot price leaves only the buy leg's slippage, which is still
+        // profitable at the cap
+        assert!(trade.input > 9 * SOL, "{:?}", trade);
+        let buy = Leg::new(&snapshot.prices[0], true, DEFAULT_FEE_BPS);
+        let sell = Leg::new(&snapshot.prices[1], false, DEFAULT_FEE_BPS);
+        assert!(sell.constant_product().is_none());
+        assert_eq!(trade.output, sell.amount_out(buy.amount_out(trade.input)));
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Home directory from `HOME`, or `USERPROFILE` on Windows
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// Expands a leading `~` to the home directory and resolves relative paths against `base`,
+/// normally the directory of the config file
+pub fn resolve_path(path: &str, base: &Path) -> PathBuf {
+    let expanded = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => match home_dir() {
+            Some(home) => home.join(rest.trim_start_matches(['/', '\\'])),
+            None => PathBuf::from(path),
+        },
+        _ => PathBuf::from(path),
+    };
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        base.join(expanded)
+    }
+}
+
+/// Absolute directory of the config file at `config_path`, against which its relative
+/// paths are resolved
+pub fn config_dir(config_path: &str) -> Result<PathBuf> {
+    let parent = Path::new(config_path).parent().unwrap_or(Path::new(""));
+    if parent.is_absolute() {
+        return Ok(parent.to_path_buf());
+    }
+    Ok(env::current_dir()
+        .context("Failed to read the current directory")?
+        .join(parent))
+}
+
+/// Creates the missing parent directories of an output file
+pub fn create_parent_dirs(path: &Path) -> Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn home() -> PathBuf {
+        home_dir().expect("HOME is set")
+    }
+
+    #[test]
+    fn tilde_expands_to_the_home_directory() {
+        let base = Path::new("/etc/bot");
+        assert_eq!(resolve_path("~", base), home());
+        assert_eq!(resolve_path("~/", base), home());
+        assert_eq!(
+            resolve_path("~/keys/wallet.json", base),
+            home().join("keys/wallet.json")
+        );
+        assert_eq!(
+            resolve_path("~\\keys\\wallet.json", base),
+            home().join("keys\\wallet.json")
+        );
+    }
+
+    #[test]
+    fn tilde_of_another_user_or_mid_path_is_left_alone() {
+        let base = Path::new("/etc/bot");
+        assert_eq!(
+            resolve_path("~alice/wallet.json", base),
+            base.join("~alice/wallet.json")
+        );
+        assert_eq!(
+            resolve_path("keys/~/wallet.json", base),
+            base.join("keys/~/wallet.json")
+        );
+    }
+
+    #[test]
+    fn relative_paths_resolve_against_the_config_directory() {
+        let base = Path::new("/etc/bot");
+        assert_eq!(
+            resolve_path("journal.jsonl", base),
+            PathBuf::from("/etc/bot/journal.jsonl")
+        );
+        assert_eq!(
+            resolve_path("./data/journal.jsonl", base),
+            PathBuf::from("/etc/bot/./data/journal.jsonl")
+        );
+        assert_eq!(
+            resolve_path("../shared/journal.jsonl", base),
+            PathBuf::from("/etc/bot/../shared/journal.jsonl")
+        );
+        assert_eq!(
+            resolve_path("/var/lib/bot/journal.jsonl", base),
+            PathBuf::from("/var/lib/bot/journal.jsonl")
+        );
+    }
+
+    #[test]
+    fn config_dir_is_absolute() {
+        let cwd = env::current_dir().unwrap();
+        assert_eq!(
+            config_dir("/etc/bot/config.toml").unwrap(),
+            PathBuf::from("/etc/bot")
+        );
+        assert_eq!(config_dir("config.toml").unwrap(), cwd);
+        assert_eq!(config_dir("conf/config.toml").unwrap(), cwd.join("conf"));
+    }
+}
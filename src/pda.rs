@@ -0,0 +1,105 @@
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+/// Bound on cached derivations; tick and bin arrays keep adding entries as prices move
+const MAX_ENTRIES: usize = 100_000;
+
+type PdaKey = (Pubkey, Vec<u8>);
+
+fn cache() -> &'static RwLock<HashMap<PdaKey, (Pubkey, u8)>> {
+    static CACHE: OnceLock<RwLock<HashMap<PdaKey, (Pubkey, u8)>>> = OnceLock::new();
+    CACHE.get_or_init(RwLock::default)
+}
+
+/// Memoized `Pubkey::find_program_address`. Derivations are pure functions of the
+/// seeds and program, so entries never go stale: a pool re-added with different mints
+/// has different seeds and therefore a different key.
+pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    // Length-prefix each seed so that ["ab", "c"] and ["a", "bc"] stay distinct
+    let mut seed_key = Vec::with_capacity(seeds.iter().map(|seed| seed.len() + 1).sum());
+    for seed in seeds {
+        seed_key.push(seed.len() as u8);
+        seed_key.extend_from_slice(seed);
+    }
+    let key = (*program_id, seed_key);
+
+    if let Some(found) = cache().read().unwrap().get(&key) {
+        return *found;
+    }
+    let found = Pubkey::find_program_address(seeds, program_id);
+    let mut cache = cache().write().unwrap();
+    if cache.len() >= MAX_ENTRIES {
+        cache.clear();
+    }
+    cache.insert(key, found);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    const POOLS: usize = 200;
+
+    /// Vault-style seeds (pool, mint) of synthetic pools
+    fn synthetic_pools() -> Vec<(Pubkey, Pubkey)> {
+        (0..POOLS)
+            .map(|_| (Pubkey::new_unique(), Pubkey::new_unique()))
+            .collect()
+    }
+
+    #[test]
+    fn cached_derivation_matches_uncached() {
+        let program = Pubkey::new_unique();
+        for (pool, mint) in synthetic_pools().iter().take(20) {
+            let seeds: &[&[u8]] = &[b"vault", pool.as_ref(), mint.as_ref()];
+            let expected = Pubkey::find_program_address(seeds, &program);
+            assert_eq!(find_program_address(seeds, &program), expected);
+            // Served from the cache the second time
+            assert_eq!(find_program_address(seeds, &program), expected);
+        }
+    }
+
+    #[test]
+    fn seed_boundaries_are_part_of_the_key() {
+        let program = Pubkey::new_unique();
+        assert_eq!(
+            find_program_address(&[b"ab", b"c"], &program),
+            Pubkey::find_program_address(&[b"ab", b"c"], &program)
+        );
+        assert_eq!(
+            find_program_address(&[b"a", b"bc"], &program),
+            Pubkey::find_program_address(&[b"a", b"bc"], &program)
+        );
+    }
+
+    /// Run with `cargo test --release pda -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn cached_derivation_is_faster_than_uncached() {
+        let program = Pubkey::new_unique();
+        let pools = synthetic_pools();
+        let derive_all = |derive: fn(&[&[u8]], &Pubkey) -> (Pubkey, u8)| {
+            let start = Instant::now();
+            for (pool, mint) in &pools {
+                derive(&[b"vault", pool.as_ref(), mint.as_ref()], &program);
+            }
+            start.elapsed()
+        };
+
+        let uncached = derive_all(Pubkey::find_program_address);
+        // Fills the cache, then every derivation is a lookup
+        derive_all(find_program_address);
+        let cached = derive_all(find_program_address);
+        println!(
+            "{} pools: uncached {:?}, cached {:?} ({:.0}x)",
+            POOLS,
+            uncached,
+            cached,
+            uncached.as_secs_f64() / cached.as_secs_f64()
+        );
+        assert!(cached < uncached);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::amm_config::{AmmConfigCache, AmmConfigKind};
+use crate::dex::raydium::clmm_info::{TickArrayBitmapExtensionState, POOL_TICK_ARRAY_BITMAP_SEED};
+use crate::dex::raydium::{get_initialized_tick_array_pubkeys, parse_bitmap_extension, PoolState};
+use crate::loader::MarketPoolKind;
+use crate::pda::find_program_address;
+use anyhow::{bail, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use tracing::warn;
+
+/// Fails unless the `kind` pool account `pool` is owned by one of `programs`
+pub fn check_owner(
+    kind: MarketPoolKind,
+    pool: &Pubkey,
+    owner: &Pubkey,
+    programs: &[Pubkey],
+) -> Result<()> {
+    if !programs.contains(owner) {
+        bail!(
+            "{} pool {} is owned by {}, not by a {} program",
+            kind.name(),
+            pool,
+            owner,
+            kind.name()
+        );
+    }
+    Ok(())
+}
+
+/// A pool account with the Raydium CLMM layout (Raydium CLMM, PancakeSwap, Byreal), decoded
+/// together with the initialized tick arrays around its current tick
+#[derive(Debug)]
+pub struct ClmmPoolAccount {
+    pub state: PoolState,
+    pub tick_arrays: Vec<Pubkey>,
+}
+
+/// Decodes a CLMM pool account owned by `program_id` and derives its tick arrays. The tick
+/// spacing of the pool's cached AMM config takes precedence over the pool state's; with an
+/// `rpc_client` the config is fetched first when it is not cached yet.
+pub fn load_clmm_pool(
+    kind: MarketPoolKind,
+    pool: &Pubkey,
+    data: &[u8],
+    program_id: &Pubkey,
+    bitmap_extension: Option<&TickArrayBitmapExtensionState>,
+    rpc_client: Option<&RpcClient>,
+) -> Result<ClmmPoolAccount> {
+    let mut state = PoolState::load_checked(data)?;
+    let cache = AmmConfigCache::global();
+    if let Some(rpc_client) = rpc_client {
+        // Cache hit after the first load; retries configs whose earlier fetch failed
+        if let Err(e) = cache.get_or_fetch(rpc_client, &state.amm_config, AmmConfigKind::Clmm) {
+            warn!(
+                "Failed to load AMM config {} for {} pool {}: {}",
+                state.amm_config,
+                kind.name(),
+                pool,
+                e
+            );
+        }
+    }
+    cache.reconcile_tick_spacing(pool, &mut state);
+    let tick_arrays =
+        get_initialized_tick_array_pubkeys(pool, &state, bitmap_extension, program_id)?;
+    Ok(ClmmPoolAccount { state, tick_arrays })
+}
+
+/// Address of a CLMM pool's tick array bitmap extension
+pub fn clmm_bitmap_extension_address(pool: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    find_program_address(
+        &[POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(), pool.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+/// Fetches and parses a CLMM tick array bitmap extension, None when missing or unreadable
+pub fn fetch_bitmap_extension(
+    rpc_client: &RpcClient,
+    bitmap_extension: &Pubkey,
+) -> Option<TickArrayBitmapExtensionState> {
+    rpc_client
+        .get_account(bitmap_extension)
+        .ok()
+        .and_then(|account| parse_bitmap_extension(&account.data))
+}
+
+/// `(token vault, base vault)` of a CLMM pool quoted in `base`, None when `base` is neither
+/// of its mints
+pub fn clmm_vaults(state: &PoolState, base: &Pubkey) -> Option<(Pubkey, Pubkey)> {
+    if *base == state.token_mint_0 {
+        Some((state.token_vault_1, state.token_vault_0))
+    } else if *base == state.token_mint_1 {
+        Some((state.token_vault_0, state.token_vault_1))
+    } else {
+        None
+    }
+}
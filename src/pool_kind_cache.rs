@@ -0,0 +1,191 @@
+use crate::account_batcher::AccountBatcher;
+use crate::paths::create_parent_dirs;
+use crate::loader::MarketPoolKind;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+const CACHE_VERSION: u32 = 2;
+
+/// What startup detection learned about a market account
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachedMarket {
+    pub owner: Pubkey,
+    pub kind: MarketPoolKind,
+    pub token_mint: Pubkey,
+    pub base_mint: Pubkey,
+}
+
+/// On-disk layout. Pubkeys are base58 encoded.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    markets: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    owner: String,
+    kind: MarketPoolKind,
+    token_mint: String,
+    base_mint: String,
+}
+
+/// Sidecar file of detected pool kinds, so known markets skip the detection fetch on
+/// the next start. Unreadable, corrupted or outdated files are ignored and rebuilt.
+#[derive(Debug, Default, Clone)]
+pub struct PoolKindCache {
+    markets: HashMap<Pubkey, CachedMarket>,
+}
+
+impl PoolKindCache {
+    pub fn load(path: &str) -> Self {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!("Ignoring pool kind cache {}: {}", path, e);
+                return Self::default();
+            }
+        };
+        let file: CacheFile = match serde_json::from_str(&contents) {
+            Ok(file) => file,
+            Err(e) => {
+                warn!("Ignoring corrupted pool kind cache {}: {}", path, e);
+                return Self::default();
+            }
+        };
+        if file.version != CACHE_VERSION {
+            warn!(
+                "Ignoring pool kind cache {} with version {} (expected {})",
+                path, file.version, CACHE_VERSION
+            );
+            return Self::default();
+        }
+
+        let mut markets = HashMap::with_capacity(file.markets.len());
+        for (market, entry) in file.markets {
+            let parsed = (
+                Pubkey::from_str(&market),
+                Pubkey::from_str(&entry.owner),
+                Pubkey::from_str(&entry.token_mint),
+                Pubkey::from_str(&entry.base_mint),
+            );
+            match parsed {
+                (Ok(market), Ok(owner), Ok(token_mint), Ok(base_mint)) => {
+                    markets.insert(
+                        market,
+                        CachedMarket {
+                            owner,
+                            kind: entry.kind,
+                            token_mint,
+                            base_mint,
+                        },
+                    );
+                }
+                _ => warn!("Ignoring malformed pool kind cache entry for {}", market),
+            }
+        }
+        info!("Loaded {} cached pool kinds from {}", markets.len(), path);
+        Self { markets }
+    }
+
+    /// Writes the cache atomically through a temporary file
+    pub fn save(&self, path: &str) -> Result<()> {
+        let file = CacheFile {
+            version: CACHE_VERSION,
+            markets: self
+                .markets
+                .iter()
+                .map(|(market, cached)| {
+                    (
+                        market.to_string(),
+                        CacheEntry {
+                            owner: cached.owner.to_string(),
+                            kind: cached.kind,
+                            token_mint: cached.token_mint.to_string(),
+                            base_mint: cached.base_mint.to_string(),
+                        },
+                    )
+                })
+                .collect(),
+        };
+        create_parent_dirs(Path::new(path))?;
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, serde_json::to_vec_pretty(&file)?)
+            .with_context(|| format!("Failed to write pool kind cache {}", tmp_path))?;
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to replace pool kind cache {}", path))?;
+        Ok(())
+    }
+
+    pub fn get(&self, market: &Pubkey) -> Option<&CachedMarket> {
+        self.markets.get(market)
+    }
+
+    pub fn insert(&mut self, market: Pubkey, cached: CachedMarket) {
+        self.markets.insert(market, cached);
+    }
+
+    /// Drops entries of markets no longer configured
+    pub fn retain<F: FnMut(&Pubkey) -> bool>(&mut self, mut keep: F) {
+        self.markets.retain(|market, _| keep(market));
+    }
+
+    pub fn len(&self) -> usize {
+        self.markets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    /// Re-checks the owners of `markets` in the background and drops entries whose
+    /// account is gone or changed owner, so the next start detects them again
+    pub fn spawn_verification(
+        mut self,
+        path: String,
+        markets: Vec<Pubkey>,
+        rpc_client: Arc<RpcClient>,
+    ) {
+        if markets.is_empty() {
+            return;
+        }
+        tokio::spawn(async move {
+            let batcher = AccountBatcher::global();
+            let accounts = match batcher.get_multiple_accounts(&rpc_client, &markets) {
+                Ok(accounts) => accounts,
+                Err(e) => {
+                    warn!("Pool kind cache verification failed: {}", e);
+                    return;
+                }
+            };
+            let mut invalidated = 0;
+            for (market, account) in markets.iter().zip(accounts) {
+                let Some(cached) = self.markets.get(market) else {
+                    continue;
+                };
+                if account.map(|account| account.owner) != Some(cached.owner) {
+                    warn!(
+                        "Cached {:?} market {} changed owner or closed, re-detecting next start",
+                        cached.kind, market
+                    );
+                    self.markets.remove(market);
+                    invalidated += 1;
+                }
+            }
+            if invalidated > 0 {
+                if let Err(e) = self.save(&path) {
+                    warn!("{:#}", e);
+                }
+            }
+        });
+    }
+}
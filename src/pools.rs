@@ -1,27 +1,37 @@
+use crate::amm_config::AmmConfigKind;
+use crate::dex::meteora::dammv2_info::DammV2Fees;
+use crate::pda::find_program_address;
+use crate::pricing::{priced_from_reserves, SolPrice};
+use crate::program_registry::ProgramRegistry;
 use crate::{
     constants::sol_mint,
-    dex::{
-        byreal::byreal_program_id,
-        pancakeswap::pancakeswap_program_id,
-        raydium::{clmm_info::POOL_TICK_ARRAY_BITMAP_SEED, raydium_clmm_program_id},
-    },
+    loader::MarketPoolKind,
+    dex::raydium::clmm_info::POOL_TICK_ARRAY_BITMAP_SEED,
 };
 
 const POOL_TICK_ARRAY_BITMAP_SEED_CLMM: &str = "pool_tick_array_bitmap_extension";
 use solana_program::instruction::AccountMeta;
+use solana_program::native_token::LAMPORTS_PER_SOL;
 use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
 
+/// Raydium AMM V4 pool of a mint
 #[derive(Debug, Clone)]
 pub struct RaydiumPool {
+    pub program_id: Pubkey,
     pub pool: Pubkey,
     pub token_vault: Pubkey,
     pub sol_vault: Pubkey,
     pub token_mint: Pubkey,
     pub base_mint: Pubkey,
+    /// `RaydiumAmmInfo::layout_version` the pool was decoded with
+    pub layout_version: u8,
 }
 
+/// Raydium CP-Swap pool of a mint
 #[derive(Debug, Clone)]
 pub struct RaydiumCpPool {
+    pub program_id: Pubkey,
     pub pool: Pubkey,
     pub token_vault: Pubkey,
     pub sol_vault: Pubkey,
@@ -29,8 +39,25 @@ pub struct RaydiumCpPool {
     pub observation: Pubkey,
     pub token_mint: Pubkey,
     pub base_mint: Pubkey,
+    /// Uncollected protocol and fund fees held in the token vault, as of the last refresh
+    pub token_fees: u64,
+    /// Uncollected protocol and fund fees held in the base vault, as of the last refresh
+    pub base_fees: u64,
 }
 
+impl RaydiumCpPool {
+    /// Swappable `(token, base)` reserves from the `(token, base)` vault balances. The
+    /// program quotes against the vault balances minus the fees it has not collected yet.
+    pub fn net_reserves(&self, vault_balances: (u64, u64)) -> (u64, u64) {
+        let (token_balance, base_balance) = vault_balances;
+        (
+            token_balance.saturating_sub(self.token_fees),
+            base_balance.saturating_sub(self.base_fees),
+        )
+    }
+}
+
+/// Pump AMM pool of a mint
 #[derive(Debug, Clone)]
 pub struct PumpPool {
     pub pool: Pubkey,
@@ -45,8 +72,11 @@ pub struct PumpPool {
     pub base_mint: Pubkey,
     pub is_mayhem_mode: bool,
     pub is_cashback_coin: bool,
+    /// `PumpAmmInfo::layout_version` the pool was decoded with
+    pub layout_version: u8,
 }
 
+/// Meteora DLMM pair of a mint, with the bin arrays around its active bin
 #[derive(Debug, Clone)]
 pub struct DlmmPool {
     pub pair: Pubkey,
@@ -55,11 +85,16 @@ pub struct DlmmPool {
     pub oracle: Pubkey,
     pub bin_array_bitmap_extension: Option<Pubkey>,
     pub bin_arrays: Vec<Pubkey>,
+    pub bin_step: u16,
     pub memo_program: Option<Pubkey>, // For Token 2022 support
     pub token_mint: Pubkey,
     pub base_mint: Pubkey,
+    /// Context slot of the pool account the arrays were last computed from, 0 before the
+    /// first refresh
+    pub refreshed_slot: u64,
 }
 
+/// Orca Whirlpool of a mint, with the tick arrays around its current tick
 #[derive(Debug, Clone)]
 pub struct WhirlpoolPool {
     pub pool: Pubkey,
@@ -70,10 +105,15 @@ pub struct WhirlpoolPool {
     pub memo_program: Option<Pubkey>, // For Token 2022 support
     pub token_mint: Pubkey,
     pub base_mint: Pubkey,
+    /// Context slot of the pool account the arrays were last computed from, 0 before the
+    /// first refresh
+    pub refreshed_slot: u64,
 }
 
+/// Raydium CLMM pool of a mint, with the tick arrays around its current tick
 #[derive(Debug, Clone)]
 pub struct RaydiumClmmPool {
+    pub program_id: Pubkey,
     pub pool: Pubkey,
     pub amm_config: Pubkey,
     pub observation_state: Pubkey,
@@ -84,8 +124,16 @@ pub struct RaydiumClmmPool {
     pub memo_program: Option<Pubkey>, // For Token 2022 support
     pub token_mint: Pubkey,
     pub base_mint: Pubkey,
+    /// Active liquidity, price and tick as of the last refresh, for quoting
+    pub liquidity: u128,
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    /// Context slot of the pool account the arrays were last computed from, 0 before the
+    /// first refresh
+    pub refreshed_slot: u64,
 }
 
+/// Meteora DAMM pool of a mint, with the dynamic vaults holding its reserves
 #[derive(Debug, Clone)]
 pub struct MeteoraDAmmPool {
     pub pool: Pubkey,
@@ -103,6 +151,7 @@ pub struct MeteoraDAmmPool {
     pub base_mint: Pubkey,
 }
 
+/// Meteora DAMM v2 pool of a mint
 #[derive(Debug, Clone)]
 pub struct MeteoraDAmmV2Pool {
     pub pool: Pubkey,
@@ -110,8 +159,11 @@ pub struct MeteoraDAmmV2Pool {
     pub token_sol_vault: Pubkey,
     pub token_mint: Pubkey,
     pub base_mint: Pubkey,
+    /// Fee scheduler and dynamic fee state as of the last load or refresh
+    pub fees: DammV2Fees,
 }
 
+/// Vertigo pool of a mint
 #[derive(Debug, Clone)]
 pub struct VertigoPool {
     pub pool: Pubkey,
@@ -122,6 +174,7 @@ pub struct VertigoPool {
     pub base_mint: Pubkey,
 }
 
+/// Heaven pool of a mint
 #[derive(Debug, Clone)]
 pub struct HeavenPool {
     pub pool: Pubkey,
@@ -133,6 +186,7 @@ pub struct HeavenPool {
     pub token_program: Pubkey, // Support for Token-2022
 }
 
+/// Futarchy DAO pool of a mint
 #[derive(Debug, Clone)]
 pub struct FutarchyPool {
     pub event_authority: Pubkey,
@@ -143,6 +197,7 @@ pub struct FutarchyPool {
     pub base_mint: Pubkey,
 }
 
+/// HumidiFi pool of a mint
 #[derive(Debug, Clone)]
 pub struct HumidifiPool {
     pub pool: Pubkey,
@@ -152,6 +207,7 @@ pub struct HumidifiPool {
     pub base_mint: Pubkey,
 }
 
+/// PancakeSwap CLMM pool of a mint, with the tick arrays around its current tick
 #[derive(Debug, Clone)]
 pub struct PancakeswapPool {
     pub pool: Pubkey,
@@ -164,8 +220,12 @@ pub struct PancakeswapPool {
     pub memo_program: Option<Pubkey>,
     pub token_mint: Pubkey,
     pub base_mint: Pubkey,
+    /// Context slot of the pool account the arrays were last computed from, 0 before the
+    /// first refresh
+    pub refreshed_slot: u64,
 }
 
+/// Byreal CLMM pool of a mint, with the tick arrays around its current tick
 #[derive(Debug, Clone)]
 pub struct ByrealPool {
     pub pool: Pubkey,
@@ -178,13 +238,78 @@ pub struct ByrealPool {
     pub memo_program: Option<Pubkey>,
     pub token_mint: Pubkey,
     pub base_mint: Pubkey,
+    /// Context slot of the pool account the arrays were last computed from, 0 before the
+    /// first refresh
+    pub refreshed_slot: u64,
+}
+
+/// Invokes `$action!(pools, kind, pool field, token vault field, base vault field)`
+/// for every pool list of a `MintPoolData`, in account-meta order
+macro_rules! for_each_pool_list {
+    ($data:expr, $action:ident) => {
+        $action!($data.raydium_pools, MarketPoolKind::RaydiumV4, pool, token_vault, sol_vault);
+        $action!($data.raydium_cp_pools, MarketPoolKind::RaydiumCp, pool, token_vault, sol_vault);
+        $action!($data.pump_pools, MarketPoolKind::Pump, pool, token_vault, sol_vault);
+        $action!($data.dlmm_pairs, MarketPoolKind::MeteoraDlmm, pair, token_vault, sol_vault);
+        $action!($data.whirlpool_pools, MarketPoolKind::Whirlpool, pool, x_vault, y_vault);
+        $action!($data.raydium_clmm_pools, MarketPoolKind::RaydiumClmm, pool, x_vault, y_vault);
+        $action!(
+            $data.meteora_damm_pools,
+            MarketPoolKind::MeteoraDamm,
+            pool,
+            token_x_token_vault,
+            token_sol_token_vault
+        );
+        $action!(
+            $data.meteora_damm_v2_pools,
+            MarketPoolKind::MeteoraDammV2,
+            pool,
+            token_x_vault,
+            token_sol_vault
+        );
+        $action!($data.vertigo_pools, MarketPoolKind::Vertigo, pool, token_x_vault, token_sol_vault);
+        $action!($data.heaven_pools, MarketPoolKind::Heaven, pool, token_x_vault, token_base_vault);
+        $action!($data.futarchy_pools, MarketPoolKind::Futarchy, dao, token_x_vault, token_base_vault);
+        $action!($data.humidifi_pools, MarketPoolKind::Humidifi, pool, token_x_vault, token_sol_vault);
+        $action!($data.pancakeswap_pools, MarketPoolKind::PancakeSwap, pool, x_vault, y_vault);
+        $action!($data.byreal_pools, MarketPoolKind::Byreal, pool, x_vault, y_vault);
+    };
 }
 
+/// DEX-independent view of a pool's token accounts
+#[derive(Debug, Clone, Copy)]
+pub struct PoolVaults {
+    pub kind: MarketPoolKind,
+    pub pool: Pubkey,
+    pub token_vault: Pubkey,
+    pub base_vault: Pubkey,
+    pub base_mint: Pubkey,
+}
+
+impl PoolVaults {
+    /// Base-side reserve expressed in SOL. SOL-quoted pools need no price; pools quoted in
+    /// another base mint (USDC, USD1) need the SOL price in that mint, which has to come
+    /// from outside the pool set (e.g. an oracle). None when such a price is missing.
+    pub fn liquidity_sol(&self, base_reserve: u64, sol_price: Option<&SolPrice>) -> Option<f64> {
+        if self.base_mint == sol_mint() {
+            return Some(base_reserve as f64 / LAMPORTS_PER_SOL as f64);
+        }
+        let sol_price = sol_price.filter(|price| price.price > 0.0)?;
+        let base_amount = base_reserve as f64 / 10f64.powi(sol_price.base_decimals as i32);
+        Some(base_amount / sol_price.price)
+    }
+}
+
+/// Every pool of one mint across the supported DEXes, plus the trading wallet's accounts
+/// the executor instruction needs. Pools are kept per DEX in account-meta order.
 #[derive(Debug, Clone)]
 pub struct MintPoolData {
     pub mint: Pubkey,
-    pub token_program: Pubkey, // Support for both Token and Token 2022
+    /// Token program owning `mint`: SPL Token or Token 2022
+    pub token_program: Pubkey,
+    /// Trading wallet
     pub wallet_account: Pubkey,
+    /// Wrapped SOL ATA of the trading wallet
     pub wallet_wsol_account: Pubkey,
     pub raydium_pools: Vec<RaydiumPool>,
     pub raydium_cp_pools: Vec<RaydiumCpPool>,
@@ -203,6 +328,7 @@ pub struct MintPoolData {
 }
 
 impl MintPoolData {
+    /// Empty pool set of `mint` traded from `wallet_account`
     pub fn new(mint: Pubkey, wallet_account: &Pubkey, token_program: Pubkey) -> Self {
         let sol = sol_mint();
         let wallet_wsol_pk =
@@ -229,25 +355,191 @@ impl MintPoolData {
         }
     }
 
+    /// Points the wallet-derived accounts at another trading wallet
+    pub fn set_wallet(&mut self, wallet_account: &Pubkey) {
+        self.wallet_account = *wallet_account;
+        self.wallet_wsol_account =
+            spl_associated_token_account::get_associated_token_address(wallet_account, &sol_mint());
+    }
+
+    /// Token and base vaults of every pool, in account-meta order
+    pub fn pool_vaults(&self) -> Vec<PoolVaults> {
+        let mut vaults = Vec::new();
+        macro_rules! push_vaults {
+            ($pools:expr, $kind:expr, $pool:ident, $token_vault:ident, $base_vault:ident) => {
+                for pool in &$pools {
+                    vaults.push(PoolVaults {
+                        kind: $kind,
+                        pool: pool.$pool,
+                        token_vault: pool.$token_vault,
+                        base_vault: pool.$base_vault,
+                        base_mint: pool.base_mint,
+                    });
+                }
+            };
+        }
+        for_each_pool_list!(self, push_vaults);
+        vaults
+    }
+
+    /// Swappable `(token, base)` reserves of `pool` from its `(token, base)` vault balances.
+    /// Only Raydium CP vaults hold fees outside the reserve; other balances are returned as is.
+    pub fn net_reserves(&self, pool: &PoolVaults, vault_balances: (u64, u64)) -> (u64, u64) {
+        if pool.kind != MarketPoolKind::RaydiumCp {
+            return vault_balances;
+        }
+        self.raydium_cp_pools
+            .iter()
+            .find(|cp_pool| cp_pool.pool == pool.pool)
+            .map_or(vault_balances, |cp_pool| cp_pool.net_reserves(vault_balances))
+    }
+
+    /// Widest spot price spread, in bps, between two of the mint's pools sharing a base mint.
+    /// Reserve-priced pools are priced from `reserves` (token amounts by vault address),
+    /// Raydium CLMM pools from the square root price of their last refresh. Other pools are
+    /// left out. None when fewer than two pools with a common base mint have a price.
+    pub fn max_spread_bps(&self, reserves: &HashMap<Pubkey, u64>) -> Option<u32> {
+        let mut prices: Vec<(Pubkey, f64)> = self
+            .pool_vaults()
+            .iter()
+            .filter(|pool| priced_from_reserves(pool.kind))
+            .filter_map(|pool| {
+                let balances =
+                    (*reserves.get(&pool.token_vault)?, *reserves.get(&pool.base_vault)?);
+                let (token_reserve, base_reserve) = self.net_reserves(pool, balances);
+                (token_reserve > 0 && base_reserve > 0)
+                    .then(|| (pool.base_mint, base_reserve as f64 / token_reserve as f64))
+            })
+            .collect();
+        prices.extend(self.raydium_clmm_pools.iter().filter_map(|pool| {
+            // CLMM pools order their mints, so the token is mint 0 when it sorts first
+            let price = clmm_spot_price(pool.sqrt_price_x64, pool.token_mint < pool.base_mint)?;
+            Some((pool.base_mint, price))
+        }));
+
+        let mut widest: Option<f64> = None;
+        for (i, (base_a, a)) in prices.iter().enumerate() {
+            for (base_b, b) in &prices[i + 1..] {
+                if base_a != base_b {
+                    continue;
+                }
+                let bps = (a.max(*b) - a.min(*b)) / a.min(*b) * 10_000.0;
+                widest = Some(widest.map_or(bps, |widest| widest.max(bps)));
+            }
+        }
+        widest.map(|bps| bps.round().min(u32::MAX as f64) as u32)
+    }
+
+    /// Drops every pool for which `keep` returns false
+    pub fn retain_pools<F: FnMut(&PoolVaults) -> bool>(&mut self, mut keep: F) {
+        macro_rules! retain_vaults {
+            ($pools:expr, $kind:expr, $pool:ident, $token_vault:ident, $base_vault:ident) => {
+                $pools.retain(|pool| {
+                    keep(&PoolVaults {
+                        kind: $kind,
+                        pool: pool.$pool,
+                        token_vault: pool.$token_vault,
+                        base_vault: pool.$base_vault,
+                        base_mint: pool.base_mint,
+                    })
+                });
+            };
+        }
+        for_each_pool_list!(self, retain_vaults);
+    }
+
+    /// Moves every pool of `other`, initialized for the same mint and wallet, into this set
+    pub fn merge_pools(&mut self, other: MintPoolData) {
+        self.raydium_pools.extend(other.raydium_pools);
+        self.raydium_cp_pools.extend(other.raydium_cp_pools);
+        self.pump_pools.extend(other.pump_pools);
+        self.dlmm_pairs.extend(other.dlmm_pairs);
+        self.whirlpool_pools.extend(other.whirlpool_pools);
+        self.raydium_clmm_pools.extend(other.raydium_clmm_pools);
+        self.meteora_damm_pools.extend(other.meteora_damm_pools);
+        self.meteora_damm_v2_pools.extend(other.meteora_damm_v2_pools);
+        self.vertigo_pools.extend(other.vertigo_pools);
+        self.heaven_pools.extend(other.heaven_pools);
+        self.futarchy_pools.extend(other.futarchy_pools);
+        self.humidifi_pools.extend(other.humidifi_pools);
+        self.pancakeswap_pools.extend(other.pancakeswap_pools);
+        self.byreal_pools.extend(other.byreal_pools);
+    }
+
+    /// `(pool, amm_config, kind)` for every pool whose trade fee lives in a shared config
+    pub fn amm_configs(&self) -> Vec<(Pubkey, Pubkey, AmmConfigKind)> {
+        let cp = self
+            .raydium_cp_pools
+            .iter()
+            .map(|pool| (pool.pool, pool.amm_config, AmmConfigKind::Cp));
+        let clmm = self
+            .raydium_clmm_pools
+            .iter()
+            .map(|pool| (pool.pool, pool.amm_config))
+            .chain(self.pancakeswap_pools.iter().map(|pool| (pool.pool, pool.amm_config)))
+            .chain(self.byreal_pools.iter().map(|pool| (pool.pool, pool.amm_config)))
+            .map(|(pool, amm_config)| (pool, amm_config, AmmConfigKind::Clmm));
+        cp.chain(clmm).collect()
+    }
+
+    pub fn pool_count(&self) -> usize {
+        self.pool_vaults().len()
+    }
+
+    /// Pools with bin or tick arrays and the slot their arrays were last computed at
+    pub fn array_refresh_slots(&self) -> Vec<(Pubkey, u64)> {
+        let mut slots: Vec<(Pubkey, u64)> =
+            self.dlmm_pairs.iter().map(|pool| (pool.pair, pool.refreshed_slot)).collect();
+        slots.extend(self.whirlpool_pools.iter().map(|pool| (pool.pool, pool.refreshed_slot)));
+        slots.extend(self.raydium_clmm_pools.iter().map(|pool| (pool.pool, pool.refreshed_slot)));
+        slots.extend(self.pancakeswap_pools.iter().map(|pool| (pool.pool, pool.refreshed_slot)));
+        slots.extend(self.byreal_pools.iter().map(|pool| (pool.pool, pool.refreshed_slot)));
+        slots
+    }
+
+    /// Records that the arrays of `pool` were recomputed from its account at `slot`.
+    /// Pools without arrays are ignored.
+    pub fn mark_refreshed(&mut self, pool: &Pubkey, slot: u64) {
+        let refreshed_slot = if let Some(p) = self.dlmm_pairs.iter_mut().find(|p| p.pair == *pool) {
+            &mut p.refreshed_slot
+        } else if let Some(p) = self.whirlpool_pools.iter_mut().find(|p| p.pool == *pool) {
+            &mut p.refreshed_slot
+        } else if let Some(p) = self.raydium_clmm_pools.iter_mut().find(|p| p.pool == *pool) {
+            &mut p.refreshed_slot
+        } else if let Some(p) = self.pancakeswap_pools.iter_mut().find(|p| p.pool == *pool) {
+            &mut p.refreshed_slot
+        } else if let Some(p) = self.byreal_pools.iter_mut().find(|p| p.pool == *pool) {
+            &mut p.refreshed_slot
+        } else {
+            return;
+        };
+        *refreshed_slot = (*refreshed_slot).max(slot);
+    }
+
     pub fn add_raydium_pool(
         &mut self,
+        program_id: Pubkey,
         pool: Pubkey,
         token_vault: Pubkey,
         sol_vault: Pubkey,
         token_mint: Pubkey,
         base_mint: Pubkey,
+        layout_version: u8,
     ) {
         self.raydium_pools.push(RaydiumPool {
+            program_id,
             pool,
             token_vault,
             sol_vault,
             token_mint,
             base_mint,
+            layout_version,
         });
     }
 
     pub fn add_raydium_cp_pool(
         &mut self,
+        program_id: Pubkey,
         pool: Pubkey,
         token_vault: Pubkey,
         sol_vault: Pubkey,
@@ -255,8 +547,11 @@ impl MintPoolData {
         observation: Pubkey,
         token_mint: Pubkey,
         base_mint: Pubkey,
+        token_fees: u64,
+        base_fees: u64,
     ) {
         self.raydium_cp_pools.push(RaydiumCpPool {
+            program_id,
             pool,
             token_vault,
             sol_vault,
@@ -264,6 +559,8 @@ impl MintPoolData {
             observation,
             token_mint,
             base_mint,
+            token_fees,
+            base_fees,
         });
     }
 
@@ -281,6 +578,7 @@ impl MintPoolData {
         base_mint: Pubkey,
         is_mayhem_mode: bool,
         is_cashback_coin: bool,
+        layout_version: u8,
     ) {
         self.pump_pools.push(PumpPool {
             pool,
@@ -295,6 +593,7 @@ impl MintPoolData {
             base_mint,
             is_mayhem_mode,
             is_cashback_coin,
+            layout_version,
         });
     }
 
@@ -306,6 +605,7 @@ impl MintPoolData {
         oracle: Pubkey,
         bin_array_bitmap_extension: Option<Pubkey>,
         bin_arrays: Vec<Pubkey>,
+        bin_step: u16,
         memo_program: Option<Pubkey>,
         token_mint: Pubkey,
         base_mint: Pubkey,
@@ -317,9 +617,11 @@ impl MintPoolData {
             oracle,
             bin_array_bitmap_extension,
             bin_arrays,
+            bin_step,
             memo_program,
             token_mint,
             base_mint,
+            refreshed_slot: 0,
         });
     }
 
@@ -343,11 +645,13 @@ impl MintPoolData {
             memo_program,
             token_mint,
             base_mint,
+            refreshed_slot: 0,
         });
     }
 
     pub fn add_raydium_clmm_pool(
         &mut self,
+        program_id: Pubkey,
         pool: Pubkey,
         amm_config: Pubkey,
         observation_state: Pubkey,
@@ -357,17 +661,21 @@ impl MintPoolData {
         memo_program: Option<Pubkey>,
         token_mint: Pubkey,
         base_mint: Pubkey,
+        liquidity: u128,
+        sqrt_price_x64: u128,
+        tick_current: i32,
     ) {
-        let bitmap_extension = Pubkey::find_program_address(
+        let bitmap_extension = find_program_address(
             &[
                 POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
                 pool.as_ref(),
             ],
-            &raydium_clmm_program_id(),
+            &program_id,
         )
         .0;
 
         self.raydium_clmm_pools.push(RaydiumClmmPool {
+            program_id,
             pool,
             amm_config,
             observation_state,
@@ -378,6 +686,10 @@ impl MintPoolData {
             memo_program,
             token_mint,
             base_mint,
+            liquidity,
+            sqrt_price_x64,
+            tick_current,
+            refreshed_slot: 0,
         });
     }
 
@@ -421,6 +733,7 @@ impl MintPoolData {
         token_sol_vault: Pubkey,
         token_mint: Pubkey,
         base_mint: Pubkey,
+        fees: DammV2Fees,
     ) {
         self.meteora_damm_v2_pools.push(MeteoraDAmmV2Pool {
             pool,
@@ -428,6 +741,7 @@ impl MintPoolData {
             token_sol_vault,
             token_mint,
             base_mint,
+            fees,
         });
     }
 
@@ -519,9 +833,9 @@ impl MintPoolData {
         token_mint: Pubkey,
         base_mint: Pubkey,
     ) {
-        let bitmap_extension = Pubkey::find_program_address(
+        let bitmap_extension = find_program_address(
             &[POOL_TICK_ARRAY_BITMAP_SEED_CLMM.as_bytes(), pool.as_ref()],
-            &pancakeswap_program_id(),
+            &ProgramRegistry::global().pancakeswap,
         )
         .0;
 
@@ -536,6 +850,7 @@ impl MintPoolData {
             memo_program,
             token_mint,
             base_mint,
+            refreshed_slot: 0,
         });
     }
 
@@ -551,9 +866,9 @@ impl MintPoolData {
         token_mint: Pubkey,
         base_mint: Pubkey,
     ) {
-        let bitmap_extension = Pubkey::find_program_address(
+        let bitmap_extension = find_program_address(
             &[POOL_TICK_ARRAY_BITMAP_SEED_CLMM.as_bytes(), pool.as_ref()],
-            &byreal_program_id(),
+            &ProgramRegistry::global().byreal,
         )
         .0;
 
@@ -568,6 +883,18 @@ impl MintPoolData {
             memo_program,
             token_mint,
             base_mint,
+            refreshed_slot: 0,
         });
     }
 }
+
+/// Raw base units per raw token unit of a CLMM pool from its Q64.64 square root price of
+/// mint 1 in mint 0, None before the first refresh
+pub(crate) fn clmm_spot_price(sqrt_price_x64: u128, token_is_mint_0: bool) -> Option<f64> {
+    if sqrt_price_x64 == 0 {
+        return None;
+    }
+    let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+    let price = sqrt_price * sqrt_price;
+    Some(if token_is_mint_0 { price } else { 1.0 / price })
+}
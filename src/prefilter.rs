@@ -0,0 +1,97 @@
+use crate::config::PrefilterConfig;
+use crate::executor_errors::ExecutorErrors;
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use std::time::Duration;
+
+/// Result of simulating a built transaction before broadcasting it
+#[derive(Debug, Clone)]
+pub enum SimulationOutcome {
+    /// The executor found a profitable route
+    Profitable { units_consumed: Option<u64> },
+    /// The executor failed the transaction, usually because no route was profitable
+    NoRoute {
+        error: String,
+        /// Executor error code name from `executor.error_codes`, or the raw error
+        reason: String,
+        last_log: Option<String>,
+    },
+}
+
+impl PrefilterConfig {
+    /// Whether sends for this mint are simulated first
+    pub fn simulates(&self, mint: &Pubkey) -> bool {
+        if !self.simulate_before_send {
+            return false;
+        }
+        match &self.mints {
+            Some(mints) => mints.iter().any(|m| m == &mint.to_string()),
+            None => true,
+        }
+    }
+}
+
+/// Simulates against the read RPC with the blockhash replaced, so a stale
+/// cached blockhash never masks the executor's own result
+pub fn simulate_transaction(
+    rpc_client: &RpcClient,
+    tx: &VersionedTransaction,
+) -> Result<SimulationOutcome> {
+    let response = rpc_client.simulate_transaction_with_config(
+        tx,
+        RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(CommitmentConfig::processed()),
+            ..Default::default()
+        },
+    )?;
+    let result = response.value;
+
+    Ok(match result.err {
+        None => SimulationOutcome::Profitable {
+            units_consumed: result.units_consumed,
+        },
+        Some(err) => SimulationOutcome::NoRoute {
+            error: err.to_string(),
+            reason: ExecutorErrors::global().reason(&err),
+            last_log: result.logs.and_then(|logs| logs.last().cloned()),
+        },
+    })
+}
+
+/// How often the prefilter saved a send and what it cost in latency
+#[derive(Debug, Clone, Default)]
+pub struct PrefilterStats {
+    pub simulated: u64,
+    pub sends_saved: u64,
+    pub simulation_errors: u64,
+    pub total_latency: Duration,
+}
+
+impl PrefilterStats {
+    pub fn record(&mut self, latency: Duration, saved_send: bool) {
+        self.simulated += 1;
+        self.total_latency += latency;
+        if saved_send {
+            self.sends_saved += 1;
+        }
+    }
+
+    pub fn record_error(&mut self, latency: Duration) {
+        self.simulation_errors += 1;
+        self.total_latency += latency;
+    }
+
+    pub fn average_latency(&self) -> Duration {
+        let calls = self.simulated + self.simulation_errors;
+        if calls == 0 {
+            return Duration::ZERO;
+        }
+        self.total_latency / calls as u32
+    }
+}
@@ -0,0 +1,366 @@
+use crate::account_batcher::AccountBatcher;
+use crate::amm_config::AmmConfigCache;
+use crate::dex::meteora::dlmm_info::DlmmInfo;
+use crate::dex::raydium::PoolState;
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::pools::{clmm_spot_price, MintPoolData, PoolVaults};
+use crate::loader::MarketPoolKind;
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::time::Instant;
+
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const MINT_DECIMALS_OFFSET: usize = 44;
+/// Trade fee of every Raydium V4 pool
+const RAYDIUM_V4_FEE_BPS: f64 = 25.0;
+
+/// Spot price of a pool in raw base units per raw token unit
+#[derive(Debug, Clone)]
+pub struct PoolPrice {
+    pub kind: MarketPoolKind,
+    pub pool: Pubkey,
+    pub base_mint: Pubkey,
+    pub token_reserve: u64,
+    pub base_reserve: u64,
+    pub price: f64,
+    /// Price in base mint units per whole token, None when a mint's decimals are unknown
+    pub ui_price: Option<f64>,
+    /// Trade fee in bps, when known without extra requests
+    pub fee_bps: Option<f64>,
+}
+
+/// Price of one SOL in a non-SOL base mint, for converting its reserves to SOL
+#[derive(Debug, Clone, Copy)]
+pub struct SolPrice {
+    /// Base mint UI units per SOL, e.g. 150.0 USDC
+    pub price: f64,
+    pub base_decimals: u8,
+}
+
+/// Price gap between the cheapest and the most expensive pool sharing a base mint
+#[derive(Debug, Clone)]
+pub struct Spread {
+    pub buy: PoolPrice,
+    pub sell: PoolPrice,
+    pub spread_bps: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceSnapshot {
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub taken_at: Instant,
+    pub prices: Vec<PoolPrice>,
+}
+
+impl PriceSnapshot {
+    /// Widest spread across pools quoted in the same base mint
+    pub fn best_spread(&self) -> Option<Spread> {
+        let mut best: Option<Spread> = None;
+        for (i, a) in self.prices.iter().enumerate() {
+            for b in &self.prices[i + 1..] {
+                let Some(spread) = spread_between(a, b) else {
+                    continue;
+                };
+                if best
+                    .as_ref()
+                    .map_or(true, |best| spread.spread_bps > best.spread_bps)
+                {
+                    best = Some(spread);
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Spread between two pools, None when they are quoted in different base mints
+pub fn spread_between(a: &PoolPrice, b: &PoolPrice) -> Option<Spread> {
+    if a.base_mint != b.base_mint || a.price <= 0.0 || b.price <= 0.0 {
+        return None;
+    }
+    let (buy, sell) = if a.price <= b.price { (a, b) } else { (b, a) };
+    Some(Spread {
+        spread_bps: (sell.price - buy.price) / buy.price * 10_000.0,
+        buy: buy.clone(),
+        sell: sell.clone(),
+    })
+}
+
+/// Whether the vault ratio is the pool's spot price
+pub fn priced_from_reserves(kind: MarketPoolKind) -> bool {
+    matches!(
+        kind,
+        MarketPoolKind::Pump
+            | MarketPoolKind::RaydiumV4
+            | MarketPoolKind::RaydiumCp
+            | MarketPoolKind::MeteoraDamm
+            | MarketPoolKind::Heaven
+            | MarketPoolKind::Futarchy
+    )
+}
+
+/// Whether the spot price is read from the pool account rather than its vaults
+pub fn priced_from_state(kind: MarketPoolKind) -> bool {
+    matches!(
+        kind,
+        MarketPoolKind::MeteoraDlmm
+            | MarketPoolKind::Whirlpool
+            | MarketPoolKind::RaydiumClmm
+            | MarketPoolKind::PancakeSwap
+            | MarketPoolKind::Byreal
+    )
+}
+
+/// Reads vault balances for the mint's constant-product and concentrated liquidity pools,
+/// and the CLMM pool accounts, and derives their spot prices
+pub fn fetch_price_snapshot(
+    rpc_client: &RpcClient,
+    pool_data: &MintPoolData,
+) -> Result<PriceSnapshot> {
+    let pools: Vec<PoolVaults> = pool_data
+        .pool_vaults()
+        .into_iter()
+        .filter(|pool| priced_from_reserves(pool.kind) || priced_from_state(pool.kind))
+        .collect();
+
+    let amm_configs: HashMap<Pubkey, Pubkey> = pool_data
+        .amm_configs()
+        .into_iter()
+        .map(|(pool, amm_config, _)| (pool, amm_config))
+        .collect();
+
+    let state_pools: Vec<Pubkey> = pools
+        .iter()
+        .filter(|pool| priced_from_state(pool.kind))
+        .map(|pool| pool.pool)
+        .collect();
+    let mut mints = vec![pool_data.mint];
+    for pool in &pools {
+        if !mints.contains(&pool.base_mint) {
+            mints.push(pool.base_mint);
+        }
+    }
+
+    // Vault pairs first, then the CLMM pool accounts, then the mints for their decimals
+    let keys: Vec<Pubkey> = pools
+        .iter()
+        .flat_map(|pool| [pool.token_vault, pool.base_vault])
+        .chain(state_pools.iter().copied())
+        .chain(mints.iter().copied())
+        .collect();
+
+    let fetched = AccountBatcher::global().get_multiple_accounts_with_commitment(
+        rpc_client,
+        &keys,
+        CommitmentConfig::processed(),
+    )?;
+    let slot = fetched.slot;
+    let (vault_accounts, rest) = fetched.accounts.split_at(pools.len() * 2);
+    let (state_accounts, mint_accounts) = rest.split_at(state_pools.len());
+    let balances: Vec<Option<u64>> = vault_accounts
+        .iter()
+        .map(|account| account.as_ref().and_then(|a| token_amount(&a.data)))
+        .collect();
+    let states: HashMap<Pubkey, &[u8]> = state_pools
+        .iter()
+        .zip(state_accounts)
+        .filter_map(|(pool, account)| Some((*pool, account.as_ref()?.data.as_slice())))
+        .collect();
+    let decimals: HashMap<Pubkey, u8> = mints
+        .iter()
+        .zip(mint_accounts)
+        .filter_map(|(mint, account)| Some((*mint, mint_decimals(&account.as_ref()?.data)?)))
+        .collect();
+
+    let prices = pools
+        .iter()
+        .zip(balances.chunks(2))
+        .filter_map(|(pool, balances)| {
+            let (token_reserve, base_reserve) =
+                pool_data.net_reserves(pool, (balances[0]?, balances[1]?));
+            if token_reserve == 0 || base_reserve == 0 {
+                return None;
+            }
+            let price = if priced_from_state(pool.kind) {
+                spot_price_from_state(pool.kind, &pool_data.mint, states.get(&pool.pool)?)?
+            } else {
+                base_reserve as f64 / token_reserve as f64
+            };
+            let ui_price = match (decimals.get(&pool_data.mint), decimals.get(&pool.base_mint)) {
+                (Some(&token_decimals), Some(&base_decimals)) => {
+                    Some(ui_price(price, token_decimals, base_decimals))
+                }
+                _ => None,
+            };
+            Some(PoolPrice {
+                kind: pool.kind,
+                pool: pool.pool,
+                base_mint: pool.base_mint,
+                token_reserve,
+                base_reserve,
+                price,
+                ui_price,
+                fee_bps: known_fee_bps(pool, &amm_configs),
+            })
+        })
+        .collect();
+
+    Ok(PriceSnapshot {
+        mint: pool_data.mint,
+        slot,
+        taken_at: Instant::now(),
+        prices,
+    })
+}
+
+/// Spot price in raw base units per raw token unit from a DLMM pair's active bin, or from the
+/// square root price of a Whirlpool or Raydium CLMM layout pool
+pub fn spot_price_from_state(
+    kind: MarketPoolKind,
+    token_mint: &Pubkey,
+    data: &[u8],
+) -> Option<f64> {
+    let price = match kind {
+        MarketPoolKind::MeteoraDlmm => {
+            let info = DlmmInfo::load_checked(data).ok()?;
+            // The bin price is X in Y
+            let price = info.price_from_active_id();
+            if *token_mint == info.token_x_mint {
+                price
+            } else {
+                1.0 / price
+            }
+        }
+        MarketPoolKind::Whirlpool => {
+            let whirlpool = Whirlpool::try_deserialize(data).ok()?;
+            clmm_spot_price(whirlpool.sqrt_price, *token_mint == whirlpool.token_mint_a)?
+        }
+        MarketPoolKind::RaydiumClmm | MarketPoolKind::PancakeSwap | MarketPoolKind::Byreal => {
+            let state = PoolState::load_checked(data).ok()?;
+            clmm_spot_price(state.sqrt_price_x64, *token_mint == state.token_mint_0)?
+        }
+        _ => return None,
+    };
+    (price.is_finite() && price > 0.0).then_some(price)
+}
+
+/// Converts a raw base-per-token price to base mint units per whole token
+pub fn ui_price(price: f64, token_decimals: u8, base_decimals: u8) -> f64 {
+    price * 10f64.powi(token_decimals as i32 - base_decimals as i32)
+}
+
+/// Fee of Raydium V4 pools, and of pools whose AMM config was cached at startup
+fn known_fee_bps(pool: &PoolVaults, amm_configs: &HashMap<Pubkey, Pubkey>) -> Option<f64> {
+    if pool.kind == MarketPoolKind::RaydiumV4 {
+        return Some(RAYDIUM_V4_FEE_BPS);
+    }
+    let config = AmmConfigCache::global().get(amm_configs.get(&pool.pool)?)?;
+    // trade_fee_rate is in hundredths of a bip
+    Some(config.trade_fee_rate as f64 / 100.0)
+}
+
+/// Mint field of an SPL Token or Token-2022 account
+pub fn token_account_mint(data: &[u8]) -> Option<Pubkey> {
+    data.get(..32)
+        .map(|bytes| Pubkey::new_from_array(bytes.try_into().unwrap()))
+}
+
+/// Decimals field of an SPL Token or Token-2022 mint
+pub fn mint_decimals(data: &[u8]) -> Option<u8> {
+    data.get(MINT_DECIMALS_OFFSET).copied()
+}
+
+/// Amount field of an SPL Token or Token-2022 account
+pub fn token_amount(data: &[u8]) -> Option<u64> {
+    data.get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::meteora::dlmm_info::LbPair;
+    use std::mem::{offset_of, size_of};
+
+    /// LbPair account with token X, the active bin and the bin step set
+    fn lb_pair_account(token_x_mint: &Pubkey, active_id: i32, bin_step: u16) -> Vec<u8> {
+        let mut data = vec![0u8; 8 + size_of::<LbPair>()];
+        let mint_offset = 8 + offset_of!(LbPair, token_x_mint);
+        let active_id_offset = 8 + offset_of!(LbPair, active_id);
+        let bin_step_offset = 8 + offset_of!(LbPair, bin_step);
+        data[mint_offset..mint_offset + 32].copy_from_slice(token_x_mint.as_ref());
+        data[active_id_offset..active_id_offset + 4].copy_from_slice(&active_id.to_le_bytes());
+        data[bin_step_offset..bin_step_offset + 2].copy_from_slice(&bin_step.to_le_bytes());
+        data
+    }
+
+    /// Whirlpool account with token A and the Q64.64 square root price set
+    fn whirlpool_account(token_mint_a: &Pubkey, sqrt_price: u128) -> Vec<u8> {
+        const SQRT_PRICE_OFFSET: usize = 8 + 32 + 1 + 2 + 2 + 2 + 2 + 16;
+        const TOKEN_MINT_A_OFFSET: usize = SQRT_PRICE_OFFSET + 16 + 4 + 8 + 8;
+        let mut data = vec![0u8; Whirlpool::LEN];
+        data[SQRT_PRICE_OFFSET..SQRT_PRICE_OFFSET + 16].copy_from_slice(&sqrt_price.to_le_bytes());
+        data[TOKEN_MINT_A_OFFSET..TOKEN_MINT_A_OFFSET + 32].copy_from_slice(token_mint_a.as_ref());
+        data
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            ((actual - expected) / expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn dlmm_price_is_quoted_per_token() {
+        let (token, base) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // Token X at bin -4744 with bin step 4: 1.0004^-4744 base raw units per token raw unit
+        let data = lb_pair_account(&token, -4744, 4);
+        let price = spot_price_from_state(MarketPoolKind::MeteoraDlmm, &token, &data).unwrap();
+        assert_close(price, 0.14998491131561187);
+        assert_eq!((ui_price(price, 9, 6) * 100.0).round() / 100.0, 149.98);
+
+        // The same pair with the token on the Y side quotes the reciprocal
+        let data = lb_pair_account(&base, -4744, 4);
+        let price = spot_price_from_state(MarketPoolKind::MeteoraDlmm, &token, &data).unwrap();
+        assert_close(price, 1.0 / 0.14998491131561187);
+    }
+
+    #[test]
+    fn sqrt_price_is_quoted_per_token() {
+        let (token, base) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let sqrt_price = 2u128 << 64;
+        let data = whirlpool_account(&token, sqrt_price);
+        let price = spot_price_from_state(MarketPoolKind::Whirlpool, &token, &data).unwrap();
+        assert_close(price, 4.0);
+
+        let data = whirlpool_account(&base, sqrt_price);
+        let price = spot_price_from_state(MarketPoolKind::Whirlpool, &token, &data).unwrap();
+        assert_close(price, 0.25);
+
+        // An uninitialized pool has no price
+        let data = whirlpool_account(&token, 0);
+        assert!(spot_price_from_state(MarketPoolKind::Whirlpool, &token, &data).is_none());
+        assert!(spot_price_from_state(MarketPoolKind::Whirlpool, &token, &[0u8; 16]).is_none());
+    }
+
+    #[test]
+    fn ui_price_scales_by_the_mint_decimals() {
+        // 1000 lamports per raw unit of a 6-decimal token is 1 SOL per token
+        assert_close(ui_price(1_000.0, 6, 9), 1.0);
+        // 0.15 raw USDC units per lamport is $150 per SOL
+        assert_close(ui_price(0.15, 9, 6), 150.0);
+        assert_close(ui_price(2.5, 9, 9), 2.5);
+
+        let mut mint = vec![0u8; 82];
+        mint[MINT_DECIMALS_OFFSET] = 6;
+        assert_eq!(mint_decimals(&mint), Some(6));
+        assert_eq!(mint_decimals(&mint[..40]), None);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::dex::byreal::byreal_program_id;
+use crate::dex::futarchy::futarchy_program_id;
+use crate::dex::heaven::constants::heaven_program_id;
+use crate::dex::humidifi::humidifi_program_id;
+use crate::dex::meteora::constants::{
+    damm_program_id, damm_v2_program_id, dlmm_program_id, vault_program_id,
+};
+use crate::dex::pancakeswap::pancakeswap_program_id;
+use crate::dex::pump::pump_program_id;
+use crate::dex::raydium::{
+    raydium_clmm_devnet_program_id, raydium_clmm_program_id, raydium_cp_devnet_program_id,
+    raydium_cp_program_id, raydium_devnet_program_id, raydium_program_id,
+};
+use crate::dex::vertigo::constants::vertigo_program_id;
+use crate::dex::whirlpool::constants::whirlpool_program_id;
+use anyhow::{anyhow, Context, Result};
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing::info;
+
+static GLOBAL: OnceLock<ProgramRegistry> = OnceLock::new();
+
+/// Keys accepted in `[program_overrides]`
+pub const PROGRAM_NAMES: [&str; 15] = [
+    "pump",
+    "raydium",
+    "raydium_cp",
+    "raydium_clmm",
+    "meteora_dlmm",
+    "meteora_damm",
+    "meteora_damm_v2",
+    "meteora_vault",
+    "whirlpool",
+    "vertigo",
+    "heaven",
+    "futarchy",
+    "humidifi",
+    "pancakeswap",
+    "byreal",
+];
+
+/// Program ID of every supported DEX. Defaults to the mainnet deployments; forks and devnet
+/// deployments are set through `[program_overrides]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramRegistry {
+    pub pump: Pubkey,
+    pub raydium: Pubkey,
+    pub raydium_cp: Pubkey,
+    pub raydium_clmm: Pubkey,
+    pub meteora_dlmm: Pubkey,
+    pub meteora_damm: Pubkey,
+    pub meteora_damm_v2: Pubkey,
+    /// Dynamic vault program used by DAMM pools
+    pub meteora_vault: Pubkey,
+    pub whirlpool: Pubkey,
+    pub vertigo: Pubkey,
+    pub heaven: Pubkey,
+    pub futarchy: Pubkey,
+    pub humidifi: Pubkey,
+    pub pancakeswap: Pubkey,
+    pub byreal: Pubkey,
+}
+
+impl Default for ProgramRegistry {
+    fn default() -> Self {
+        Self {
+            pump: pump_program_id(),
+            raydium: raydium_program_id(),
+            raydium_cp: raydium_cp_program_id(),
+            raydium_clmm: raydium_clmm_program_id(),
+            meteora_dlmm: dlmm_program_id(),
+            meteora_damm: damm_program_id(),
+            meteora_damm_v2: damm_v2_program_id(),
+            meteora_vault: vault_program_id(),
+            whirlpool: whirlpool_program_id(),
+            vertigo: vertigo_program_id(),
+            heaven: heaven_program_id(),
+            futarchy: futarchy_program_id(),
+            humidifi: humidifi_program_id(),
+            pancakeswap: pancakeswap_program_id(),
+            byreal: byreal_program_id(),
+        }
+    }
+}
+
+impl ProgramRegistry {
+    /// Mainnet program IDs with the given `name -> address` overrides applied
+    pub fn with_overrides(overrides: &HashMap<String, String>) -> Result<Self> {
+        let mut registry = Self::default();
+        for (name, address) in overrides {
+            let program = registry.program_mut(name).ok_or_else(|| {
+                anyhow!(
+                    "Unknown program_overrides key {}, expected one of {}",
+                    name,
+                    PROGRAM_NAMES.join(", ")
+                )
+            })?;
+            *program = Pubkey::from_str(address)
+                .with_context(|| format!("Invalid program_overrides.{} {}", name, address))?;
+        }
+        Ok(registry)
+    }
+
+    fn program_mut(&mut self, name: &str) -> Option<&mut Pubkey> {
+        Some(match name {
+            "pump" => &mut self.pump,
+            "raydium" => &mut self.raydium,
+            "raydium_cp" => &mut self.raydium_cp,
+            "raydium_clmm" => &mut self.raydium_clmm,
+            "meteora_dlmm" => &mut self.meteora_dlmm,
+            "meteora_damm" => &mut self.meteora_damm,
+            "meteora_damm_v2" => &mut self.meteora_damm_v2,
+            "meteora_vault" => &mut self.meteora_vault,
+            "whirlpool" => &mut self.whirlpool,
+            "vertigo" => &mut self.vertigo,
+            "heaven" => &mut self.heaven,
+            "futarchy" => &mut self.futarchy,
+            "humidifi" => &mut self.humidifi,
+            "pancakeswap" => &mut self.pancakeswap,
+            "byreal" => &mut self.byreal,
+            _ => return None,
+        })
+    }
+
+    /// Raydium AMM V4 deployments whose pools are accepted: the configured one and devnet
+    pub fn raydium_programs(&self) -> [Pubkey; 2] {
+        [self.raydium, raydium_devnet_program_id()]
+    }
+
+    /// Raydium CP-Swap deployments whose pools are accepted: the configured one and devnet
+    pub fn raydium_cp_programs(&self) -> [Pubkey; 2] {
+        [self.raydium_cp, raydium_cp_devnet_program_id()]
+    }
+
+    /// Raydium CLMM deployments whose pools are accepted: the configured one and devnet
+    pub fn raydium_clmm_programs(&self) -> [Pubkey; 2] {
+        [self.raydium_clmm, raydium_clmm_devnet_program_id()]
+    }
+
+    /// Applies `[program_overrides]`. Must run before `global` is first called.
+    pub fn init(overrides: Option<&HashMap<String, String>>) -> Result<()> {
+        let Some(overrides) = overrides.filter(|overrides| !overrides.is_empty()) else {
+            return Ok(());
+        };
+        let registry = Self::with_overrides(overrides)?;
+        let mut names: Vec<&String> = overrides.keys().collect();
+        names.sort();
+        for name in names {
+            info!("Using program {} for {}", overrides[name], name);
+        }
+        GLOBAL.set(registry).map_err(|_| {
+            anyhow!("Program IDs were read before the [program_overrides] were applied")
+        })
+    }
+
+    pub fn global() -> &'static ProgramRegistry {
+        GLOBAL.get_or_init(ProgramRegistry::default)
+    }
+}
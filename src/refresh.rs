@@ -1,1817 +0,0 @@
-use crate::config::MarketsConfig;
-use crate::constants::sol_mint;
-use crate::dex::byreal::byreal_program_id;
-use crate::dex::futarchy::{futarchy_event_authority, futarchy_program_id, FutarchyInfo};
-use crate::dex::heaven::{heaven_program_id, HeavenPoolState};
-use crate::dex::humidifi::{humidifi_program_id, HumidifiInfo};
-use crate::dex::meteora::constants::{damm_program_id, damm_v2_program_id};
-use crate::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
-use crate::dex::meteora::{constants::dlmm_program_id, dlmm_info::DlmmInfo};
-use crate::dex::pancakeswap::pancakeswap_program_id;
-use crate::dex::pump::{pump_fee_wallet, pump_mayhem_fee_wallet, pump_program_id, PumpAmmInfo};
-use crate::dex::raydium::{
-    get_initialized_tick_array_pubkeys, parse_bitmap_extension, raydium_clmm_program_id,
-    raydium_cp_program_id, raydium_program_id, PoolState, RaydiumAmmInfo, RaydiumCpAmmInfo,
-    POOL_TICK_ARRAY_BITMAP_SEED,
-};
-use crate::dex::vertigo::{derive_vault_address, vertigo_program_id, VertigoInfo};
-use crate::dex::whirlpool::{
-    constants::whirlpool_program_id, state::Whirlpool, update_tick_array_accounts_for_onchain,
-};
-use crate::pools::*;
-use solana_client::rpc_client::RpcClient;
-use solana_program::pubkey::Pubkey;
-use spl_associated_token_account;
-use std::collections::HashMap;
-use std::sync::Arc;
-use tracing::{error, info, warn};
-
-/// Enum representing the different DEX pool types
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum MarketPoolKind {
-    Pump,
-    RaydiumV4,
-    RaydiumCp,
-    RaydiumClmm,
-    MeteoraDlmm,
-    MeteoraDamm,
-    MeteoraDammV2,
-    Whirlpool,
-    Vertigo,
-    Heaven,
-    Futarchy,
-    Humidifi,
-    PancakeSwap,
-    Byreal,
-}
-
-/// Internal structure for grouping pools by mint during detection
-#[derive(Default)]
-struct MintPoolsBuilder {
-    pump_pools: Vec<Pubkey>,
-    raydium_pools: Vec<Pubkey>,
-    raydium_cp_pools: Vec<Pubkey>,
-    raydium_clmm_pools: Vec<Pubkey>,
-    dlmm_pools: Vec<Pubkey>,
-    damm_pools: Vec<Pubkey>,
-    damm_v2_pools: Vec<Pubkey>,
-    whirlpool_pools: Vec<Pubkey>,
-    vertigo_pools: Vec<Pubkey>,
-    heaven_pools: Vec<Pubkey>,
-    futarchy_pools: Vec<Pubkey>,
-    humidifi_pools: Vec<Pubkey>,
-    pancakeswap_pools: Vec<Pubkey>,
-    byreal_pools: Vec<Pubkey>,
-}
-
-/// Detect the pool kind based on the account owner (program ID)
-pub fn detect_pool_kind(owner: &Pubkey) -> Option<MarketPoolKind> {
-    if *owner == pump_program_id() {
-        Some(MarketPoolKind::Pump)
-    } else if *owner == raydium_program_id() {
-        Some(MarketPoolKind::RaydiumV4)
-    } else if *owner == raydium_cp_program_id() {
-        Some(MarketPoolKind::RaydiumCp)
-    } else if *owner == raydium_clmm_program_id() {
-        Some(MarketPoolKind::RaydiumClmm)
-    } else if *owner == dlmm_program_id() {
-        Some(MarketPoolKind::MeteoraDlmm)
-    } else if *owner == damm_program_id() {
-        Some(MarketPoolKind::MeteoraDamm)
-    } else if *owner == damm_v2_program_id() {
-        Some(MarketPoolKind::MeteoraDammV2)
-    } else if *owner == whirlpool_program_id() {
-        Some(MarketPoolKind::Whirlpool)
-    } else if *owner == vertigo_program_id() {
-        Some(MarketPoolKind::Vertigo)
-    } else if *owner == heaven_program_id() {
-        Some(MarketPoolKind::Heaven)
-    } else if *owner == futarchy_program_id() {
-        Some(MarketPoolKind::Futarchy)
-    } else if *owner == humidifi_program_id() {
-        Some(MarketPoolKind::Humidifi)
-    } else if *owner == pancakeswap_program_id() {
-        Some(MarketPoolKind::PancakeSwap)
-    } else if *owner == byreal_program_id() {
-        Some(MarketPoolKind::Byreal)
-    } else {
-        None
-    }
-}
-
-/// Extract the non-SOL token mint from a pool based on its kind
-fn extract_token_mint(
-    kind: MarketPoolKind,
-    data: &[u8],
-    pool_pubkey: &Pubkey,
-) -> anyhow::Result<Option<Pubkey>> {
-    let sol = sol_mint();
-
-    match kind {
-        MarketPoolKind::Pump => {
-            let info = PumpAmmInfo::load_checked(data)?;
-            let token_mint = if info.base_mint == sol {
-                info.quote_mint
-            } else if info.quote_mint == sol {
-                info.base_mint
-            } else {
-                return Ok(None); // Neither side is SOL
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::RaydiumV4 => {
-            let info = RaydiumAmmInfo::load_checked(data)?;
-            let token_mint = if info.coin_mint == sol {
-                info.pc_mint
-            } else if info.pc_mint == sol {
-                info.coin_mint
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::RaydiumCp => {
-            let info = RaydiumCpAmmInfo::load_checked(data)?;
-            let token_mint = if info.token_0_mint == sol {
-                info.token_1_mint
-            } else if info.token_1_mint == sol {
-                info.token_0_mint
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::RaydiumClmm => {
-            let info = PoolState::load_checked(data)?;
-            let token_mint = if info.token_mint_0 == sol {
-                info.token_mint_1
-            } else if info.token_mint_1 == sol {
-                info.token_mint_0
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::MeteoraDlmm => {
-            let info = DlmmInfo::load_checked(data)?;
-            let token_mint = if info.token_x_mint == sol {
-                info.token_y_mint
-            } else if info.token_y_mint == sol {
-                info.token_x_mint
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::MeteoraDamm => {
-            let pool = meteora_damm_cpi::Pool::deserialize_unchecked(data)?;
-            let token_mint = if pool.token_a_mint == sol {
-                pool.token_b_mint
-            } else if pool.token_b_mint == sol {
-                pool.token_a_mint
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::MeteoraDammV2 => {
-            let info = MeteoraDAmmV2Info::load_checked(data)?;
-            let token_mint = if info.base_mint == sol {
-                info.quote_mint
-            } else if info.quote_mint == sol {
-                info.base_mint
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::Whirlpool => {
-            let whirlpool = Whirlpool::try_deserialize(data)?;
-            let token_mint = if whirlpool.token_mint_a == sol {
-                whirlpool.token_mint_b
-            } else if whirlpool.token_mint_b == sol {
-                whirlpool.token_mint_a
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::Vertigo => {
-            let info = VertigoInfo::load_checked(data, pool_pubkey)?;
-            let token_mint = if info.mint_a == sol {
-                info.mint_b
-            } else if info.mint_b == sol {
-                info.mint_a
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::Heaven => {
-            let info = HeavenPoolState::parse(data).ok_or_else(|| {
-                anyhow::anyhow!("Failed to parse Heaven pool")
-            })?;
-            let usdc_mint: Pubkey = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".parse().unwrap();
-            let token_mint = if info.mint_a == sol || info.mint_a == usdc_mint {
-                info.mint_b
-            } else if info.mint_b == sol || info.mint_b == usdc_mint {
-                info.mint_a
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::Futarchy => {
-            let info = FutarchyInfo::load_checked(data)?;
-            let token_mint = if info.base_mint == sol {
-                info.quote_mint
-            } else if info.quote_mint == sol {
-                info.base_mint
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::Humidifi => {
-            let info = HumidifiInfo::load_checked(data)?;
-            let token_mint = if info.base_mint == sol {
-                info.quote_mint
-            } else if info.quote_mint == sol {
-                info.base_mint
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-        MarketPoolKind::PancakeSwap | MarketPoolKind::Byreal => {
-            // PancakeSwap and Byreal share the same CLMM layout as Raydium
-            let info = PoolState::load_checked(data)?;
-            let token_mint = if info.token_mint_0 == sol {
-                info.token_mint_1
-            } else if info.token_mint_1 == sol {
-                info.token_mint_0
-            } else {
-                return Ok(None);
-            };
-            Ok(Some(token_mint))
-        }
-    }
-}
-
-/// Initialize pools from a simplified markets config
-/// This function:
-/// 1. Fetches all market accounts
-/// 2. Detects the pool kind for each
-/// 3. Extracts the token mint
-/// 4. Groups pools by mint
-/// 5. Initializes MintPoolData for each mint
-pub async fn initialize_pools_from_markets(
-    markets_config: &MarketsConfig,
-    wallet_account: &Pubkey,
-    rpc_client: Arc<RpcClient>,
-) -> anyhow::Result<HashMap<Pubkey, MintPoolData>> {
-    info!("Initializing pools from {} markets", markets_config.markets.len());
-
-    // Parse all market addresses
-    let market_pubkeys: Vec<Pubkey> = markets_config
-        .markets
-        .iter()
-        .filter_map(|s| {
-            match s.parse::<Pubkey>() {
-                Ok(pk) => Some(pk),
-                Err(e) => {
-                    error!("Invalid market address {}: {}", s, e);
-                    None
-                }
-            }
-        })
-        .collect();
-
-    if market_pubkeys.is_empty() {
-        return Ok(HashMap::new());
-    }
-
-    // Fetch all accounts in batches
-    let mut mint_pools: HashMap<Pubkey, MintPoolsBuilder> = HashMap::new();
-
-    // Process in batches of 100 (RPC limit for getMultipleAccounts)
-    for chunk in market_pubkeys.chunks(100) {
-        let accounts = rpc_client.get_multiple_accounts(chunk)?;
-
-        for (i, maybe_account) in accounts.iter().enumerate() {
-            let pool_pubkey = chunk[i];
-
-            let account = match maybe_account {
-                Some(acc) => acc,
-                None => {
-                    warn!("Market account {} not found", pool_pubkey);
-                    continue;
-                }
-            };
-
-            // Detect pool kind
-            let kind = match detect_pool_kind(&account.owner) {
-                Some(k) => k,
-                None => {
-                    warn!(
-                        "Unknown pool program {} for market {}",
-                        account.owner, pool_pubkey
-                    );
-                    continue;
-                }
-            };
-
-            info!("Detected {:?} pool: {}", kind, pool_pubkey);
-
-            // Extract token mint
-            let token_mint = match extract_token_mint(kind, &account.data, &pool_pubkey) {
-                Ok(Some(mint)) => mint,
-                Ok(None) => {
-                    warn!("Pool {} does not have SOL as one side, skipping", pool_pubkey);
-                    continue;
-                }
-                Err(e) => {
-                    error!("Failed to parse pool {}: {}", pool_pubkey, e);
-                    continue;
-                }
-            };
-
-            info!("  Token mint: {}", token_mint);
-
-            // Group by mint
-            let builder = mint_pools.entry(token_mint).or_default();
-
-            match kind {
-                MarketPoolKind::Pump => builder.pump_pools.push(pool_pubkey),
-                MarketPoolKind::RaydiumV4 => builder.raydium_pools.push(pool_pubkey),
-                MarketPoolKind::RaydiumCp => builder.raydium_cp_pools.push(pool_pubkey),
-                MarketPoolKind::RaydiumClmm => builder.raydium_clmm_pools.push(pool_pubkey),
-                MarketPoolKind::MeteoraDlmm => builder.dlmm_pools.push(pool_pubkey),
-                MarketPoolKind::MeteoraDamm => builder.damm_pools.push(pool_pubkey),
-                MarketPoolKind::MeteoraDammV2 => builder.damm_v2_pools.push(pool_pubkey),
-                MarketPoolKind::Whirlpool => builder.whirlpool_pools.push(pool_pubkey),
-                MarketPoolKind::Vertigo => builder.vertigo_pools.push(pool_pubkey),
-                MarketPoolKind::Heaven => builder.heaven_pools.push(pool_pubkey),
-                MarketPoolKind::Futarchy => builder.futarchy_pools.push(pool_pubkey),
-                MarketPoolKind::Humidifi => builder.humidifi_pools.push(pool_pubkey),
-                MarketPoolKind::PancakeSwap => builder.pancakeswap_pools.push(pool_pubkey),
-                MarketPoolKind::Byreal => builder.byreal_pools.push(pool_pubkey),
-            }
-        }
-    }
-
-    info!("Found {} unique token mints", mint_pools.len());
-
-    // Initialize MintPoolData for each mint
-    let mut result: HashMap<Pubkey, MintPoolData> = HashMap::new();
-
-    for (mint, builder) in mint_pools {
-        info!("Initializing pools for mint: {}", mint);
-
-        let pool_data = initialize_pool_data(
-            mint,
-            wallet_account,
-            if builder.raydium_pools.is_empty() { None } else { Some(&builder.raydium_pools) },
-            if builder.raydium_cp_pools.is_empty() { None } else { Some(&builder.raydium_cp_pools) },
-            if builder.pump_pools.is_empty() { None } else { Some(&builder.pump_pools) },
-            if builder.dlmm_pools.is_empty() { None } else { Some(&builder.dlmm_pools) },
-            if builder.whirlpool_pools.is_empty() { None } else { Some(&builder.whirlpool_pools) },
-            if builder.raydium_clmm_pools.is_empty() { None } else { Some(&builder.raydium_clmm_pools) },
-            if builder.damm_pools.is_empty() { None } else { Some(&builder.damm_pools) },
-            if builder.damm_v2_pools.is_empty() { None } else { Some(&builder.damm_v2_pools) },
-            if builder.vertigo_pools.is_empty() { None } else { Some(&builder.vertigo_pools) },
-            if builder.heaven_pools.is_empty() { None } else { Some(&builder.heaven_pools) },
-            if builder.futarchy_pools.is_empty() { None } else { Some(&builder.futarchy_pools) },
-            if builder.humidifi_pools.is_empty() { None } else { Some(&builder.humidifi_pools) },
-            if builder.pancakeswap_pools.is_empty() { None } else { Some(&builder.pancakeswap_pools) },
-            if builder.byreal_pools.is_empty() { None } else { Some(&builder.byreal_pools) },
-            rpc_client.clone(),
-        )
-        .await?;
-
-        result.insert(mint, pool_data);
-    }
-
-    Ok(result)
-}
-
-pub async fn initialize_pool_data(
-    mint: Pubkey,
-    wallet_account: &Pubkey,
-    raydium_pools: Option<&Vec<Pubkey>>,
-    raydium_cp_pools: Option<&Vec<Pubkey>>,
-    pump_pools: Option<&Vec<Pubkey>>,
-    dlmm_pools: Option<&Vec<Pubkey>>,
-    whirlpool_pools: Option<&Vec<Pubkey>>,
-    raydium_clmm_pools: Option<&Vec<Pubkey>>,
-    meteora_damm_pools: Option<&Vec<Pubkey>>,
-    meteora_damm_v2_pools: Option<&Vec<Pubkey>>,
-    vertigo_pools: Option<&Vec<Pubkey>>,
-    heaven_pools: Option<&Vec<Pubkey>>,
-    futarchy_pools: Option<&Vec<Pubkey>>,
-    humidifi_pools: Option<&Vec<Pubkey>>,
-    pancakeswap_pools: Option<&Vec<Pubkey>>,
-    byreal_pools: Option<&Vec<Pubkey>>,
-    rpc_client: Arc<RpcClient>,
-) -> anyhow::Result<MintPoolData> {
-    info!("Initializing pool data for mint: {}", mint);
-
-    // Fetch mint account to determine token program
-    let mint_account = rpc_client.get_account(&mint)?;
-
-    // Determine token program based on mint account owner
-    let token_2022_program_id: Pubkey = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb".parse().unwrap();
-    let token_program = if mint_account.owner == spl_token::ID {
-        spl_token::ID
-    } else if mint_account.owner == token_2022_program_id {
-        token_2022_program_id
-    } else {
-        return Err(anyhow::anyhow!("Unknown token program for mint: {}", mint));
-    };
-
-    info!("Detected token program: {}", token_program);
-
-    // Determine memo_program based on whether token uses Token 2022
-    // Token 2022 pools require the memo program in swap accounts
-    let memo_program_id: Option<Pubkey> = if token_program != spl_token::ID {
-        Some("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr".parse().unwrap())
-    } else {
-        None
-    };
-
-    let mut pool_data = MintPoolData::new(mint, wallet_account, token_program);
-    info!("Pool data initialized for mint: {}", mint);
-
-    if let Some(pools) = pump_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != pump_program_id() {
-                        error!(
-                            "Error: Pump pool account is not owned by the Pump program. Expected: {}, Actual: {}",
-                            pump_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Pump pool account is not owned by the Pump program"
-                        ));
-                    }
-
-                    match PumpAmmInfo::load_checked(&account.data) {
-                        Ok(amm_info) => {
-                            let (token_vault, sol_vault) = if mint == amm_info.base_mint {
-                                (
-                                    amm_info.pool_base_token_account,
-                                    amm_info.pool_quote_token_account,
-                                )
-                            } else if mint == amm_info.quote_mint {
-                                (
-                                    amm_info.pool_quote_token_account,
-                                    amm_info.pool_base_token_account,
-                                )
-                            } else {
-                                error!(
-                                    "Pump pool {} does not contain mint {} (base {}, quote {})",
-                                    pool_pubkey, mint, amm_info.base_mint, amm_info.quote_mint
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Pump pool does not contain configured mint"
-                                ));
-                            };
-
-                            let (fee_wallet, fee_token_wallet) =
-                                if amm_info.is_mayhem_mode {
-                                    let wallet = pump_mayhem_fee_wallet();
-                                    (
-                                        wallet,
-                                        spl_associated_token_account::get_associated_token_address(
-                                            &wallet,
-                                            &amm_info.quote_mint,
-                                        ),
-                                    )
-                                } else {
-                                    let wallet = pump_fee_wallet();
-                                    (
-                                        wallet,
-                                        spl_associated_token_account::get_associated_token_address(
-                                            &wallet,
-                                            &amm_info.quote_mint,
-                                        ),
-                                    )
-                                };
-
-                            let coin_creator_vault_ata =
-                                spl_associated_token_account::get_associated_token_address(
-                                    &amm_info.coin_creator_vault_authority,
-                                    &amm_info.quote_mint,
-                                );
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == amm_info.base_mint {
-                                (amm_info.base_mint, amm_info.quote_mint)
-                            } else {
-                                (amm_info.quote_mint, amm_info.base_mint)
-                            };
-
-                            pool_data.add_pump_pool(
-                                pool_pubkey,
-                                token_vault,
-                                sol_vault,
-                                fee_wallet,
-                                fee_token_wallet,
-                                coin_creator_vault_ata,
-                                amm_info.coin_creator_vault_authority,
-                                amm_info.coin_creator,
-                                token_mint,
-                                base_mint,
-                                amm_info.is_mayhem_mode,
-                                amm_info.is_cashback_coin,
-                            );
-                            info!("Pump pool added: {}", pool_pubkey);
-                            info!("    Base mint: {}", amm_info.base_mint);
-                            info!("    Quote mint: {}", amm_info.quote_mint);
-                            info!("    Token vault: {}", token_vault);
-                            info!("    Sol vault: {}", sol_vault);
-                            info!("    Fee wallet: {}", fee_wallet);
-                            info!("    Fee token wallet: {}", fee_token_wallet);
-                            info!(
-                                "    Coin creator vault ata: {}",
-                                coin_creator_vault_ata
-                            );
-                            info!(
-                                "    Coin creator vault authority: {}",
-                                amm_info.coin_creator_vault_authority
-                            );
-                            info!("    Coin creator: {}", amm_info.coin_creator);
-                            info!("    Mayhem mode: {}", amm_info.is_mayhem_mode);
-                            info!("    Cashback coin: {}", amm_info.is_cashback_coin);
-                            info!("    Initialized Pump pool: {}\n", pool_pubkey);
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing AmmInfo from Pump pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            return Err(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Pump pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Pump pool account"));
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = raydium_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != raydium_program_id() {
-                        error!(
-                            "Error: Raydium pool account is not owned by the Raydium program. Expected: {}, Actual: {}",
-                            raydium_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Raydium pool account is not owned by the Raydium program"
-                        ));
-                    }
-
-                    match RaydiumAmmInfo::load_checked(&account.data) {
-                        Ok(amm_info) => {
-                            if amm_info.coin_mint != pool_data.mint
-                                && amm_info.pc_mint != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Raydium pool {}, skipping",
-                                    pool_data.mint, pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Raydium pool: {}",
-                                    pool_pubkey
-                                ));
-                            }
-
-                            if amm_info.coin_mint != sol_mint() && amm_info.pc_mint != sol_mint() {
-                                error!(
-                                    "SOL is not present in Raydium pool {}",
-                                    pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "SOL is not present in Raydium pool: {}",
-                                    pool_pubkey
-                                ));
-                            }
-
-                            let (sol_vault, token_vault) = if sol_mint() == amm_info.coin_mint {
-                                (amm_info.coin_vault, amm_info.pc_vault)
-                            } else {
-                                (amm_info.pc_vault, amm_info.coin_vault)
-                            };
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == amm_info.coin_mint {
-                                (amm_info.coin_mint, amm_info.pc_mint)
-                            } else {
-                                (amm_info.pc_mint, amm_info.coin_mint)
-                            };
-
-                            pool_data.add_raydium_pool(
-                                pool_pubkey,
-                                token_vault,
-                                sol_vault,
-                                token_mint,
-                                base_mint,
-                            );
-                            info!("Raydium pool added: {}", pool_pubkey);
-                            info!("    Coin mint: {}", amm_info.coin_mint);
-                            info!("    PC mint: {}", amm_info.pc_mint);
-                            info!("    Token vault: {}", token_vault);
-                            info!("    Sol vault: {}", sol_vault);
-                            info!("    Initialized Raydium pool: {}\n", pool_pubkey);
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing AmmInfo from Raydium pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            return Err(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Raydium pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Raydium pool account"));
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = raydium_cp_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != raydium_cp_program_id() {
-                        error!(
-                            "Error: Raydium CP pool account is not owned by the Raydium CP program. Expected: {}, Actual: {}",
-                            raydium_cp_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Raydium CP pool account is not owned by the Raydium CP program"
-                        ));
-                    }
-
-                    match RaydiumCpAmmInfo::load_checked(&account.data) {
-                        Ok(amm_info) => {
-                            if amm_info.token_0_mint != pool_data.mint
-                                && amm_info.token_1_mint != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Raydium CP pool {}, skipping",
-                                    pool_data.mint, pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Raydium CP pool: {}",
-                                    pool_pubkey
-                                ));
-                            }
-
-                            let (sol_vault, token_vault) = if sol_mint() == amm_info.token_0_mint {
-                                (amm_info.token_0_vault, amm_info.token_1_vault)
-                            } else if sol_mint() == amm_info.token_1_mint {
-                                (amm_info.token_1_vault, amm_info.token_0_vault)
-                            } else {
-                                error!(
-                                    "SOL is not present in Raydium CP pool {}",
-                                    pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "SOL is not present in Raydium CP pool: {}",
-                                    pool_pubkey
-                                ));
-                            };
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == amm_info.token_0_mint {
-                                (amm_info.token_0_mint, amm_info.token_1_mint)
-                            } else {
-                                (amm_info.token_1_mint, amm_info.token_0_mint)
-                            };
-
-                            pool_data.add_raydium_cp_pool(
-                                pool_pubkey,
-                                token_vault,
-                                sol_vault,
-                                amm_info.amm_config,
-                                amm_info.observation_key,
-                                token_mint,
-                                base_mint,
-                            );
-                            info!("Raydium CP pool added: {}", pool_pubkey);
-                            info!("    Token vault: {}", token_vault);
-                            info!("    Sol vault: {}", sol_vault);
-                            info!("    AMM Config: {}", amm_info.amm_config);
-                            info!(
-                                "    Observation Key: {}\n",
-                                amm_info.observation_key
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing AmmInfo from Raydium CP pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            return Err(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Raydium CP pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Raydium CP pool account"));
-                }
-            }
-        }
-    }
-    if let Some(pools) = dlmm_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != dlmm_program_id() {
-                        error!(
-                            "Error: DLMM pool account is not owned by the DLMM program. Expected: {}, Actual: {}",
-                            dlmm_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "DLMM pool account is not owned by the DLMM program"
-                        ));
-                    }
-
-                    match DlmmInfo::load_checked(&account.data) {
-                        Ok(amm_info) => {
-                            let sol = sol_mint();
-                            let (token_vault, sol_vault) =
-                                amm_info.get_token_and_sol_vaults(&pool_data.mint, &sol);
-
-                            let bin_arrays = match amm_info.calculate_bin_arrays(&pool_pubkey)
-                            {
-                                Ok(arrays) => arrays,
-                                Err(e) => {
-                                    error!(
-                                        "Error calculating bin arrays for DLMM pool {}: {:?}",
-                                        pool_pubkey, e
-                                    );
-                                    return Err(e);
-                                }
-                            };
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == amm_info.token_x_mint {
-                                (amm_info.token_x_mint, amm_info.token_y_mint)
-                            } else {
-                                (amm_info.token_y_mint, amm_info.token_x_mint)
-                            };
-
-                            let (bitmap_extension, _) = Pubkey::find_program_address(
-                                &[b"bitmap", pool_pubkey.as_ref()],
-                                &dlmm_program_id(),
-                            );
-                            let bin_array_bitmap_extension =
-                                match rpc_client.get_account(&bitmap_extension) {
-                                    Ok(extension_account)
-                                        if extension_account.owner == dlmm_program_id() =>
-                                    {
-                                        Some(bitmap_extension)
-                                    }
-                                    _ => None,
-                                };
-
-                            pool_data.add_dlmm_pool(
-                                pool_pubkey,
-                                token_vault,
-                                sol_vault,
-                                amm_info.oracle,
-                                bin_array_bitmap_extension,
-                                bin_arrays.clone(),
-                                memo_program_id, // memo_program for Token 2022
-                                token_mint,
-                                base_mint,
-                            );
-
-                            info!("DLMM pool added: {}", pool_pubkey);
-                            info!("    Token X Mint: {}", amm_info.token_x_mint);
-                            info!("    Token Y Mint: {}", amm_info.token_y_mint);
-                            info!("    Token vault: {}", token_vault);
-                            info!("    Sol vault: {}", sol_vault);
-                            info!("    Oracle: {}", amm_info.oracle);
-                            if let Some(bitmap_extension) = bin_array_bitmap_extension {
-                                info!("    Bin Array Bitmap Extension: {}", bitmap_extension);
-                            }
-                            info!("    Active ID: {}", amm_info.active_id);
-
-                            for (i, array) in bin_arrays.iter().enumerate() {
-                                info!("    Bin Array {}: {}", i, array);
-                            }
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing AmmInfo from DLMM pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            return Err(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching DLMM pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching DLMM pool account"));
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = whirlpool_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != whirlpool_program_id() {
-                        error!(
-                            "Error: Whirlpool pool account is not owned by the Whirlpool program. Expected: {}, Actual: {}",
-                            whirlpool_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Whirlpool pool account is not owned by the Whirlpool program"
-                        ));
-                    }
-
-                    match Whirlpool::try_deserialize(&account.data) {
-                        Ok(whirlpool) => {
-                            if whirlpool.token_mint_a != pool_data.mint
-                                && whirlpool.token_mint_b != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Whirlpool pool {}, skipping",
-                                    pool_data.mint, pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Whirlpool pool: {}",
-                                    pool_pubkey
-                                ));
-                            }
-
-                            let sol = sol_mint();
-                            let (sol_vault, token_vault) = if sol == whirlpool.token_mint_a {
-                                (whirlpool.token_vault_a, whirlpool.token_vault_b)
-                            } else if sol == whirlpool.token_mint_b {
-                                (whirlpool.token_vault_b, whirlpool.token_vault_a)
-                            } else {
-                                error!(
-                                    "SOL is not present in Whirlpool pool {}",
-                                    pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "SOL is not present in Whirlpool pool: {}",
-                                    pool_pubkey
-                                ));
-                            };
-
-                            let whirlpool_oracle = Pubkey::find_program_address(
-                                &[b"oracle", pool_pubkey.as_ref()],
-                                &whirlpool_program_id(),
-                            )
-                            .0;
-
-                            let whirlpool_tick_arrays = update_tick_array_accounts_for_onchain(
-                                &whirlpool,
-                                &pool_pubkey,
-                                &whirlpool_program_id(),
-                            );
-
-                            let tick_arrays: Vec<Pubkey> = whirlpool_tick_arrays
-                                .iter()
-                                .map(|meta| meta.pubkey)
-                                .collect();
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == whirlpool.token_mint_a {
-                                (whirlpool.token_mint_a, whirlpool.token_mint_b)
-                            } else {
-                                (whirlpool.token_mint_b, whirlpool.token_mint_a)
-                            };
-
-                            pool_data.add_whirlpool_pool(
-                                pool_pubkey,
-                                whirlpool_oracle,
-                                token_vault,
-                                sol_vault,
-                                tick_arrays.clone(),
-                                memo_program_id, // memo_program for Token 2022
-                                token_mint,
-                                base_mint,
-                            );
-
-                            info!("Whirlpool pool added: {}", pool_pubkey);
-                            info!("    Token mint A: {}", whirlpool.token_mint_a);
-                            info!("    Token mint B: {}", whirlpool.token_mint_b);
-                            info!("    Token vault: {}", token_vault);
-                            info!("    Sol vault: {}", sol_vault);
-                            info!("    Oracle: {}", whirlpool_oracle);
-
-                            for (i, array) in tick_arrays.iter().enumerate() {
-                                info!("    Tick Array {}: {}", i, array);
-                            }
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Whirlpool data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            return Err(anyhow::anyhow!("Error parsing Whirlpool data"));
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Whirlpool pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Whirlpool pool account"));
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = raydium_clmm_pools {
-        for &pool_pubkey in pools {
-            let raydium_clmm_prog_id = raydium_clmm_program_id();
-
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != raydium_clmm_prog_id {
-                        error!(
-                            "Raydium CLMM pool {} is not owned by the Raydium CLMM program, skipping",
-                            pool_pubkey
-                        );
-                        continue;
-                    }
-
-                    match PoolState::load_checked(&account.data) {
-                        Ok(raydium_clmm) => {
-                            if raydium_clmm.token_mint_0 != pool_data.mint
-                                && raydium_clmm.token_mint_1 != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Raydium CLMM pool {}, skipping",
-                                    pool_data.mint, pool_pubkey
-                                );
-                                continue;
-                            }
-
-                            let sol = sol_mint();
-                            let (token_vault, sol_vault) = if sol == raydium_clmm.token_mint_0
-                            {
-                                (raydium_clmm.token_vault_1, raydium_clmm.token_vault_0)
-                            } else if sol == raydium_clmm.token_mint_1 {
-                                (raydium_clmm.token_vault_0, raydium_clmm.token_vault_1)
-                            } else {
-                                error!("SOL is not present in Raydium CLMM pool {}", pool_pubkey);
-                                continue;
-                            };
-
-                            let bitmap_extension = Pubkey::find_program_address(
-                                &[
-                                    POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
-                                    pool_pubkey.as_ref(),
-                                ],
-                                &raydium_clmm_prog_id,
-                            )
-                            .0;
-                            let bitmap_extension_state = rpc_client
-                                .get_account(&bitmap_extension)
-                                .ok()
-                                .and_then(|account| parse_bitmap_extension(&account.data));
-                            let tick_arrays = match get_initialized_tick_array_pubkeys(
-                                &pool_pubkey,
-                                &raydium_clmm,
-                                bitmap_extension_state.as_ref(),
-                                &raydium_clmm_prog_id,
-                            ) {
-                                Ok(arrays) => arrays,
-                                Err(e) => {
-                                    error!(
-                                        "Raydium CLMM pool {} tick bitmap lookup failed: {:?}",
-                                        pool_pubkey, e
-                                    );
-                                    continue;
-                                }
-                            };
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == raydium_clmm.token_mint_0 {
-                                (raydium_clmm.token_mint_0, raydium_clmm.token_mint_1)
-                            } else {
-                                (raydium_clmm.token_mint_1, raydium_clmm.token_mint_0)
-                            };
-
-                            pool_data.add_raydium_clmm_pool(
-                                pool_pubkey,
-                                raydium_clmm.amm_config,
-                                raydium_clmm.observation_key,
-                                token_vault,
-                                sol_vault,
-                                tick_arrays.clone(),
-                                memo_program_id, // memo_program for Token 2022
-                                token_mint,
-                                base_mint,
-                            );
-
-                            info!("Raydium CLMM pool added: {}", pool_pubkey);
-                            info!(
-                                "    Token mint 0: {}",
-                                raydium_clmm.token_mint_0
-                            );
-                            info!(
-                                "    Token mint 1: {}",
-                                raydium_clmm.token_mint_1
-                            );
-                            info!("    Token vault: {}", token_vault);
-                            info!("    Sol vault: {}", sol_vault);
-                            info!("    AMM config: {}", raydium_clmm.amm_config);
-                            info!(
-                                "    Observation key: {}",
-                                raydium_clmm.observation_key
-                            );
-
-                            for (i, array) in tick_arrays.iter().enumerate() {
-                                info!("    Tick Array {}: {}", i, array);
-                            }
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Raydium CLMM data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Raydium CLMM pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = meteora_damm_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != damm_program_id() {
-                        error!(
-                            "Error: Meteora DAMM pool account is not owned by the Meteora DAMM program. Expected: {}, Actual: {}",
-                            damm_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Meteora DAMM pool account is not owned by the Meteora DAMM program"
-                        ));
-                    }
-
-                    match meteora_damm_cpi::Pool::deserialize_unchecked(&account.data) {
-                        Ok(pool) => {
-                            if pool.token_a_mint != pool_data.mint
-                                && pool.token_b_mint != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Meteora DAMM pool {}, skipping",
-                                    pool_data.mint, pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Meteora DAMM pool: {}",
-                                    pool_pubkey
-                                ));
-                            }
-
-                            let sol = sol_mint();
-                            if pool.token_a_mint != sol && pool.token_b_mint != sol {
-                                error!(
-                                    "SOL is not present in Meteora DAMM pool {}",
-                                    pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "SOL is not present in Meteora DAMM pool: {}",
-                                    pool_pubkey
-                                ));
-                            }
-
-                            let (x_vault, sol_vault) = if sol == pool.token_a_mint {
-                                (pool.b_vault, pool.a_vault)
-                            } else {
-                                (pool.a_vault, pool.b_vault)
-                            };
-
-                            // Fetch vault accounts
-                            let x_vault_data = rpc_client.get_account(&x_vault)?;
-                            let sol_vault_data = rpc_client.get_account(&sol_vault)?;
-
-                            let x_vault_obj = meteora_vault_cpi::Vault::deserialize_unchecked(
-                                &mut x_vault_data.data.as_slice(),
-                            )?;
-                            let sol_vault_obj = meteora_vault_cpi::Vault::deserialize_unchecked(
-                                &mut sol_vault_data.data.as_slice(),
-                            )?;
-
-                            let x_token_vault = x_vault_obj.token_vault;
-                            let sol_token_vault = sol_vault_obj.token_vault;
-                            let x_lp_mint = x_vault_obj.lp_mint;
-                            let sol_lp_mint = sol_vault_obj.lp_mint;
-
-                            let (x_pool_lp, sol_pool_lp) = if sol == pool.token_a_mint {
-                                (pool.b_vault_lp, pool.a_vault_lp)
-                            } else {
-                                (pool.a_vault_lp, pool.b_vault_lp)
-                            };
-
-                            let (x_admin_fee, sol_admin_fee) = if sol == pool.token_a_mint {
-                                (pool.admin_token_b_fee, pool.admin_token_a_fee)
-                            } else {
-                                (pool.admin_token_a_fee, pool.admin_token_b_fee)
-                            };
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == pool.token_a_mint {
-                                (pool.token_a_mint, pool.token_b_mint)
-                            } else {
-                                (pool.token_b_mint, pool.token_a_mint)
-                            };
-
-                            pool_data.add_meteora_damm_pool(
-                                pool_pubkey,
-                                x_vault,
-                                sol_vault,
-                                x_token_vault,
-                                sol_token_vault,
-                                x_lp_mint,
-                                sol_lp_mint,
-                                x_pool_lp,
-                                sol_pool_lp,
-                                x_admin_fee,
-                                sol_admin_fee,
-                                token_mint,
-                                base_mint,
-                            );
-
-                            info!("Meteora DAMM pool added: {}", pool_pubkey);
-                            info!("    Token X vault: {}", x_token_vault);
-                            info!("    SOL vault: {}", sol_token_vault);
-                            info!("    Token X LP mint: {}", x_lp_mint);
-                            info!("    SOL LP mint: {}", sol_lp_mint);
-                            info!("    Token X pool LP: {}", x_pool_lp);
-                            info!("    SOL pool LP: {}", sol_pool_lp);
-                            info!("    Token X admin fee: {}", x_admin_fee);
-                            info!("    SOL admin fee: {}", sol_admin_fee);
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Meteora DAMM pool data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            return Err(anyhow::anyhow!("Error parsing Meteora DAMM pool data"));
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Meteora DAMM pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Meteora DAMM pool account"));
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = meteora_damm_v2_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != damm_v2_program_id() {
-                        error!("Meteora DAMM V2 pool {} is not owned by the Meteora DAMM V2 program, skipping", pool_pubkey);
-                        continue;
-                    }
-
-                    match MeteoraDAmmV2Info::load_checked(&account.data) {
-                        Ok(meteora_damm_v2_info) => {
-                            info!("Meteora DAMM V2 pool added: {}", pool_pubkey);
-                            info!(
-                                "    Base mint: {}",
-                                meteora_damm_v2_info.base_mint
-                            );
-                            info!(
-                                "    Quote mint: {}",
-                                meteora_damm_v2_info.quote_mint
-                            );
-                            info!(
-                                "    Base vault: {}",
-                                meteora_damm_v2_info.base_vault
-                            );
-                            info!(
-                                "    Quote vault: {}",
-                                meteora_damm_v2_info.quote_vault
-                            );
-                            info!("");
-                            let sol = sol_mint();
-                            let token_x_vault = if sol == meteora_damm_v2_info.base_mint {
-                                meteora_damm_v2_info.quote_vault
-                            } else {
-                                meteora_damm_v2_info.base_vault
-                            };
-
-                            let token_sol_vault = if sol == meteora_damm_v2_info.base_mint {
-                                meteora_damm_v2_info.base_vault
-                            } else {
-                                meteora_damm_v2_info.quote_vault
-                            };
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == meteora_damm_v2_info.base_mint {
-                                (meteora_damm_v2_info.base_mint, meteora_damm_v2_info.quote_mint)
-                            } else {
-                                (meteora_damm_v2_info.quote_mint, meteora_damm_v2_info.base_mint)
-                            };
-
-                            pool_data.add_meteora_damm_v2_pool(
-                                pool_pubkey,
-                                token_x_vault,
-                                token_sol_vault,
-                                token_mint,
-                                base_mint,
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Meteora DAMM V2 pool data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Meteora DAMM V2 pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = vertigo_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != vertigo_program_id() {
-                        error!(
-                            "Error: Vertigo pool account is not owned by the Vertigo program. Expected: {}, Actual: {}",
-                            vertigo_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Vertigo pool account is not owned by the Vertigo program"
-                        ));
-                    }
-
-                    match VertigoInfo::load_checked(&account.data, &pool_pubkey) {
-                        Ok(vertigo_info) => {
-                            info!("Vertigo pool added: {}", pool_pubkey);
-                            info!("    Mint A: {}", vertigo_info.mint_a);
-                            info!("    Mint B: {}", vertigo_info.mint_b);
-
-                            // Following the original loading pattern from user's code:
-                            let non_base_vault = if pool_data.mint == vertigo_info.mint_a {
-                                derive_vault_address(&pool_pubkey, &vertigo_info.mint_b).0
-                            } else {
-                                derive_vault_address(&pool_pubkey, &vertigo_info.mint_a).0
-                            };
-                            let base_vault = if pool_data.mint == vertigo_info.mint_a {
-                                derive_vault_address(&pool_pubkey, &vertigo_info.mint_a).0
-                            } else {
-                                derive_vault_address(&pool_pubkey, &vertigo_info.mint_b).0
-                            };
-
-                            // Map to transaction expected fields:
-                            // base_mint is our trading token, non-base should be SOL
-                            let token_x_vault = base_vault; // vault for our trading token
-                            let token_sol_vault = non_base_vault; // vault for SOL
-
-                            info!("    Token X Vault: {}", token_x_vault);
-                            info!("    Token SOL Vault: {}", token_sol_vault);
-                            info!("");
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == vertigo_info.mint_a {
-                                (vertigo_info.mint_a, vertigo_info.mint_b)
-                            } else {
-                                (vertigo_info.mint_b, vertigo_info.mint_a)
-                            };
-
-                            pool_data.add_vertigo_pool(
-                                pool_pubkey,
-                                vertigo_info.pool,
-                                token_x_vault,
-                                token_sol_vault,
-                                token_mint,
-                                base_mint,
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Vertigo pool data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Vertigo pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = heaven_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != heaven_program_id() {
-                        error!(
-                            "Error: Heaven pool account is not owned by the Heaven program. Expected: {}, Actual: {}",
-                            heaven_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Heaven pool account is not owned by the Heaven program"
-                        ));
-                    }
-
-                    match HeavenPoolState::parse(&account.data) {
-                        Some(heaven_info) => {
-                            info!("Heaven pool added: {}", pool_pubkey);
-                            info!("    Mint A: {}", heaven_info.mint_a);
-                            info!("    Mint B: {}", heaven_info.mint_b);
-                            info!("    Vault A: {}", heaven_info.vault_a);
-                            info!("    Vault B: {}", heaven_info.vault_b);
-                            info!("    Protocol Config: {}", heaven_info.protocol_config);
-                            info!("    Reserve A: {}", heaven_info.reserve_a);
-                            info!("    Reserve B: {}", heaven_info.reserve_b);
-
-                            // Determine which vault corresponds to token and base
-                            let (token_x_vault, token_base_vault) =
-                                if mint == heaven_info.mint_a {
-                                    (heaven_info.vault_a, heaven_info.vault_b)
-                                } else {
-                                    (heaven_info.vault_b, heaven_info.vault_a)
-                                };
-
-                            // Determine token_mint and base_mint
-                            let (token_mint, base_mint) = if mint == heaven_info.mint_a {
-                                (heaven_info.mint_a, heaven_info.mint_b)
-                            } else {
-                                (heaven_info.mint_b, heaven_info.mint_a)
-                            };
-
-                            // Validate that the base mint is either SOL or USDC
-                            let usdc_mint: Pubkey = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".parse().unwrap();
-                            if base_mint != sol_mint() && base_mint != usdc_mint {
-                                error!(
-                                    "Invalid Heaven pool: Expected SOL or USDC as base mint, but found {}",
-                                    base_mint
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Heaven pool: Expected SOL or USDC as base mint"
-                                ));
-                            }
-
-                            pool_data.add_heaven_pool(
-                                pool_pubkey,
-                                heaven_info.protocol_config,
-                                token_x_vault,
-                                token_base_vault,
-                                token_mint,
-                                base_mint,
-                                token_program,
-                            );
-
-                            info!("    Initialized Heaven pool: {}\n", pool_pubkey);
-                        }
-                        None => {
-                            error!(
-                                "Error parsing Heaven pool data from pool {}",
-                                pool_pubkey
-                            );
-                            return Err(anyhow::anyhow!("Failed to parse Heaven pool data"));
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Heaven pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = futarchy_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != futarchy_program_id() {
-                        error!(
-                            "Futarchy pool {} is not owned by the Futarchy program, skipping",
-                            pool_pubkey
-                        );
-                        continue;
-                    }
-
-                    match FutarchyInfo::load_checked(&account.data) {
-                        Ok(futarchy_info) => {
-                            info!("Futarchy pool added: {}", pool_pubkey);
-                            info!("    Base mint: {}", futarchy_info.base_mint);
-                            info!("    Quote mint: {}", futarchy_info.quote_mint);
-                            info!("    Base vault: {}", futarchy_info.base_vault);
-                            info!("    Quote vault: {}", futarchy_info.quote_vault);
-
-                            let (token_x_vault, token_base_vault, token_mint, base_mint) =
-                                if mint == futarchy_info.base_mint {
-                                    (
-                                        futarchy_info.base_vault,
-                                        futarchy_info.quote_vault,
-                                        futarchy_info.base_mint,
-                                        futarchy_info.quote_mint,
-                                    )
-                                } else if mint == futarchy_info.quote_mint {
-                                    (
-                                        futarchy_info.quote_vault,
-                                        futarchy_info.base_vault,
-                                        futarchy_info.quote_mint,
-                                        futarchy_info.base_mint,
-                                    )
-                                } else {
-                                    warn!(
-                                        "{} is not present in Futarchy pool {}, skipping",
-                                        mint, pool_pubkey
-                                    );
-                                    continue;
-                                };
-
-                            pool_data.add_futarchy_pool(
-                                futarchy_event_authority(),
-                                pool_pubkey,
-                                token_x_vault,
-                                token_base_vault,
-                                token_mint,
-                                base_mint,
-                            );
-
-                            info!("    Initialized Futarchy pool: {}\n", pool_pubkey);
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Futarchy pool data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Futarchy pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = humidifi_pools {
-        for &pool_pubkey in pools {
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != humidifi_program_id() {
-                        error!(
-                            "Humidifi pool {} is not owned by the Humidifi program, skipping",
-                            pool_pubkey
-                        );
-                        continue;
-                    }
-
-                    match HumidifiInfo::load_checked(&account.data) {
-                        Ok(humidifi_info) => {
-                            info!("Humidifi pool added: {}", pool_pubkey);
-                            info!("    Base mint: {}", humidifi_info.base_mint);
-                            info!("    Quote mint: {}", humidifi_info.quote_mint);
-                            info!("    Base vault: {}", humidifi_info.base_vault);
-                            info!("    Quote vault: {}", humidifi_info.quote_vault);
-
-                            let sol = sol_mint();
-                            let (token_x_vault, token_sol_vault) = if sol == humidifi_info.base_mint {
-                                (humidifi_info.quote_vault, humidifi_info.base_vault)
-                            } else {
-                                (humidifi_info.base_vault, humidifi_info.quote_vault)
-                            };
-
-                            let (token_mint, base_mint) = if mint == humidifi_info.base_mint {
-                                (humidifi_info.base_mint, humidifi_info.quote_mint)
-                            } else {
-                                (humidifi_info.quote_mint, humidifi_info.base_mint)
-                            };
-
-                            pool_data.add_humidifi_pool(
-                                pool_pubkey,
-                                token_x_vault,
-                                token_sol_vault,
-                                token_mint,
-                                base_mint,
-                            );
-
-                            info!("    Initialized Humidifi pool: {}\n", pool_pubkey);
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Humidifi pool data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Humidifi pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = pancakeswap_pools {
-        for &pool_pubkey in pools {
-            let pancakeswap_prog_id = pancakeswap_program_id();
-
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != pancakeswap_prog_id {
-                        error!(
-                            "PancakeSwap pool {} is not owned by the PancakeSwap program, skipping",
-                            pool_pubkey
-                        );
-                        continue;
-                    }
-
-                    match PoolState::load_checked(&account.data) {
-                        Ok(pool_state) => {
-                            if pool_state.token_mint_0 != pool_data.mint
-                                && pool_state.token_mint_1 != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in PancakeSwap pool {}, skipping",
-                                    pool_data.mint, pool_pubkey
-                                );
-                                continue;
-                            }
-
-                            let sol = sol_mint();
-                            let (token_vault, sol_vault) = if sol == pool_state.token_mint_0 {
-                                (pool_state.token_vault_1, pool_state.token_vault_0)
-                            } else if sol == pool_state.token_mint_1 {
-                                (pool_state.token_vault_0, pool_state.token_vault_1)
-                            } else {
-                                error!("SOL is not present in PancakeSwap pool {}", pool_pubkey);
-                                continue;
-                            };
-
-                            let bitmap_extension = Pubkey::find_program_address(
-                                &[
-                                    POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
-                                    pool_pubkey.as_ref(),
-                                ],
-                                &pancakeswap_prog_id,
-                            )
-                            .0;
-                            let bitmap_extension_state = rpc_client
-                                .get_account(&bitmap_extension)
-                                .ok()
-                                .and_then(|account| parse_bitmap_extension(&account.data));
-                            let tick_arrays = match get_initialized_tick_array_pubkeys(
-                                &pool_pubkey,
-                                &pool_state,
-                                bitmap_extension_state.as_ref(),
-                                &pancakeswap_prog_id,
-                            ) {
-                                Ok(arrays) => arrays,
-                                Err(e) => {
-                                    error!(
-                                        "PancakeSwap pool {} tick bitmap lookup failed: {:?}",
-                                        pool_pubkey, e
-                                    );
-                                    continue;
-                                }
-                            };
-
-                            let (token_mint, base_mint) = if mint == pool_state.token_mint_0 {
-                                (pool_state.token_mint_0, pool_state.token_mint_1)
-                            } else {
-                                (pool_state.token_mint_1, pool_state.token_mint_0)
-                            };
-
-                            pool_data.add_pancakeswap_pool(
-                                pool_pubkey,
-                                pool_state.amm_config,
-                                pool_state.observation_key,
-                                token_vault,
-                                sol_vault,
-                                tick_arrays.clone(),
-                                memo_program_id, // memo_program for Token 2022
-                                token_mint,
-                                base_mint,
-                            );
-
-                            info!("PancakeSwap pool added: {}", pool_pubkey);
-                            info!("    Token mint 0: {}", pool_state.token_mint_0);
-                            info!("    Token mint 1: {}", pool_state.token_mint_1);
-                            info!("    Token vault: {}", token_vault);
-                            info!("    Sol vault: {}", sol_vault);
-                            info!("    AMM config: {}", pool_state.amm_config);
-                            info!("    Observation key: {}", pool_state.observation_key);
-
-                            for (i, array) in tick_arrays.iter().enumerate() {
-                                info!("    Tick Array {}: {}", i, array);
-                            }
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing PancakeSwap pool data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching PancakeSwap pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
-    }
-
-    if let Some(pools) = byreal_pools {
-        for &pool_pubkey in pools {
-            let byreal_prog_id = byreal_program_id();
-
-            match rpc_client.get_account(&pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != byreal_prog_id {
-                        error!(
-                            "Byreal pool {} is not owned by the Byreal program, skipping",
-                            pool_pubkey
-                        );
-                        continue;
-                    }
-
-                    match PoolState::load_checked(&account.data) {
-                        Ok(pool_state) => {
-                            if pool_state.token_mint_0 != pool_data.mint
-                                && pool_state.token_mint_1 != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Byreal pool {}, skipping",
-                                    pool_data.mint, pool_pubkey
-                                );
-                                continue;
-                            }
-
-                            let sol = sol_mint();
-                            let (token_vault, sol_vault) = if sol == pool_state.token_mint_0 {
-                                (pool_state.token_vault_1, pool_state.token_vault_0)
-                            } else if sol == pool_state.token_mint_1 {
-                                (pool_state.token_vault_0, pool_state.token_vault_1)
-                            } else {
-                                error!("SOL is not present in Byreal pool {}", pool_pubkey);
-                                continue;
-                            };
-
-                            let bitmap_extension = Pubkey::find_program_address(
-                                &[
-                                    POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
-                                    pool_pubkey.as_ref(),
-                                ],
-                                &byreal_prog_id,
-                            )
-                            .0;
-                            let bitmap_extension_state = rpc_client
-                                .get_account(&bitmap_extension)
-                                .ok()
-                                .and_then(|account| parse_bitmap_extension(&account.data));
-                            let tick_arrays = match get_initialized_tick_array_pubkeys(
-                                &pool_pubkey,
-                                &pool_state,
-                                bitmap_extension_state.as_ref(),
-                                &byreal_prog_id,
-                            ) {
-                                Ok(arrays) => arrays,
-                                Err(e) => {
-                                    error!(
-                                        "Byreal pool {} tick bitmap lookup failed: {:?}",
-                                        pool_pubkey, e
-                                    );
-                                    continue;
-                                }
-                            };
-
-                            let (token_mint, base_mint) = if mint == pool_state.token_mint_0 {
-                                (pool_state.token_mint_0, pool_state.token_mint_1)
-                            } else {
-                                (pool_state.token_mint_1, pool_state.token_mint_0)
-                            };
-
-                            pool_data.add_byreal_pool(
-                                pool_pubkey,
-                                pool_state.amm_config,
-                                pool_state.observation_key,
-                                token_vault,
-                                sol_vault,
-                                tick_arrays.clone(),
-                                memo_program_id, // memo_program for Token 2022
-                                token_mint,
-                                base_mint,
-                            );
-
-                            info!("Byreal pool added: {}", pool_pubkey);
-                            info!("    Token mint 0: {}", pool_state.token_mint_0);
-                            info!("    Token mint 1: {}", pool_state.token_mint_1);
-                            info!("    Token vault: {}", token_vault);
-                            info!("    Sol vault: {}", sol_vault);
-                            info!("    AMM config: {}", pool_state.amm_config);
-                            info!("    Observation key: {}", pool_state.observation_key);
-
-                            for (i, array) in tick_arrays.iter().enumerate() {
-                                info!("    Tick Array {}: {}", i, array);
-                            }
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Byreal pool data from pool {}: {:?}",
-                                pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Byreal pool account {}: {:?}",
-                        pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
-    }
-
-    Ok(pool_data)
-}
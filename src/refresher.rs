@@ -0,0 +1,1110 @@
+use crate::account_batcher::AccountBatcher;
+use crate::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
+use crate::dex::meteora::dlmm_info::DlmmInfo;
+use crate::dex::pump::PumpAmmInfo;
+use crate::dex::raydium::clmm_info::TickArrayBitmapExtensionState;
+use crate::dex::raydium::RaydiumCpAmmInfo;
+use crate::dex::vertigo::vertigo_vault_problem;
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::dex::whirlpool::update_tick_array_accounts_for_onchain;
+use crate::dex_rpc::DexRpcClients;
+use crate::log_dedup::LogDedup;
+use crate::loader::MarketPoolKind;
+use crate::pool_accounts::{check_owner, fetch_bitmap_extension, load_clmm_pool, ClmmPoolAccount};
+use crate::pools::{
+    ByrealPool, DlmmPool, MeteoraDAmmV2Pool, MintPoolData, PancakeswapPool, PumpPool,
+    RaydiumClmmPool, RaydiumCpPool, WhirlpoolPool,
+};
+use crate::program_registry::ProgramRegistry;
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use std::collections::{HashMap, HashSet};
+use tracing::{info, warn, Level};
+
+/// Pool fetch failures, deduplicated per pool
+static FETCH_ERRORS: LogDedup = LogDedup::new(Level::WARN);
+
+/// Program IDs of the pools whose state is refreshed here
+pub(crate) struct ProgramIds {
+    pub pump: Pubkey,
+    pub dlmm: Pubkey,
+    pub whirlpool: Pubkey,
+    pub pancakeswap: Pubkey,
+    pub byreal: Pubkey,
+    pub damm: Pubkey,
+    pub damm_v2: Pubkey,
+    pub heaven: Pubkey,
+    pub futarchy: Pubkey,
+    pub humidifi: Pubkey,
+}
+
+impl ProgramIds {
+    pub fn new() -> Self {
+        Self::from_registry(ProgramRegistry::global())
+    }
+
+    pub fn from_registry(registry: &ProgramRegistry) -> Self {
+        Self {
+            pump: registry.pump,
+            dlmm: registry.meteora_dlmm,
+            whirlpool: registry.whirlpool,
+            pancakeswap: registry.pancakeswap,
+            byreal: registry.byreal,
+            damm: registry.meteora_damm,
+            damm_v2: registry.meteora_damm_v2,
+            heaven: registry.heaven,
+            futarchy: registry.futarchy,
+            humidifi: registry.humidifi,
+        }
+    }
+}
+
+impl Default for ProgramIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recalculates a DLMM pool's bin arrays from its pair account and returns the active bin
+pub(crate) fn recompute_dlmm_from_account(pool: &mut DlmmPool, data: &[u8]) -> Result<i32> {
+    let dlmm_info = DlmmInfo::load_checked(data)?;
+    pool.bin_arrays = dlmm_info.calculate_bin_arrays(&pool.pair)?;
+    Ok(dlmm_info.active_id)
+}
+
+/// Recalculates a Whirlpool's tick arrays from its account and returns the current tick
+pub(crate) fn recompute_whirlpool_from_account(
+    pool: &mut WhirlpoolPool,
+    data: &[u8],
+    program_id: &Pubkey,
+) -> Result<i32> {
+    let whirlpool = Whirlpool::try_deserialize(data)?;
+    let tick_array_metas =
+        update_tick_array_accounts_for_onchain(&whirlpool, &pool.pool, program_id);
+    pool.tick_arrays = tick_array_metas.iter().map(|m| m.pubkey).collect();
+    Ok(whirlpool.tick_current_index)
+}
+
+/// Pools with the Raydium CLMM layout (Raydium CLMM, PancakeSwap, Byreal), refreshed alike
+trait ClmmPool {
+    const KIND: MarketPoolKind;
+    fn pool(&self) -> Pubkey;
+    fn bitmap_extension(&self) -> Pubkey;
+    /// Program the pool was initialized from
+    fn program_id(&self, program_ids: &ProgramIds) -> Pubkey;
+    /// Keeps the derived tick arrays and whatever the pool quotes from its state
+    fn apply(&mut self, account: ClmmPoolAccount);
+    fn set_refreshed_slot(&mut self, slot: u64);
+}
+
+impl ClmmPool for RaydiumClmmPool {
+    const KIND: MarketPoolKind = MarketPoolKind::RaydiumClmm;
+
+    fn pool(&self) -> Pubkey {
+        self.pool
+    }
+
+    fn bitmap_extension(&self) -> Pubkey {
+        self.bitmap_extension
+    }
+
+    fn program_id(&self, _program_ids: &ProgramIds) -> Pubkey {
+        self.program_id
+    }
+
+    fn apply(&mut self, account: ClmmPoolAccount) {
+        self.tick_arrays = account.tick_arrays;
+        self.liquidity = account.state.liquidity;
+        self.sqrt_price_x64 = account.state.sqrt_price_x64;
+        self.tick_current = account.state.tick_current;
+    }
+
+    fn set_refreshed_slot(&mut self, slot: u64) {
+        self.refreshed_slot = slot;
+    }
+}
+
+impl ClmmPool for PancakeswapPool {
+    const KIND: MarketPoolKind = MarketPoolKind::PancakeSwap;
+
+    fn pool(&self) -> Pubkey {
+        self.pool
+    }
+
+    fn bitmap_extension(&self) -> Pubkey {
+        self.bitmap_extension
+    }
+
+    fn program_id(&self, program_ids: &ProgramIds) -> Pubkey {
+        program_ids.pancakeswap
+    }
+
+    fn apply(&mut self, account: ClmmPoolAccount) {
+        self.tick_arrays = account.tick_arrays;
+    }
+
+    fn set_refreshed_slot(&mut self, slot: u64) {
+        self.refreshed_slot = slot;
+    }
+}
+
+impl ClmmPool for ByrealPool {
+    const KIND: MarketPoolKind = MarketPoolKind::Byreal;
+
+    fn pool(&self) -> Pubkey {
+        self.pool
+    }
+
+    fn bitmap_extension(&self) -> Pubkey {
+        self.bitmap_extension
+    }
+
+    fn program_id(&self, program_ids: &ProgramIds) -> Pubkey {
+        program_ids.byreal
+    }
+
+    fn apply(&mut self, account: ClmmPoolAccount) {
+        self.tick_arrays = account.tick_arrays;
+    }
+
+    fn set_refreshed_slot(&mut self, slot: u64) {
+        self.refreshed_slot = slot;
+    }
+}
+
+/// Applies a CLMM pool account and returns the current tick. With an `rpc_client`, an AMM
+/// config missing from the cache is fetched before the tick arrays are derived.
+fn recompute_clmm_from_account<P: ClmmPool>(
+    pool: &mut P,
+    data: &[u8],
+    bitmap_extension: Option<&TickArrayBitmapExtensionState>,
+    program_id: &Pubkey,
+    rpc_client: Option<&RpcClient>,
+) -> Result<i32> {
+    let account = load_clmm_pool(
+        P::KIND,
+        &pool.pool(),
+        data,
+        program_id,
+        bitmap_extension,
+        rpc_client,
+    )?;
+    let tick = account.state.tick_current;
+    pool.apply(account);
+    Ok(tick)
+}
+
+/// Applies a streamed CLMM pool account. False when the pool changed owner.
+fn recompute_clmm_update<P: ClmmPool>(
+    pool: &mut P,
+    program_ids: &ProgramIds,
+    owner: &Pubkey,
+    data: &[u8],
+    bitmap_extensions: &HashMap<Pubkey, TickArrayBitmapExtensionState>,
+) -> Result<bool> {
+    let program_id = pool.program_id(program_ids);
+    if *owner != program_id {
+        return Ok(false);
+    }
+    let extension = bitmap_extensions.get(&pool.bitmap_extension());
+    recompute_clmm_from_account(pool, data, extension, &program_id, None)?;
+    Ok(true)
+}
+
+/// Applies a Raydium CP pool account, keeping the fees held in its vaults for quoting
+pub(crate) fn recompute_raydium_cp_from_account(
+    pool: &mut RaydiumCpPool,
+    data: &[u8],
+) -> Result<()> {
+    let amm_info = RaydiumCpAmmInfo::load_checked(data)?;
+    pool.token_fees = amm_info.accrued_fees(&pool.token_vault);
+    pool.base_fees = amm_info.accrued_fees(&pool.sol_vault);
+    Ok(())
+}
+
+/// Applies a Meteora DAMM v2 pool account, keeping its fee schedule and dynamic fee for
+/// quoting
+pub(crate) fn recompute_meteora_damm_v2_from_account(
+    pool: &mut MeteoraDAmmV2Pool,
+    data: &[u8],
+) -> Result<()> {
+    pool.fees = MeteoraDAmmV2Info::load_checked(data)?.fees;
+    Ok(())
+}
+
+/// Follows mayhem mode toggles of a Pump pool. Returns true when the fee wallet changed.
+pub(crate) fn recompute_pump_from_account(pool: &mut PumpPool, data: &[u8]) -> Result<bool> {
+    let amm_info = PumpAmmInfo::load_checked(data)?;
+    if amm_info.layout_version != pool.layout_version {
+        info!(
+            "Pump pool {} layout changed from version {} to {}",
+            pool.pool, pool.layout_version, amm_info.layout_version
+        );
+        pool.layout_version = amm_info.layout_version;
+    }
+    if amm_info.is_mayhem_mode == pool.is_mayhem_mode {
+        return Ok(false);
+    }
+    let (fee_wallet, fee_token_wallet) = amm_info.fee_wallets();
+    pool.is_mayhem_mode = amm_info.is_mayhem_mode;
+    pool.fee_wallet = fee_wallet;
+    pool.fee_token_wallet = fee_token_wallet;
+    Ok(true)
+}
+
+/// Applies an account update to whichever pool of `pool_data` it belongs to, with the
+/// same decoding as the RPC refreshers. `bitmap_extensions` holds the latest tick array
+/// bitmap extensions by address. Returns false for accounts of no tracked pool and for
+/// accounts no longer owned by the pool's program, which the next RPC refresh removes.
+pub(crate) fn recompute_from_account(
+    pool_data: &mut MintPoolData,
+    program_ids: &ProgramIds,
+    pubkey: &Pubkey,
+    owner: &Pubkey,
+    data: &[u8],
+    bitmap_extensions: &HashMap<Pubkey, TickArrayBitmapExtensionState>,
+) -> Result<bool> {
+    if let Some(pool) = pool_data.pump_pools.iter_mut().find(|pool| pool.pool == *pubkey) {
+        if *owner != program_ids.pump {
+            return Ok(false);
+        }
+        recompute_pump_from_account(pool, data)?;
+        return Ok(true);
+    }
+    if let Some(pool) = pool_data
+        .raydium_cp_pools
+        .iter_mut()
+        .find(|pool| pool.pool == *pubkey)
+    {
+        if *owner != pool.program_id {
+            return Ok(false);
+        }
+        recompute_raydium_cp_from_account(pool, data)?;
+        return Ok(true);
+    }
+    if let Some(pool) = pool_data.dlmm_pairs.iter_mut().find(|pool| pool.pair == *pubkey) {
+        if *owner != program_ids.dlmm {
+            return Ok(false);
+        }
+        recompute_dlmm_from_account(pool, data)?;
+        return Ok(true);
+    }
+    if let Some(pool) = pool_data.whirlpool_pools.iter_mut().find(|pool| pool.pool == *pubkey) {
+        if *owner != program_ids.whirlpool {
+            return Ok(false);
+        }
+        recompute_whirlpool_from_account(pool, data, &program_ids.whirlpool)?;
+        return Ok(true);
+    }
+    if let Some(pool) = pool_data
+        .raydium_clmm_pools
+        .iter_mut()
+        .find(|pool| pool.pool == *pubkey)
+    {
+        return recompute_clmm_update(pool, program_ids, owner, data, bitmap_extensions);
+    }
+    if let Some(pool) = pool_data
+        .pancakeswap_pools
+        .iter_mut()
+        .find(|pool| pool.pool == *pubkey)
+    {
+        return recompute_clmm_update(pool, program_ids, owner, data, bitmap_extensions);
+    }
+    if let Some(pool) = pool_data.byreal_pools.iter_mut().find(|pool| pool.pool == *pubkey) {
+        return recompute_clmm_update(pool, program_ids, owner, data, bitmap_extensions);
+    }
+    if let Some(pool) = pool_data
+        .meteora_damm_v2_pools
+        .iter_mut()
+        .find(|pool| pool.pool == *pubkey)
+    {
+        if *owner != program_ids.damm_v2 {
+            return Ok(false);
+        }
+        recompute_meteora_damm_v2_from_account(pool, data)?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Refresh DLMM pools by recalculating bin arrays based on current active_id
+pub(crate) fn refresh_dlmm_pools(
+    pool_data: &mut MintPoolData,
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    suppress_logs: bool,
+    skip: &HashSet<Pubkey>,
+) -> Result<Vec<Pubkey>> {
+    let mut gone = Vec::new();
+    for pool in pool_data.dlmm_pairs.iter_mut() {
+        if skip.contains(&pool.pair) {
+            continue;
+        }
+        let programs = [*program_id];
+        match fetch_pool_account(rpc_client, MarketPoolKind::MeteoraDlmm, &pool.pair, &programs) {
+            Ok(PoolFetch::Gone(reason)) => {
+                warn!("{}, removing", reason);
+                gone.push(pool.pair);
+            }
+            Ok(PoolFetch::Live(account, slot)) => {
+                match recompute_dlmm_from_account(pool, &account.data) {
+                    Ok(active_id) => {
+                        pool.refreshed_slot = slot;
+                        if !suppress_logs {
+                            info!(
+                                "DLMM pool {} bin arrays refreshed, active_id: {}",
+                                pool.pair, active_id
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to refresh bin arrays of DLMM pool {}: {}", pool.pair, e);
+                    }
+                }
+            }
+            Err(e) => {
+                FETCH_ERRORS.log(
+                    &pool.pair.to_string(),
+                    format_args!("Failed to fetch DLMM pool {}: {}", pool.pair, e),
+                );
+            }
+        }
+    }
+    Ok(gone)
+}
+
+/// Refresh Whirlpool pools by recalculating tick arrays based on current tick
+pub(crate) fn refresh_whirlpool_pools(
+    pool_data: &mut MintPoolData,
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    suppress_logs: bool,
+    skip: &HashSet<Pubkey>,
+) -> Result<Vec<Pubkey>> {
+    let mut gone = Vec::new();
+    for pool in pool_data.whirlpool_pools.iter_mut() {
+        if skip.contains(&pool.pool) {
+            continue;
+        }
+        let programs = [*program_id];
+        match fetch_pool_account(rpc_client, MarketPoolKind::Whirlpool, &pool.pool, &programs) {
+            Ok(PoolFetch::Gone(reason)) => {
+                warn!("{}, removing", reason);
+                gone.push(pool.pool);
+            }
+            Ok(PoolFetch::Live(account, slot)) => {
+                match recompute_whirlpool_from_account(pool, &account.data, program_id) {
+                    Ok(tick) => {
+                        pool.refreshed_slot = slot;
+                        if !suppress_logs {
+                            info!("Whirlpool {} tick arrays refreshed at tick {}", pool.pool, tick);
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to parse Whirlpool {}: {}", pool.pool, e);
+                    }
+                }
+            }
+            Err(e) => {
+                FETCH_ERRORS.log(
+                    &pool.pool.to_string(),
+                    format_args!("Failed to fetch Whirlpool pool {}: {}", pool.pool, e),
+                );
+            }
+        }
+    }
+    Ok(gone)
+}
+
+/// A pool account fetched for a refresh
+enum PoolFetch {
+    /// Still owned by the pool's program, with the context slot of the response
+    Live(Account, u64),
+    /// Closed or reassigned to another program, with the reason to log as it is removed
+    Gone(String),
+}
+
+/// Classifies a fetched `kind` pool account. A closed account (`None`, once garbage
+/// collected) and an account owned by none of `programs` are gone.
+fn classify_pool_account(
+    kind: MarketPoolKind,
+    pool: &Pubkey,
+    account: Option<Account>,
+    slot: u64,
+    programs: &[Pubkey],
+) -> PoolFetch {
+    let Some(account) = account else {
+        return PoolFetch::Gone(format!("{} pool {} was closed", kind.name(), pool));
+    };
+    match check_owner(kind, pool, &account.owner, programs) {
+        Ok(()) => PoolFetch::Live(account, slot),
+        Err(e) => PoolFetch::Gone(e.to_string()),
+    }
+}
+
+/// Fetches a pool account together with the context slot of the response
+fn fetch_pool_account(
+    rpc_client: &RpcClient,
+    kind: MarketPoolKind,
+    pool: &Pubkey,
+    programs: &[Pubkey],
+) -> Result<PoolFetch> {
+    let response = rpc_client.get_account_with_commitment(pool, rpc_client.commitment())?;
+    Ok(classify_pool_account(
+        kind,
+        pool,
+        response.value,
+        response.context.slot,
+        programs,
+    ))
+}
+
+/// Pools of `pools`, given as `(pool, programs owning it)`, whose fetched account in
+/// `accounts` is gone
+fn gone_pools(
+    kind: MarketPoolKind,
+    pools: &[(Pubkey, Vec<Pubkey>)],
+    accounts: Vec<Option<Account>>,
+) -> Vec<Pubkey> {
+    let mut gone = Vec::new();
+    for ((pool, programs), account) in pools.iter().zip(accounts) {
+        if let PoolFetch::Gone(reason) = classify_pool_account(kind, pool, account, 0, programs) {
+            warn!("{}, removing", reason);
+            gone.push(*pool);
+        }
+    }
+    gone
+}
+
+/// Checks that pools whose state is not refreshed (Raydium, DAMM, Heaven, Futarchy,
+/// HumidiFi) still exist and belong to their program, in one batched fetch per DEX
+fn refresh_pool_owners(
+    kind: MarketPoolKind,
+    pools: Vec<(Pubkey, Vec<Pubkey>)>,
+    rpc_client: &RpcClient,
+    suppress_logs: bool,
+    skip: &HashSet<Pubkey>,
+) -> Vec<Pubkey> {
+    let pools: Vec<(Pubkey, Vec<Pubkey>)> = pools
+        .into_iter()
+        .filter(|(pool, _)| !skip.contains(pool))
+        .collect();
+    if pools.is_empty() {
+        return Vec::new();
+    }
+    let addresses: Vec<Pubkey> = pools.iter().map(|(pool, _)| *pool).collect();
+    match AccountBatcher::global().get_multiple_accounts(rpc_client, &addresses) {
+        Ok(accounts) => {
+            if !suppress_logs {
+                info!("{} {} pool owners checked", pools.len(), kind.name());
+            }
+            gone_pools(kind, &pools, accounts)
+        }
+        Err(e) => {
+            FETCH_ERRORS.log(
+                kind.name(),
+                format_args!("Failed to fetch {} pools: {}", kind.name(), e),
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Refresh CLMM pools by recalculating tick arrays based on current tick. Each pool is
+/// checked against the deployment it was initialized from.
+fn refresh_clmm_pools<P: ClmmPool>(
+    pools: &mut [P],
+    rpc_client: &RpcClient,
+    program_ids: &ProgramIds,
+    suppress_logs: bool,
+    skip: &HashSet<Pubkey>,
+) -> Result<Vec<Pubkey>> {
+    let name = P::KIND.name();
+    let mut gone = Vec::new();
+    for pool in pools.iter_mut() {
+        let address = pool.pool();
+        if skip.contains(&address) {
+            continue;
+        }
+        let program_id = pool.program_id(program_ids);
+        match fetch_pool_account(rpc_client, P::KIND, &address, &[program_id]) {
+            Ok(PoolFetch::Gone(reason)) => {
+                warn!("{}, removing", reason);
+                gone.push(address);
+            }
+            Ok(PoolFetch::Live(account, slot)) => {
+                let extension = fetch_bitmap_extension(rpc_client, &pool.bitmap_extension());
+                match recompute_clmm_from_account(
+                    pool,
+                    &account.data,
+                    extension.as_ref(),
+                    &program_id,
+                    Some(rpc_client),
+                ) {
+                    Ok(tick) => {
+                        pool.set_refreshed_slot(slot);
+                        if !suppress_logs {
+                            info!("{} {} tick arrays refreshed at tick {}", name, address, tick);
+                        }
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to refresh tick arrays of {} pool {}: {}",
+                            name, address, e
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                FETCH_ERRORS.log(
+                    &address.to_string(),
+                    format_args!("Failed to fetch {} pool {}: {}", name, address, e),
+                );
+            }
+        }
+    }
+    Ok(gone)
+}
+
+/// Refresh Pump pools so fee wallets follow mayhem mode toggles
+pub(crate) fn refresh_pump_pools(
+    pool_data: &mut MintPoolData,
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    suppress_logs: bool,
+    skip: &HashSet<Pubkey>,
+) -> Result<Vec<Pubkey>> {
+    let mut gone = Vec::new();
+    for pool in pool_data.pump_pools.iter_mut() {
+        if skip.contains(&pool.pool) {
+            continue;
+        }
+        let programs = [*program_id];
+        match fetch_pool_account(rpc_client, MarketPoolKind::Pump, &pool.pool, &programs) {
+            Ok(PoolFetch::Gone(reason)) => {
+                warn!("{}, removing", reason);
+                gone.push(pool.pool);
+            }
+            Ok(PoolFetch::Live(account, _)) => {
+                match recompute_pump_from_account(pool, &account.data) {
+                    Ok(true) => {
+                        if !suppress_logs {
+                            info!(
+                                "Pump pool {} mayhem mode changed to {}, fee wallet now {}",
+                                pool.pool, pool.is_mayhem_mode, pool.fee_wallet
+                            );
+                        }
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        warn!("Failed to parse Pump pool {}: {}", pool.pool, e);
+                    }
+                }
+            }
+            Err(e) => {
+                FETCH_ERRORS.log(
+                    &pool.pool.to_string(),
+                    format_args!("Failed to fetch Pump pool {}: {}", pool.pool, e),
+                );
+            }
+        }
+    }
+    Ok(gone)
+}
+
+/// Refresh the uncollected fees of Raydium CP pools so their net reserves stay current.
+/// Each pool is checked against the deployment it was initialized from.
+pub(crate) fn refresh_raydium_cp_pools(
+    pool_data: &mut MintPoolData,
+    rpc_client: &RpcClient,
+    suppress_logs: bool,
+    skip: &HashSet<Pubkey>,
+) -> Result<Vec<Pubkey>> {
+    let mut gone = Vec::new();
+    for pool in pool_data.raydium_cp_pools.iter_mut() {
+        if skip.contains(&pool.pool) {
+            continue;
+        }
+        let programs = [pool.program_id];
+        match fetch_pool_account(rpc_client, MarketPoolKind::RaydiumCp, &pool.pool, &programs) {
+            Ok(PoolFetch::Gone(reason)) => {
+                warn!("{}, removing", reason);
+                gone.push(pool.pool);
+            }
+            Ok(PoolFetch::Live(account, _)) => {
+                match recompute_raydium_cp_from_account(pool, &account.data) {
+                    Ok(()) => {
+                        if !suppress_logs {
+                            info!(
+                                "Raydium CP pool {} fees refreshed (token {}, base {})",
+                                pool.pool, pool.token_fees, pool.base_fees
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to parse Raydium CP pool {}: {}", pool.pool, e);
+                    }
+                }
+            }
+            Err(e) => {
+                FETCH_ERRORS.log(
+                    &pool.pool.to_string(),
+                    format_args!("Failed to fetch Raydium CP pool {}: {}", pool.pool, e),
+                );
+            }
+        }
+    }
+    Ok(gone)
+}
+
+/// Refresh Meteora DAMM v2 fee schedules and dynamic fees
+pub(crate) fn refresh_meteora_damm_v2_pools(
+    pool_data: &mut MintPoolData,
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    suppress_logs: bool,
+    skip: &HashSet<Pubkey>,
+) -> Result<Vec<Pubkey>> {
+    let mut gone = Vec::new();
+    for pool in pool_data.meteora_damm_v2_pools.iter_mut() {
+        if skip.contains(&pool.pool) {
+            continue;
+        }
+        let programs = [*program_id];
+        match fetch_pool_account(
+            rpc_client,
+            MarketPoolKind::MeteoraDammV2,
+            &pool.pool,
+            &programs,
+        ) {
+            Ok(PoolFetch::Gone(reason)) => {
+                warn!("{}, removing", reason);
+                gone.push(pool.pool);
+            }
+            Ok(PoolFetch::Live(account, _)) => {
+                match recompute_meteora_damm_v2_from_account(pool, &account.data) {
+                    Ok(()) => {
+                        if !suppress_logs {
+                            info!(
+                                "Meteora DAMM V2 pool {} fees refreshed (variable fee {})",
+                                pool.pool,
+                                pool.fees.variable_fee_numerator()
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to parse Meteora DAMM V2 pool {}: {}", pool.pool, e);
+                    }
+                }
+            }
+            Err(e) => {
+                FETCH_ERRORS.log(
+                    &pool.pool.to_string(),
+                    format_args!("Failed to fetch Meteora DAMM V2 pool {}: {}", pool.pool, e),
+                );
+            }
+        }
+    }
+    Ok(gone)
+}
+
+/// Re-check Vertigo vault balances and report pools that were drained since init
+pub(crate) fn refresh_vertigo_pools(
+    pool_data: &mut MintPoolData,
+    rpc_client: &RpcClient,
+    suppress_logs: bool,
+    skip: &HashSet<Pubkey>,
+) -> Result<Vec<Pubkey>> {
+    let mut gone = Vec::new();
+    for pool in pool_data.vertigo_pools.iter() {
+        if skip.contains(&pool.pool) {
+            continue;
+        }
+        let vaults = [
+            (pool.token_x_vault, pool.token_mint),
+            (pool.token_sol_vault, pool.base_mint),
+        ];
+        match vertigo_vault_problem(rpc_client, &vaults) {
+            Ok(None) => {
+                if !suppress_logs {
+                    info!("Vertigo pool {} vaults checked", pool.pool);
+                }
+            }
+            Ok(Some(problem)) => {
+                warn!("Vertigo pool {} {}, removing", pool.pool, problem);
+                gone.push(pool.pool);
+            }
+            Err(e) => {
+                FETCH_ERRORS.log(
+                    &pool.pool.to_string(),
+                    format_args!("Failed to fetch Vertigo vaults for pool {}: {}", pool.pool, e),
+                );
+            }
+        }
+    }
+    Ok(gone)
+}
+
+/// Pool data refresher that orchestrates all DEX-specific refreshers
+pub struct PoolDataRefresher {
+    pub(crate) program_ids: ProgramIds,
+}
+
+impl PoolDataRefresher {
+    pub fn new() -> Self {
+        Self {
+            program_ids: ProgramIds::new(),
+        }
+    }
+
+    /// Refresh Pump fee wallets, Raydium CP and DAMM v2 fees and all CLMM pool bin/tick
+    /// arrays based on current pool state, then drop pools whose account was closed or is no
+    /// longer owned by their DEX program and Vertigo pools whose vaults were drained. Pools in `skip` keep
+    /// their previous state. Each DEX is read through its `rpc.dex_urls` endpoint when one is
+    /// set, else `rpc_client`.
+    pub fn refresh_all_pools(
+        &self,
+        pool_data: &mut MintPoolData,
+        rpc_client: &RpcClient,
+        suppress_logs: bool,
+        skip: &HashSet<Pubkey>,
+    ) -> Result<()> {
+        let clients = DexRpcClients::global();
+        let mut gone = Vec::new();
+
+        // Refresh Pump pools (mayhem mode fee wallets)
+        if !pool_data.pump_pools.is_empty() {
+            gone.extend(refresh_pump_pools(
+                pool_data,
+                clients.client(MarketPoolKind::Pump, rpc_client),
+                &self.program_ids.pump,
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        // Refresh Raydium CP pools (uncollected fees)
+        if !pool_data.raydium_cp_pools.is_empty() {
+            gone.extend(refresh_raydium_cp_pools(
+                pool_data,
+                clients.client(MarketPoolKind::RaydiumCp, rpc_client),
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        // Refresh DLMM pools (Meteora)
+        if !pool_data.dlmm_pairs.is_empty() {
+            gone.extend(refresh_dlmm_pools(
+                pool_data,
+                clients.client(MarketPoolKind::MeteoraDlmm, rpc_client),
+                &self.program_ids.dlmm,
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        // Refresh Whirlpool pools (Orca)
+        if !pool_data.whirlpool_pools.is_empty() {
+            gone.extend(refresh_whirlpool_pools(
+                pool_data,
+                clients.client(MarketPoolKind::Whirlpool, rpc_client),
+                &self.program_ids.whirlpool,
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        // Refresh Raydium CLMM pools
+        if !pool_data.raydium_clmm_pools.is_empty() {
+            gone.extend(refresh_clmm_pools(
+                &mut pool_data.raydium_clmm_pools,
+                clients.client(MarketPoolKind::RaydiumClmm, rpc_client),
+                &self.program_ids,
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        // Refresh PancakeSwap pools
+        if !pool_data.pancakeswap_pools.is_empty() {
+            gone.extend(refresh_clmm_pools(
+                &mut pool_data.pancakeswap_pools,
+                clients.client(MarketPoolKind::PancakeSwap, rpc_client),
+                &self.program_ids,
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        // Refresh Byreal pools
+        if !pool_data.byreal_pools.is_empty() {
+            gone.extend(refresh_clmm_pools(
+                &mut pool_data.byreal_pools,
+                clients.client(MarketPoolKind::Byreal, rpc_client),
+                &self.program_ids,
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        // Refresh Meteora DAMM v2 pools (fee scheduler and dynamic fee)
+        if !pool_data.meteora_damm_v2_pools.is_empty() {
+            gone.extend(refresh_meteora_damm_v2_pools(
+                pool_data,
+                clients.client(MarketPoolKind::MeteoraDammV2, rpc_client),
+                &self.program_ids.damm_v2,
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        // Check that pools without refreshed state still exist and belong to their program
+        let owner_checks = [
+            (
+                MarketPoolKind::RaydiumV4,
+                pool_data
+                    .raydium_pools
+                    .iter()
+                    .map(|pool| (pool.pool, vec![pool.program_id]))
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                MarketPoolKind::MeteoraDamm,
+                pool_data
+                    .meteora_damm_pools
+                    .iter()
+                    .map(|pool| (pool.pool, vec![self.program_ids.damm]))
+                    .collect(),
+            ),
+            (
+                MarketPoolKind::Heaven,
+                pool_data
+                    .heaven_pools
+                    .iter()
+                    .map(|pool| (pool.pool, vec![self.program_ids.heaven]))
+                    .collect(),
+            ),
+            (
+                MarketPoolKind::Futarchy,
+                pool_data
+                    .futarchy_pools
+                    .iter()
+                    .map(|pool| (pool.dao, vec![self.program_ids.futarchy]))
+                    .collect(),
+            ),
+            (
+                MarketPoolKind::Humidifi,
+                pool_data
+                    .humidifi_pools
+                    .iter()
+                    .map(|pool| (pool.pool, vec![self.program_ids.humidifi]))
+                    .collect(),
+            ),
+        ];
+        for (kind, pools) in owner_checks {
+            gone.extend(refresh_pool_owners(
+                kind,
+                pools,
+                clients.client(kind, rpc_client),
+                suppress_logs,
+                skip,
+            ));
+        }
+
+        // Refresh Vertigo pools (drained vaults)
+        if !pool_data.vertigo_pools.is_empty() {
+            gone.extend(refresh_vertigo_pools(
+                pool_data,
+                clients.client(MarketPoolKind::Vertigo, rpc_client),
+                suppress_logs,
+                skip,
+            )?);
+        }
+
+        if !gone.is_empty() {
+            pool_data.retain_pools(|pool| !gone.contains(&pool.pool));
+            warn!(
+                "Removed {} closed or reassigned pools for mint {}, {} pools left",
+                gone.len(),
+                pool_data.mint,
+                pool_data.pool_count()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PoolDataRefresher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::sol_mint;
+
+    fn account_owned_by(owner: Pubkey) -> Account {
+        Account {
+            lamports: 1,
+            data: Vec::new(),
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn closed_and_reassigned_pool_accounts_are_gone() {
+        let program = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let kind = MarketPoolKind::MeteoraDlmm;
+
+        let PoolFetch::Gone(reason) = classify_pool_account(kind, &pool, None, 7, &[program])
+        else {
+            panic!("closed pool is live");
+        };
+        assert_eq!(reason, format!("Meteora DLMM pool {} was closed", pool));
+
+        let other = Pubkey::new_unique();
+        let account = Some(account_owned_by(other));
+        let PoolFetch::Gone(reason) = classify_pool_account(kind, &pool, account, 7, &[program])
+        else {
+            panic!("reassigned pool is live");
+        };
+        assert!(reason.contains(&format!("is owned by {}", other)));
+
+        let account = Some(account_owned_by(program));
+        let PoolFetch::Live(account, slot) =
+            classify_pool_account(kind, &pool, account, 7, &[program])
+        else {
+            panic!("live pool is gone");
+        };
+        assert_eq!((account.owner, slot), (program, 7));
+    }
+
+    #[test]
+    fn gone_pools_are_removed_by_retain_pools() {
+        let program = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut pool_data = MintPoolData::new(mint, &Pubkey::new_unique(), spl_token::ID);
+        for _ in 0..3 {
+            let pool = Pubkey::new_unique();
+            let vault = Pubkey::new_unique;
+            pool_data.add_raydium_pool(program, pool, vault(), vault(), mint, sol_mint(), 4);
+        }
+        let pools: Vec<(Pubkey, Vec<Pubkey>)> = pool_data
+            .raydium_pools
+            .iter()
+            .map(|pool| (pool.pool, vec![pool.program_id]))
+            .collect();
+        let accounts = vec![
+            None,
+            Some(account_owned_by(Pubkey::new_unique())),
+            Some(account_owned_by(program)),
+        ];
+
+        let gone = gone_pools(MarketPoolKind::RaydiumV4, &pools, accounts);
+        assert_eq!(gone, vec![pools[0].0, pools[1].0]);
+
+        pool_data.retain_pools(|pool| !gone.contains(&pool.pool));
+        assert_eq!(pool_data.pool_count(), 1);
+        assert_eq!(pool_data.raydium_pools[0].pool, pools[2].0);
+    }
+
+    /// Pool data with one DLMM, one Whirlpool and one Raydium CLMM pool, each with known
+    /// bin or tick arrays
+    fn pool_data_with_arrays() -> MintPoolData {
+        let key = Pubkey::new_unique;
+        let mint = key();
+        let sol = sol_mint();
+        let mut pool_data = MintPoolData::new(mint, &key(), spl_token::ID);
+        let bins = vec![key(), key(), key()];
+        pool_data.add_dlmm_pool(key(), key(), key(), key(), None, bins, 10, None, mint, sol);
+        let ticks = vec![key(), key(), key()];
+        pool_data.add_whirlpool_pool(key(), key(), key(), key(), ticks, None, mint, sol);
+        pool_data.add_raydium_clmm_pool(
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            vec![key(), key(), key()],
+            None,
+            mint,
+            sol,
+            0,
+            0,
+            0,
+        );
+        pool_data
+    }
+
+    /// Bin and tick arrays of every pool of `pool_data`
+    fn arrays(pool_data: &MintPoolData) -> Vec<Vec<Pubkey>> {
+        vec![
+            pool_data.dlmm_pairs[0].bin_arrays.clone(),
+            pool_data.whirlpool_pools[0].tick_arrays.clone(),
+            pool_data.raydium_clmm_pools[0].tick_arrays.clone(),
+        ]
+    }
+
+    #[test]
+    fn parse_failures_keep_the_previous_arrays() {
+        let mut pool_data = pool_data_with_arrays();
+        let before = arrays(&pool_data);
+        let program_ids = ProgramIds::new();
+        let garbage = [7u8; 64];
+
+        assert!(recompute_dlmm_from_account(&mut pool_data.dlmm_pairs[0], &garbage).is_err());
+        assert!(recompute_whirlpool_from_account(
+            &mut pool_data.whirlpool_pools[0],
+            &garbage,
+            &program_ids.whirlpool,
+        )
+        .is_err());
+        let clmm = &mut pool_data.raydium_clmm_pools[0];
+        let program_id = clmm.program_id;
+        assert!(recompute_clmm_from_account(clmm, &garbage, None, &program_id, None).is_err());
+
+        assert_eq!(arrays(&pool_data), before);
+    }
+
+    #[test]
+    fn fetch_failures_keep_the_previous_arrays() {
+        let mut pool_data = pool_data_with_arrays();
+        let before = arrays(&pool_data);
+        let program_ids = ProgramIds::new();
+        // Every request to the "fails" mock returns an unparsable response
+        let rpc_client = RpcClient::new_mock("fails".to_string());
+        let skip = HashSet::new();
+
+        let dlmm = refresh_dlmm_pools(&mut pool_data, &rpc_client, &program_ids.dlmm, true, &skip);
+        let whirlpool = refresh_whirlpool_pools(
+            &mut pool_data,
+            &rpc_client,
+            &program_ids.whirlpool,
+            true,
+            &skip,
+        );
+        let clmm = refresh_clmm_pools(
+            &mut pool_data.raydium_clmm_pools,
+            &rpc_client,
+            &program_ids,
+            true,
+            &skip,
+        );
+        // Failed fetches are not mistaken for closed pools either
+        for gone in [dlmm, whirlpool, clmm] {
+            assert!(gone.unwrap().is_empty());
+        }
+        assert_eq!(arrays(&pool_data), before);
+        assert_eq!(pool_data.pool_count(), 3);
+    }
+}
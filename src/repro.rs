@@ -0,0 +1,336 @@
+use crate::account_batcher::MAX_MULTIPLE_ACCOUNTS;
+use crate::base_mints::BaseMints;
+use crate::config::Config;
+use crate::constants::executor_program_id;
+use crate::ix_data::ArbIxData;
+use crate::loader::{initialize_discovered_pools, initialize_pools_from_markets, MarketPoolKind};
+use crate::paths::create_parent_dirs;
+use crate::pools::MintPoolData;
+use crate::snapshot::{
+    load_config, recording_client, render_instruction, replay_client, replay_wallet,
+    AccountSnapshot,
+};
+use crate::startup::StartupProgress;
+use crate::transaction::{build_swap_instruction_for, rotating_route_accounts};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::v0::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+const REPRO_VERSION: u32 = 1;
+
+/// Pool of a captured route
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReproPool {
+    pub address: String,
+    pub kind: MarketPoolKind,
+}
+
+/// Account of the captured executor instruction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReproAccount {
+    pub address: String,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+/// One mint's route with everything needed to rebuild it offline: the raw accounts its
+/// pools were loaded from, the blockhash and lookup tables, and the executor instruction
+/// as it was built
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RouteRepro {
+    pub version: u32,
+    pub mint: String,
+    /// Wallet the route was built for; its token accounts are derived from it
+    pub wallet: String,
+    pub blockhash: String,
+    pub pools: Vec<ReproPool>,
+    pub lookup_tables: Vec<String>,
+    /// Executor instruction data, base58
+    pub data: String,
+    pub accounts: Vec<ReproAccount>,
+    /// Accounts read while loading the pools, plus every account of the instruction, the
+    /// pools' AMM configs and the lookup tables, as returned by the RPC
+    pub snapshot: AccountSnapshot,
+}
+
+/// A route rebuilt from a `RouteRepro`
+#[derive(Debug)]
+pub struct RebuiltRoute {
+    pub pool_data: MintPoolData,
+    pub instruction: Instruction,
+    /// The instruction compiled with the captured blockhash and lookup tables
+    pub message: Message,
+}
+
+impl RouteRepro {
+    pub fn load(path: &str) -> Result<Self> {
+        let contents = fs::read(path).with_context(|| format!("Failed to read repro {}", path))?;
+        let repro: RouteRepro = serde_json::from_slice(&contents)
+            .with_context(|| format!("Failed to parse repro {}", path))?;
+        if repro.version != REPRO_VERSION {
+            return Err(anyhow!(
+                "Repro {} has version {}, expected {}",
+                path,
+                repro.version,
+                REPRO_VERSION
+            ));
+        }
+        Ok(repro)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        create_parent_dirs(Path::new(path))?;
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write repro {}", path))
+    }
+
+    /// The executor instruction as it was captured
+    pub fn instruction(&self) -> Result<Instruction> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                Ok(AccountMeta {
+                    pubkey: parse_pubkey(&account.address)?,
+                    is_signer: account.signer,
+                    is_writable: account.writable,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let data = bs58::decode(&self.data)
+            .into_vec()
+            .map_err(|e| anyhow!("Invalid instruction data: {}", e))?;
+        Ok(Instruction {
+            program_id: executor_program_id(),
+            accounts,
+            data,
+        })
+    }
+
+    /// Loads the pools from the captured accounts alone and builds the route again with
+    /// the captured pool flags. Needs the program IDs and canonical mints of `config`.
+    pub async fn rebuild(&self, config: &Config, base_mints: &BaseMints) -> Result<RebuiltRoute> {
+        let mint = parse_pubkey(&self.mint)?;
+        let wallet = parse_pubkey(&self.wallet)?;
+        let pools = self
+            .pools
+            .iter()
+            .map(|pool| Ok((parse_pubkey(&pool.address)?, pool.kind)))
+            .collect::<Result<Vec<_>>>()?;
+        let rpc_client = Arc::new(replay_client(self.snapshot.clone()));
+        let pool_data =
+            initialize_discovered_pools(mint, &wallet, &pools, base_mints, rpc_client.clone())
+                .await?;
+
+        let pool_flags = ArbIxData::from_bytes(&self.instruction()?.data)?.pool_flags;
+        let instruction = build_swap_instruction_for(&wallet, config, &pool_data, pool_flags)?;
+
+        let mut lookup_tables = Vec::new();
+        for address in &self.lookup_tables {
+            let key = parse_pubkey(address)?;
+            let account = rpc_client.get_account(&key)?;
+            let table = AddressLookupTable::deserialize(&account.data)
+                .map_err(|e| anyhow!("Invalid lookup table {}: {}", key, e))?;
+            lookup_tables.push(AddressLookupTableAccount {
+                key,
+                addresses: table.addresses.into_owned(),
+            });
+        }
+        let blockhash =
+            Hash::from_str(&self.blockhash).map_err(|e| anyhow!("Invalid blockhash: {}", e))?;
+        let message =
+            Message::try_compile(&wallet, &[instruction.clone()], &lookup_tables, blockhash)?;
+
+        Ok(RebuiltRoute {
+            pool_data,
+            instruction,
+            message,
+        })
+    }
+}
+
+fn parse_pubkey(address: &str) -> Result<Pubkey> {
+    Pubkey::from_str(address).map_err(|e| anyhow!("Invalid address {}: {}", address, e))
+}
+
+/// Captures `route`, the executor instruction built from `pool_data`, for offline replay.
+/// The mint's pools are loaded again through a recording transport so every account the
+/// loader reads is kept; the route's accounts, AMM configs and lookup tables are read
+/// with it. Pools of DEXes with their own `rpc.dex_urls` endpoint are read there and not
+/// recorded.
+pub async fn capture_route_repro(
+    pool_data: &MintPoolData,
+    route: &Instruction,
+    blockhash: Hash,
+    lookup_tables: &[Pubkey],
+    base_mints: &BaseMints,
+    rpc_client: &RpcClient,
+) -> Result<RouteRepro> {
+    let (recorder, snapshot) = recording_client(rpc_client.url());
+    let recorder = Arc::new(recorder);
+    let pools: Vec<(Pubkey, MarketPoolKind)> = pool_data
+        .pool_vaults()
+        .iter()
+        .map(|pool| (pool.pool, pool.kind))
+        .collect();
+    initialize_discovered_pools(
+        pool_data.mint,
+        &pool_data.wallet_account,
+        &pools,
+        base_mints,
+        recorder.clone(),
+    )
+    .await?;
+
+    // AMM configs are served from the shared cache while loading, so read them here
+    let mut addresses: Vec<Pubkey> = route.accounts.iter().map(|meta| meta.pubkey).collect();
+    addresses.extend(
+        pool_data
+            .amm_configs()
+            .into_iter()
+            .map(|(_, config, _)| config),
+    );
+    addresses.extend_from_slice(lookup_tables);
+    addresses.sort();
+    addresses.dedup();
+    for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        recorder.get_multiple_accounts(chunk)?;
+    }
+
+    let snapshot = snapshot.lock().unwrap().clone();
+    Ok(RouteRepro {
+        version: REPRO_VERSION,
+        mint: pool_data.mint.to_string(),
+        wallet: pool_data.wallet_account.to_string(),
+        blockhash: blockhash.to_string(),
+        pools: pools
+            .iter()
+            .map(|(address, kind)| ReproPool {
+                address: address.to_string(),
+                kind: *kind,
+            })
+            .collect(),
+        lookup_tables: lookup_tables.iter().map(|key| key.to_string()).collect(),
+        data: bs58::encode(&route.data).into_string(),
+        accounts: route
+            .accounts
+            .iter()
+            .map(|meta| ReproAccount {
+                address: meta.pubkey.to_string(),
+                writable: meta.is_writable,
+                signer: meta.is_signer,
+            })
+            .collect(),
+        snapshot,
+    })
+}
+
+/// Builds the route of `mint` from the config against `rpc.url` and writes it with its
+/// accounts to `output`. The route is built for the fixed replay wallet without pool flags.
+pub async fn capture_repro(config_path: &str, mint: &str, output: &str) -> Result<()> {
+    let mut config = load_config(config_path)?;
+    let mint = parse_pubkey(mint)?;
+    config.routing.markets.allowlist = Some(vec![mint.to_string()]);
+    let rpc_client = Arc::new(RpcClient::new(config.rpc.url.clone()));
+    let base_mints = BaseMints::from_config(&config.routing)?;
+    let wallet = replay_wallet().pubkey();
+    let mut pools = initialize_pools_from_markets(
+        &config.routing.markets,
+        &base_mints,
+        &wallet,
+        rpc_client.clone(),
+        &StartupProgress::new(None),
+    )
+    .await?;
+    let pool_data = pools
+        .remove(&mint)
+        .ok_or_else(|| anyhow!("No pools found for mint {}", mint))?;
+
+    let route = build_swap_instruction_for(&wallet, &config, &pool_data, None)?;
+    let blockhash = rpc_client.get_latest_blockhash()?;
+    let lookup_tables = config
+        .routing
+        .markets
+        .lookup_table_accounts
+        .iter()
+        .flatten()
+        .map(|address| parse_pubkey(address))
+        .collect::<Result<Vec<_>>>()?;
+    let repro = capture_route_repro(
+        &pool_data,
+        &route,
+        blockhash,
+        &lookup_tables,
+        &base_mints,
+        &rpc_client,
+    )
+    .await?;
+    repro.save(output)?;
+    info!(
+        "Captured the route of mint {} with {} accounts at slot {} to {}",
+        mint,
+        repro.snapshot.accounts.len(),
+        repro.snapshot.slot,
+        output
+    );
+    Ok(())
+}
+
+/// Rebuilds the route of the repro at `repro_path` without network access and compares it
+/// with the captured instruction, rotating accounts masked
+pub async fn replay_repro(config_path: &str, repro_path: &str) -> Result<()> {
+    let config = load_config(config_path)?;
+    let repro = RouteRepro::load(repro_path)?;
+    let base_mints = BaseMints::from_config(&config.routing)?;
+    let rebuilt = repro.rebuild(&config, &base_mints).await?;
+    info!(
+        "Rebuilt the route of mint {} from {} accounts captured at slot {}: {} accounts, \
+         {} through lookup tables",
+        repro.mint,
+        repro.snapshot.accounts.len(),
+        repro.snapshot.slot,
+        rebuilt.instruction.accounts.len(),
+        rebuilt
+            .message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum::<usize>()
+    );
+
+    let rotating = rotating_route_accounts(&rebuilt.pool_data);
+    let expected = render_instruction(&repro.instruction()?, &rotating);
+    let actual = render_instruction(&rebuilt.instruction, &rotating);
+    if expected == actual {
+        info!("Rebuilt route matches the captured one");
+        return Ok(());
+    }
+    for (line, (expected, actual)) in expected.lines().zip(actual.lines()).enumerate() {
+        if expected != actual {
+            warn!(
+                "First difference at line {}: captured `{}`, rebuilt `{}`",
+                line + 1,
+                expected,
+                actual
+            );
+            break;
+        }
+    }
+    Err(anyhow!(
+        "Rebuilt route differs from the captured one ({} lines captured, {} rebuilt)",
+        expected.lines().count(),
+        actual.lines().count()
+    ))
+}
@@ -0,0 +1,207 @@
+use crate::config::RiskConfig;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{error, info};
+
+const LOSS_WINDOW_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    /// (unix timestamp, realized pnl in lamports)
+    records: VecDeque<(i64, i64)>,
+    tripped_at: Option<Instant>,
+}
+
+/// Pauses sending once realized losses over the last 24h exceed the configured limit
+#[derive(Debug)]
+pub struct DailyLossBreaker {
+    max_daily_loss: u64,
+    resume_after: Option<Duration>,
+    state: Mutex<BreakerState>,
+}
+
+impl DailyLossBreaker {
+    pub fn new(max_daily_loss: u64, resume_after: Option<Duration>) -> Self {
+        Self {
+            max_daily_loss,
+            resume_after,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Returns None when no daily loss limit is configured
+    pub fn from_config(config: &RiskConfig) -> Option<Self> {
+        config.max_daily_loss_lamports.map(|max_daily_loss| {
+            Self::new(
+                max_daily_loss,
+                config.resume_after_secs.map(Duration::from_secs),
+            )
+        })
+    }
+
+    /// Records a realized result (fees and tips are negative) at the given unix timestamp
+    pub fn record(&self, timestamp: i64, pnl_lamports: i64) {
+        let mut state = self.state.lock().unwrap();
+        state.records.push_back((timestamp, pnl_lamports));
+        prune(&mut state.records, unix_now());
+
+        let loss = realized_loss(&state.records);
+        if loss > self.max_daily_loss && state.tripped_at.is_none() {
+            state.tripped_at = Some(Instant::now());
+            error!(
+                "Daily loss circuit breaker tripped: realized loss {} lamports exceeds limit {}; sending paused",
+                loss, self.max_daily_loss
+            );
+        }
+    }
+
+    pub fn max_daily_loss(&self) -> u64 {
+        self.max_daily_loss
+    }
+
+    /// Realized loss over the window in lamports (0 when net positive)
+    pub fn realized_loss(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        prune(&mut state.records, unix_now());
+        realized_loss(&state.records)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(tripped_at) = state.tripped_at else {
+            return false;
+        };
+        match self.resume_after {
+            Some(resume_after) if tripped_at.elapsed() >= resume_after => {
+                state.tripped_at = None;
+                info!("Daily loss circuit breaker cooldown elapsed; sending resumed");
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Clears a tripped breaker, returning whether it was tripped. The loss records are kept,
+    /// so the next losing trade trips it again while the window is still over the limit.
+    pub fn resume(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let tripped = state.tripped_at.take().is_some();
+        if tripped {
+            info!("Daily loss circuit breaker manually resumed");
+        }
+        tripped
+    }
+}
+
+fn prune(records: &mut VecDeque<(i64, i64)>, now: i64) {
+    records.retain(|(timestamp, _)| now - timestamp < LOSS_WINDOW_SECS);
+}
+
+fn realized_loss(records: &VecDeque<(i64, i64)>) -> u64 {
+    let net: i64 = records.iter().map(|(_, pnl)| pnl).sum();
+    if net < 0 {
+        net.unsigned_abs()
+    } else {
+        0
+    }
+}
+
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: u64 = 1_000_000;
+
+    fn breaker() -> DailyLossBreaker {
+        DailyLossBreaker::new(LIMIT, None)
+    }
+
+    #[test]
+    fn trips_only_once_losses_exceed_the_limit() {
+        let breaker = breaker();
+        let now = unix_now();
+        breaker.record(now - 60, -600_000);
+        breaker.record(now - 30, -400_000);
+        // Exactly at the limit
+        assert_eq!(breaker.realized_loss(), LIMIT);
+        assert!(!breaker.is_paused());
+
+        breaker.record(now, -1);
+        assert_eq!(breaker.realized_loss(), LIMIT + 1);
+        assert!(breaker.is_paused());
+    }
+
+    #[test]
+    fn profits_offset_losses() {
+        let breaker = breaker();
+        let now = unix_now();
+        breaker.record(now - 60, -1_500_000);
+        breaker.record(now - 30, 1_200_000);
+        assert_eq!(breaker.realized_loss(), 300_000);
+        breaker.record(now, 500_000);
+        assert_eq!(breaker.realized_loss(), 0);
+        assert!(!breaker.is_paused());
+    }
+
+    #[test]
+    fn losses_older_than_the_window_are_pruned() {
+        let breaker = breaker();
+        let now = unix_now();
+        breaker.record(now - LOSS_WINDOW_SECS - 1, -5_000_000);
+        breaker.record(now - LOSS_WINDOW_SECS + 3_600, -700_000);
+        assert_eq!(breaker.realized_loss(), 700_000);
+        assert!(!breaker.is_paused());
+    }
+
+    #[test]
+    fn resume_clears_a_tripped_breaker() {
+        let breaker = breaker();
+        assert!(!breaker.resume());
+
+        let now = unix_now();
+        breaker.record(now - 10, -2_000_000);
+        assert!(breaker.is_paused());
+        assert!(breaker.resume());
+        assert!(!breaker.is_paused());
+        assert!(!breaker.resume());
+
+        // The losses are still in the window, so the next losing trade trips it again
+        breaker.record(now, -1);
+        assert!(breaker.is_paused());
+    }
+
+    #[test]
+    fn resume_after_elapses_automatically() {
+        let breaker = DailyLossBreaker::new(LIMIT, Some(Duration::ZERO));
+        breaker.record(unix_now(), -2_000_000);
+        assert!(!breaker.is_paused());
+
+        let breaker = DailyLossBreaker::new(LIMIT, Some(Duration::from_secs(3_600)));
+        breaker.record(unix_now(), -2_000_000);
+        assert!(breaker.is_paused());
+    }
+
+    #[test]
+    fn from_config_requires_a_limit() {
+        let mut config = RiskConfig {
+            max_daily_loss_lamports: None,
+            resume_after_secs: Some(60),
+            reconcile_signatures: None,
+            reconcile_lookback_secs: None,
+        };
+        assert!(DailyLossBreaker::from_config(&config).is_none());
+
+        config.max_daily_loss_lamports = Some(LIMIT);
+        let breaker = DailyLossBreaker::from_config(&config).unwrap();
+        assert_eq!(breaker.max_daily_loss(), LIMIT);
+        assert_eq!(breaker.resume_after, Some(Duration::from_secs(60)));
+    }
+}
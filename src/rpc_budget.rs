@@ -0,0 +1,162 @@
+use crate::config::RpcConfig;
+use crate::loader::MarketPoolKind;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// RPC calls a pool refresh makes, by pool kind. Kinds without a refresher cost nothing.
+pub fn refresh_cost(kind: MarketPoolKind) -> u64 {
+    match kind {
+        // pool account plus tick array bitmap extension
+        MarketPoolKind::RaydiumClmm | MarketPoolKind::PancakeSwap | MarketPoolKind::Byreal => 2,
+        MarketPoolKind::Pump
+        | MarketPoolKind::MeteoraDlmm
+        | MarketPoolKind::Whirlpool
+        | MarketPoolKind::Vertigo => 1,
+        _ => 0,
+    }
+}
+
+#[derive(Debug)]
+struct Window {
+    started: Instant,
+    calls: u64,
+    warned: bool,
+}
+
+/// Caps the RPC calls made per mint iteration and per minute across all workers.
+/// Calls over budget are not refused; lower-priority pool refreshes are deferred instead.
+#[derive(Debug)]
+pub struct RpcBudget {
+    per_iteration: Option<u64>,
+    per_minute: Option<u64>,
+    window: Mutex<Window>,
+    total_calls: AtomicU64,
+    deferred_refreshes: AtomicU64,
+}
+
+impl RpcBudget {
+    /// None when neither `rpc.max_calls_per_iteration` nor `rpc.max_calls_per_minute` is set
+    pub fn from_config(config: &RpcConfig) -> Option<Self> {
+        if config.max_calls_per_iteration.is_none() && config.max_calls_per_minute.is_none() {
+            return None;
+        }
+        Some(Self {
+            per_iteration: config.max_calls_per_iteration,
+            per_minute: config.max_calls_per_minute,
+            window: Mutex::new(Window {
+                started: Instant::now(),
+                calls: 0,
+                warned: false,
+            }),
+            total_calls: AtomicU64::new(0),
+            deferred_refreshes: AtomicU64::new(0),
+        })
+    }
+
+    pub fn start_iteration(self: &Arc<Self>) -> IterationBudget {
+        IterationBudget {
+            budget: self.clone(),
+            calls: 0,
+        }
+    }
+
+    /// Calls made in the current one-minute window
+    pub fn calls_this_minute(&self) -> u64 {
+        let mut window = self.window.lock().unwrap();
+        Self::roll(&mut window);
+        window.calls
+    }
+
+    fn roll(window: &mut Window) {
+        if window.started.elapsed() >= WINDOW {
+            window.started = Instant::now();
+            window.calls = 0;
+            window.warned = false;
+        }
+    }
+
+    /// Adds `calls` to the minute window when they fit, or unconditionally when `force`
+    fn charge(&self, calls: u64, force: bool) -> bool {
+        let mut window = self.window.lock().unwrap();
+        Self::roll(&mut window);
+        let fits = self
+            .per_minute
+            .map_or(true, |limit| window.calls + calls <= limit);
+        if !fits && !window.warned {
+            warn!(
+                "RPC budget of {} calls per minute reached, deferring pool refreshes",
+                self.per_minute.unwrap_or_default()
+            );
+            window.warned = true;
+        }
+        if fits || force {
+            window.calls += calls;
+            self.total_calls.fetch_add(calls, Ordering::Relaxed);
+        }
+        fits
+    }
+
+    /// Prometheus text exposition of the budget counters
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE rpc_budget_calls_total counter");
+        let total_calls = self.total_calls.load(Ordering::Relaxed);
+        let _ = writeln!(out, "rpc_budget_calls_total {}", total_calls);
+        let _ = writeln!(out, "# TYPE rpc_budget_calls_current_minute gauge");
+        let _ = writeln!(out, "rpc_budget_calls_current_minute {}", self.calls_this_minute());
+        if let Some(limit) = self.per_minute {
+            let _ = writeln!(out, "# TYPE rpc_budget_calls_per_minute_limit gauge");
+            let _ = writeln!(out, "rpc_budget_calls_per_minute_limit {}", limit);
+        }
+        let _ = writeln!(out, "# TYPE rpc_budget_deferred_refreshes_total counter");
+        let _ = writeln!(
+            out,
+            "rpc_budget_deferred_refreshes_total {}",
+            self.deferred_refreshes.load(Ordering::Relaxed)
+        );
+        out
+    }
+}
+
+/// Calls made by one mint iteration
+pub struct IterationBudget {
+    budget: Arc<RpcBudget>,
+    calls: u64,
+}
+
+impl IterationBudget {
+    /// Spends `calls` if both the iteration and the minute budget allow it
+    pub fn try_spend(&mut self, calls: u64) -> bool {
+        if let Some(limit) = self.budget.per_iteration {
+            if self.calls + calls > limit {
+                return false;
+            }
+        }
+        if !self.budget.charge(calls, false) {
+            return false;
+        }
+        self.calls += calls;
+        true
+    }
+
+    /// Counts calls that are made regardless of the budget (pricing, sends)
+    pub fn record(&mut self, calls: u64) {
+        self.budget.charge(calls, true);
+        self.calls += calls;
+    }
+
+    pub fn record_deferred(&self, pools: usize) {
+        self.budget
+            .deferred_refreshes
+            .fetch_add(pools as u64, Ordering::Relaxed);
+    }
+
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+}
@@ -0,0 +1,249 @@
+use crate::config::SendingEndpoint;
+use crate::transaction::endpoint_label;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::http_sender::HttpSender;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const DEFAULT_LOG_INTERVAL: Duration = Duration::from_secs(60);
+/// Same as the default of `HttpSender::new`
+const DEFAULT_SEND_TIMEOUT: Duration = Duration::from_secs(30);
+/// Upper bounds of the latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Call count and latency histogram of a single RPC method
+#[derive(Debug, Clone, Default)]
+pub struct MethodStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub total_latency: Duration,
+    /// Calls per bucket of `LATENCY_BUCKETS`, plus one overflow bucket
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+}
+
+impl MethodStats {
+    fn record(&mut self, latency: Duration, failed: bool) {
+        self.calls += 1;
+        if failed {
+            self.errors += 1;
+        }
+        self.total_latency += latency;
+        let secs = latency.as_secs_f64();
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket] += 1;
+    }
+}
+
+/// Per-method RPC counters shared by every instrumented client
+#[derive(Debug, Clone)]
+pub struct RpcMetrics {
+    methods: Arc<Mutex<BTreeMap<String, MethodStats>>>,
+    log_interval: Duration,
+}
+
+impl RpcMetrics {
+    pub fn new(log_interval_secs: Option<u64>) -> Self {
+        Self {
+            methods: Arc::default(),
+            log_interval: log_interval_secs
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_LOG_INTERVAL),
+        }
+    }
+
+    fn record(&self, method: String, latency: Duration, failed: bool) {
+        let mut methods = self.methods.lock().unwrap();
+        methods.entry(method).or_default().record(latency, failed);
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, MethodStats> {
+        self.methods.lock().unwrap().clone()
+    }
+
+    /// Blocking RPC client whose requests are counted and timed
+    pub fn client(&self, url: String) -> RpcClient {
+        self.client_with_sender(HttpSender::new(url))
+    }
+
+    fn client_with_sender(&self, sender: HttpSender) -> RpcClient {
+        RpcClient::new_sender(
+            MeteredSender {
+                inner: sender,
+                metrics: self.clone(),
+            },
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+        )
+    }
+
+    /// Prometheus text exposition of every method's counters and latency histogram
+    pub fn render_prometheus(&self) -> String {
+        let methods = self.snapshot();
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE rpc_requests_total counter");
+        for (method, stats) in &methods {
+            let _ = writeln!(out, "rpc_requests_total{{method=\"{}\"}} {}", method, stats.calls);
+        }
+        let _ = writeln!(out, "# TYPE rpc_request_errors_total counter");
+        for (method, stats) in &methods {
+            let _ = writeln!(
+                out,
+                "rpc_request_errors_total{{method=\"{}\"}} {}",
+                method, stats.errors
+            );
+        }
+        let _ = writeln!(out, "# TYPE rpc_request_duration_seconds histogram");
+        for (method, stats) in &methods {
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(stats.buckets.iter()) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "rpc_request_duration_seconds_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    method, bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "rpc_request_duration_seconds_bucket{{method=\"{}\",le=\"+Inf\"}} {}",
+                method, stats.calls
+            );
+            let _ = writeln!(
+                out,
+                "rpc_request_duration_seconds_sum{{method=\"{}\"}} {}",
+                method,
+                stats.total_latency.as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "rpc_request_duration_seconds_count{{method=\"{}\"}} {}",
+                method, stats.calls
+            );
+        }
+        out
+    }
+
+    /// Logs calls per minute and average latency of each method every `log_interval_secs`
+    pub fn spawn_reporter(&self) {
+        let metrics = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(metrics.log_interval);
+            interval.tick().await;
+            let mut previous = metrics.snapshot();
+            loop {
+                interval.tick().await;
+                let current = metrics.snapshot();
+                let minutes = metrics.log_interval.as_secs_f64() / 60.0;
+                let mut line = String::new();
+                for (method, stats) in &current {
+                    let last = previous.get(method).cloned().unwrap_or_default();
+                    let calls = stats.calls - last.calls;
+                    if calls == 0 {
+                        continue;
+                    }
+                    let latency = (stats.total_latency - last.total_latency) / calls as u32;
+                    let _ = write!(
+                        line,
+                        " {}: {:.0}/min avg {}ms,",
+                        method,
+                        calls as f64 / minutes,
+                        latency.as_millis()
+                    );
+                }
+                if !line.is_empty() {
+                    info!("RPC usage:{}", line.trim_end_matches(','));
+                }
+                previous = current;
+            }
+        });
+    }
+}
+
+/// HTTP transport that records every request in `RpcMetrics`
+struct MeteredSender {
+    inner: HttpSender,
+    metrics: RpcMetrics,
+}
+
+#[async_trait]
+impl RpcSender for MeteredSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let started = Instant::now();
+        let result = self.inner.send(request, params).await;
+        self.metrics.record(request.to_string(), started.elapsed(), result.is_err());
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// Plain RPC client, or an instrumented one when metrics are enabled
+pub fn new_rpc_client(url: String, metrics: Option<&RpcMetrics>) -> RpcClient {
+    match metrics {
+        Some(metrics) => metrics.client(url),
+        None => RpcClient::new(url),
+    }
+}
+
+/// Client for a `spam.sending_rpc_urls` entry, with the entry's extra header and timeout
+pub fn new_sending_rpc_client(
+    endpoint: &SendingEndpoint,
+    metrics: Option<&RpcMetrics>,
+) -> Result<RpcClient> {
+    let sender = sending_http_sender(endpoint)?;
+    Ok(match metrics {
+        Some(metrics) => metrics.client_with_sender(sender),
+        None => RpcClient::new_sender(
+            sender,
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+        ),
+    })
+}
+
+fn sending_http_sender(endpoint: &SendingEndpoint) -> Result<HttpSender> {
+    // Only the host goes into errors, the path or header may carry an API key
+    let label = endpoint_label(endpoint.url());
+    let mut headers = HeaderMap::new();
+    if let Some(header) = endpoint.api_key_header() {
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("api_key_header of {} must be \"name: value\"", label))?;
+        let value = match value.trim().strip_prefix('$') {
+            Some(var) => env::var(var)
+                .with_context(|| format!("reading `{}` from env for {}", var, label))?,
+            None => value.trim().to_string(),
+        };
+        headers.insert(
+            HeaderName::from_bytes(name.trim().as_bytes())
+                .with_context(|| format!("Invalid api_key_header name for {}", label))?,
+            HeaderValue::from_str(&value)
+                .with_context(|| format!("Invalid api_key_header value for {}", label))?,
+        );
+    }
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(endpoint.timeout().unwrap_or(DEFAULT_SEND_TIMEOUT))
+        .build()
+        .with_context(|| format!("Failed to build HTTP client for {}", label))?;
+    Ok(HttpSender::new_with_client(endpoint.url(), client))
+}
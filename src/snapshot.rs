@@ -0,0 +1,328 @@
+use crate::account_batcher::AccountBatcher;
+use crate::base_mints::BaseMints;
+use crate::config::Config;
+use crate::constants::init_canonical_mints;
+use crate::paths::create_parent_dirs;
+use crate::pools::MintPoolData;
+use crate::program_registry::ProgramRegistry;
+use crate::loader::initialize_pools_from_markets;
+use crate::startup::StartupProgress;
+use crate::transaction::{build_swap_instruction, rotating_route_accounts};
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_client::http_sender::HttpSender;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{keypair_from_seed, Keypair};
+use solana_sdk::signer::Signer;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+const SNAPSHOT_VERSION: u32 = 1;
+/// Stands in for accounts a route picks at random in the golden file
+const ROTATING: &str = "rotating";
+
+/// Every account read while initializing the pools of a config, as returned by the RPC
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountSnapshot {
+    pub version: u32,
+    /// Highest context slot seen while recording
+    pub slot: u64,
+    /// Account JSON by address, null for accounts that did not exist
+    pub accounts: BTreeMap<String, Value>,
+}
+
+impl AccountSnapshot {
+    pub fn load(path: &str) -> Result<Self> {
+        let contents =
+            fs::read(path).with_context(|| format!("Failed to read snapshot {}", path))?;
+        let snapshot: AccountSnapshot = serde_json::from_slice(&contents)
+            .with_context(|| format!("Failed to parse snapshot {}", path))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(anyhow!(
+                "Snapshot {} has version {}, expected {}",
+                path,
+                snapshot.version,
+                SNAPSHOT_VERSION
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    fn save(&self, path: &str) -> Result<()> {
+        create_parent_dirs(Path::new(path))?;
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write snapshot {}", path))
+    }
+
+    fn record(&mut self, request: RpcRequest, params: &Value, result: &Value) {
+        let slot = result["context"]["slot"].as_u64().unwrap_or_default();
+        self.slot = self.slot.max(slot);
+        match request {
+            RpcRequest::GetAccountInfo => {
+                if let Some(address) = params[0].as_str() {
+                    self.accounts.insert(address.to_string(), result["value"].clone());
+                }
+            }
+            RpcRequest::GetMultipleAccounts => {
+                let addresses = params[0].as_array().into_iter().flatten();
+                let values = result["value"].as_array().into_iter().flatten();
+                for (address, value) in addresses.zip(values) {
+                    if let Some(address) = address.as_str() {
+                        self.accounts.insert(address.to_string(), value.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn account(&self, address: &Value) -> ClientResult<Value> {
+        let address = address.as_str().unwrap_or_default();
+        self.accounts
+            .get(address)
+            .cloned()
+            .ok_or_else(|| custom_error(format!("account {} is not in the snapshot", address)))
+    }
+
+    fn reply(&self, request: RpcRequest, params: &Value) -> ClientResult<Value> {
+        let context = json!({ "slot": self.slot });
+        match request {
+            RpcRequest::GetAccountInfo => Ok(json!({
+                "context": context,
+                "value": self.account(&params[0])?,
+            })),
+            RpcRequest::GetMultipleAccounts => {
+                let values = params[0]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|address| self.account(address))
+                    .collect::<ClientResult<Vec<Value>>>()?;
+                Ok(json!({ "context": context, "value": values }))
+            }
+            RpcRequest::GetSlot => Ok(json!(self.slot)),
+            other => Err(custom_error(format!("{} is not served from snapshots", other))),
+        }
+    }
+}
+
+fn custom_error(message: String) -> ClientError {
+    ClientErrorKind::Custom(message).into()
+}
+
+/// HTTP transport that keeps a copy of every account it reads
+struct RecordingSender {
+    inner: HttpSender,
+    snapshot: Arc<Mutex<AccountSnapshot>>,
+}
+
+#[async_trait]
+impl RpcSender for RecordingSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let result = self.inner.send(request, params.clone()).await?;
+        self.snapshot.lock().unwrap().record(request, &params, &result);
+        Ok(result)
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// Transport answering account reads from a snapshot, without network access
+struct ReplaySender {
+    snapshot: AccountSnapshot,
+}
+
+#[async_trait]
+impl RpcSender for ReplaySender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        self.snapshot.reply(request, &params)
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "snapshot".to_string()
+    }
+}
+
+/// Client reading through `url` that keeps a copy of every account it reads
+pub(crate) fn recording_client(url: String) -> (RpcClient, Arc<Mutex<AccountSnapshot>>) {
+    let snapshot = Arc::new(Mutex::new(AccountSnapshot {
+        version: SNAPSHOT_VERSION,
+        ..Default::default()
+    }));
+    let sender = RecordingSender {
+        inner: HttpSender::new(url),
+        snapshot: snapshot.clone(),
+    };
+    (RpcClient::new_sender(sender, client_config()), snapshot)
+}
+
+/// Client answering account reads from `snapshot`, without network access
+pub(crate) fn replay_client(snapshot: AccountSnapshot) -> RpcClient {
+    RpcClient::new_sender(ReplaySender { snapshot }, client_config())
+}
+
+/// Fixed wallet of recording and replay, so derived wallet accounts match across runs
+pub(crate) fn replay_wallet() -> Keypair {
+    keypair_from_seed(&[7; 32]).unwrap()
+}
+
+fn client_config() -> RpcClientConfig {
+    RpcClientConfig::with_commitment(CommitmentConfig::default())
+}
+
+/// Config prepared for recording and replay: the pool kind cache is neither read nor
+/// written, so every market goes through detection
+pub(crate) fn load_config(config_path: &str) -> Result<Config> {
+    let mut config = Config::load(config_path)?;
+    init_canonical_mints(config.mints.as_ref())?;
+    ProgramRegistry::init(config.program_overrides.as_ref())?;
+    config.routing.markets.pool_kind_cache_path = None;
+    AccountBatcher::init(&config.rpc);
+    Ok(config)
+}
+
+async fn initialize_pools(
+    config: &Config,
+    rpc_client: Arc<RpcClient>,
+) -> Result<BTreeMap<Pubkey, MintPoolData>> {
+    let base_mints = BaseMints::from_config(&config.routing)?;
+    let pools = initialize_pools_from_markets(
+        &config.routing.markets,
+        &base_mints,
+        &replay_wallet().pubkey(),
+        rpc_client,
+        &StartupProgress::new(None),
+    )
+    .await?;
+    Ok(pools.into_iter().collect())
+}
+
+/// Initializes the pools of the config against `rpc.url` and writes every account read
+/// to `output`
+pub async fn record_snapshot(config_path: &str, output: &str) -> Result<()> {
+    let config = load_config(config_path)?;
+    let (rpc_client, snapshot) = recording_client(config.rpc.url.clone());
+    let pools = initialize_pools(&config, Arc::new(rpc_client)).await?;
+
+    let snapshot = snapshot.lock().unwrap();
+    snapshot.save(output)?;
+    info!(
+        "Recorded {} accounts for {} mints at slot {} to {}",
+        snapshot.accounts.len(),
+        pools.len(),
+        snapshot.slot,
+        output
+    );
+    Ok(())
+}
+
+/// The swap instruction of every mint, rendered for comparison: instruction data in hex
+/// and one `address writable signer` line per account, with rotating accounts masked
+fn render_routes(config: &Config, pools: &BTreeMap<Pubkey, MintPoolData>) -> Result<String> {
+    let wallet = replay_wallet();
+    let mut out = String::new();
+    for (mint, pool_data) in pools {
+        let instruction = build_swap_instruction(&wallet, config, pool_data, None)?;
+        let _ = writeln!(out, "mint {}", mint);
+        out.push_str(&render_instruction(&instruction, &rotating_route_accounts(pool_data)));
+        let _ = writeln!(out);
+    }
+    Ok(out)
+}
+
+/// Instruction data in hex and one `address writable signer` line per account, with the
+/// `rotating` accounts masked
+pub(crate) fn render_instruction(instruction: &Instruction, rotating: &HashSet<Pubkey>) -> String {
+    let mut out = String::new();
+    let data: String = instruction.data.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let _ = writeln!(out, "data {}", data);
+    for meta in &instruction.accounts {
+        let address = if rotating.contains(&meta.pubkey) {
+            ROTATING.to_string()
+        } else {
+            meta.pubkey.to_string()
+        };
+        let _ = writeln!(
+            out,
+            "{} {} {}",
+            address,
+            if meta.is_writable { "w" } else { "r" },
+            if meta.is_signer { "s" } else { "-" }
+        );
+    }
+    out
+}
+
+/// Initializes the pools of the config from `snapshot_path` and builds every mint's swap
+/// instruction. The result is compared with `golden_path`, or written there when the file
+/// does not exist yet or `update` is set.
+pub async fn replay_snapshot(
+    config_path: &str,
+    snapshot_path: &str,
+    golden_path: &str,
+    update: bool,
+) -> Result<()> {
+    let config = load_config(config_path)?;
+    let snapshot = AccountSnapshot::load(snapshot_path)?;
+    info!(
+        "Replaying {} accounts recorded at slot {}",
+        snapshot.accounts.len(),
+        snapshot.slot
+    );
+    let pools = initialize_pools(&config, Arc::new(replay_client(snapshot))).await?;
+    let rendered = render_routes(&config, &pools)?;
+
+    if update || !Path::new(golden_path).exists() {
+        create_parent_dirs(Path::new(golden_path))?;
+        fs::write(golden_path, &rendered)
+            .with_context(|| format!("Failed to write golden file {}", golden_path))?;
+        info!("Wrote routes of {} mints to {}", pools.len(), golden_path);
+        return Ok(());
+    }
+
+    let golden = fs::read_to_string(golden_path)
+        .with_context(|| format!("Failed to read golden file {}", golden_path))?;
+    if golden == rendered {
+        info!("Routes of {} mints match {}", pools.len(), golden_path);
+        return Ok(());
+    }
+    for (line, (expected, actual)) in golden.lines().zip(rendered.lines()).enumerate() {
+        if expected != actual {
+            warn!(
+                "First difference at line {}: expected `{}`, got `{}`",
+                line + 1,
+                expected,
+                actual
+            );
+            break;
+        }
+    }
+    Err(anyhow!(
+        "Routes differ from {} ({} lines expected, {} produced)",
+        golden_path,
+        golden.lines().count(),
+        rendered.lines().count()
+    ))
+}
@@ -0,0 +1,95 @@
+use crate::config::{StartupConfig, StartupTimeoutAction};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Counters of pool initialization, logged periodically by `spawn_reporter`
+#[derive(Debug)]
+pub struct StartupProgress {
+    started: Instant,
+    deadline: Option<Instant>,
+    on_timeout: StartupTimeoutAction,
+    markets_total: AtomicUsize,
+    markets_fetched: AtomicUsize,
+    mints_total: AtomicUsize,
+    mints_initialized: AtomicUsize,
+    done: AtomicBool,
+}
+
+impl StartupProgress {
+    pub fn new(config: Option<&StartupConfig>) -> Arc<Self> {
+        let started = Instant::now();
+        Arc::new(Self {
+            started,
+            deadline: config
+                .and_then(|config| config.timeout_secs)
+                .map(|secs| started + Duration::from_secs(secs)),
+            on_timeout: config.map(|config| config.on_timeout).unwrap_or_default(),
+            markets_total: AtomicUsize::new(0),
+            markets_fetched: AtomicUsize::new(0),
+            mints_total: AtomicUsize::new(0),
+            mints_initialized: AtomicUsize::new(0),
+            done: AtomicBool::new(false),
+        })
+    }
+
+    /// Logs progress every `progress_interval_secs` until `finish` is called
+    pub fn spawn_reporter(self: &Arc<Self>, config: Option<&StartupConfig>) {
+        let interval = config
+            .and_then(|config| config.progress_interval_secs)
+            .map_or(DEFAULT_PROGRESS_INTERVAL, Duration::from_secs);
+        let progress = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if progress.done.load(Ordering::Relaxed) {
+                    break;
+                }
+                info!("Startup: {}", progress.summary());
+            }
+        });
+    }
+
+    pub fn set_markets_total(&self, total: usize) {
+        self.markets_total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn add_markets_fetched(&self, count: usize) {
+        self.markets_fetched.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn set_mints_total(&self, total: usize) {
+        self.mints_total.store(total, Ordering::Relaxed);
+    }
+
+    pub fn mint_initialized(&self) {
+        self.mints_initialized.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// What to do when `startup.timeout_secs` has passed, None while there is time left
+    pub fn timed_out(&self) -> Option<StartupTimeoutAction> {
+        self.deadline
+            .filter(|deadline| Instant::now() >= *deadline)
+            .map(|_| self.on_timeout)
+    }
+
+    /// Stops the reporter and logs the final counts
+    pub fn finish(&self) {
+        self.done.store(true, Ordering::Relaxed);
+        info!("Startup finished: {}", self.summary());
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "markets fetched {}/{}, mints initialized {}/{}, elapsed {}s",
+            self.markets_fetched.load(Ordering::Relaxed),
+            self.markets_total.load(Ordering::Relaxed),
+            self.mints_initialized.load(Ordering::Relaxed),
+            self.mints_total.load(Ordering::Relaxed),
+            self.started.elapsed().as_secs()
+        )
+    }
+}
@@ -0,0 +1,368 @@
+use crate::base_mints::BaseMints;
+use crate::config::StatusConfig;
+use crate::endpoint_health::EndpointHealth;
+use crate::executor_errors::ExecutorErrors;
+use crate::fee_spend::FeeSpend;
+use crate::prefilter::PrefilterStats;
+use crate::pricing::{PoolPrice, PriceSnapshot};
+use crate::journal::SendJournal;
+use crate::rpc_budget::RpcBudget;
+use crate::risk::{unix_now, DailyLossBreaker};
+use crate::rpc_metrics::RpcMetrics;
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{info, warn};
+
+const DEFAULT_STALE_AFTER: Duration = Duration::from_secs(15);
+
+/// Latest state published by a mint worker
+#[derive(Debug, Clone, Default)]
+pub struct MintStatus {
+    pub delay_ms: u64,
+    pub delay_reason: Option<String>,
+    pub cooldown_active: bool,
+    /// Iterations skipped in post-land cooldowns since startup
+    pub cooldown_skips: u64,
+    pub prices: Option<PriceSnapshot>,
+    pub prefilter: Option<PrefilterStats>,
+}
+
+/// Per-mint status shared between the workers and the status endpoint
+#[derive(Debug, Clone, Default)]
+pub struct StatusRegistry {
+    mints: Arc<RwLock<BTreeMap<Pubkey, MintStatus>>>,
+    stale_after: Duration,
+    rpc_metrics: Option<RpcMetrics>,
+    rpc_budget: Option<Arc<RpcBudget>>,
+    send_journal: Option<SendJournal>,
+    loss_breakers: Arc<RwLock<Vec<(Pubkey, Arc<DailyLossBreaker>)>>>,
+}
+
+impl StatusRegistry {
+    pub fn new(
+        stale_after: Duration,
+        rpc_metrics: Option<RpcMetrics>,
+        rpc_budget: Option<Arc<RpcBudget>>,
+        send_journal: Option<SendJournal>,
+    ) -> Self {
+        Self {
+            mints: Arc::default(),
+            stale_after,
+            rpc_metrics,
+            rpc_budget,
+            send_journal,
+            loss_breakers: Arc::default(),
+        }
+    }
+
+    /// Shows `wallet`'s daily loss breaker on the status page and lets `/risk/resume` clear it
+    pub fn register_loss_breaker(&self, wallet: Pubkey, breaker: Arc<DailyLossBreaker>) {
+        self.loss_breakers.write().unwrap().push((wallet, breaker));
+    }
+
+    /// Clears every tripped daily loss breaker, returning how many were tripped
+    fn resume_loss_breakers(&self) -> usize {
+        let breakers = self.loss_breakers.read().unwrap();
+        let resumed = breakers
+            .iter()
+            .filter(|(_, breaker)| breaker.resume())
+            .count();
+        info!(
+            "Resumed {} of {} daily loss breakers from the status endpoint",
+            resumed,
+            breakers.len()
+        );
+        resumed
+    }
+
+    /// Status line and body for a request
+    fn respond(&self, method: &str, path: &str) -> (&'static str, String) {
+        match (method, path) {
+            (_, "/" | "/status") => ("200 OK", self.render()),
+            (_, "/metrics") => ("200 OK", self.render_metrics()),
+            ("POST", "/risk/resume") => {
+                let resumed = self.resume_loss_breakers();
+                ("200 OK", format!("resumed {} loss breakers\n", resumed))
+            }
+            (_, "/risk/resume") => ("405 Method Not Allowed", "use POST to resume\n".to_string()),
+            _ => ("404 Not Found", "not found\n".to_string()),
+        }
+    }
+
+    /// Prometheus text of the RPC metrics, RPC budget and send-to-land latency when enabled,
+    /// followed by the per-mint counters, executor failures, sending endpoint health and fee
+    /// spend, which are always served
+    fn render_metrics(&self) -> String {
+        let mut out = String::new();
+        if let Some(rpc_metrics) = &self.rpc_metrics {
+            out.push_str(&rpc_metrics.render_prometheus());
+        }
+        if let Some(rpc_budget) = &self.rpc_budget {
+            out.push_str(&rpc_budget.render_prometheus());
+        }
+        if let Some(send_journal) = &self.send_journal {
+            out.push_str(&send_journal.render_prometheus());
+        }
+        out.push_str(&self.render_mint_metrics());
+        out.push_str(&ExecutorErrors::global().render_prometheus());
+        out.push_str(&EndpointHealth::global().render_prometheus());
+        out.push_str(&FeeSpend::global().render_prometheus(unix_now()));
+        out
+    }
+
+    /// Prometheus text of the per-mint counters
+    fn render_mint_metrics(&self) -> String {
+        let mints = self.mints.read().unwrap();
+        let mut out = String::new();
+        let _ = writeln!(out, "# TYPE post_land_cooldown_skips_total counter");
+        for (mint, status) in mints.iter() {
+            let _ = writeln!(
+                out,
+                "post_land_cooldown_skips_total{{mint=\"{}\"}} {}",
+                mint, status.cooldown_skips
+            );
+        }
+        out
+    }
+
+    pub fn update<F: FnOnce(&mut MintStatus)>(&self, mint: &Pubkey, update: F) {
+        let mut mints = self.mints.write().unwrap();
+        update(mints.entry(*mint).or_default());
+    }
+
+    pub fn publish_prices(&self, snapshot: PriceSnapshot) {
+        let mint = snapshot.mint;
+        self.update(&mint, |status| status.prices = Some(snapshot));
+    }
+
+    /// Plain-text report of every mint's state and implied prices
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (wallet, breaker) in self.loss_breakers.read().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "wallet {} loss breaker: {} of {} lamports lost in 24h{}",
+                wallet,
+                breaker.realized_loss(),
+                breaker.max_daily_loss(),
+                if breaker.is_paused() { " [PAUSED]" } else { "" }
+            );
+        }
+        let mints = self.mints.read().unwrap();
+        for (mint, status) in mints.iter() {
+            let _ = writeln!(out, "mint {}", mint);
+            let _ = write!(out, "  delay: {}ms", status.delay_ms);
+            if let Some(reason) = &status.delay_reason {
+                let _ = write!(out, " ({})", reason);
+            }
+            if status.cooldown_active {
+                let _ = write!(out, " [post-land cooldown]");
+            }
+            if status.cooldown_skips > 0 {
+                let _ = write!(out, ", {} iterations skipped in cooldowns", status.cooldown_skips);
+            }
+            let _ = writeln!(out);
+            if let Some(spend) = FeeSpend::global().mint(mint, unix_now()) {
+                let _ = writeln!(
+                    out,
+                    "  fee spend: {} lamports today, {} fees + {} tips over {} landed",
+                    spend.today_lamports,
+                    spend.fee_lamports,
+                    spend.tip_lamports,
+                    spend.transactions
+                );
+            }
+            if let Some(prefilter) = &status.prefilter {
+                let _ = writeln!(
+                    out,
+                    "  prefilter: {} simulated, {} sends saved, {} errors, avg {}ms",
+                    prefilter.simulated,
+                    prefilter.sends_saved,
+                    prefilter.simulation_errors,
+                    prefilter.average_latency().as_millis()
+                );
+            }
+
+            let Some(snapshot) = &status.prices else {
+                let _ = writeln!(out, "  prices: none yet");
+                continue;
+            };
+            let age = snapshot.taken_at.elapsed();
+            let _ = writeln!(
+                out,
+                "  prices: slot {}, {}ms old{}",
+                snapshot.slot,
+                age.as_millis(),
+                if age > self.stale_after { " (STALE)" } else { "" }
+            );
+            for price in &snapshot.prices {
+                let _ = writeln!(
+                    out,
+                    "    {:<14} {} base {} price {}",
+                    format!("{:?}", price.kind),
+                    price.pool,
+                    price.base_mint,
+                    display_price(price)
+                );
+            }
+            match snapshot.best_spread() {
+                Some(spread) => {
+                    let _ = writeln!(
+                        out,
+                        "  best ask: {} ({}), best bid: {} ({}), max spread: {:.2} bps",
+                        spread.buy.pool,
+                        display_price(&spread.buy),
+                        spread.sell.pool,
+                        display_price(&spread.sell),
+                        spread.spread_bps
+                    );
+                }
+                None => {
+                    let _ = writeln!(out, "  max spread: n/a");
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Base mint units per whole token, or the raw unit ratio when the mint decimals are unknown
+fn display_price(price: &PoolPrice) -> String {
+    match price.ui_price {
+        Some(ui_price) => format!("{:.12} {}", ui_price, BaseMints::label(&price.base_mint)),
+        None => format!("{:.12} (raw units)", price.price),
+    }
+}
+
+/// Binds the status endpoint and serves it in the background. Prometheus metrics are served
+/// on `/metrics`, including the RPC metrics, RPC budget and send-to-land latency when enabled.
+pub async fn spawn_status_server(
+    config: &StatusConfig,
+    rpc_metrics: Option<RpcMetrics>,
+    rpc_budget: Option<Arc<RpcBudget>>,
+    send_journal: Option<SendJournal>,
+) -> Result<StatusRegistry> {
+    let registry = StatusRegistry::new(
+        config
+            .stale_after_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_STALE_AFTER),
+        rpc_metrics,
+        rpc_budget,
+        send_journal,
+    );
+    let listener = TcpListener::bind(&config.listen_addr)
+        .await
+        .with_context(|| format!("Failed to bind status endpoint on {}", config.listen_addr))?;
+    info!("Status endpoint listening on http://{}/status", config.listen_addr);
+
+    let server_registry = registry.clone();
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let registry = server_registry.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &registry).await {
+                            warn!("Status endpoint connection failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Status endpoint accept failed: {}", e),
+            }
+        }
+    });
+
+    Ok(registry)
+}
+
+async fn handle_connection(mut stream: TcpStream, registry: &StatusRegistry) -> Result<()> {
+    let mut buf = [0u8; 4096];
+    let len = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..len]);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let method = request_line.next().unwrap_or("GET");
+    let path = request_line.next().unwrap_or("/");
+
+    let (status, body) = registry.respond(method, path);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metrics_include_global_registries_without_optional_sources() {
+        let registry = StatusRegistry::new(DEFAULT_STALE_AFTER, None, None, None);
+        let metrics = registry.render_metrics();
+        for family in [
+            "post_land_cooldown_skips_total",
+            "executor_failures_total",
+            "sending_endpoint_slot_lag",
+            "fee_spend_lamports_total",
+        ] {
+            assert!(
+                metrics.contains(&format!("# TYPE {} ", family)),
+                "{} missing from:\n{}",
+                family,
+                metrics
+            );
+        }
+    }
+
+    #[test]
+    fn metrics_export_cooldown_skips_per_mint() {
+        let registry = StatusRegistry::new(DEFAULT_STALE_AFTER, None, None, None);
+        let mint = Pubkey::new_unique();
+        registry.update(&mint, |status| status.cooldown_skips = 7);
+        let metrics = registry.render_metrics();
+        let line = format!("post_land_cooldown_skips_total{{mint=\"{}\"}} 7", mint);
+        assert!(metrics.contains(&line), "{} missing from:\n{}", line, metrics);
+        assert!(registry.render().contains("7 iterations skipped in cooldowns"));
+    }
+
+    #[test]
+    fn risk_resume_clears_tripped_breakers_on_post_only() {
+        let registry = StatusRegistry::new(DEFAULT_STALE_AFTER, None, None, None);
+        let tripped = Arc::new(DailyLossBreaker::new(100, None));
+        let healthy = Arc::new(DailyLossBreaker::new(100, None));
+        tripped.record(unix_now(), -101);
+        registry.register_loss_breaker(Pubkey::new_unique(), tripped.clone());
+        registry.register_loss_breaker(Pubkey::new_unique(), healthy.clone());
+        assert!(registry
+            .render()
+            .contains("101 of 100 lamports lost in 24h [PAUSED]"));
+
+        let (status, _) = registry.respond("GET", "/risk/resume");
+        assert_eq!(status, "405 Method Not Allowed");
+        assert!(tripped.is_paused());
+
+        let (status, body) = registry.respond("POST", "/risk/resume");
+        assert_eq!(
+            (status, body.as_str()),
+            ("200 OK", "resumed 1 loss breakers\n")
+        );
+        assert!(!tripped.is_paused());
+        assert!(!healthy.is_paused());
+        assert!(!registry.render().contains("[PAUSED]"));
+    }
+}
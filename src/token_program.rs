@@ -0,0 +1,110 @@
+use crate::constants::token_2022_program_id;
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+/// Size of a mint without extensions
+const MINT_LEN: usize = 82;
+/// Size of a token account without extensions. Token-2022 pads extended mints to this
+/// length too, so anything longer carries extensions.
+const ACCOUNT_LEN: usize = 165;
+
+/// Token program a mint or token account belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenProgramKind {
+    Spl,
+    Token2022,
+}
+
+impl TokenProgramKind {
+    pub fn pubkey(&self) -> Pubkey {
+        match self {
+            TokenProgramKind::Spl => spl_token::ID,
+            TokenProgramKind::Token2022 => token_2022_program_id(),
+        }
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        *self == TokenProgramKind::Token2022
+    }
+}
+
+/// Classifies an account owner as SPL Token or Token-2022
+pub fn classify_token_program(owner: &Pubkey) -> Result<TokenProgramKind> {
+    if *owner == spl_token::ID {
+        Ok(TokenProgramKind::Spl)
+    } else if *owner == token_2022_program_id() {
+        Ok(TokenProgramKind::Token2022)
+    } else {
+        Err(anyhow!("Unknown token program {}", owner))
+    }
+}
+
+/// Classifies a mint or token account by owner when known, otherwise by data length:
+/// accounts longer than the base layout carry Token-2022 extensions, base-sized ones
+/// are taken as SPL Token
+pub fn classify_token_account(owner: Option<&Pubkey>, data: &[u8]) -> Result<TokenProgramKind> {
+    if let Some(owner) = owner {
+        return classify_token_program(owner);
+    }
+    match data.len() {
+        MINT_LEN | ACCOUNT_LEN => Ok(TokenProgramKind::Spl),
+        len if len > ACCOUNT_LEN => Ok(TokenProgramKind::Token2022),
+        len => Err(anyhow!("{} bytes is not a mint or token account", len)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_both_token_programs() {
+        assert_eq!(
+            classify_token_program(&spl_token::ID).unwrap(),
+            TokenProgramKind::Spl
+        );
+        assert_eq!(
+            classify_token_program(&token_2022_program_id()).unwrap(),
+            TokenProgramKind::Token2022
+        );
+        for kind in [TokenProgramKind::Spl, TokenProgramKind::Token2022] {
+            assert_eq!(classify_token_program(&kind.pubkey()).unwrap(), kind);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_owners() {
+        let owner = Pubkey::new_unique();
+        let err = classify_token_program(&owner).unwrap_err();
+        assert_eq!(err.to_string(), format!("Unknown token program {}", owner));
+        // A known owner wins over the data length, an unknown one is not guessed from it
+        assert!(classify_token_account(Some(&owner), &[0; MINT_LEN]).is_err());
+        assert_eq!(
+            classify_token_account(Some(&token_2022_program_id()), &[0; MINT_LEN]).unwrap(),
+            TokenProgramKind::Token2022
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_data_length_without_an_owner() {
+        assert_eq!(
+            classify_token_account(None, &[0; MINT_LEN]).unwrap(),
+            TokenProgramKind::Spl
+        );
+        assert_eq!(
+            classify_token_account(None, &[0; ACCOUNT_LEN]).unwrap(),
+            TokenProgramKind::Spl
+        );
+        assert_eq!(
+            classify_token_account(None, &[0; ACCOUNT_LEN + 1]).unwrap(),
+            TokenProgramKind::Token2022
+        );
+        for len in [0, MINT_LEN - 1, MINT_LEN + 1, ACCOUNT_LEN - 1] {
+            let err = classify_token_account(None, &vec![0; len]).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("{} bytes is not a mint or token account", len)
+            );
+        }
+    }
+}
@@ -1,12 +1,16 @@
-use crate::config::Config;
-use crate::dex::byreal::byreal_program_id;
-use crate::dex::futarchy::futarchy_program_id;
-use crate::dex::heaven::constants::{heaven_program_id, heaven_protocol_account_1, heaven_protocol_account_2};
-use crate::dex::humidifi::humidifi_program_id;
-use crate::dex::pancakeswap::pancakeswap_program_id;
-use crate::dex::raydium::{raydium_authority, raydium_cp_authority};
-use crate::dex::vertigo::constants::vertigo_program_id;
+use crate::blockhash::CachedBlockhash;
+use crate::config::{Config, WsolStrategy};
+use crate::dex::heaven::constants::{heaven_protocol_account_1, heaven_protocol_account_2};
+use crate::dex::raydium::{raydium_authority, raydium_authority_for, raydium_cp_authority_for};
+use crate::endpoint_health::{is_node_behind, EndpointHealth};
+use crate::executor_errors::ExecutorErrors;
+use crate::ix_data::{ArbIxData, PoolFlags};
+use crate::jito::JitoTips;
+use crate::log_dedup::LogDedup;
+use crate::pda::find_program_address;
 use crate::pools::MintPoolData;
+use crate::program_registry::ProgramRegistry;
+use solana_client::client_error::ClientError;
 use solana_client::rpc_client::RpcClient;
 use solana_program::instruction::Instruction;
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
@@ -16,20 +20,23 @@ use solana_sdk::hash::Hash;
 use solana_sdk::message::v0::Message;
 use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::signer::Signer;
-use solana_sdk::transaction::VersionedTransaction;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::collections::HashSet;
 use std::sync::Arc;
-use tracing::{debug, error, info};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, warn, Level};
 
-use crate::constants::sol_mint;
+use crate::constants::{
+    executor_program_id, memo_program_id, sol_mint, usd1_mint, usdc_mint, MAX_COMPUTE_UNIT_LIMIT,
+};
 use crate::dex::meteora::constants::{
-    damm_program_id, damm_v2_event_authority, damm_v2_pool_authority, damm_v2_program_id,
-    dlmm_event_authority, dlmm_program_id, vault_program_id,
+    damm_v2_event_authority_for, damm_v2_pool_authority_for, dlmm_event_authority_for,
 };
-use crate::dex::pump::constants::{pump_program_id, pump_swap_fee_recipient};
-use crate::dex::raydium::constants::{
-    raydium_clmm_program_id, raydium_cp_program_id, raydium_program_id,
+use crate::dex::pump::constants::{
+    pump_event_authority_for, pump_global_config_for, pump_swap_fee_recipient, PUMP_FEE_WALLETS,
+    PUMP_MAYHEM_FEE_WALLETS,
 };
-use crate::dex::whirlpool::constants::whirlpool_program_id;
+use crate::dex::raydium::constants::raydium_program_id;
 use solana_program::instruction::AccountMeta;
 use solana_program::pubkey::Pubkey;
 use solana_program::system_program;
@@ -37,20 +44,36 @@ use spl_associated_token_account::ID as associated_token_program_id;
 use spl_token::ID as token_program_id;
 use std::str::FromStr;
 
-pub async fn build_and_send_transaction(
+const MAINTENANCE_SEND_ATTEMPTS: u32 = 3;
+/// SOL fee collectors of the executor; each transaction picks one at random
+const SOL_FEE_COLLECTORS: [&str; 3] = [
+    "GPpkDpzCDmYJY5qNhYmM14c7rct1zmkjWc2CjR5g7RZ1",
+    "J6c7noBHvWju4mMA3wXt3igbBSp2m9ATbA6cjMtAUged",
+    "BjsfwxDu7GX7RRW6oSRTpMkASdXAgCcHnXEcatqSfuuY",
+];
+/// Flashloan vault authorities; SOL flashloans pick one at random
+const FLASHLOAN_VAULT_AUTHORITIES: [&str; 2] = [
+    "5LFpzqgsxrSfhKwbaFiAEJ2kbc9QyimjKueswsyU4T3o",
+    "4B2yxi8n7jr8w3K7cssokLNJZ6k2NjiwKwLdQ8L9dbAA",
+];
+const MAINTENANCE_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Send failures, deduplicated per sending endpoint
+static SEND_ERRORS: LogDedup = LogDedup::new(Level::ERROR);
+
+/// Builds and signs the arbitrage transaction for this mint's route
+pub fn build_transaction(
     wallet_kp: &Keypair,
     config: &Config,
     mint_pool_data: &MintPoolData,
-    rpc_clients: &[Arc<RpcClient>],
     blockhash: Hash,
     address_lookup_table_accounts: &[AddressLookupTableAccount],
-) -> anyhow::Result<Vec<Signature>> {
-    let enable_flashloan = config.flashloan.as_ref().map_or(false, |k| k.enabled);
-    let compute_unit_limit = config.bot.compute_unit_limit;
+    pool_flags: Option<Vec<PoolFlags>>,
+) -> anyhow::Result<VersionedTransaction> {
+    let compute_unit_limit = config.compute_unit_limit_for(&mint_pool_data.mint);
     let mut instructions = vec![];
     // Add a random number here to make each transaction unique
     let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(
-        compute_unit_limit + rand::random::<u32>() % 1000,
+        (compute_unit_limit + rand::random::<u32>() % 1000).min(MAX_COMPUTE_UNIT_LIMIT),
     );
     instructions.push(compute_budget_ix);
 
@@ -59,18 +82,59 @@ pub async fn build_and_send_transaction(
         ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price);
     instructions.push(compute_budget_price_ix);
 
-    let swap_ix = create_swap_instruction(
-        wallet_kp,
-        mint_pool_data,
-        compute_unit_limit,
-        enable_flashloan,
-    )?;
+    if let Some(memo) = config.bot.memo.as_deref().filter(|memo| !memo.is_empty()) {
+        instructions.push(Instruction {
+            program_id: memo_program_id(),
+            accounts: vec![],
+            data: memo.as_bytes().to_vec(),
+        });
+    }
+
+    let swap_ix = build_swap_instruction(wallet_kp, config, mint_pool_data, pool_flags)?;
+    if let Some(bytes) = config.heap_frame_bytes_for(swap_ix.accounts.len()) {
+        instructions.push(ComputeBudgetInstruction::request_heap_frame(bytes));
+    }
+
+    let wrap_per_trade = config.bot.wsol_strategy == WsolStrategy::PerTrade;
+    if wrap_per_trade {
+        let wrap_amount = config.bot.wsol_wrap_amount.ok_or_else(|| {
+            anyhow::anyhow!("bot.wsol_wrap_amount is required when wsol_strategy is per_trade")
+        })?;
+        instructions.extend(wrap_wsol_instructions(
+            &wallet_kp.pubkey(),
+            &mint_pool_data.wallet_wsol_account,
+            wrap_amount,
+        )?);
+    }
 
     let mut all_instructions = instructions.clone();
 
+    let coverage = AltCoverage::compute(&swap_ix.accounts, address_lookup_table_accounts);
+    debug!(
+        "Lookup table coverage for mint {}: {}/{} accounts ({:.1}%)",
+        mint_pool_data.mint,
+        coverage.covered,
+        coverage.total,
+        coverage.percent()
+    );
+
     debug!("Adding swap instruction");
     all_instructions.push(swap_ix);
 
+    if let Some(tips) = JitoTips::global() {
+        all_instructions.push(tips.tip_instruction(&wallet_kp.pubkey()));
+    }
+
+    if wrap_per_trade {
+        all_instructions.push(spl_token::instruction::close_account(
+            &token_program_id,
+            &mint_pool_data.wallet_wsol_account,
+            &wallet_kp.pubkey(),
+            &wallet_kp.pubkey(),
+            &[],
+        )?);
+    }
+
     let message = Message::try_compile(
         &wallet_kp.pubkey(),
         &all_instructions,
@@ -78,49 +142,231 @@ pub async fn build_and_send_transaction(
         blockhash,
     )?;
 
-    let tx = VersionedTransaction::try_new(
+    Ok(VersionedTransaction::try_new(
         solana_sdk::message::VersionedMessage::V0(message),
         &[wallet_kp],
+    )?)
+}
+
+/// The executor instruction of this mint's route, as included by `build_transaction`
+pub fn build_swap_instruction(
+    wallet_kp: &Keypair,
+    config: &Config,
+    mint_pool_data: &MintPoolData,
+    pool_flags: Option<Vec<PoolFlags>>,
+) -> anyhow::Result<Instruction> {
+    build_swap_instruction_for(&wallet_kp.pubkey(), config, mint_pool_data, pool_flags)
+}
+
+/// `build_swap_instruction` for a wallet known by its address only, e.g. to rebuild a
+/// captured route
+pub fn build_swap_instruction_for(
+    wallet: &Pubkey,
+    config: &Config,
+    mint_pool_data: &MintPoolData,
+    pool_flags: Option<Vec<PoolFlags>>,
+) -> anyhow::Result<Instruction> {
+    let enable_flashloan = config.flashloan.as_ref().map_or(false, |k| k.enabled);
+    let mut swap_ix = create_swap_instruction(
+        wallet,
+        mint_pool_data,
+        config.compute_unit_limit_for(&mint_pool_data.mint),
+        enable_flashloan,
+        config.readonly_bitmap_extensions(),
+        pool_flags,
     )?;
+    if let Some(executor) = &config.executor {
+        force_writable(&mut swap_ix.accounts, &executor.force_writable_keys);
+    }
+    Ok(swap_ix)
+}
+
+/// Accounts a route may pick at random from a fixed set: fee collectors, flashloan vaults
+/// and Pump protocol fee wallets, plus their token accounts for the route's mints
+pub fn rotating_route_accounts(mint_pool_data: &MintPoolData) -> HashSet<Pubkey> {
+    let wallets: Vec<Pubkey> = SOL_FEE_COLLECTORS
+        .iter()
+        .chain(&FLASHLOAN_VAULT_AUTHORITIES)
+        .chain(&PUMP_FEE_WALLETS)
+        .chain(&PUMP_MAYHEM_FEE_WALLETS)
+        .map(|wallet| Pubkey::from_str(wallet).unwrap())
+        .collect();
+    let mut mints = vec![mint_pool_data.mint, sol_mint(), usdc_mint(), usd1_mint()];
+    mints.extend(mint_pool_data.pump_pools.iter().map(|pool| pool.base_mint));
+
+    let mut accounts: HashSet<Pubkey> = wallets.iter().copied().collect();
+    for mint in &mints {
+        accounts.insert(derive_vault_token_account(&executor_program_id(), mint).0);
+        for wallet in &wallets {
+            accounts.insert(spl_associated_token_account::get_associated_token_address(
+                wallet, mint,
+            ));
+        }
+    }
+    accounts
+}
+
+/// One endpoint's send of a transaction
+#[derive(Debug, Clone)]
+pub struct EndpointSend {
+    /// Host of the sending RPC, without path or query which may carry an API key
+    pub endpoint: String,
+    pub sent_at: SystemTime,
+    pub slot_estimate: u64,
+    /// Slots the endpoint trailed the primary RPC at its last probe, None when unknown
+    pub slot_lag: Option<u64>,
+    pub result: Result<Signature, String>,
+}
+
+/// What was signed and when and where it was sent
+#[derive(Debug, Clone)]
+pub struct SendReport {
+    pub blockhash: CachedBlockhash,
+    /// Slot estimate when the first send started
+    pub slot_estimate: u64,
+    pub endpoints: Vec<EndpointSend>,
+}
+
+impl SendReport {
+    /// Signatures of the sends that succeeded
+    pub fn signatures(&self) -> Vec<Signature> {
+        self.endpoints
+            .iter()
+            .filter_map(|send| send.result.as_ref().ok().copied())
+            .collect()
+    }
+}
 
+/// Marks the accounts in `executor.force_writable` writable, for accounts the route passes
+/// read-only that the deployed executor or a DEX turns out to write
+fn force_writable(accounts: &mut [AccountMeta], forced: &HashSet<Pubkey>) {
+    for meta in accounts.iter_mut().filter(|meta| forced.contains(&meta.pubkey)) {
+        meta.is_writable = true;
+    }
+}
+
+/// A sending RPC with the options of its `spam.sending_rpc_urls` entry
+#[derive(Clone)]
+pub struct SendingClient {
+    pub client: Arc<RpcClient>,
+    pub skip_preflight: bool,
+    /// Copies of each transaction sent through this client
+    pub weight: u32,
+}
+
+impl SendingClient {
+    /// The main RPC, used when spam sending is off
+    pub fn primary(client: Arc<RpcClient>) -> Self {
+        Self {
+            client,
+            skip_preflight: true,
+            weight: 1,
+        }
+    }
+}
+
+/// Broadcasts `weight` copies through every sending RPC, reporting the outcome and timing
+/// of each send. Endpoints benched for lagging behind are skipped unless all of them are.
+pub async fn send_transaction(
+    config: &Config,
+    tx: &VersionedTransaction,
+    rpc_clients: &[SendingClient],
+    blockhash: &CachedBlockhash,
+) -> SendReport {
     let max_retries = config
         .spam
         .as_ref()
         .and_then(|s| s.max_retries)
         .unwrap_or(3);
 
-    let mut signatures = Vec::new();
-
-    for (i, client) in rpc_clients.iter().enumerate() {
-        debug!("Sending transaction through RPC client {}", i);
+    let mut report = SendReport {
+        blockhash: *blockhash,
+        slot_estimate: blockhash.slot_at(SystemTime::now()),
+        endpoints: Vec::with_capacity(rpc_clients.len()),
+    };
 
-        let signature = match send_transaction_with_retries(client, &tx, max_retries).await {
-            Ok(sig) => sig,
-            Err(e) => {
-                error!("Failed to send transaction through RPC client {}: {}", i, e);
-                continue;
-            }
-        };
+    let health = EndpointHealth::global();
+    let labels: Vec<String> = rpc_clients
+        .iter()
+        .map(|sending| endpoint_label(&sending.client.url()))
+        .collect();
+    let all_benched = labels.iter().all(|label| health.is_benched(label));
+    if all_benched {
+        warn!("Every sending endpoint is benched for lagging, sending through all of them");
+    }
 
-        info!(
-            "Transaction sent successfully through RPC client {}: {}",
-            i, signature
-        );
-        signatures.push(signature);
+    for (i, (sending, endpoint)) in rpc_clients.iter().zip(labels).enumerate() {
+        let state = health.state(&endpoint);
+        if state.benched && !all_benched {
+            debug!("Skipping benched sending endpoint {}", endpoint);
+            continue;
+        }
+        for copy in 0..sending.weight {
+            debug!("Sending transaction through RPC client {} (copy {})", i, copy + 1);
+
+            let sent_at = SystemTime::now();
+            let result = match send_transaction_with_retries(
+                &sending.client,
+                tx,
+                max_retries,
+                sending.skip_preflight,
+            )
+            .await
+            {
+                Ok(signature) => {
+                    info!(
+                        "Transaction sent successfully through RPC client {}: {}",
+                        i, signature
+                    );
+                    Ok(signature)
+                }
+                Err(e) => {
+                    SEND_ERRORS.log(
+                        &endpoint,
+                        format_args!(
+                            "Failed to send transaction through RPC client {}: {}",
+                            i, e
+                        ),
+                    );
+                    if e.downcast_ref::<ClientError>().map_or(false, is_node_behind) {
+                        health.record_node_behind(&endpoint);
+                    }
+                    Err(e.to_string())
+                }
+            };
+            report.endpoints.push(EndpointSend {
+                endpoint: endpoint.clone(),
+                sent_at,
+                slot_estimate: blockhash.slot_at(sent_at),
+                slot_lag: state.slot_lag,
+                result,
+            });
+        }
     }
 
-    Ok(signatures)
+    report
+}
+
+/// Host part of an RPC URL
+pub fn endpoint_label(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme
+        .split(['/', '?'])
+        .next()
+        .unwrap_or(without_scheme)
+        .to_string()
 }
 
 async fn send_transaction_with_retries(
     client: &RpcClient,
     tx: &VersionedTransaction,
     max_retries: u64,
+    skip_preflight: bool,
 ) -> anyhow::Result<Signature> {
     Ok(client.send_transaction_with_config(
         tx,
         solana_client::rpc_config::RpcSendTransactionConfig {
-            skip_preflight: true,
+            skip_preflight,
             max_retries: Some(max_retries as usize),
             preflight_commitment: Some(CommitmentLevel::Confirmed),
             ..Default::default()
@@ -128,14 +374,66 @@ async fn send_transaction_with_retries(
     )?)
 }
 
+/// Sends a non-arbitrage transaction through `maintenance.send_rpc_url` and waits for its
+/// confirmation. Transport errors are retried with backoff; a transaction that fails on
+/// chain is not, and is reported with its classified reason.
+pub fn send_maintenance_transaction(
+    client: &RpcClient,
+    tx: &Transaction,
+    what: &str,
+) -> anyhow::Result<Signature> {
+    let mut backoff = MAINTENANCE_INITIAL_BACKOFF;
+    for attempt in 1..=MAINTENANCE_SEND_ATTEMPTS {
+        match client.send_and_confirm_transaction(tx) {
+            Ok(signature) => return Ok(signature),
+            Err(e) => {
+                if let Some(tx_error) = e.get_transaction_error() {
+                    let reason = ExecutorErrors::global().reason(&tx_error);
+                    return Err(anyhow::anyhow!("{} failed: {}", what, reason));
+                }
+                if attempt == MAINTENANCE_SEND_ATTEMPTS {
+                    return Err(anyhow::anyhow!(
+                        "{} failed after {} attempts: {}",
+                        what,
+                        attempt,
+                        e
+                    ));
+                }
+                warn!("{} attempt {} failed: {}, retrying in {:?}", what, attempt, e, backoff);
+                std::thread::sleep(backoff);
+                backoff *= 2;
+            }
+        }
+    }
+    unreachable!("MAINTENANCE_SEND_ATTEMPTS is at least 1")
+}
+
+/// Creates the WSOL ATA if needed and wraps `amount` lamports into it
+fn wrap_wsol_instructions(
+    wallet: &Pubkey,
+    wallet_wsol_account: &Pubkey,
+    amount: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    Ok(vec![
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            wallet,
+            wallet,
+            &sol_mint(),
+            &token_program_id,
+        ),
+        solana_program::system_instruction::transfer(wallet, wallet_wsol_account, amount),
+        spl_token::instruction::sync_native(&token_program_id, wallet_wsol_account)?,
+    ])
+}
+
 /// Helper function to derive the vault token account PDA address for a given mint
 pub fn derive_vault_token_account(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[b"vault_token_account", mint.as_ref()], program_id)
+    find_program_address(&[b"vault_token_account", mint.as_ref()], program_id)
 }
 
 /// Helper function to derive the Pump pool-v2 PDA for a given mint
 pub fn derive_pump_pool_v2(mint: &Pubkey, pump_program_id: &Pubkey) -> Pubkey {
-    Pubkey::find_program_address(&[b"pool-v2", mint.as_ref()], pump_program_id).0
+    find_program_address(&[b"pool-v2", mint.as_ref()], pump_program_id).0
 }
 
 fn is_pump_pool_base_mint_quote(
@@ -186,16 +484,18 @@ fn push_pump_v2_tail_accounts(
     is_cashback_coin: bool,
 ) {
     if is_cashback_coin {
-        let (user_volume_accumulator, _) = Pubkey::find_program_address(
+        let (user_volume_accumulator, _) = find_program_address(
             &[b"user_volume_accumulator", wallet.as_ref()],
             pump_program_id,
         );
-        let user_volume_accumulator_wsol_ata =
-            spl_associated_token_account::get_associated_token_address(
+        // Cashback accrues in the pool's quote mint: WSOL, or e.g. USD1
+        let user_volume_accumulator_quote_ata =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
                 &user_volume_accumulator,
-                &sol_mint(),
+                pool_base_mint,
+                pool_base_token_program,
             );
-        accounts.push(AccountMeta::new(user_volume_accumulator_wsol_ata, false));
+        accounts.push(AccountMeta::new(user_volume_accumulator_quote_ata, false));
     }
 
     if *coin_creator != Pubkey::default() {
@@ -216,30 +516,129 @@ fn push_pump_v2_tail_accounts(
     accounts.push(AccountMeta::new(fee_recipient_quote_ata, false));
 }
 
+/// How many of a route's lookup-eligible accounts the loaded lookup tables resolve.
+/// Signers and top-level program ids always stay in the static keys and are not counted.
+#[derive(Debug, Clone)]
+pub struct AltCoverage {
+    pub total: usize,
+    pub covered: usize,
+    pub missing: Vec<Pubkey>,
+}
+
+impl AltCoverage {
+    pub fn compute(accounts: &[AccountMeta], lookup_tables: &[AddressLookupTableAccount]) -> Self {
+        let in_tables: HashSet<&Pubkey> = lookup_tables
+            .iter()
+            .flat_map(|table| table.addresses.iter())
+            .collect();
+        let static_programs = [solana_sdk::compute_budget::ID, executor_program_id()];
+
+        let mut seen = HashSet::new();
+        let mut coverage = AltCoverage {
+            total: 0,
+            covered: 0,
+            missing: Vec::new(),
+        };
+        for meta in accounts {
+            if meta.is_signer
+                || static_programs.contains(&meta.pubkey)
+                || !seen.insert(meta.pubkey)
+            {
+                continue;
+            }
+            coverage.total += 1;
+            if in_tables.contains(&meta.pubkey) {
+                coverage.covered += 1;
+            } else {
+                coverage.missing.push(meta.pubkey);
+            }
+        }
+        coverage
+    }
+
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        self.covered as f64 / self.total as f64 * 100.0
+    }
+}
+
+/// Returns the ordered account metas the bot would submit for this mint's route,
+/// including the compute budget and executor programs ahead of the swap accounts.
+/// The swap accounts are those of `build_swap_instruction_for`, so flashloan, read-only
+/// bitmap extensions and `executor.force_writable` apply as when sending.
+pub fn build_route_account_metas(
+    config: &Config,
+    mint_pool_data: &MintPoolData,
+) -> anyhow::Result<Vec<AccountMeta>> {
+    let swap_ix =
+        build_swap_instruction_for(&mint_pool_data.wallet_account, config, mint_pool_data, None)?;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(solana_sdk::compute_budget::ID, false),
+        AccountMeta::new_readonly(executor_program_id(), false),
+    ];
+    accounts.extend(swap_ix.accounts);
+    Ok(accounts)
+}
+
 // See https://docs.solanamevbot.com/home/onchain-bot/onchain-program for more information
 fn create_swap_instruction(
-    wallet_kp: &Keypair,
+    wallet: &Pubkey,
     mint_pool_data: &MintPoolData,
     compute_unit_limit: u32,
     use_flashloan: bool,
+    readonly_bitmap_extensions: bool,
+    pool_flags: Option<Vec<PoolFlags>>,
 ) -> anyhow::Result<Instruction> {
     debug!("Creating swap instruction for all DEX types");
 
-    let executor_program_id =
-        Pubkey::from_str("MEViEnscUm6tsQRoGd9h6nLQaQspKj7DB2M5FwM3Xvz").unwrap();
+    let accounts =
+        swap_account_metas(wallet, mint_pool_data, use_flashloan, readonly_bitmap_extensions);
+
+    let data = ArbIxData {
+        minimum_profit: 0,
+        compute_unit_limit,
+        // When true, the bot will not fail the transaction even when it can't find a profitable arbitrage. It will just do nothing and succeed.
+        no_failure_mode: false,
+        use_flashloan,
+        pool_flags,
+    };
+
+    Ok(Instruction {
+        program_id: executor_program_id(),
+        accounts,
+        data: data.to_bytes()?,
+    })
+}
 
-    let pump_global_config =
-        Pubkey::from_str("ADyA8hdefvWN2dbGGWFotbzWxrAvLW83WG6QCVXvJKqw").unwrap();
-    let pump_authority = Pubkey::from_str("GS4CU59F31iL7aR2Q8zVS8DRrcRnXX1yjQ66TqNVQnaR").unwrap();
+/// Executor accounts of the route. DLMM and CLMM bitmap extensions are writable unless
+/// `readonly_bitmap_extensions` is set.
+fn swap_account_metas(
+    wallet: &Pubkey,
+    mint_pool_data: &MintPoolData,
+    use_flashloan: bool,
+    readonly_bitmap_extensions: bool,
+) -> Vec<AccountMeta> {
+    let registry = ProgramRegistry::global();
+    // Swaps only read the bitmap extensions, liquidity changes are what flip their bits.
+    // Passing them read-only avoids write locks shared with other traders.
+    let bitmap_extension_meta = |bitmap_extension: Pubkey| AccountMeta {
+        pubkey: bitmap_extension,
+        is_signer: false,
+        is_writable: !readonly_bitmap_extensions,
+    };
+    let pump_global_config = pump_global_config_for(&registry.pump);
+    let pump_authority = pump_event_authority_for(&registry.pump);
     let sysvar_instructions =
         Pubkey::from_str("Sysvar1nstructions1111111111111111111111111").unwrap();
-    let memo_program = Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr").unwrap();
+    let memo_program = memo_program_id();
 
-    let wallet = wallet_kp.pubkey();
+    let wallet = *wallet;
     let sol_mint_pubkey = sol_mint();
     let wallet_sol_account = mint_pool_data.wallet_wsol_account;
-    let usdc_mint = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
-    let usd1_mint = Pubkey::from_str("USD1ttGY1N17NEEHLmELoaybftRBUSErhqYiQzvEmuB").unwrap();
+    let usdc_mint = usdc_mint();
+    let usd1_mint = usd1_mint();
 
     // Step 1: Determine flashloan_base_mint FIRST by checking ALL pool types
     let flashloan_base_mint = if use_flashloan {
@@ -310,12 +709,8 @@ fn create_swap_instruction(
         Pubkey::from_str("GzVRuLF349u78FHpr8KbqMhrZ1aDxnhSF59JWiZ6tbgt").unwrap()
     } else {
         // SOL base mint uses random SOL fee collector
-        let fee_accounts = [
-            Pubkey::from_str("GPpkDpzCDmYJY5qNhYmM14c7rct1zmkjWc2CjR5g7RZ1").unwrap(),
-            Pubkey::from_str("J6c7noBHvWju4mMA3wXt3igbBSp2m9ATbA6cjMtAUged").unwrap(),
-            Pubkey::from_str("BjsfwxDu7GX7RRW6oSRTpMkASdXAgCcHnXEcatqSfuuY").unwrap(),
-        ];
-        fee_accounts[rand::random::<usize>() % fee_accounts.len()]
+        let index = rand::random::<usize>() % SOL_FEE_COLLECTORS.len();
+        Pubkey::from_str(SOL_FEE_COLLECTORS[index]).unwrap()
     };
 
     // Step 4: Build accounts vector with dynamic base_mint and wallet_base_account
@@ -331,10 +726,8 @@ fn create_swap_instruction(
 
     // Step 5: Add flashloan accounts with USDC vault_index=0 constraint
     if use_flashloan {
-        let vault_authorities = [
-            Pubkey::from_str("5LFpzqgsxrSfhKwbaFiAEJ2kbc9QyimjKueswsyU4T3o").unwrap(),
-            Pubkey::from_str("4B2yxi8n7jr8w3K7cssokLNJZ6k2NjiwKwLdQ8L9dbAA").unwrap(),
-        ];
+        let vault_authorities =
+            FLASHLOAN_VAULT_AUTHORITIES.map(|authority| Pubkey::from_str(authority).unwrap());
         // USDC flashloan uses the PDA vault (index 0) only
         let vault_index = if flashloan_base_mint == usdc_mint {
             0
@@ -347,7 +740,7 @@ fn create_swap_instruction(
         // Vault index 0 uses PDA-derived token account, index 1 uses associated token account
         // Defensive check: always use PDA for USDC regardless of vault_index
         let vault_token_account = if vault_index == 0 || flashloan_base_mint == usdc_mint {
-            let token_pda = derive_vault_token_account(&executor_program_id, &flashloan_base_mint);
+            let token_pda = derive_vault_token_account(&executor_program_id(), &flashloan_base_mint);
             token_pda.0
         } else {
             spl_associated_token_account::get_associated_token_address(
@@ -450,9 +843,9 @@ fn create_swap_instruction(
 
     // Add Raydium pools
     for pool in &mint_pool_data.raydium_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(pool.program_id, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
-        accounts.push(AccountMeta::new_readonly(raydium_authority(), false));
+        accounts.push(AccountMeta::new_readonly(raydium_authority_for(&pool.program_id), false));
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new(pool.token_vault, false));
         accounts.push(AccountMeta::new(pool.sol_vault, false));
@@ -460,9 +853,9 @@ fn create_swap_instruction(
 
     // Add Raydium CP pools
     for pool in &mint_pool_data.raydium_cp_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_cp_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(pool.program_id, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
-        accounts.push(AccountMeta::new_readonly(raydium_cp_authority(), false));
+        accounts.push(AccountMeta::new_readonly(raydium_cp_authority_for(&pool.program_id), false));
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
         accounts.push(AccountMeta::new(pool.token_vault, false));
@@ -472,7 +865,7 @@ fn create_swap_instruction(
 
     // Add Pump pools
     for pool in &mint_pool_data.pump_pools {
-        accounts.push(AccountMeta::new_readonly(pump_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.pump, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
         accounts.push(AccountMeta::new_readonly(pump_global_config, false));
         accounts.push(AccountMeta::new_readonly(pump_authority, false));
@@ -486,10 +879,10 @@ fn create_swap_instruction(
             pool.coin_creator_vault_authority,
             false,
         ));
-        let pump_program_id = pump_program_id();
+        let pump_program_id = registry.pump;
         let (global_volume_accumulator, _) =
-            Pubkey::find_program_address(&[b"global_volume_accumulator"], &pump_program_id);
-        let (user_volume_accumulator, _) = Pubkey::find_program_address(
+            find_program_address(&[b"global_volume_accumulator"], &pump_program_id);
+        let (user_volume_accumulator, _) = find_program_address(
             &[b"user_volume_accumulator", wallet.as_ref()],
             &pump_program_id,
         );
@@ -518,9 +911,12 @@ fn create_swap_instruction(
 
     // Add DLMM pairs
     for pair in &mint_pool_data.dlmm_pairs {
-        accounts.push(AccountMeta::new_readonly(dlmm_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.meteora_dlmm, false));
         accounts.push(AccountMeta::new_readonly(pair.base_mint, false)); // V9: Add base mint
-        accounts.push(AccountMeta::new_readonly(dlmm_event_authority(), false));
+        accounts.push(AccountMeta::new_readonly(
+            dlmm_event_authority_for(&registry.meteora_dlmm),
+            false,
+        ));
         if let Some(memo_program) = pair.memo_program {
             accounts.push(AccountMeta::new_readonly(memo_program, false));
         }
@@ -529,7 +925,7 @@ fn create_swap_instruction(
         accounts.push(AccountMeta::new(pair.sol_vault, false));
         accounts.push(AccountMeta::new(pair.oracle, false));
         if let Some(bitmap_extension) = pair.bin_array_bitmap_extension {
-            accounts.push(AccountMeta::new(bitmap_extension, false));
+            accounts.push(bitmap_extension_meta(bitmap_extension));
         }
         for bin_array in &pair.bin_arrays {
             accounts.push(AccountMeta::new(*bin_array, false));
@@ -538,7 +934,7 @@ fn create_swap_instruction(
 
     // Add Whirlpool pools
     for pool in &mint_pool_data.whirlpool_pools {
-        accounts.push(AccountMeta::new_readonly(whirlpool_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.whirlpool, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
         accounts.push(AccountMeta::new_readonly(memo_program, false)); // Always add memo program for Whirlpool
         accounts.push(AccountMeta::new(pool.pool, false));
@@ -552,7 +948,7 @@ fn create_swap_instruction(
 
     // Add Raydium CLMM pools
     for pool in &mint_pool_data.raydium_clmm_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_clmm_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(pool.program_id, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
         if let Some(memo_program) = pool.memo_program {
             accounts.push(AccountMeta::new_readonly(memo_program, false));
@@ -560,7 +956,7 @@ fn create_swap_instruction(
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
         accounts.push(AccountMeta::new(pool.observation_state, false));
-        accounts.push(AccountMeta::new(pool.bitmap_extension, false));
+        accounts.push(bitmap_extension_meta(pool.bitmap_extension));
         accounts.push(AccountMeta::new(pool.x_vault, false));
         accounts.push(AccountMeta::new(pool.y_vault, false));
         for tick_array in &pool.tick_arrays {
@@ -570,9 +966,9 @@ fn create_swap_instruction(
 
     // Add Meteora DAMM pools
     for pool in &mint_pool_data.meteora_damm_pools {
-        accounts.push(AccountMeta::new_readonly(damm_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.meteora_damm, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
-        accounts.push(AccountMeta::new_readonly(vault_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.meteora_vault, false));
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new(pool.token_x_vault, false));
         accounts.push(AccountMeta::new(pool.token_sol_vault, false));
@@ -588,10 +984,14 @@ fn create_swap_instruction(
 
     // Add Meteora DAMM V2 pools
     for pool in &mint_pool_data.meteora_damm_v2_pools {
-        accounts.push(AccountMeta::new_readonly(damm_v2_program_id(), false));
+        let damm_v2_program = registry.meteora_damm_v2;
+        let event_authority = damm_v2_event_authority_for(&damm_v2_program);
+        let pool_authority = damm_v2_pool_authority_for(&damm_v2_program);
+        accounts.push(AccountMeta::new_readonly(damm_v2_program, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
-        accounts.push(AccountMeta::new_readonly(damm_v2_event_authority(), false));
-        accounts.push(AccountMeta::new_readonly(damm_v2_pool_authority(), false));
+        accounts.push(AccountMeta::new_readonly(event_authority, false));
+        accounts.push(AccountMeta::new_readonly(pool_authority, false));
+        // The fee scheduler and dynamic fee state live in the pool account itself
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new(pool.token_x_vault, false));
         accounts.push(AccountMeta::new(pool.token_sol_vault, false));
@@ -602,7 +1002,7 @@ fn create_swap_instruction(
 
     // Add Vertigo pools
     for pool in &mint_pool_data.vertigo_pools {
-        accounts.push(AccountMeta::new_readonly(vertigo_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.vertigo, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new_readonly(pool.pool_owner, false));
@@ -612,7 +1012,7 @@ fn create_swap_instruction(
 
     // Add Heaven pools
     for pool in &mint_pool_data.heaven_pools {
-        accounts.push(AccountMeta::new_readonly(heaven_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.heaven, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false)); // V9: Add base mint
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new(pool.protocol_config, false)); // Protocol config is writable for Heaven
@@ -637,7 +1037,7 @@ fn create_swap_instruction(
 
     // Add Futarchy pools
     for pool in &mint_pool_data.futarchy_pools {
-        accounts.push(AccountMeta::new_readonly(futarchy_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.futarchy, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false));
         accounts.push(AccountMeta::new_readonly(pool.event_authority, false));
         accounts.push(AccountMeta::new(pool.dao, false));
@@ -647,7 +1047,7 @@ fn create_swap_instruction(
 
     // Add Humidifi pools
     for pool in &mint_pool_data.humidifi_pools {
-        accounts.push(AccountMeta::new_readonly(humidifi_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.humidifi, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false));
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new(pool.token_x_vault, false));
@@ -656,7 +1056,7 @@ fn create_swap_instruction(
 
     // Add PancakeSwap pools (CLMM layout)
     for pool in &mint_pool_data.pancakeswap_pools {
-        accounts.push(AccountMeta::new_readonly(pancakeswap_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.pancakeswap, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false));
         if let Some(memo_program) = pool.memo_program {
             accounts.push(AccountMeta::new_readonly(memo_program, false));
@@ -664,7 +1064,7 @@ fn create_swap_instruction(
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
         accounts.push(AccountMeta::new(pool.observation_state, false));
-        accounts.push(AccountMeta::new(pool.bitmap_extension, false));
+        accounts.push(bitmap_extension_meta(pool.bitmap_extension));
         accounts.push(AccountMeta::new(pool.x_vault, false));
         accounts.push(AccountMeta::new(pool.y_vault, false));
         for tick_array in &pool.tick_arrays {
@@ -674,7 +1074,7 @@ fn create_swap_instruction(
 
     // Add Byreal pools (CLMM layout)
     for pool in &mint_pool_data.byreal_pools {
-        accounts.push(AccountMeta::new_readonly(byreal_program_id(), false));
+        accounts.push(AccountMeta::new_readonly(registry.byreal, false));
         accounts.push(AccountMeta::new_readonly(pool.base_mint, false));
         if let Some(memo_program) = pool.memo_program {
             accounts.push(AccountMeta::new_readonly(memo_program, false));
@@ -682,7 +1082,7 @@ fn create_swap_instruction(
         accounts.push(AccountMeta::new(pool.pool, false));
         accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
         accounts.push(AccountMeta::new(pool.observation_state, false));
-        accounts.push(AccountMeta::new(pool.bitmap_extension, false));
+        accounts.push(bitmap_extension_meta(pool.bitmap_extension));
         accounts.push(AccountMeta::new(pool.x_vault, false));
         accounts.push(AccountMeta::new(pool.y_vault, false));
         for tick_array in &pool.tick_arrays {
@@ -690,22 +1090,358 @@ fn create_swap_instruction(
         }
     }
 
-    // Create instruction data
-    let mut data = vec![28u8];
+    accounts
+}
 
-    let minimum_profit: u64 = 0;
-    // When true, the bot will not fail the transaction even when it can't find a profitable arbitrage. It will just do nothing and succeed.
-    let no_failure_mode = false;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::meteora::dammv2_info::DammV2Fees;
+    use crate::loader::MarketPoolKind;
 
-    data.extend_from_slice(&minimum_profit.to_le_bytes());
-    data.extend_from_slice(&compute_unit_limit.to_le_bytes());
-    data.extend_from_slice(if no_failure_mode { &[1] } else { &[0] });
-    data.extend_from_slice(&0u16.to_le_bytes()); // reserved
-    data.extend_from_slice(if use_flashloan { &[1] } else { &[0] });
+    fn key() -> Pubkey {
+        Pubkey::new_unique()
+    }
 
-    Ok(Instruction {
-        program_id: executor_program_id,
-        accounts,
-        data,
-    })
+    /// Pool set of a fresh mint with one pool per DEX, two Raydium pools and two DLMM pairs
+    fn pool_data_with_every_dex() -> MintPoolData {
+        let mint = key();
+        let sol = sol_mint();
+        let mut data = MintPoolData::new(mint, &key(), token_program_id);
+        for _ in 0..2 {
+            data.add_raydium_pool(raydium_program_id(), key(), key(), key(), mint, sol, 4);
+        }
+        data.add_raydium_cp_pool(key(), key(), key(), key(), key(), key(), mint, sol, 0, 0);
+        data.add_pump_pool(
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            mint,
+            sol,
+            false,
+            false,
+            2,
+        );
+        for _ in 0..2 {
+            let bins = vec![key(), key(), key()];
+            data.add_dlmm_pool(key(), key(), key(), key(), Some(key()), bins, 10, None, mint, sol);
+        }
+        data.add_whirlpool_pool(key(), key(), key(), key(), vec![key(); 3], None, mint, sol);
+        data.add_raydium_clmm_pool(
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            vec![key(); 3],
+            None,
+            mint,
+            sol,
+            0,
+            0,
+            0,
+        );
+        data.add_meteora_damm_pool(
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            key(),
+            mint,
+            sol,
+        );
+        data.add_meteora_damm_v2_pool(key(), key(), key(), mint, sol, DammV2Fees::default());
+        data.add_vertigo_pool(key(), key(), key(), key(), mint, sol);
+        data.add_heaven_pool(key(), key(), key(), key(), mint, sol, token_program_id);
+        data.add_futarchy_pool(key(), key(), key(), key(), mint, sol);
+        data.add_humidifi_pool(key(), key(), key(), mint, sol);
+        let tick_arrays = vec![key(), key(), key()];
+        data.add_pancakeswap_pool(key(), key(), key(), key(), key(), tick_arrays, None, mint, sol);
+        let tick_arrays = vec![key(), key(), key()];
+        data.add_byreal_pool(key(), key(), key(), key(), key(), tick_arrays, None, mint, sol);
+        data
+    }
+
+    fn position(metas: &[AccountMeta], account: &Pubkey) -> usize {
+        let mut positions = metas
+            .iter()
+            .enumerate()
+            .filter(|(_, meta)| meta.pubkey == *account)
+            .map(|(i, _)| i);
+        let first = positions.next().expect("account missing from the metas");
+        assert!(positions.next().is_none(), "account {} passed twice", account);
+        first
+    }
+
+    #[test]
+    fn pool_vaults_follow_swap_account_meta_order() {
+        let data = pool_data_with_every_dex();
+        let metas = swap_account_metas(&data.wallet_account, &data, false, false);
+        let pools = data.pool_vaults();
+        assert_eq!(pools.len(), data.pool_count());
+
+        let positions: Vec<usize> = pools.iter().map(|pool| position(&metas, &pool.pool)).collect();
+        for (pair, pools) in positions.windows(2).zip(pools.windows(2)) {
+            assert!(
+                pair[0] < pair[1],
+                "{:?} pool {} comes after {:?} pool {} in the account metas",
+                pools[0].kind,
+                pools[0].pool,
+                pools[1].kind,
+                pools[1].pool
+            );
+        }
+        // Each pool's vaults follow the pool account itself
+        for pool in &pools {
+            let pool_position = position(&metas, &pool.pool);
+            assert!(position(&metas, &pool.token_vault) > pool_position);
+            assert!(position(&metas, &pool.base_vault) > pool_position);
+        }
+    }
+
+    /// Wallet, programs, base accounts and the mint's token accounts ahead of the pools, for
+    /// a SOL-based route without flashloan
+    const ROUTE_PREFIX_LEN: usize = 10;
+
+    const KINDS: [MarketPoolKind; 14] = [
+        MarketPoolKind::RaydiumV4,
+        MarketPoolKind::RaydiumCp,
+        MarketPoolKind::Pump,
+        MarketPoolKind::MeteoraDlmm,
+        MarketPoolKind::Whirlpool,
+        MarketPoolKind::RaydiumClmm,
+        MarketPoolKind::MeteoraDamm,
+        MarketPoolKind::MeteoraDammV2,
+        MarketPoolKind::Vertigo,
+        MarketPoolKind::Heaven,
+        MarketPoolKind::Futarchy,
+        MarketPoolKind::Humidifi,
+        MarketPoolKind::PancakeSwap,
+        MarketPoolKind::Byreal,
+    ];
+
+    /// Writable accounts of the route's pool section
+    fn writable_pool_accounts(data: &MintPoolData, readonly_bitmaps: bool) -> HashSet<Pubkey> {
+        swap_account_metas(&data.wallet_account, data, false, readonly_bitmaps)
+            .into_iter()
+            .skip(ROUTE_PREFIX_LEN)
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect()
+    }
+
+    /// Accounts each DEX's swap writes, and the bitmap extensions unless passed read-only
+    fn expected_writable(data: &MintPoolData, readonly_bitmaps: bool) -> HashSet<Pubkey> {
+        let mut writable = Vec::new();
+        for pool in &data.raydium_pools {
+            writable.extend([pool.pool, pool.token_vault, pool.sol_vault]);
+        }
+        for pool in &data.raydium_cp_pools {
+            writable.extend([pool.pool, pool.token_vault, pool.sol_vault, pool.observation]);
+        }
+        for pool in &data.pump_pools {
+            let pump = ProgramRegistry::global().pump;
+            let wallet = data.wallet_account;
+            writable.extend([
+                pool.fee_wallet,
+                pool.pool,
+                pool.token_vault,
+                pool.sol_vault,
+                pool.fee_token_wallet,
+                pool.coin_creator_vault_ata,
+                find_program_address(&[b"user_volume_accumulator", wallet.as_ref()], &pump).0,
+                derive_pump_fee_recipient_quote_ata(
+                    &pool.fee_wallet,
+                    &pool.fee_token_wallet,
+                    &pool.base_mint,
+                    &token_program_id,
+                    &data.mint,
+                    &data.token_program,
+                ),
+            ]);
+        }
+        for pair in &data.dlmm_pairs {
+            writable.extend([pair.pair, pair.token_vault, pair.sol_vault, pair.oracle]);
+            writable.extend(pair.bin_arrays.iter().copied());
+            if !readonly_bitmaps {
+                writable.extend(pair.bin_array_bitmap_extension);
+            }
+        }
+        for pool in &data.whirlpool_pools {
+            writable.extend([pool.pool, pool.oracle, pool.x_vault, pool.y_vault]);
+            writable.extend(pool.tick_arrays.iter().copied());
+        }
+        macro_rules! clmm_writable {
+            ($pools:expr) => {
+                for pool in &$pools {
+                    let state = pool.observation_state;
+                    writable.extend([pool.pool, state, pool.x_vault, pool.y_vault]);
+                    writable.extend(pool.tick_arrays.iter().copied());
+                    if !readonly_bitmaps {
+                        writable.push(pool.bitmap_extension);
+                    }
+                }
+            };
+        }
+        clmm_writable!(data.raydium_clmm_pools);
+        clmm_writable!(data.pancakeswap_pools);
+        clmm_writable!(data.byreal_pools);
+        for pool in &data.meteora_damm_pools {
+            writable.extend([
+                pool.pool,
+                pool.token_x_vault,
+                pool.token_sol_vault,
+                pool.token_x_token_vault,
+                pool.token_sol_token_vault,
+                pool.token_x_lp_mint,
+                pool.token_sol_lp_mint,
+                pool.token_x_pool_lp,
+                pool.token_sol_pool_lp,
+                pool.admin_token_fee_x,
+                pool.admin_token_fee_sol,
+            ]);
+        }
+        for pool in &data.meteora_damm_v2_pools {
+            writable.extend([pool.pool, pool.token_x_vault, pool.token_sol_vault]);
+        }
+        for pool in &data.vertigo_pools {
+            writable.extend([pool.pool, pool.token_x_vault, pool.token_sol_vault]);
+        }
+        for pool in &data.heaven_pools {
+            let config = pool.protocol_config;
+            writable.extend([pool.pool, config, pool.token_x_vault, pool.token_base_vault]);
+        }
+        for pool in &data.futarchy_pools {
+            writable.extend([pool.dao, pool.token_x_vault, pool.token_base_vault]);
+        }
+        for pool in &data.humidifi_pools {
+            writable.extend([pool.pool, pool.token_x_vault, pool.token_sol_vault]);
+        }
+        writable.into_iter().collect()
+    }
+
+    #[test]
+    fn writable_accounts_per_pool_type() {
+        let every_dex = pool_data_with_every_dex();
+        for kind in KINDS {
+            let mut data = every_dex.clone();
+            data.retain_pools(|pool| pool.kind == kind);
+            assert!(data.pool_count() > 0, "no {:?} pool in the fixture", kind);
+            for readonly_bitmaps in [false, true] {
+                assert_eq!(
+                    writable_pool_accounts(&data, readonly_bitmaps),
+                    expected_writable(&data, readonly_bitmaps),
+                    "{:?} pools, readonly_bitmap_extensions {}",
+                    kind,
+                    readonly_bitmaps
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bitmap_extensions_are_writable_unless_opted_out() {
+        let data = pool_data_with_every_dex();
+        let bitmap_extensions: Vec<Pubkey> = data
+            .dlmm_pairs
+            .iter()
+            .filter_map(|pair| pair.bin_array_bitmap_extension)
+            .chain(data.raydium_clmm_pools.iter().map(|pool| pool.bitmap_extension))
+            .chain(data.pancakeswap_pools.iter().map(|pool| pool.bitmap_extension))
+            .chain(data.byreal_pools.iter().map(|pool| pool.bitmap_extension))
+            .collect();
+        assert_eq!(bitmap_extensions.len(), 5);
+        for (readonly_bitmaps, writable) in [(false, true), (true, false)] {
+            let metas = swap_account_metas(&data.wallet_account, &data, false, readonly_bitmaps);
+            for bitmap_extension in &bitmap_extensions {
+                assert_eq!(metas[position(&metas, bitmap_extension)].is_writable, writable);
+            }
+        }
+    }
+
+    #[test]
+    fn force_writable_only_marks_listed_accounts() {
+        let data = pool_data_with_every_dex();
+        let mut metas = swap_account_metas(&data.wallet_account, &data, false, true);
+        let amm_config = data.raydium_cp_pools[0].amm_config;
+        let bitmap_extension = data.raydium_clmm_pools[0].bitmap_extension;
+        let before: Vec<bool> = metas.iter().map(|meta| meta.is_writable).collect();
+        force_writable(&mut metas, &HashSet::from([amm_config, bitmap_extension]));
+        for (meta, was_writable) in metas.iter().zip(before) {
+            let forced = meta.pubkey == amm_config || meta.pubkey == bitmap_extension;
+            assert_eq!(meta.is_writable, was_writable || forced, "{}", meta.pubkey);
+        }
+        assert!(metas[position(&metas, &amm_config)].is_writable);
+    }
+
+    fn config(executor: &str) -> Config {
+        let mut config: Config = toml::from_str(&format!(
+            "[bot]\ncompute_unit_limit = 400000\n\
+             [routing.markets]\nmarkets = []\nprocess_delay = 1000\n\
+             [rpc]\nurl = \"http://127.0.0.1:8899\"\n\
+             [wallet]\n{}",
+            executor
+        ))
+        .unwrap();
+        config.validate().unwrap();
+        config
+    }
+
+    #[test]
+    fn route_account_metas_match_the_sent_instruction() {
+        let data = pool_data_with_every_dex();
+        let amm_config = data.raydium_cp_pools[0].amm_config;
+        let executor = format!(
+            "[executor]\nreadonly_bitmap_extensions = true\nforce_writable = [\"{}\"]\n\
+             [flashloan]\nenabled = true",
+            amm_config
+        );
+        for config in [config(""), config(&executor)] {
+            let swap_ix =
+                build_swap_instruction_for(&data.wallet_account, &config, &data, None).unwrap();
+            let metas = build_route_account_metas(&config, &data).unwrap();
+            assert_eq!(metas[0].pubkey, solana_sdk::compute_budget::ID);
+            assert_eq!(metas[1].pubkey, executor_program_id());
+            assert_eq!(metas.len(), swap_ix.accounts.len() + 2);
+            // Fee collectors and flashloan vaults are picked anew for every instruction
+            let rotating = rotating_route_accounts(&data);
+            for (meta, sent) in metas[2..].iter().zip(&swap_ix.accounts) {
+                if rotating.contains(&sent.pubkey) {
+                    assert!(rotating.contains(&meta.pubkey), "{}", meta.pubkey);
+                    assert_eq!(meta.is_writable, sent.is_writable, "{}", meta.pubkey);
+                } else {
+                    assert_eq!(meta, sent);
+                }
+            }
+        }
+
+        let config = config(&executor);
+        let metas = build_route_account_metas(&config, &data).unwrap();
+        let bitmap_extension = data.raydium_clmm_pools[0].bitmap_extension;
+        assert!(metas[position(&metas, &amm_config)].is_writable);
+        assert!(!metas[position(&metas, &bitmap_extension)].is_writable);
+    }
+
+    #[test]
+    fn pool_flags_encode_one_byte_per_pool_vault() {
+        let data = pool_data_with_every_dex();
+        let flags = crate::ix_data::pool_flags_from_snapshot(&data, None);
+        let ix =
+            create_swap_instruction(&data.wallet_account, &data, 400_000, false, false, Some(flags))
+                .unwrap();
+        let decoded = ArbIxData::from_bytes(&ix.data).unwrap();
+        assert_eq!(decoded.pool_flags.unwrap().len(), data.pool_vaults().len());
+    }
 }
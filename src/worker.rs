@@ -0,0 +1,932 @@
+use crate::account_batcher::{AccountBatcher, MAX_MULTIPLE_ACCOUNTS};
+use crate::adaptive_delay::AdaptiveDelay;
+use crate::blockhash::{BlockhashHealth, CachedBlockhash, DEFAULT_BLOCKHASH_GRACE};
+use crate::config::Config;
+use crate::confirmation::{LandedTransaction, LandingTracker};
+use crate::ix_data::{pool_flags_from_snapshot, PoolFlags};
+use crate::dex::raydium::clmm_info::TickArrayBitmapExtensionState;
+use crate::dex::raydium::parse_bitmap_extension;
+use crate::executor_errors::{triggers_pool_refresh, ExecutorErrors, FailureSource};
+use crate::geyser::{streamed_accounts, PoolAccountUpdate};
+use crate::journal::SendJournal;
+use crate::log_dedup::LogDedup;
+use crate::migration::MigrationWatcher;
+use crate::observations::ObservationSink;
+use crate::paper_trade::PaperLedger;
+use crate::refresher::{recompute_from_account, PoolDataRefresher};
+use crate::pools::{MintPoolData, PoolVaults};
+use crate::pricing::{fetch_price_snapshot, PriceSnapshot};
+use crate::risk::DailyLossBreaker;
+use crate::status::StatusRegistry;
+use crate::prefilter::{simulate_transaction, PrefilterStats, SimulationOutcome};
+use crate::rpc_budget::{refresh_cost, IterationBudget, RpcBudget};
+use crate::transaction::{build_transaction, send_transaction, SendingClient};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{watch, Mutex, Semaphore};
+use tracing::{debug, error, info, warn, Level};
+
+const POOL_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+/// Pricing older than this no longer drives per-pool flags
+const PRICES_MAX_AGE: Duration = Duration::from_secs(15);
+const PREFILTER_LOG_EVERY: u64 = 100;
+/// Minimum time between failure-triggered pool refreshes of a mint
+const TRIGGERED_REFRESH_MIN_INTERVAL: Duration = Duration::from_secs(2);
+/// Default of `routing.markets.delay_jitter_pct`
+const DEFAULT_DELAY_JITTER_PCT: f64 = 20.0;
+
+/// State shared by every mint worker
+#[derive(Clone)]
+pub struct WorkerContext {
+    pub config: Config,
+    pub wallet_kp: Arc<Keypair>,
+    pub rpc_client: Arc<RpcClient>,
+    pub sending_rpc_clients: Vec<SendingClient>,
+    pub cached_blockhash: Arc<Mutex<CachedBlockhash>>,
+    pub blockhash_health: watch::Receiver<BlockhashHealth>,
+    pub lookup_tables: Arc<Vec<AddressLookupTableAccount>>,
+    pub landing_tracker: LandingTracker,
+    pub loss_breaker: Option<Arc<DailyLossBreaker>>,
+    pub observation_sink: Option<ObservationSink>,
+    pub status: Option<StatusRegistry>,
+    /// Set in paper trading mode: opportunities are booked here and nothing is sent
+    pub paper_ledger: Option<PaperLedger>,
+    pub rpc_budget: Option<Arc<RpcBudget>>,
+    /// Set when `[journal]` is configured
+    pub send_journal: Option<SendJournal>,
+    /// In-flight slots of the wallet, shared by its mints; set by
+    /// `bot.max_in_flight_per_wallet`
+    pub in_flight: Option<Arc<Semaphore>>,
+}
+
+/// Refresh and send loop for a single mint
+pub struct MintWorker {
+    mint: Pubkey,
+    pool_data: MintPoolData,
+    ctx: WorkerContext,
+    pool_refresher: PoolDataRefresher,
+    last_pool_refresh: Option<Instant>,
+    last_triggered_refresh: Option<Instant>,
+    process_delay: Duration,
+    adaptive_delay: Option<AdaptiveDelay>,
+    /// `adaptive_delay.spread_threshold_bps`, when the adaptive delay is enabled
+    spread_threshold_bps: Option<f64>,
+    /// When the pricing last checked against `spread_threshold_bps` was taken
+    spread_checked_at: Option<Instant>,
+    /// Largest random change of a delay, as a fraction of it
+    delay_jitter: f64,
+    post_land_cooldown: Option<Duration>,
+    cooldown_until: Option<Instant>,
+    /// Iterations skipped in post-land cooldowns since startup
+    cooldown_skips: u64,
+    /// `cooldown_skips` when the current cooldown started
+    cooldown_started_skips: u64,
+    latest_prices: Arc<StdMutex<Option<PriceSnapshot>>>,
+    prefilter_stats: Option<PrefilterStats>,
+    migration: Option<MigrationWatcher>,
+    /// RPC calls of the current iteration, when a budget is configured
+    iteration_budget: Option<IterationBudget>,
+    iteration_started: Instant,
+    evaluation_timeout: Option<Duration>,
+    last_refreshed: HashMap<Pubkey, Instant>,
+    /// Pool account updates from the Geyser stream, when configured
+    geyser_rx: Option<UnboundedReceiver<PoolAccountUpdate>>,
+    bitmap_extensions: HashMap<Pubkey, TickArrayBitmapExtensionState>,
+    landed_tx: UnboundedSender<LandedTransaction>,
+    landed_rx: UnboundedReceiver<LandedTransaction>,
+}
+
+impl MintWorker {
+    /// Worker for `mint` trading `pool_data`, with the delays of `ctx.config`
+    pub fn new(mint: Pubkey, pool_data: MintPoolData, ctx: WorkerContext) -> Self {
+        let markets = &ctx.config.routing.markets;
+        let process_delay = Duration::from_millis(markets.process_delay);
+        let adaptive_delay = markets
+            .adaptive_delay
+            .as_ref()
+            .filter(|adaptive| adaptive.enabled)
+            .map(|adaptive| AdaptiveDelay::new(adaptive, process_delay));
+        let spread_threshold_bps = markets
+            .adaptive_delay
+            .as_ref()
+            .filter(|adaptive| adaptive.enabled)
+            .and_then(|adaptive| adaptive.spread_threshold_bps);
+        let delay_jitter =
+            markets.delay_jitter_pct.unwrap_or(DEFAULT_DELAY_JITTER_PCT).clamp(0.0, 100.0) / 100.0;
+        let post_land_cooldown = ctx
+            .config
+            .routing
+            .post_land_cooldown_ms
+            .map(Duration::from_millis);
+        let evaluation_timeout = ctx
+            .config
+            .routing
+            .evaluation_timeout_ms
+            .map(Duration::from_millis);
+        let prefilter_stats = ctx
+            .config
+            .prefilter
+            .as_ref()
+            .filter(|prefilter| prefilter.simulates(&mint))
+            .map(|_| PrefilterStats::default());
+        // Validated at startup
+        let migration = MigrationWatcher::from_config(&ctx.config).ok().flatten();
+        let (landed_tx, landed_rx) = unbounded_channel();
+
+        Self {
+            mint,
+            pool_data,
+            ctx,
+            pool_refresher: PoolDataRefresher::new(),
+            last_pool_refresh: None,
+            last_triggered_refresh: None,
+            process_delay,
+            adaptive_delay,
+            spread_threshold_bps,
+            spread_checked_at: None,
+            delay_jitter,
+            post_land_cooldown,
+            cooldown_until: None,
+            cooldown_skips: 0,
+            cooldown_started_skips: 0,
+            latest_prices: Arc::default(),
+            prefilter_stats,
+            migration,
+            iteration_budget: None,
+            iteration_started: Instant::now(),
+            evaluation_timeout,
+            last_refreshed: HashMap::new(),
+            geyser_rx: None,
+            bitmap_extensions: HashMap::new(),
+            landed_tx,
+            landed_rx,
+        }
+    }
+
+    /// Mint this worker trades
+    pub fn mint(&self) -> &Pubkey {
+        &self.mint
+    }
+
+    /// Refreshes the pools once before the send loop starts and warns about bin and tick
+    /// arrays that do not exist, so the first send already uses current arrays
+    pub fn warm_pools(&mut self) {
+        if !self.refresh_pools() {
+            return;
+        }
+        let pool_data = &self.pool_data;
+        let arrays: Vec<Pubkey> = pool_data
+            .dlmm_pairs
+            .iter()
+            .flat_map(|pool| pool.bin_arrays.iter())
+            .chain(pool_data.whirlpool_pools.iter().flat_map(|pool| pool.tick_arrays.iter()))
+            .chain(pool_data.raydium_clmm_pools.iter().flat_map(|pool| pool.tick_arrays.iter()))
+            .chain(pool_data.pancakeswap_pools.iter().flat_map(|pool| pool.tick_arrays.iter()))
+            .chain(pool_data.byreal_pools.iter().flat_map(|pool| pool.tick_arrays.iter()))
+            .copied()
+            .collect();
+        if arrays.is_empty() {
+            return;
+        }
+        match AccountBatcher::global().get_multiple_accounts(&self.ctx.rpc_client, &arrays) {
+            Ok(accounts) => {
+                let missing = accounts.iter().filter(|account| account.is_none()).count();
+                if missing > 0 {
+                    warn!(
+                        "{} of {} bin/tick arrays of mint {} do not exist after warm-up",
+                        missing,
+                        arrays.len(),
+                        self.mint
+                    );
+                }
+            }
+            Err(e) => warn!("Failed to check bin/tick arrays of mint {}: {}", self.mint, e),
+        }
+    }
+
+    /// Delay to wait before the next iteration
+    pub fn next_delay(&self) -> Duration {
+        self.adaptive_delay
+            .as_ref()
+            .map(|adaptive| adaptive.current())
+            .unwrap_or(self.process_delay)
+    }
+
+    /// `next_delay` moved at random by up to `delay_jitter_pct` in either direction, so
+    /// mints with the same delay drift apart instead of refreshing in lockstep
+    pub fn jittered_delay(&self) -> Duration {
+        jitter(self.next_delay(), self.delay_jitter)
+    }
+
+    /// Random wait before the first iteration, up to `delay_jitter_pct` of the delay
+    pub fn initial_offset(&self) -> Duration {
+        self.process_delay.mul_f64(self.delay_jitter * rand::random::<f64>())
+    }
+
+    /// Runs iterations forever, sleeping the jittered delay between them
+    pub async fn run(mut self) {
+        tokio::time::sleep(self.initial_offset()).await;
+        loop {
+            self.run_once().await;
+            tokio::time::sleep(self.jittered_delay()).await;
+        }
+    }
+
+    /// Runs a single refresh and send iteration
+    pub async fn run_once(&mut self) {
+        self.iteration_started = Instant::now();
+        self.iteration_budget = self.ctx.rpc_budget.as_ref().map(RpcBudget::start_iteration);
+        self.drain_landed_transactions();
+        self.check_spread();
+        self.apply_geyser_updates();
+        self.publish_status();
+
+        if let Some(until) = self.cooldown_until {
+            if Instant::now() < until {
+                self.cooldown_skips += 1;
+                if let Some(status) = &self.ctx.status {
+                    let skips = self.cooldown_skips;
+                    status.update(&self.mint, |mint_status| mint_status.cooldown_skips = skips);
+                }
+                return;
+            }
+            info!(
+                "Post-land cooldown ended for mint {} ({} iterations skipped, {} in total)",
+                self.mint,
+                self.cooldown_skips - self.cooldown_started_skips,
+                self.cooldown_skips
+            );
+            self.cooldown_until = None;
+            // Pool state has moved since we landed, refresh before sending again
+            self.last_pool_refresh = None;
+        }
+
+        let refreshed = self.refresh_pools_if_due();
+        if refreshed {
+            if let Some(migration) = self.migration.as_mut() {
+                migration
+                    .check(&mut self.pool_data, self.ctx.rpc_client.clone())
+                    .await;
+            }
+        }
+        if self.pool_data.pool_count() == 0 {
+            debug!("No pools left for mint {}, nothing to send", self.mint);
+            return;
+        }
+        self.price_pools(refreshed);
+        if self.ctx.paper_ledger.is_some() {
+            return;
+        }
+
+        if let Some(loss_breaker) = &self.ctx.loss_breaker {
+            if loss_breaker.is_paused() {
+                debug!("Sending paused for mint {} by the daily loss breaker", self.mint);
+                return;
+            }
+        }
+
+        if self.evaluation_expired() {
+            return;
+        }
+        self.send().await;
+    }
+
+    /// Whether this iteration's evaluation ran past `routing.evaluation_timeout_ms`, in which
+    /// case the prices and blockhash it would send with are too old to be worth it
+    fn evaluation_expired(&self) -> bool {
+        let Some(timeout) = self.evaluation_timeout else {
+            return false;
+        };
+        let elapsed = self.iteration_started.elapsed();
+        if elapsed <= timeout {
+            return false;
+        }
+        info!(
+            "Evaluation of mint {} took {}ms, over the {}ms timeout; skipping send",
+            self.mint,
+            elapsed.as_millis(),
+            timeout.as_millis()
+        );
+        true
+    }
+
+    /// Returns true when the pools were refreshed in this call
+    fn refresh_pools_if_due(&mut self) -> bool {
+        let due = self
+            .last_pool_refresh
+            .map_or(true, |last| last.elapsed() >= POOL_REFRESH_INTERVAL);
+        due && self.refresh_pools()
+    }
+
+    /// Refreshes the pools right away after a failure pointing at stale pool accounts,
+    /// at most once per `TRIGGERED_REFRESH_MIN_INTERVAL`
+    fn trigger_pool_refresh(&mut self, reason: &str) {
+        if self
+            .last_triggered_refresh
+            .map_or(false, |last| last.elapsed() < TRIGGERED_REFRESH_MIN_INTERVAL)
+        {
+            debug!("Pool refresh for mint {} after {} rate limited", self.mint, reason);
+            return;
+        }
+        info!("Refreshing pools of mint {} after a {} failure", self.mint, reason);
+        self.last_triggered_refresh = Some(Instant::now());
+        ExecutorErrors::global().record_triggered_refresh(reason);
+        self.refresh_pools();
+    }
+
+    /// Returns true when the refresh succeeded
+    fn refresh_pools(&mut self) -> bool {
+        let now = Instant::now();
+        let deferred = self.deferred_pools();
+        match self.pool_refresher.refresh_all_pools(
+            &mut self.pool_data,
+            &self.ctx.rpc_client,
+            false,
+            &deferred,
+        ) {
+            Ok(_) => {
+                self.last_pool_refresh = Some(now);
+                for pool in self.pool_data.pool_vaults() {
+                    if !deferred.contains(&pool.pool) {
+                        self.last_refreshed.insert(pool.pool, now);
+                    }
+                }
+                if deferred.is_empty() {
+                    info!("Pool data refreshed for mint {}", self.mint);
+                } else {
+                    info!(
+                        "Pool data refreshed for mint {}, {} pools deferred by the RPC budget",
+                        self.mint,
+                        deferred.len()
+                    );
+                }
+                true
+            }
+            Err(e) => {
+                static REFRESH_ERRORS: LogDedup = LogDedup::new(Level::ERROR);
+                REFRESH_ERRORS.log(
+                    &self.mint.to_string(),
+                    format_args!("Failed to refresh pool data for mint {}: {}", self.mint, e),
+                );
+                false
+            }
+        }
+    }
+
+    /// Pools whose refresh is deferred to stay within the RPC budget. The most liquid pools
+    /// by last priced base reserve are refreshed first; ties, including pools without a
+    /// price, go to the pool refreshed longest ago so none is starved.
+    fn deferred_pools(&mut self) -> HashSet<Pubkey> {
+        let Some(iteration) = self.iteration_budget.as_mut() else {
+            return HashSet::new();
+        };
+        let reserves: HashMap<Pubkey, u64> = self
+            .latest_prices
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|snapshot| {
+                snapshot
+                    .prices
+                    .iter()
+                    .map(|price| (price.pool, price.base_reserve))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut pools: Vec<PoolVaults> = self
+            .pool_data
+            .pool_vaults()
+            .into_iter()
+            .filter(|pool| refresh_cost(pool.kind) > 0)
+            .collect();
+        pools.sort_by_key(|pool| {
+            (
+                Reverse(reserves.get(&pool.pool).copied().unwrap_or(0)),
+                self.last_refreshed.get(&pool.pool).copied(),
+            )
+        });
+
+        let deferred: HashSet<Pubkey> = pools
+            .into_iter()
+            .filter(|pool| !iteration.try_spend(refresh_cost(pool.kind)))
+            .map(|pool| pool.pool)
+            .collect();
+        iteration.record_deferred(deferred.len());
+        deferred
+    }
+
+    /// Prices the pools off the worker's critical path for the observation sink
+    /// (when sampled) and the paper ledger, and once per pool refresh for the status
+    /// endpoint and pool flags
+    fn price_pools(&mut self, refreshed: bool) {
+        let sink = self
+            .ctx
+            .observation_sink
+            .as_ref()
+            .filter(|sink| sink.should_sample())
+            .cloned();
+        let status = self.ctx.status.as_ref().filter(|_| refreshed).cloned();
+        let latest_prices = (refreshed
+            && (self.ctx.config.encodes_pool_flags()
+                || self.iteration_budget.is_some()
+                || self.spread_threshold_bps.is_some()))
+        .then(|| self.latest_prices.clone());
+        let paper_ledger = self.ctx.paper_ledger.clone();
+        if sink.is_none() && status.is_none() && latest_prices.is_none() && paper_ledger.is_none()
+        {
+            return;
+        }
+
+        if let Some(iteration) = self.iteration_budget.as_mut() {
+            let vaults = self.pool_data.pool_count() as u64 * 2;
+            iteration.record(vaults.div_ceil(MAX_MULTIPLE_ACCOUNTS as u64));
+        }
+        let pool_data = self.pool_data.clone();
+        let rpc_client = self.ctx.rpc_client.clone();
+        tokio::task::spawn_blocking(move || {
+            match fetch_price_snapshot(&rpc_client, &pool_data) {
+                Ok(snapshot) => {
+                    if let Some(sink) = sink {
+                        sink.record(&snapshot);
+                    }
+                    if let Some(paper_ledger) = paper_ledger {
+                        paper_ledger.record(&snapshot);
+                    }
+                    if let Some(latest_prices) = latest_prices {
+                        *latest_prices.lock().unwrap() = Some(snapshot.clone());
+                    }
+                    if let Some(status) = status {
+                        status.publish_prices(snapshot);
+                    }
+                }
+                Err(e) => debug!("Failed to price pools for mint {}: {}", pool_data.mint, e),
+            }
+        });
+    }
+
+    fn publish_status(&self) {
+        let Some(status) = &self.ctx.status else {
+            return;
+        };
+        let delay_ms = self.next_delay().as_millis() as u64;
+        let delay_reason = self
+            .adaptive_delay
+            .as_ref()
+            .map(|adaptive| adaptive.reason().to_string());
+        let cooldown_active = self
+            .cooldown_until
+            .map_or(false, |until| Instant::now() < until);
+        status.update(&self.mint, |mint_status| {
+            mint_status.delay_ms = delay_ms;
+            mint_status.delay_reason = delay_reason;
+            mint_status.cooldown_active = cooldown_active;
+        });
+    }
+
+    async fn send(&mut self) {
+        let grace = self
+            .ctx
+            .config
+            .rpc
+            .blockhash_grace_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_BLOCKHASH_GRACE);
+        if self.ctx.blockhash_health.borrow().is_stale(grace) {
+            debug!("Blockhash refresher is failing, skipping send for mint {}", self.mint);
+            return;
+        }
+
+        let blockhash = {
+            let guard = self.ctx.cached_blockhash.lock().await;
+            *guard
+        };
+
+        let stale = self.stale_pools(blockhash.slot_at(SystemTime::now()));
+        let fresh_pools;
+        let pool_data = if stale.is_empty() {
+            &self.pool_data
+        } else if self.ctx.config.routing.skip_stale_pools {
+            let mut fresh = self.pool_data.clone();
+            fresh.retain_pools(|pool| !stale.contains(&pool.pool));
+            if fresh.pool_count() < 2 {
+                debug!("Fewer than two fresh pools for mint {}, skipping send", self.mint);
+                return;
+            }
+            fresh_pools = fresh;
+            &fresh_pools
+        } else {
+            warn!(
+                "{} pools of mint {} have bin/tick arrays older than the slot lag limit",
+                stale.len(),
+                self.mint
+            );
+            &self.pool_data
+        };
+
+        // Held until the send lands or times out; dropped on every early return
+        let in_flight = match &self.ctx.in_flight {
+            Some(in_flight) => match in_flight.clone().try_acquire_owned() {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    debug!(
+                        "Wallet {} has its maximum of transactions in flight, skipping send for \
+                         mint {}",
+                        self.ctx.wallet_kp.pubkey(),
+                        self.mint
+                    );
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        let tx = match build_transaction(
+            &self.ctx.wallet_kp,
+            &self.ctx.config,
+            pool_data,
+            blockhash.hash,
+            &self.ctx.lookup_tables,
+            self.pool_flags(pool_data),
+        ) {
+            Ok(tx) => tx,
+            Err(e) => {
+                error!("Error building transaction for mint {}: {}", self.mint, e);
+                self.record_send_failure();
+                return;
+            }
+        };
+
+        // The simulation round trip counts towards the evaluation timeout
+        if self.prefilter_stats.is_some()
+            && (!self.passes_prefilter(&tx) || self.evaluation_expired())
+        {
+            return;
+        }
+
+        let report = send_transaction(
+            &self.ctx.config,
+            &tx,
+            &self.ctx.sending_rpc_clients,
+            &blockhash,
+        )
+        .await;
+        let signatures = report.signatures();
+        if let Some(iteration) = self.iteration_budget.as_mut() {
+            let sends: u32 = self.ctx.sending_rpc_clients.iter().map(|c| c.weight).sum();
+            iteration.record(sends as u64);
+        }
+        info!("Transactions sent successfully for mint {}", self.mint);
+        if signatures.is_empty() {
+            self.record_send_failure();
+        } else if let Some(adaptive) = self.adaptive_delay.as_mut() {
+            adaptive.on_sent();
+        }
+        for signature in &signatures {
+            info!("  Signature: {}", signature);
+        }
+        if let Some(journal) = &self.ctx.send_journal {
+            journal.record_send(&self.mint, &report);
+        }
+        if self.tracks_landings() {
+            self.ctx
+                .landing_tracker
+                .track(self.mint, report, self.landed_tx.clone(), in_flight);
+        }
+    }
+
+    /// Simulates the transaction and returns whether it should be broadcast.
+    /// Simulation RPC errors let the send through so the prefilter never blocks trading.
+    fn passes_prefilter(&mut self, tx: &VersionedTransaction) -> bool {
+        let started = Instant::now();
+        let outcome = simulate_transaction(&self.ctx.rpc_client, tx);
+        let latency = started.elapsed();
+        if let Some(iteration) = self.iteration_budget.as_mut() {
+            iteration.record(1);
+        }
+        let Some(stats) = self.prefilter_stats.as_mut() else {
+            return true;
+        };
+        let mut refresh_reason = None;
+
+        let passes = match outcome {
+            Ok(SimulationOutcome::Profitable { units_consumed }) => {
+                debug!(
+                    "Simulation for mint {} succeeded in {}ms ({:?} CU)",
+                    self.mint,
+                    latency.as_millis(),
+                    units_consumed
+                );
+                stats.record(latency, false);
+                true
+            }
+            Ok(SimulationOutcome::NoRoute {
+                error,
+                reason,
+                last_log,
+            }) => {
+                debug!(
+                    "Simulation for mint {} found no route: {} ({}, {})",
+                    self.mint,
+                    error,
+                    reason,
+                    last_log.unwrap_or_default()
+                );
+                ExecutorErrors::global().record(FailureSource::Simulation, &reason);
+                refresh_reason = Some(reason).filter(|reason| triggers_pool_refresh(reason));
+                stats.record(latency, true);
+                false
+            }
+            Err(e) => {
+                warn!("Simulation failed for mint {}: {}", self.mint, e);
+                stats.record_error(latency);
+                true
+            }
+        };
+
+        let stats = stats.clone();
+        if (stats.simulated + stats.simulation_errors) % PREFILTER_LOG_EVERY == 0 {
+            info!(
+                "Prefilter for mint {}: {} simulated, {} sends saved, {} errors, avg {}ms",
+                self.mint,
+                stats.simulated,
+                stats.sends_saved,
+                stats.simulation_errors,
+                stats.average_latency().as_millis()
+            );
+        }
+        if let Some(reason) = refresh_reason {
+            self.trigger_pool_refresh(&reason);
+        }
+        if !passes {
+            if let Some(adaptive) = self.adaptive_delay.as_mut() {
+                if adaptive.on_no_profit() {
+                    log_adaptive_delay(&self.mint, adaptive);
+                }
+            }
+        }
+        if let Some(status) = &self.ctx.status {
+            status.update(&self.mint, |mint_status| mint_status.prefilter = Some(stats));
+        }
+        passes
+    }
+
+    /// Pools whose bin/tick arrays were computed more than `routing.max_pool_slot_lag` slots
+    /// before `current_slot`, including pools not refreshed since startup
+    fn stale_pools(&self, current_slot: u64) -> HashSet<Pubkey> {
+        let Some(max_lag) = self.ctx.config.routing.max_pool_slot_lag else {
+            return HashSet::new();
+        };
+        self.pool_data
+            .array_refresh_slots()
+            .into_iter()
+            .filter(|(_, slot)| current_slot.saturating_sub(*slot) > max_lag)
+            .map(|(pool, _)| pool)
+            .collect()
+    }
+
+    /// Per-pool flags for executors that accept them, from the latest fresh pricing
+    fn pool_flags(&self, pool_data: &MintPoolData) -> Option<Vec<PoolFlags>> {
+        if !self.ctx.config.encodes_pool_flags() {
+            return None;
+        }
+        let latest_prices = self.latest_prices.lock().unwrap();
+        let snapshot = latest_prices
+            .as_ref()
+            .filter(|snapshot| snapshot.taken_at.elapsed() <= PRICES_MAX_AGE);
+        Some(pool_flags_from_snapshot(pool_data, snapshot))
+    }
+
+    /// Landings are only polled for when something consumes them
+    fn tracks_landings(&self) -> bool {
+        self.ctx.in_flight.is_some()
+            || self.post_land_cooldown.is_some()
+            || self.adaptive_delay.is_some()
+            || self.ctx.landing_tracker.tracks_pnl()
+            || self.ctx.landing_tracker.tracks_journal()
+    }
+
+    /// Routes Geyser updates of this mint's pool accounts to the worker. The current tick
+    /// array bitmap extensions are fetched once; later changes arrive through the stream.
+    pub fn subscribe_geyser(
+        &mut self,
+        routes: &mut HashMap<Pubkey, UnboundedSender<PoolAccountUpdate>>,
+    ) {
+        let (geyser_tx, geyser_rx) = unbounded_channel();
+        for account in streamed_accounts(&self.pool_data) {
+            routes.insert(account, geyser_tx.clone());
+        }
+        self.geyser_rx = Some(geyser_rx);
+
+        let extensions = self.bitmap_extension_accounts();
+        match AccountBatcher::global().get_multiple_accounts(&self.ctx.rpc_client, &extensions) {
+            Ok(accounts) => {
+                for (extension, account) in extensions.into_iter().zip(accounts) {
+                    if let Some(state) = account.and_then(|a| parse_bitmap_extension(&a.data)) {
+                        self.bitmap_extensions.insert(extension, state);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to fetch bitmap extensions of mint {}: {}", self.mint, e),
+        }
+    }
+
+    fn bitmap_extension_accounts(&self) -> Vec<Pubkey> {
+        let pool_data = &self.pool_data;
+        pool_data
+            .raydium_clmm_pools
+            .iter()
+            .map(|pool| pool.bitmap_extension)
+            .chain(pool_data.pancakeswap_pools.iter().map(|pool| pool.bitmap_extension))
+            .chain(pool_data.byreal_pools.iter().map(|pool| pool.bitmap_extension))
+            .collect()
+    }
+
+    /// Applies pending Geyser account updates to the pool state
+    fn apply_geyser_updates(&mut self) {
+        if self.geyser_rx.is_none() {
+            return;
+        }
+        let extensions = self.bitmap_extension_accounts();
+        let Some(geyser_rx) = self.geyser_rx.as_mut() else {
+            return;
+        };
+        while let Ok(update) = geyser_rx.try_recv() {
+            if extensions.contains(&update.pubkey) {
+                if let Some(state) = parse_bitmap_extension(&update.data) {
+                    self.bitmap_extensions.insert(update.pubkey, state);
+                }
+                continue;
+            }
+            match recompute_from_account(
+                &mut self.pool_data,
+                &self.pool_refresher.program_ids,
+                &update.pubkey,
+                &update.owner,
+                &update.data,
+                &self.bitmap_extensions,
+            ) {
+                Ok(true) => self.pool_data.mark_refreshed(&update.pubkey, update.slot),
+                Ok(false) => {}
+                Err(e) => warn!(
+                    "Failed to apply Geyser update of {} at slot {}: {}",
+                    update.pubkey, update.slot, e
+                ),
+            }
+        }
+    }
+
+    fn drain_landed_transactions(&mut self) {
+        while let Ok(landed) = self.landed_rx.try_recv() {
+            if !landed.succeeded {
+                let refresh_reason = landed.failure.filter(|reason| triggers_pool_refresh(reason));
+                if let Some(reason) = refresh_reason {
+                    self.trigger_pool_refresh(&reason);
+                }
+                continue;
+            }
+            info!(
+                "Transaction {} landed for mint {} in slot {}",
+                landed.signature, landed.mint, landed.slot
+            );
+            if let Some(adaptive) = self.adaptive_delay.as_mut() {
+                if adaptive.on_landed() {
+                    log_adaptive_delay(&self.mint, adaptive);
+                }
+            }
+            if let Some(cooldown) = self.post_land_cooldown {
+                if self.cooldown_until.is_none() {
+                    self.cooldown_started_skips = self.cooldown_skips;
+                }
+                self.cooldown_until = Some(Instant::now() + cooldown);
+            }
+        }
+    }
+
+    /// Shrinks the adaptive delay when the widest spread of a new pricing is above
+    /// `adaptive_delay.spread_threshold_bps`. Each pricing is checked once.
+    fn check_spread(&mut self) {
+        let (Some(threshold), Some(adaptive)) =
+            (self.spread_threshold_bps, self.adaptive_delay.as_mut())
+        else {
+            return;
+        };
+        let spread_bps = {
+            let latest_prices = self.latest_prices.lock().unwrap();
+            let Some(snapshot) = latest_prices
+                .as_ref()
+                .filter(|snapshot| snapshot.taken_at.elapsed() <= PRICES_MAX_AGE)
+            else {
+                return;
+            };
+            if self.spread_checked_at == Some(snapshot.taken_at) {
+                return;
+            }
+            self.spread_checked_at = Some(snapshot.taken_at);
+            snapshot.best_spread().map(|spread| spread.spread_bps)
+        };
+        let Some(spread_bps) = spread_bps.filter(|spread_bps| *spread_bps > threshold) else {
+            return;
+        };
+        debug!(
+            "Spread of {:.1} bps for mint {} is above the {} bps threshold",
+            spread_bps, self.mint, threshold
+        );
+        if adaptive.on_spread_detected() {
+            log_adaptive_delay(&self.mint, adaptive);
+        }
+    }
+
+    fn record_send_failure(&mut self) {
+        if let Some(adaptive) = self.adaptive_delay.as_mut() {
+            if adaptive.on_send_failure() {
+                log_adaptive_delay(&self.mint, adaptive);
+            }
+        }
+    }
+}
+
+/// Index of the worker group a mint belongs to when sharding across `count` groups
+pub fn shard_for(mint: &Pubkey, count: usize) -> usize {
+    let bytes = mint.to_bytes();
+    let prefix = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+    (prefix % count.max(1) as u64) as usize
+}
+
+/// Several mint workers sharing one task. Each mint keeps its own delay; the group
+/// runs whichever mints are due and sleeps until the next one is.
+pub struct WorkerGroup {
+    workers: Vec<(MintWorker, Instant)>,
+}
+
+impl WorkerGroup {
+    pub fn new(workers: Vec<MintWorker>) -> Self {
+        let now = Instant::now();
+        Self {
+            workers: workers
+                .into_iter()
+                .map(|worker| {
+                    let first_run = now + worker.initial_offset();
+                    (worker, first_run)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.workers.is_empty()
+    }
+
+    pub async fn run(mut self) {
+        if self.workers.is_empty() {
+            return;
+        }
+        loop {
+            for (worker, next_run) in self.workers.iter_mut() {
+                if Instant::now() < *next_run {
+                    continue;
+                }
+                worker.run_once().await;
+                *next_run = Instant::now() + worker.jittered_delay();
+            }
+
+            let next_due = self
+                .workers
+                .iter()
+                .map(|(_, next_run)| *next_run)
+                .min()
+                .unwrap_or_else(Instant::now);
+            tokio::time::sleep_until(next_due.into()).await;
+        }
+    }
+}
+
+/// `delay` scaled by a random factor between `1 - jitter` and `1 + jitter`, which keeps
+/// the average delay unchanged
+fn jitter(delay: Duration, jitter: f64) -> Duration {
+    if jitter <= 0.0 {
+        return delay;
+    }
+    delay.mul_f64(1.0 + jitter * (rand::random::<f64>() * 2.0 - 1.0))
+}
+
+fn log_adaptive_delay(mint: &Pubkey, adaptive: &AdaptiveDelay) {
+    info!(
+        "Adaptive delay for mint {}: {}ms ({})",
+        mint,
+        adaptive.current().as_millis(),
+        adaptive.reason()
+    );
+}
@@ -0,0 +1,151 @@
+//! Submits a transaction from `build_transaction` to a solana-program-test bank with a stub
+//! executor, proving it resolves its lookup tables, is signed and fits in a packet.
+//!
+//! Starts a bank, so it is ignored by default:
+//! `cargo test --test executor_program_test -- --ignored`
+
+use solana_onchain_arbitrage_bot::constants::{executor_program_id, sol_mint};
+use solana_onchain_arbitrage_bot::dex::raydium::constants::raydium_program_id;
+use solana_onchain_arbitrage_bot::transaction::{build_swap_instruction, build_transaction};
+use solana_onchain_arbitrage_bot::{Config, MintPoolData};
+use solana_program::account_info::AccountInfo;
+use solana_program::address_lookup_table::state::{AddressLookupTable, LookupTableMeta};
+use solana_program::entrypoint::ProgramResult;
+use solana_program::msg;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::{processor, ProgramTest};
+use solana_sdk::account::Account;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::borrow::Cow;
+
+const STUB_LOG: &str = "stub executor";
+
+/// Stands in for the executor: logs what it was passed and succeeds
+fn stub_executor(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    msg!(
+        "{}: {} accounts, {} data bytes",
+        STUB_LOG,
+        accounts.len(),
+        data.len()
+    );
+    Ok(())
+}
+
+fn config() -> Config {
+    toml::from_str(
+        r#"
+        [bot]
+        compute_unit_limit = 400000
+
+        [routing.markets]
+        markets = []
+        process_delay = 1000
+
+        [rpc]
+        url = "http://127.0.0.1:8899"
+
+        [wallet]
+        "#,
+    )
+    .unwrap()
+}
+
+/// Two Raydium pools and a DLMM pair of a fresh mint; the stub never reads their accounts
+fn pool_data(wallet: &Pubkey) -> MintPoolData {
+    let key = Pubkey::new_unique;
+    let mint = key();
+    let sol = sol_mint();
+    let mut data = MintPoolData::new(mint, wallet, spl_token::ID);
+    for _ in 0..2 {
+        data.add_raydium_pool(raydium_program_id(), key(), key(), key(), mint, sol, 4);
+    }
+    let bins = vec![key(), key(), key()];
+    data.add_dlmm_pool(key(), key(), key(), key(), None, bins, 10, None, mint, sol);
+    data
+}
+
+/// Lookup table account holding `addresses`, usable from the slot after its creation
+fn lookup_table_account(addresses: &[Pubkey]) -> Account {
+    let table = AddressLookupTable {
+        meta: LookupTableMeta::default(),
+        addresses: Cow::Borrowed(addresses),
+    };
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: table.serialize_for_tests().unwrap(),
+        owner: solana_program::address_lookup_table::program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn built_transaction_executes_against_a_stub_executor() {
+    let config = config();
+    let wallet = Keypair::new();
+    let data = pool_data(&wallet.pubkey());
+
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(false);
+    program_test.add_program(
+        "executor_stub",
+        executor_program_id(),
+        processor!(stub_executor),
+    );
+    program_test.add_account(
+        wallet.pubkey(),
+        Account::new(
+            10 * LAMPORTS_PER_SOL,
+            0,
+            &solana_program::system_program::ID,
+        ),
+    );
+
+    // Everything the swap instruction passes except the wallet goes through the table,
+    // as with the lookup tables the bot loads
+    let swap_ix = build_swap_instruction(&wallet, &config, &data, None).unwrap();
+    let mut addresses: Vec<Pubkey> = Vec::new();
+    for meta in &swap_ix.accounts {
+        if meta.pubkey != wallet.pubkey() && !addresses.contains(&meta.pubkey) {
+            addresses.push(meta.pubkey);
+        }
+    }
+    let table_key = Pubkey::new_unique();
+    program_test.add_account(table_key, lookup_table_account(&addresses));
+
+    let mut context = program_test.start_with_context().await;
+    // Addresses are only active after the slot they were added in
+    context.warp_to_slot(2).unwrap();
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+
+    let lookup_tables = [AddressLookupTableAccount {
+        key: table_key,
+        addresses,
+    }];
+    let transaction =
+        build_transaction(&wallet, &config, &data, blockhash, &lookup_tables, None).unwrap();
+    let size = bincode::serialize(&transaction).unwrap().len();
+    assert!(
+        size <= solana_sdk::packet::PACKET_DATA_SIZE,
+        "{} bytes",
+        size
+    );
+
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    assert!(result.result.is_ok(), "{:?}", result.result);
+    let logs = result.metadata.unwrap().log_messages;
+    let expected = format!("{}: {} accounts", STUB_LOG, swap_ix.accounts.len());
+    assert!(
+        logs.iter().any(|log| log.contains(&expected)),
+        "stub executor did not run with the route's accounts: {:?}",
+        logs
+    );
+}